@@ -0,0 +1,63 @@
+-- RocoKnight WPE 封包解析器（Wireshark Lua）
+--
+-- 用法：将本文件放入 Wireshark 的 plugins 目录，或通过
+--   wireshark -X lua_script:rocoknight.lua capture.pcapng
+-- 打开 RocoKnight 导出的 pcapng。导出文件使用 LINKTYPE_USER0，
+-- 每个数据帧即一条游戏层封包（不含 IP/TCP 头）。
+
+local roco = Proto("rocoknight", "RocoKnight Game Packet")
+
+local f_magic = ProtoField.uint16("rocoknight.magic", "Magic", base.HEX)
+local f_length = ProtoField.uint32("rocoknight.length", "Length", base.DEC)
+local f_command = ProtoField.uint16("rocoknight.command", "Command", base.HEX)
+local f_qq = ProtoField.uint64("rocoknight.qq", "QQ", base.DEC)
+local f_data = ProtoField.bytes("rocoknight.data", "Data")
+local f_text = ProtoField.string("rocoknight.text", "Text")
+
+roco.fields = { f_magic, f_length, f_command, f_qq, f_data, f_text }
+
+local COMMANDS = {
+  [0x0003] = "map_jump",
+  [0x0014] = "pet_storage",
+  [0x0052] = "home_training",
+}
+
+function roco.dissector(buffer, pinfo, tree)
+  local len = buffer:len()
+  if len == 0 then
+    return 0
+  end
+  pinfo.cols.protocol = "ROCO"
+  local subtree = tree:add(roco, buffer(), "RocoKnight Game Packet")
+
+  -- 二进制封包：magic 0x9527，字节序按首两个字节判定
+  local le = len >= 2 and buffer(0, 1):uint() == 0x27 and buffer(1, 1):uint() == 0x95
+  local be = len >= 2 and buffer(0, 1):uint() == 0x95 and buffer(1, 1):uint() == 0x27
+  if (le or be) and len >= 16 then
+    local command
+    if le then
+      subtree:add_le(f_magic, buffer(0, 2))
+      subtree:add_le(f_length, buffer(2, 4))
+      subtree:add_le(f_command, buffer(6, 2))
+      subtree:add_le(f_qq, buffer(8, 8))
+      command = buffer(6, 2):le_uint()
+    else
+      subtree:add(f_magic, buffer(0, 2))
+      subtree:add(f_length, buffer(2, 4))
+      subtree:add(f_command, buffer(6, 2))
+      subtree:add(f_qq, buffer(8, 8))
+      command = buffer(6, 2):uint()
+    end
+    if len > 16 then
+      subtree:add(f_data, buffer(16))
+    end
+    local name = COMMANDS[command] or "unknown"
+    pinfo.cols.info = string.format("cmd=0x%04X (%s) len=%d", command, name, len)
+  else
+    subtree:add(f_text, buffer())
+    pinfo.cols.info = "text: " .. buffer():string()
+  end
+  return len
+end
+
+DissectorTable.get("wtap_encap"):add(wtap.USER0, roco)
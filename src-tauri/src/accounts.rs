@@ -0,0 +1,288 @@
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use tauri::{AppHandle, Manager};
+use tracing::{info, warn};
+
+use crate::saved_login::{account_of, expires_at, protect, unprotect};
+use crate::state::AppState;
+
+// 多账号
+//
+// 开启记住登录（login.remember）后，每次 login3 抓取成功都按 QQ 号保存一份账号档案：
+// AppData/accounts/index.json 记录 QQ 号、显示名称、保存时间和脱敏后的 flashVars（不含凭据），
+// 凭据本身用 DPAPI 加密后单独保存为 accounts/<QQ 号>.bin。
+// 工具栏可以直接切换到另一个账号：停止当前 projector，用该账号保存的凭据重新启动，不必重新登录。
+// 凭据过期规则与记住登录相同（login.max_age_hours），过期后只删除凭据，档案保留，重新登录该账号即可恢复。
+// 登录页 webview 的 cookie 仍是切换前账号的 QQ 登录态，之后重新打开登录页会自动登录成旧账号；
+// 因此切换到其他账号时（login.isolate_sessions，默认开启）通过 WebView2 CookieManager 清除登录页的 cookie。
+// 启动时用 --account/--profile 隔离的用户数据目录不受影响，只清除当前目录中的 cookie。
+
+const ACCOUNTS_DIR: &str = "accounts";
+const INDEX_FILE: &str = "index.json";
+
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct AccountProfile {
+    pub qq_num: u64,
+    pub display_name: String,
+    /// 最近一次保存凭据的时间（Unix 毫秒）
+    pub saved_at: u64,
+    /// 脱敏后的 flashVars，只用于排查问题
+    pub redacted: String,
+}
+
+/// list_accounts 返回的条目
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct AccountEntry {
+    #[serde(flatten)]
+    pub profile: AccountProfile,
+    /// 凭据已过期或缺失，需要重新登录
+    pub expired: bool,
+    /// 当前正在运行的账号
+    pub active: bool,
+}
+
+static ACCOUNTS_PATH: OnceLock<PathBuf> = OnceLock::new();
+static INDEX: Mutex<Vec<AccountProfile>> = Mutex::new(Vec::new());
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// 加载账号列表（在 Tauri setup 中调用）
+pub fn init(app: &AppHandle) {
+    let Ok(dir) = app.path().app_data_dir() else {
+        warn!("[Accounts] failed to resolve app data dir, account profiles disabled");
+        return;
+    };
+    let dir = dir.join(ACCOUNTS_DIR);
+    let profiles: Vec<AccountProfile> = std::fs::read_to_string(dir.join(INDEX_FILE))
+        .ok()
+        .and_then(|text| serde_json::from_str(&text).ok())
+        .unwrap_or_default();
+    info!(count = profiles.len(), "[Accounts] loaded");
+    *INDEX.lock().expect("accounts lock") = profiles;
+    let _ = ACCOUNTS_PATH.set(dir);
+}
+
+fn credentials_path(dir: &std::path::Path, qq_num: u64) -> PathBuf {
+    dir.join(format!("{qq_num}.bin"))
+}
+
+fn save_index(profiles: &[AccountProfile]) {
+    let Some(dir) = ACCOUNTS_PATH.get() else {
+        return;
+    };
+    let _ = std::fs::create_dir_all(dir);
+    match serde_json::to_string_pretty(profiles) {
+        Ok(json) => {
+            if let Err(e) = std::fs::write(dir.join(INDEX_FILE), json) {
+                warn!(error = %e, "[Accounts] failed to save index");
+            }
+        }
+        Err(e) => warn!(error = %e, "[Accounts] failed to serialize index"),
+    }
+}
+
+/// 新增或更新档案，保留已有的显示名称
+fn upsert(profiles: &mut Vec<AccountProfile>, qq_num: u64, saved_at: u64, redacted: String) {
+    match profiles.iter_mut().find(|p| p.qq_num == qq_num) {
+        Some(profile) => {
+            profile.saved_at = saved_at;
+            profile.redacted = redacted;
+        }
+        None => profiles.push(AccountProfile {
+            qq_num,
+            display_name: format!("QQ {qq_num}"),
+            saved_at,
+            redacted,
+        }),
+    }
+}
+
+/// login3 抓取成功后调用；未开启记住登录或 flashVars 中没有 QQ 号时忽略
+pub fn remember(flash_vars: &str) {
+    if !crate::config::current().login.remember {
+        return;
+    }
+    let (Some(dir), Some(qq_num)) = (ACCOUNTS_PATH.get(), account_of(flash_vars)) else {
+        return;
+    };
+    let encrypted = match protect(flash_vars.as_bytes()) {
+        Ok(data) => data,
+        Err(e) => {
+            warn!(error = %e, "[Accounts] failed to encrypt credentials, profile not saved");
+            return;
+        }
+    };
+    let _ = std::fs::create_dir_all(dir);
+    if let Err(e) = std::fs::write(credentials_path(dir, qq_num), encrypted) {
+        warn!(error = %e, "[Accounts] failed to write credentials");
+        return;
+    }
+    let mut profiles = INDEX.lock().expect("accounts lock");
+    upsert(
+        &mut profiles,
+        qq_num,
+        now_ms(),
+        crate::redact::redact_secrets(flash_vars),
+    );
+    save_index(&profiles);
+    info!(qq_num = qq_num, "[Accounts] profile saved");
+}
+
+fn is_expired(profile: &AccountProfile, max_age_hours: u64) -> bool {
+    let missing = ACCOUNTS_PATH
+        .get()
+        .is_none_or(|dir| !credentials_path(dir, profile.qq_num).exists());
+    missing || now_ms() >= expires_at(profile.saved_at, max_age_hours)
+}
+
+pub fn list(app: &AppHandle) -> Vec<AccountEntry> {
+    let active = app
+        .state::<Mutex<AppState>>()
+        .lock()
+        .expect("state lock")
+        .qq_num;
+    let max_age_hours = crate::config::current().login.max_age_hours;
+    INDEX
+        .lock()
+        .expect("accounts lock")
+        .iter()
+        .map(|profile| AccountEntry {
+            expired: is_expired(profile, max_age_hours),
+            active: active == Some(profile.qq_num),
+            profile: profile.clone(),
+        })
+        .collect()
+}
+
+pub fn rename(qq_num: u64, display_name: String) -> Result<(), String> {
+    let mut profiles = INDEX.lock().expect("accounts lock");
+    let profile = profiles
+        .iter_mut()
+        .find(|p| p.qq_num == qq_num)
+        .ok_or_else(|| format!("Account {qq_num} not found."))?;
+    profile.display_name = display_name;
+    save_index(&profiles);
+    Ok(())
+}
+
+/// 删除档案和保存的凭据，返回是否找到
+pub fn remove(qq_num: u64) -> bool {
+    if let Some(dir) = ACCOUNTS_PATH.get() {
+        let _ = std::fs::remove_file(credentials_path(dir, qq_num));
+    }
+    let mut profiles = INDEX.lock().expect("accounts lock");
+    let before = profiles.len();
+    profiles.retain(|p| p.qq_num != qq_num);
+    let removed = profiles.len() != before;
+    if removed {
+        save_index(&profiles);
+        info!(qq_num = qq_num, "[Accounts] profile removed");
+    }
+    removed
+}
+
+/// 读取账号的凭据；过期或无法解密时删除凭据文件（档案保留）
+pub fn credentials(qq_num: u64) -> Result<String, String> {
+    let dir = ACCOUNTS_PATH
+        .get()
+        .ok_or_else(|| "Account profiles are unavailable.".to_string())?;
+    let profile = INDEX
+        .lock()
+        .expect("accounts lock")
+        .iter()
+        .find(|p| p.qq_num == qq_num)
+        .cloned()
+        .ok_or_else(|| format!("Account {qq_num} not found."))?;
+    let path = credentials_path(dir, qq_num);
+    let login = crate::config::current().login;
+    let flash_vars = if login.remember && !is_expired(&profile, login.max_age_hours) {
+        std::fs::read(&path)
+            .ok()
+            .and_then(|data| unprotect(&data).ok())
+            .and_then(|data| String::from_utf8(data).ok())
+    } else {
+        None
+    };
+    flash_vars.ok_or_else(|| {
+        let _ = std::fs::remove_file(&path);
+        warn!(
+            qq_num = qq_num,
+            "[Accounts] saved credentials expired or unreadable"
+        );
+        format!("Saved login for {qq_num} has expired, please log in again.")
+    })
+}
+
+/// 清除登录页 webview 的 cookie（QQ 登录态），下次打开登录页需要重新登录
+pub fn clear_login_session(app: &AppHandle) -> Result<(), String> {
+    let login = app
+        .get_webview("login")
+        .ok_or_else(|| "Login WebView not found.".to_string())?;
+    // with_webview 在主线程上异步执行，命令本身也可能在主线程上，不等待结果
+    login
+        .with_webview(|webview| match delete_cookies(webview) {
+            Ok(()) => info!("[Accounts] login session cleared"),
+            Err(e) => warn!(error = %e, "[Accounts] failed to clear login session"),
+        })
+        .map_err(|_| "Failed to access login webview.".to_string())
+}
+
+/// 切换到另一个账号前调用：与当前账号不同且开启了 login.isolate_sessions 时清除登录页 cookie
+pub fn isolate_session(app: &AppHandle, qq_num: u64) {
+    let active = app
+        .state::<Mutex<AppState>>()
+        .lock()
+        .expect("state lock")
+        .qq_num;
+    if !crate::config::current().login.isolate_sessions || active == Some(qq_num) {
+        return;
+    }
+    if let Err(e) = clear_login_session(app) {
+        warn!(error = %e, "[Accounts] failed to isolate login session");
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn delete_cookies(webview: tauri::webview::PlatformWebview) -> Result<(), String> {
+    use webview2_com::Microsoft::Web::WebView2::Win32::ICoreWebView2_2;
+    use windows::core::Interface;
+
+    let core = unsafe { webview.controller().CoreWebView2() }
+        .map_err(|e| format!("CoreWebView2 not available: {e}"))?;
+    let core2: ICoreWebView2_2 = core
+        .cast()
+        .map_err(|e| format!("ICoreWebView2_2 not available: {e}"))?;
+    let manager =
+        unsafe { core2.CookieManager() }.map_err(|e| format!("CookieManager failed: {e}"))?;
+    unsafe { manager.DeleteAllCookies() }.map_err(|e| format!("DeleteAllCookies failed: {e}"))
+}
+
+#[cfg(not(target_os = "windows"))]
+fn delete_cookies(_webview: tauri::webview::PlatformWebview) -> Result<(), String> {
+    Err("仅支持 Windows 平台。".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn upsert_keeps_display_name() {
+        let mut profiles = Vec::new();
+        upsert(&mut profiles, 10001, 1_000, "a".to_string());
+        profiles[0].display_name = "大号".to_string();
+        upsert(&mut profiles, 10001, 2_000, "b".to_string());
+        upsert(&mut profiles, 10002, 3_000, "c".to_string());
+        assert_eq!(profiles.len(), 2);
+        assert_eq!(profiles[0].display_name, "大号");
+        assert_eq!(profiles[0].saved_at, 2_000);
+        assert_eq!(profiles[1].display_name, "QQ 10002");
+    }
+}
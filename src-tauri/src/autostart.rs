@@ -0,0 +1,168 @@
+use tracing::{info, warn};
+
+/// 开机自启动时附加的启动参数（启动后最小化主窗口）
+pub const MINIMIZED_ARG: &str = "--minimized";
+
+#[cfg(target_os = "windows")]
+const RUN_KEY: &str = "Software\\Microsoft\\Windows\\CurrentVersion\\Run";
+#[cfg(target_os = "windows")]
+const VALUE_NAME: &str = "RocoKnight";
+
+/// 自启动状态（配置开关与注册表实际状态分开上报）
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct AutostartStatus {
+    /// 配置中是否开启
+    pub enabled: bool,
+    /// HKCU Run 中是否存在指向当前可执行文件的项
+    pub registered: bool,
+    /// Run 中记录的命令行（不存在时为 None）
+    pub command: Option<String>,
+}
+
+/// 当前进程是否由自启动拉起
+pub fn launched_minimized() -> bool {
+    std::env::args().any(|arg| arg == MINIMIZED_ARG)
+}
+
+fn expected_command() -> Result<String, String> {
+    let exe =
+        std::env::current_exe().map_err(|_| "Failed to resolve executable path.".to_string())?;
+    Ok(format!("\"{}\" {}", exe.display(), MINIMIZED_ARG))
+}
+
+pub fn status() -> AutostartStatus {
+    let command = read_run_value();
+    let registered = match (&command, expected_command()) {
+        (Some(command), Ok(expected)) => command.eq_ignore_ascii_case(&expected),
+        _ => false,
+    };
+    AutostartStatus {
+        enabled: crate::config::current().launcher.autostart,
+        registered,
+        command,
+    }
+}
+
+/// 写入或移除 HKCU Run 项
+pub fn apply(enabled: bool) -> Result<(), String> {
+    if enabled {
+        let command = expected_command()?;
+        write_run_value(&command)?;
+        info!(command = %command, "[Autostart] registered");
+    } else {
+        delete_run_value()?;
+        info!("[Autostart] unregistered");
+    }
+    Ok(())
+}
+
+/// 启动时校正注册表：可执行文件被移动后 Run 项会指向旧路径
+pub fn sync_with_config() {
+    let status = status();
+    // 关闭时无论 Run 项指向哪个路径都要移除，开启时只需在未指向当前可执行文件时重写
+    let stale = if status.enabled {
+        !status.registered
+    } else {
+        status.command.is_some()
+    };
+    if !stale {
+        return;
+    }
+    if let Err(e) = apply(status.enabled) {
+        warn!(error = %e, "[Autostart] failed to sync run key");
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn wide(s: &str) -> Vec<u16> {
+    s.encode_utf16().chain(std::iter::once(0)).collect()
+}
+
+#[cfg(target_os = "windows")]
+fn read_run_value() -> Option<String> {
+    use windows::core::PCWSTR;
+    use windows::Win32::Foundation::ERROR_SUCCESS;
+    use windows::Win32::System::Registry::{RegGetValueW, HKEY_CURRENT_USER, RRF_RT_REG_SZ};
+
+    let key = wide(RUN_KEY);
+    let name = wide(VALUE_NAME);
+    let mut buf = [0u16; 1024];
+    let mut size = (buf.len() * 2) as u32;
+    let result = unsafe {
+        RegGetValueW(
+            HKEY_CURRENT_USER,
+            PCWSTR(key.as_ptr()),
+            PCWSTR(name.as_ptr()),
+            RRF_RT_REG_SZ,
+            None,
+            Some(buf.as_mut_ptr() as *mut _),
+            Some(&mut size as *mut _),
+        )
+    };
+    if result != ERROR_SUCCESS {
+        return None;
+    }
+    let len = (size as usize / 2).saturating_sub(1).min(buf.len());
+    Some(String::from_utf16_lossy(&buf[..len]))
+}
+
+#[cfg(target_os = "windows")]
+fn write_run_value(command: &str) -> Result<(), String> {
+    use windows::core::PCWSTR;
+    use windows::Win32::Foundation::ERROR_SUCCESS;
+    use windows::Win32::System::Registry::{RegSetKeyValueW, HKEY_CURRENT_USER, REG_SZ};
+
+    let key = wide(RUN_KEY);
+    let name = wide(VALUE_NAME);
+    let data = wide(command);
+    let result = unsafe {
+        RegSetKeyValueW(
+            HKEY_CURRENT_USER,
+            PCWSTR(key.as_ptr()),
+            PCWSTR(name.as_ptr()),
+            REG_SZ.0,
+            Some(data.as_ptr() as *const _),
+            (data.len() * 2) as u32,
+        )
+    };
+    if result != ERROR_SUCCESS {
+        return Err(format!("Failed to write autostart entry: {}", result.0));
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "windows")]
+fn delete_run_value() -> Result<(), String> {
+    use windows::core::PCWSTR;
+    use windows::Win32::Foundation::{ERROR_FILE_NOT_FOUND, ERROR_SUCCESS};
+    use windows::Win32::System::Registry::{RegDeleteKeyValueW, HKEY_CURRENT_USER};
+
+    let key = wide(RUN_KEY);
+    let name = wide(VALUE_NAME);
+    let result = unsafe {
+        RegDeleteKeyValueW(
+            HKEY_CURRENT_USER,
+            PCWSTR(key.as_ptr()),
+            PCWSTR(name.as_ptr()),
+        )
+    };
+    if result != ERROR_SUCCESS && result != ERROR_FILE_NOT_FOUND {
+        return Err(format!("Failed to remove autostart entry: {}", result.0));
+    }
+    Ok(())
+}
+
+#[cfg(not(target_os = "windows"))]
+fn read_run_value() -> Option<String> {
+    None
+}
+
+#[cfg(not(target_os = "windows"))]
+fn write_run_value(_command: &str) -> Result<(), String> {
+    Err("仅支持 Windows 平台。".to_string())
+}
+
+#[cfg(not(target_os = "windows"))]
+fn delete_run_value() -> Result<(), String> {
+    Ok(())
+}
@@ -0,0 +1,182 @@
+use std::collections::{BTreeMap, HashMap};
+
+use tracing::{info, warn};
+
+use crate::game_config::ServerEntry;
+
+// 频道（服务器）切换
+//
+// 服务器列表来自抓取到的 Global.xml（game_config 中的 server 元素）。main.swf 地址的查询参数就是
+// flashVars，选择频道时把其中指定服务器的参数改写为所选 server 元素的属性值，再用新地址重新启动 projector。
+// 改写哪些参数由配置 launcher.channel.params 指定（flashVars 参数名 -> server 属性名）；
+// 未配置时改写与 server 属性同名的参数（不区分大小写，name/title 除外）。
+// 地址中没有可改写的参数时不重启，返回错误，避免“切换”后仍连到原来的服务器。
+
+/// 只用于显示的属性，不参与改写
+const DISPLAY_ATTRIBUTES: &[&str] = &["name", "title"];
+
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize)]
+pub struct Channel {
+    /// server 元素的 id 属性，没有时为从 1 开始的序号
+    pub id: String,
+    pub name: Option<String>,
+    pub host: Option<String>,
+    pub port: Option<u16>,
+    /// 当前 main.swf 地址指向该服务器
+    pub selected: bool,
+}
+
+/// 服务器列表；`swf_url` 为当前地址，用于标记选中的频道
+pub fn list(swf_url: Option<&str>) -> Vec<Channel> {
+    let params = crate::config::current().launcher.channel.params;
+    servers()
+        .into_iter()
+        .map(|(id, server)| Channel {
+            selected: swf_url.is_some_and(|url| {
+                targets(url, &server, &params)
+                    .is_some_and(|targets| targets.iter().all(|(_, old, new)| old == new))
+            }),
+            id,
+            name: server.name,
+            host: server.host,
+            port: server.port,
+        })
+        .collect()
+}
+
+/// 把 `swf_url` 改写为指向频道 `id` 的地址
+pub fn select(swf_url: &str, id: &str) -> Result<String, String> {
+    let Some((_, server)) = servers().into_iter().find(|(server_id, _)| server_id == id) else {
+        warn!(id = %id, "[Channel] unknown channel");
+        return Err(format!("Unknown channel: {id}."));
+    };
+    let params = crate::config::current().launcher.channel.params;
+    let url = rewrite(swf_url, &server, &params)?;
+    info!(
+        id = %id,
+        name = ?server.name,
+        host = ?server.host,
+        "[Channel] channel selected"
+    );
+    Ok(url)
+}
+
+fn servers() -> Vec<(String, ServerEntry)> {
+    let Some(config) = crate::game_config::current() else {
+        return Vec::new();
+    };
+    config
+        .parsed
+        .servers
+        .into_iter()
+        .enumerate()
+        .map(|(index, server)| (channel_id(index, &server), server))
+        .collect()
+}
+
+fn channel_id(index: usize, server: &ServerEntry) -> String {
+    server
+        .attributes
+        .iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case("id"))
+        .map(|(_, value)| value.clone())
+        .unwrap_or_else(|| (index + 1).to_string())
+}
+
+/// 地址中需要改写的参数：(序号, 原值, 新值)；没有可改写的参数时为 None
+fn targets(
+    swf_url: &str,
+    server: &ServerEntry,
+    params: &HashMap<String, String>,
+) -> Option<Vec<(usize, String, String)>> {
+    let (_, query) = swf_url.split_once('?')?;
+    let attributes: BTreeMap<String, &String> = server
+        .attributes
+        .iter()
+        .map(|(key, value)| (key.to_ascii_lowercase(), value))
+        .collect();
+    let targets: Vec<_> = query
+        .split('&')
+        .enumerate()
+        .filter_map(|(index, pair)| {
+            let (key, value) = url::form_urlencoded::parse(pair.as_bytes()).next()?;
+            let attribute = if params.is_empty() {
+                let key = key.to_ascii_lowercase();
+                (!DISPLAY_ATTRIBUTES.contains(&key.as_str())).then_some(key)?
+            } else {
+                params.get(key.as_ref())?.to_ascii_lowercase()
+            };
+            let new = attributes.get(&attribute)?;
+            Some((index, value.into_owned(), new.to_string()))
+        })
+        .collect();
+    (!targets.is_empty()).then_some(targets)
+}
+
+fn rewrite(
+    swf_url: &str,
+    server: &ServerEntry,
+    params: &HashMap<String, String>,
+) -> Result<String, String> {
+    let targets = targets(swf_url, server, params).ok_or_else(|| {
+        "main.swf url has no server parameters to rewrite; set launcher.channel.params.".to_string()
+    })?;
+    let (base, query) = swf_url.split_once('?').unwrap_or((swf_url, ""));
+    let mut pairs: Vec<String> = query.split('&').map(str::to_string).collect();
+    for (index, _, new) in targets {
+        let pair = &mut pairs[index];
+        let key = pair.split_once('=').map_or(pair.as_str(), |(key, _)| key);
+        let value: String = url::form_urlencoded::byte_serialize(new.as_bytes()).collect();
+        *pair = format!("{key}={value}");
+    }
+    Ok(format!("{base}?{}", pairs.join("&")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn server(attributes: &[(&str, &str)]) -> ServerEntry {
+        ServerEntry {
+            attributes: attributes
+                .iter()
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .collect(),
+            ..ServerEntry::default()
+        }
+    }
+
+    #[test]
+    fn rewrites_server_params() {
+        let url = "https://res.example.com/main.swf?0.5=&config=//res/Global.xml&IP=10.0.0.1&port=443&name=a";
+        let target = server(&[
+            ("id", "2"),
+            ("name", "二区"),
+            ("ip", "10.0.0.2"),
+            ("port", "8443"),
+        ]);
+        assert_eq!(
+            rewrite(url, &target, &HashMap::new()).unwrap(),
+            "https://res.example.com/main.swf?0.5=&config=//res/Global.xml&IP=10.0.0.2&port=8443&name=a"
+        );
+
+        // 按配置映射参数名
+        let params: HashMap<String, String> = [("srv".to_string(), "id".to_string())]
+            .into_iter()
+            .collect();
+        let url = "https://res.example.com/main.swf?srv=1&ip=10.0.0.1";
+        assert_eq!(
+            rewrite(url, &target, &params).unwrap(),
+            "https://res.example.com/main.swf?srv=2&ip=10.0.0.1"
+        );
+
+        // 没有可改写的参数
+        assert!(rewrite(
+            "https://res.example.com/main.swf?a=1",
+            &target,
+            &HashMap::new()
+        )
+        .is_err());
+        assert_eq!(channel_id(0, &server(&[("ip", "10.0.0.1")])), "1");
+    }
+}
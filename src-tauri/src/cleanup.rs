@@ -0,0 +1,120 @@
+use std::path::{Path, PathBuf};
+
+use tauri::{AppHandle, Manager};
+use tracing::{info, warn};
+
+/// 清理结果（confirm 为 false 时只列出将被删除的路径）
+#[derive(Debug, Default, serde::Serialize)]
+pub struct CleanupReport {
+    pub dry_run: bool,
+    /// 已删除（或将被删除）的路径
+    pub removed: Vec<String>,
+    /// 删除失败的路径及原因（通常是文件仍被占用）
+    pub failed: Vec<String>,
+    /// 涉及的总字节数
+    pub bytes: u64,
+}
+
+/// 启动器在本机写入数据的全部位置
+///
+/// - %LOCALAPPDATA%/RocoKnight：日志、login3 抓取结果
+/// - Tauri 的 app data / local data / cache / log 目录：配置、会话记录、WebView 数据
+fn data_dirs(app: &AppHandle) -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+    #[cfg(target_os = "windows")]
+    if let Ok(local) = std::env::var("LOCALAPPDATA") {
+        dirs.push(PathBuf::from(local).join("RocoKnight"));
+    }
+    let resolver = app.path();
+    dirs.extend(
+        [
+            resolver.app_data_dir(),
+            resolver.app_local_data_dir(),
+            resolver.app_cache_dir(),
+            resolver.app_log_dir(),
+        ]
+        .into_iter()
+        .flatten(),
+    );
+
+    dirs.sort();
+    dirs.dedup();
+    // 去掉被其他目录包含的子目录，避免重复统计
+    let all = dirs.clone();
+    dirs.retain(|dir| {
+        !all.iter()
+            .any(|other| other != dir && dir.starts_with(other))
+    });
+    dirs
+}
+
+fn dir_size(path: &Path) -> u64 {
+    let Ok(entries) = std::fs::read_dir(path) else {
+        return std::fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+    };
+    entries
+        .flatten()
+        .map(|entry| match entry.file_type() {
+            Ok(ty) if ty.is_dir() => dir_size(&entry.path()),
+            _ => entry.metadata().map(|m| m.len()).unwrap_or(0),
+        })
+        .sum()
+}
+
+/// 删除目录下的全部内容；被占用的文件（当前日志、WebView 数据）跳过并记录
+fn remove_tree(path: &Path, report: &mut CleanupReport) {
+    let Ok(entries) = std::fs::read_dir(path) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let child = entry.path();
+        let result = match entry.file_type() {
+            Ok(ty) if ty.is_dir() => {
+                remove_tree(&child, report);
+                std::fs::remove_dir(&child)
+            }
+            _ => std::fs::remove_file(&child),
+        };
+        if let Err(e) = result {
+            // 子目录因内部文件被占用而非空时，失败已在递归中记录
+            if child.is_file() {
+                report.failed.push(format!("{}: {e}", child.display()));
+            }
+        }
+    }
+}
+
+/// 清理启动器产生的所有本地数据
+///
+/// 删除后内存中的配置与会话记录仍在，调用方应随后退出应用。
+pub fn cleanup_all(app: &AppHandle, confirm: bool) -> CleanupReport {
+    let mut report = CleanupReport {
+        dry_run: !confirm,
+        ..Default::default()
+    };
+
+    for dir in data_dirs(app) {
+        if !dir.exists() {
+            continue;
+        }
+        report.bytes += dir_size(&dir);
+        report.removed.push(dir.display().to_string());
+        if confirm {
+            remove_tree(&dir, &mut report);
+            let _ = std::fs::remove_dir(&dir);
+        }
+    }
+
+    if confirm {
+        info!(
+            dirs = report.removed.len(),
+            failed = report.failed.len(),
+            bytes = report.bytes,
+            "[Cleanup] local data removed"
+        );
+        if !report.failed.is_empty() {
+            warn!(failed = ?report.failed, "[Cleanup] some files could not be removed");
+        }
+    }
+    report
+}
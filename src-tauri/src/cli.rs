@@ -0,0 +1,211 @@
+use std::sync::OnceLock;
+
+use tracing::warn;
+
+// 命令行参数（在 Tauri Builder 之前解析），供快捷方式和脚本以预设方式启动：
+//
+//   --profile <name>    使用独立的配置文件 AppData/profiles/<name>/config.json
+//   --account <qq>      期望登录的账号：登录前用于状态显示，登录后账号不一致时告警
+//   --safe-mode         诊断模式：本次运行按纯净模式处理，并关闭所有实验开关（不写入配置）
+//   --debug-console     启动后直接打开 Debug Console
+//   --swf-url <url>     跳过登录页，直接用给定的 main.swf 地址启动 projector
+//   --login-url <url>   本次运行使用的登录页地址（覆盖配置 endpoints.login_url）
+//   --swf-base <url>    本次运行使用的 main.swf 地址（覆盖配置 endpoints.swf_base）
+//   --headless          不显示登录页，用保存的凭据或 QQ 登录态自动登录（配合 --account）
+//
+// 参数同时支持 `--flag value` 和 `--flag=value`，无法识别的参数只记录警告。
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CliArgs {
+    pub profile: Option<String>,
+    pub account: Option<u64>,
+    pub safe_mode: bool,
+    pub debug_console: bool,
+    pub swf_url: Option<String>,
+    pub login_url: Option<String>,
+    pub swf_base: Option<String>,
+    pub headless: bool,
+}
+
+impl CliArgs {
+    /// 登录页 WebView2 用户数据目录名：按 --account（其次 --profile）隔离 cookie，
+    /// 多开不同账号时会话互不串号；都未指定时沿用默认目录
+    pub fn webview_data_key(&self) -> Option<String> {
+        match (self.account, &self.profile) {
+            (Some(qq), _) => Some(format!("account-{qq}")),
+            (None, Some(profile)) => Some(format!("profile-{profile}")),
+            (None, None) => None,
+        }
+    }
+}
+
+static ARGS: OnceLock<CliArgs> = OnceLock::new();
+static PROBLEMS: OnceLock<Vec<String>> = OnceLock::new();
+
+/// 配置档名称只允许字母、数字、`-` 和 `_`，避免拼出 AppData 之外的路径
+fn valid_profile(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= 64
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/// 取参数值：`--flag=value` 的内联值，或下一个参数
+fn take_value(
+    name: &str,
+    inline: Option<&str>,
+    rest: &mut impl Iterator<Item = String>,
+    problems: &mut Vec<String>,
+) -> Option<String> {
+    let value = inline.map(str::to_string).or_else(|| rest.next());
+    if value.is_none() {
+        problems.push(format!("{name} requires a value"));
+    }
+    value
+}
+
+/// 解析参数（不含程序名），返回结果和无法识别/无效的参数说明
+pub fn parse<I: IntoIterator<Item = String>>(args: I) -> (CliArgs, Vec<String>) {
+    let mut parsed = CliArgs::default();
+    let mut problems = Vec::new();
+    let mut rest = args.into_iter();
+
+    while let Some(arg) = rest.next() {
+        let (flag, inline) = match arg.split_once('=') {
+            Some((flag, value)) if flag.starts_with("--") => (flag, Some(value)),
+            _ => (arg.as_str(), None),
+        };
+
+        match flag {
+            "--profile" => {
+                if let Some(name) = take_value(flag, inline, &mut rest, &mut problems) {
+                    if valid_profile(&name) {
+                        parsed.profile = Some(name);
+                    } else {
+                        problems.push(format!("invalid profile name: {name}"));
+                    }
+                }
+            }
+            "--account" => {
+                if let Some(text) = take_value(flag, inline, &mut rest, &mut problems) {
+                    match text.parse::<u64>() {
+                        Ok(qq) if qq > 0 => parsed.account = Some(qq),
+                        _ => problems.push(format!("invalid account: {text}")),
+                    }
+                }
+            }
+            "--swf-url" => {
+                if let Some(url) = take_value(flag, inline, &mut rest, &mut problems) {
+                    if url.starts_with("http://") || url.starts_with("https://") {
+                        parsed.swf_url = Some(url);
+                    } else {
+                        problems.push("--swf-url must be an http(s) URL".to_string());
+                    }
+                }
+            }
+            "--login-url" | "--swf-base" => {
+                if let Some(url) = take_value(flag, inline, &mut rest, &mut problems) {
+                    if !crate::endpoints::valid_url(&url) {
+                        problems.push(format!("{flag} must be an http(s) URL"));
+                    } else if flag == "--login-url" {
+                        parsed.login_url = Some(url);
+                    } else {
+                        parsed.swf_base = Some(url);
+                    }
+                }
+            }
+            "--safe-mode" => parsed.safe_mode = true,
+            "--debug-console" => parsed.debug_console = true,
+            "--headless" => parsed.headless = true,
+            // 开机自启动参数由 autostart 模块处理
+            crate::autostart::MINIMIZED_ARG => {}
+            _ => problems.push(format!("unknown argument: {arg}")),
+        }
+    }
+    (parsed, problems)
+}
+
+/// 解析当前进程的命令行（在 main 开头、Tauri Builder 之前调用一次）
+pub fn init() {
+    let (args, problems) = parse(std::env::args().skip(1));
+    let _ = ARGS.set(args);
+    let _ = PROBLEMS.set(problems);
+}
+
+/// 记录无法识别的参数（解析时日志尚未初始化，在 setup 中调用）
+pub fn report_problems() {
+    for problem in PROBLEMS.get().into_iter().flatten() {
+        warn!(problem = %problem, "[Cli] ignoring argument");
+    }
+}
+
+pub fn args() -> &'static CliArgs {
+    ARGS.get_or_init(CliArgs::default)
+}
+
+/// 是否以 --safe-mode 启动
+pub fn safe_mode() -> bool {
+    args().safe_mode
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(args: &[&str]) -> Vec<String> {
+        args.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn parses_flags_and_reports_problems() {
+        let (args, problems) = parse(strings(&[
+            "--profile",
+            "alt_1",
+            "--account=10001",
+            "--safe-mode",
+            "--debug-console",
+            "--headless",
+            "--swf-url",
+            "https://example.com/main.swf?a=1",
+            "--login-url=https://test.example.com/login.html",
+            "--swf-base",
+            "https://cdn.example.com/main.swf",
+            "--minimized",
+        ]));
+        assert_eq!(
+            args,
+            CliArgs {
+                profile: Some("alt_1".to_string()),
+                account: Some(10001),
+                safe_mode: true,
+                debug_console: true,
+                swf_url: Some("https://example.com/main.swf?a=1".to_string()),
+                login_url: Some("https://test.example.com/login.html".to_string()),
+                swf_base: Some("https://cdn.example.com/main.swf".to_string()),
+                headless: true,
+            }
+        );
+        assert!(problems.is_empty());
+        assert_eq!(args.webview_data_key().as_deref(), Some("account-10001"));
+
+        let (args, problems) = parse(strings(&[
+            "--profile",
+            "../x",
+            "--account",
+            "abc",
+            "--bogus",
+        ]));
+        assert_eq!(args, CliArgs::default());
+        assert_eq!(problems.len(), 3);
+
+        let (_, problems) = parse(strings(&["--swf-url"]));
+        assert_eq!(problems, vec!["--swf-url requires a value".to_string()]);
+
+        let (_, problems) = parse(strings(&["--login-url", "17roco.qq.com"]));
+        assert_eq!(
+            problems,
+            vec!["--login-url must be an http(s) URL".to_string()]
+        );
+    }
+}
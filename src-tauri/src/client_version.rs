@@ -0,0 +1,158 @@
+use tauri::{AppHandle, Emitter};
+use tracing::{info, warn};
+
+// 游戏客户端版本指纹
+//
+// 从 main.swf 地址中取出表示版本的参数，排序后做 FNV-1a 哈希，得到客户端版本指纹并记入会话记录。
+// 命令表（wpe::commands）整理时绑定了客户端版本的，与当前指纹不一致时：
+// 记录警告、发出 client_version_mismatch 事件，并让注入、发送列表等自动化功能直接报错，
+// 避免按旧格式构造的封包破坏游戏状态。地址中没有版本参数时指纹未知，不做限制。
+
+/// 视为版本号的参数名（不区分大小写）；登录 token 等其他参数不参与指纹
+const VERSION_KEYS: &[&str] = &[
+    "v",
+    "ver",
+    "version",
+    "build",
+    "clientver",
+    "client_version",
+    "swfver",
+];
+
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Compatibility {
+    /// 地址中没有版本参数，或命令表未绑定版本
+    Unknown,
+    Verified,
+    Mismatch {
+        expected: String,
+        actual: String,
+    },
+}
+
+/// 计算客户端版本指纹（16 位十六进制），地址无法解析或没有版本参数时为 None
+pub fn fingerprint(swf_url: &str) -> Option<String> {
+    let parsed = url::Url::parse(swf_url).ok()?;
+    let mut pairs: Vec<(String, String)> = parsed
+        .query_pairs()
+        .map(|(key, value)| (key.to_ascii_lowercase(), value.into_owned()))
+        .filter(|(key, value)| VERSION_KEYS.contains(&key.as_str()) && !value.is_empty())
+        .collect();
+    if pairs.is_empty() {
+        return None;
+    }
+    pairs.sort();
+
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for (key, value) in &pairs {
+        for byte in key.bytes().chain([b'=']).chain(value.bytes()).chain([b'&']) {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+    Some(format!("{hash:016x}"))
+}
+
+pub fn check(actual: Option<&str>, expected: Option<&str>) -> Compatibility {
+    match (actual, expected) {
+        (Some(actual), Some(expected)) if actual == expected => Compatibility::Verified,
+        (Some(actual), Some(expected)) => Compatibility::Mismatch {
+            expected: expected.to_string(),
+            actual: actual.to_string(),
+        },
+        _ => Compatibility::Unknown,
+    }
+}
+
+/// 命令表绑定的客户端指纹：配置 capture.catalog_client 优先，未配置时使用命令表内置的值
+fn catalog_client() -> Option<String> {
+    let configured = crate::config::current().capture.catalog_client;
+    let configured = configured.trim();
+    if configured.is_empty() {
+        crate::wpe::commands::CATALOG_CLIENT.map(str::to_string)
+    } else {
+        Some(configured.to_ascii_lowercase())
+    }
+}
+
+/// 不兼容时让注入、发送列表报错的原因
+fn mismatch_reason(compatibility: &Compatibility) -> Option<String> {
+    match compatibility {
+        Compatibility::Mismatch { expected, actual } => Some(format!(
+            "packet catalog targets client {expected}, running {actual}"
+        )),
+        _ => None,
+    }
+}
+
+/// 识别即将启动的客户端版本并同步到 WPE 的兼容性检查，返回指纹（记入会话记录）
+pub fn apply(app: &AppHandle, swf_url: &str) -> Option<String> {
+    let actual = fingerprint(swf_url);
+    let compatibility = check(actual.as_deref(), catalog_client().as_deref());
+    crate::wpe::set_client_mismatch(mismatch_reason(&compatibility));
+    match &compatibility {
+        Compatibility::Mismatch { expected, actual } => {
+            warn!(
+                expected = %expected,
+                actual = %actual,
+                "[ClientVersion] packet catalog was built for a different client"
+            );
+            let _ = app.emit(crate::events::CLIENT_VERSION_MISMATCH, &compatibility);
+        }
+        _ => {
+            info!(
+                fingerprint = ?actual,
+                compatibility = ?compatibility,
+                "[ClientVersion] client identified"
+            );
+        }
+    }
+    actual
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fingerprint_uses_only_version_params() {
+        let a = fingerprint("https://res.17roco.qq.com/main.swf?123=&ver=20240601&skey=abc");
+        let b = fingerprint("https://res.17roco.qq.com/main.swf?987=&skey=xyz&VER=20240601");
+        let c = fingerprint("https://res.17roco.qq.com/main.swf?123=&ver=20240715&skey=abc");
+        assert!(a.is_some());
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+        assert_eq!(
+            fingerprint("https://res.17roco.qq.com/main.swf?123=&skey=abc"),
+            None
+        );
+
+        assert_eq!(check(a.as_deref(), None), Compatibility::Unknown);
+        assert_eq!(check(a.as_deref(), a.as_deref()), Compatibility::Verified);
+        assert!(matches!(
+            check(c.as_deref(), a.as_deref()),
+            Compatibility::Mismatch { .. }
+        ));
+    }
+
+    #[test]
+    fn mismatch_blocks_catalog_features() {
+        let catalog = fingerprint("https://res.17roco.qq.com/main.swf?ver=20240601");
+        let running = fingerprint("https://res.17roco.qq.com/main.swf?ver=20240715");
+        let compatibility = check(running.as_deref(), catalog.as_deref());
+        let reason = mismatch_reason(&compatibility).expect("mismatch reason");
+        assert!(reason.contains(catalog.as_deref().unwrap()));
+        assert!(reason.contains(running.as_deref().unwrap()));
+        assert_eq!(
+            crate::wpe::WpeError::IncompatibleClient(reason.clone()).to_string(),
+            format!("Incompatible game client: {reason}")
+        );
+
+        assert_eq!(
+            mismatch_reason(&check(catalog.as_deref(), catalog.as_deref())),
+            None
+        );
+        assert_eq!(mismatch_reason(&check(None, catalog.as_deref())), None);
+    }
+}
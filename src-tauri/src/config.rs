@@ -0,0 +1,620 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+
+use tauri::{AppHandle, Manager};
+use tracing::{info, warn};
+
+const CONFIG_FILE: &str = "config.json";
+
+/// 长时间运行后自动重启 projector（Flash 长时间运行会持续泄漏内存）
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct AutoRestartConfig {
+    pub enabled: bool,
+    /// 会话运行超过该时长（小时）后重启，0 表示不按时长重启
+    pub max_session_hours: f64,
+    /// 工作集超过该值（MB）后重启，0 表示不按内存重启
+    pub max_working_set_mb: u64,
+    /// 重启前倒计时提醒（秒）
+    pub warning_secs: u64,
+    /// 无操作多久（秒）视为可以安全重启
+    pub idle_secs: u64,
+}
+
+impl Default for AutoRestartConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_session_hours: 6.0,
+            max_working_set_mb: 1536,
+            warning_secs: 60,
+            idle_secs: 30,
+        }
+    }
+}
+
+/// projector 资源监视
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct MetricsConfig {
+    pub enabled: bool,
+    /// 工作集超过该值（MB）时发出告警事件，0 表示不告警
+    pub warn_working_set_mb: u64,
+}
+
+impl Default for MetricsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            warn_working_set_mb: 1200,
+        }
+    }
+}
+
+/// 从镜像下载的 projector 版本
+#[derive(Clone, Debug, Default, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct ProjectorManagerConfig {
+    /// 镜像地址（其下的 projectors.json 为版本清单），为空时不能下载
+    pub mirror: String,
+    /// 使用的已下载版本，为空时使用随程序分发的 projector.exe
+    pub version: String,
+    /// 额外信任的版本（版本 -> SHA-256），与程序内置的列表合并；不能覆盖内置的摘要
+    pub pinned_sha256: HashMap<String, String>,
+}
+
+/// 服务器断开连接后自动重连
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct ReconnectConfig {
+    pub enabled: bool,
+    /// 连续重试的最大次数
+    pub max_attempts: u32,
+    /// 第一次重连前的等待时间（秒），之后每次翻倍
+    pub initial_delay_secs: u64,
+    /// 等待时间上限（秒）
+    pub max_delay_secs: u64,
+}
+
+impl Default for ReconnectConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_attempts: 5,
+            initial_delay_secs: 5,
+            max_delay_secs: 60,
+        }
+    }
+}
+
+/// 查找 projector 窗口的条件
+///
+/// 默认取该进程的第一个顶层窗口；Flash 先创建启动画面等辅助窗口时，可按类名、标题、尺寸筛选。
+#[derive(Clone, Debug, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct WindowMatchConfig {
+    /// 窗口类名（不区分大小写），为空时不限制
+    pub class_name: String,
+    /// 窗口标题需包含的内容，为空时不限制
+    pub title_contains: String,
+    /// 窗口最小宽度（物理像素），0 表示不限制
+    pub min_width: u32,
+    /// 窗口最小高度（物理像素），0 表示不限制
+    pub min_height: u32,
+}
+
+impl WindowMatchConfig {
+    pub fn matches(&self, class_name: &str, title: &str, width: i32, height: i32) -> bool {
+        let class_wanted = self.class_name.trim();
+        (class_wanted.is_empty() || class_name.eq_ignore_ascii_case(class_wanted))
+            && (self.title_contains.is_empty() || title.contains(self.title_contains.as_str()))
+            && i64::from(width) >= i64::from(self.min_width)
+            && i64::from(height) >= i64::from(self.min_height)
+    }
+}
+
+/// 切换频道时改写的 flashVars 参数
+#[derive(Clone, Debug, Default, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct ChannelConfig {
+    /// flashVars 参数名 -> Global.xml 中 server 元素的属性名；为空时改写与属性同名的参数
+    pub params: HashMap<String, String>,
+}
+
+/// 启动播放器进程的工作目录、追加参数和环境变量（支持 {swf_url}、{profile} 占位符）
+#[derive(Clone, Debug, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct LaunchConfig {
+    /// 工作目录，为空时继承启动器
+    pub working_dir: String,
+    /// 追加的参数；包含 {swf_url} 时不再在末尾追加地址
+    pub args: Vec<String>,
+    /// 追加或覆盖的环境变量
+    pub env: HashMap<String, String>,
+}
+
+/// 登录过期自动重新登录
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct ReloginConfig {
+    pub enabled: bool,
+    /// 服务器文本封包中表示登录已失效的内容（包含任意一项即触发）
+    pub patterns: Vec<String>,
+    /// 隐藏登录页自动登录的最长等待时间，超时后显示登录页
+    pub timeout_secs: u64,
+}
+
+impl Default for ReloginConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            patterns: vec![
+                "重新登录".to_string(),
+                "登录超时".to_string(),
+                "登录已失效".to_string(),
+            ],
+            timeout_secs: 60,
+        }
+    }
+}
+
+/// 启动器相关配置
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct LauncherConfig {
+    /// 启动时在后台预读 projector 可执行文件，让正式启动命中磁盘缓存（不创建进程）
+    pub warm_pool: bool,
+    /// 随 Windows 登录自动启动（最小化）
+    pub autostart: bool,
+    pub auto_restart: AutoRestartConfig,
+    pub metrics: MetricsConfig,
+    pub reconnect: ReconnectConfig,
+    pub relogin: ReloginConfig,
+    pub projector_window: WindowMatchConfig,
+    pub projector_manager: ProjectorManagerConfig,
+    pub launch: LaunchConfig,
+    pub channel: ChannelConfig,
+    /// 独立窗口模式：projector 不嵌入主窗口，保留为单独的顶层窗口（便于 OBS 捕获、多显示器）
+    pub external_window: bool,
+    /// 启动时在后台预先解析登录页域名并请求一次登录页
+    pub prefetch_login: bool,
+    /// 退出时等待清理完成的最长时间（毫秒），超时后强制结束进程
+    pub exit_fallback_ms: u64,
+    /// 停止 projector 时先发送 WM_CLOSE 让其自行退出（写回存档），关闭时直接结束进程
+    pub graceful_close: bool,
+    /// 等待 projector 自行退出的时间（毫秒），超时后强制结束
+    pub close_grace_ms: u64,
+    /// 播放器后端：auto（优先 projector，找不到时用 Ruffle）、projector、ruffle
+    pub runtime: crate::projector::runtime::RuntimePreference,
+    /// Ruffle 可执行文件路径，为空时使用 resources/ruffle/ruffle.exe
+    pub ruffle_path: String,
+}
+
+impl Default for LauncherConfig {
+    fn default() -> Self {
+        Self {
+            warm_pool: false,
+            autostart: false,
+            auto_restart: AutoRestartConfig::default(),
+            metrics: MetricsConfig::default(),
+            reconnect: ReconnectConfig::default(),
+            relogin: ReloginConfig::default(),
+            projector_window: WindowMatchConfig::default(),
+            projector_manager: ProjectorManagerConfig::default(),
+            launch: LaunchConfig::default(),
+            channel: ChannelConfig::default(),
+            external_window: false,
+            prefetch_login: true,
+            exit_fallback_ms: 1000,
+            graceful_close: true,
+            close_grace_ms: 3000,
+            runtime: crate::projector::runtime::RuntimePreference::Auto,
+            ruffle_path: String::new(),
+        }
+    }
+}
+
+/// 界面相关配置
+#[derive(Clone, Debug, Default, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct UiConfig {
+    /// 各 webview 的缩放倍率（按 label 保存，未设置时使用内置默认值）
+    pub webview_zoom: HashMap<String, f64>,
+    /// 命名的窗口布局预设
+    pub layout_presets: HashMap<String, crate::layout::LayoutPreset>,
+}
+
+/// 封包触发的通知规则
+#[derive(Clone, Debug, Default, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct NotificationConfig {
+    pub rules: Vec<crate::notify::NotificationRule>,
+}
+
+/// 外发 webhook
+#[derive(Clone, Debug, Default, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct WebhookConfig {
+    pub endpoints: Vec<crate::webhook::WebhookEndpoint>,
+}
+
+fn default_disguise_title() -> String {
+    "无标题 - 记事本".to_string()
+}
+
+fn default_disguise_hotkey() -> String {
+    "Ctrl+Alt+H".to_string()
+}
+
+/// 隐私伪装
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct PrivacyConfig {
+    /// 伪装时使用的主窗口标题
+    pub disguise_title: String,
+    /// 切换伪装的全局快捷键（如 "Ctrl+Alt+H"），为空时不注册
+    pub hotkey: String,
+}
+
+impl Default for PrivacyConfig {
+    fn default() -> Self {
+        Self {
+            disguise_title: default_disguise_title(),
+            hotkey: default_disguise_hotkey(),
+        }
+    }
+}
+
+/// 叠加层计时器
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct TimersConfig {
+    /// 快速开始倒计时的全局快捷键，为空时不注册
+    pub countdown_hotkey: String,
+    /// 快捷键开始的倒计时时长（秒）
+    pub countdown_secs: u64,
+    /// 快捷键开始的倒计时名称
+    pub countdown_label: String,
+}
+
+impl Default for TimersConfig {
+    fn default() -> Self {
+        Self {
+            countdown_hotkey: "Ctrl+Alt+T".to_string(),
+            countdown_secs: 60,
+            countdown_label: "倒计时".to_string(),
+        }
+    }
+}
+
+/// 提示音
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct SoundConfig {
+    /// 静音全部提示音
+    pub muted: bool,
+    /// 提示音音量（0-100）
+    pub volume: u8,
+    /// 事件 -> 声音（内置名称或 .wav 文件路径），未配置的事件不播放
+    pub events: HashMap<crate::sound::SoundEvent, String>,
+}
+
+impl Default for SoundConfig {
+    fn default() -> Self {
+        Self {
+            muted: false,
+            volume: 80,
+            events: HashMap::new(),
+        }
+    }
+}
+
+/// 功能使用统计（只保存在本地，不会上传）
+#[derive(Clone, Debug, Default, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct UsageConfig {
+    pub enabled: bool,
+}
+
+/// 登录相关配置
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct LoginConfig {
+    /// 记住登录：加密保存 login3 凭据，重新登录时可以静默启动
+    pub remember: bool,
+    /// 保存的凭据超过该时长（小时）视为过期
+    pub max_age_hours: u64,
+    /// login3 响应无法解析时自动刷新登录页的次数
+    pub capture_retries: u32,
+    /// 切换到其他账号时清除登录页的 cookie，避免之后自动登录成旧账号
+    pub isolate_sessions: bool,
+    /// 允许无界面登录（login_headless 命令）
+    pub headless: bool,
+    /// 无界面登录依靠登录页自动登录时的超时（秒）
+    pub headless_timeout_secs: u64,
+    /// login3 抓取超时（秒），限制在 30-1800
+    pub capture_timeout_secs: u64,
+    /// login3 响应最多读取的字节数，限制在 64 KB-16 MB
+    pub max_response_bytes: usize,
+}
+
+impl Default for LoginConfig {
+    fn default() -> Self {
+        Self {
+            remember: false,
+            max_age_hours: 12,
+            capture_retries: 3,
+            isolate_sessions: true,
+            headless: false,
+            headless_timeout_secs: 90,
+            capture_timeout_secs: 180,
+            max_response_bytes: 1_500_000,
+        }
+    }
+}
+
+/// 登录页与游戏资源地址（测试服、镜像或其他地区的服务器）
+///
+/// 命令行 --login-url/--swf-base 和环境变量 ROCO_LOGIN_URL/ROCO_SWF_BASE 优先于这里的配置
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct EndpointsConfig {
+    /// 登录页地址
+    pub login_url: String,
+    /// main.swf 地址（不含查询参数），flashVars 拼接在其后
+    pub swf_base: String,
+}
+
+impl Default for EndpointsConfig {
+    fn default() -> Self {
+        Self {
+            login_url: crate::endpoints::DEFAULT_LOGIN_URL.to_string(),
+            swf_base: crate::endpoints::DEFAULT_SWF_BASE.to_string(),
+        }
+    }
+}
+
+/// 封包捕获配置
+#[derive(Clone, Debug, Default, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct CaptureConfig {
+    /// WinDivert 不可用（未提权或缺少驱动）时改用本地 TCP 代理捕获
+    /// （需要在 hosts 中把游戏服务器域名指向 127.0.0.1）
+    pub proxy_fallback: bool,
+    /// 真实游戏服务器地址（ip:port，不能写域名）
+    pub proxy_upstream: String,
+    /// 代理监听端口，0 表示与服务器端口相同
+    pub proxy_listen_port: u16,
+    /// 以拦截模式打开 WinDivert：报文经规则和 handler 处理后才放行，等长修改可以生效
+    /// （系统中所有 TCP 数据报文都要经过启动器，默认关闭，只以 SNIFF 模式旁路读取）
+    pub windivert_inline: bool,
+    /// 命令表核对过的客户端版本指纹（会话记录中的 client_version），为空时使用命令表内置的值
+    pub catalog_client: String,
+}
+
+/// 安全相关配置
+#[derive(Clone, Debug, Default, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct SafetyConfig {
+    /// 纯净模式：只保留启动器/嵌入功能，在子系统层面禁用一切会改动游戏的功能
+    /// （封包注入与修改、变速、内存写入、自动化发包）
+    pub clean_mode: bool,
+}
+
+/// 实验开关说明
+#[derive(Clone, Copy, Debug, serde::Serialize)]
+pub struct ExperimentInfo {
+    pub name: &'static str,
+    pub description: &'static str,
+    /// 配置中未设置时的取值
+    pub default: bool,
+}
+
+/// 已知的实验开关
+///
+/// 风险较高的子系统在入口处读取对应开关，可以先默认关闭发布，再按需打开而无需重新构建。
+pub const EXPERIMENTS: &[ExperimentInfo] = &[
+    ExperimentInfo {
+        name: "windivert_capture",
+        description: "使用 WinDivert 捕获真实游戏流量（关闭时 WPE 使用空后端）",
+        default: false,
+    },
+    ExperimentInfo {
+        name: "warm_pool",
+        description: "允许启动器预读 projector 可执行文件（还需打开 launcher.warm_pool）",
+        default: false,
+    },
+    ExperimentInfo {
+        name: "ruffle_runtime",
+        description: "允许使用 Ruffle 运行游戏（launcher.runtime 为 auto 时的回退，或指定 ruffle）",
+        default: false,
+    },
+];
+
+/// 实验开关及其当前取值
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct ExperimentState {
+    #[serde(flatten)]
+    pub info: ExperimentInfo,
+    pub enabled: bool,
+}
+
+/// 应用配置（持久化到 AppData/config.json）
+#[derive(Clone, Debug, Default, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct CoreConfig {
+    pub launcher: LauncherConfig,
+    pub ui: UiConfig,
+    pub notifications: NotificationConfig,
+    pub webhooks: WebhookConfig,
+    pub safety: SafetyConfig,
+    pub privacy: PrivacyConfig,
+    pub timers: TimersConfig,
+    pub sounds: SoundConfig,
+    pub usage: UsageConfig,
+    pub login: LoginConfig,
+    pub endpoints: EndpointsConfig,
+    pub capture: CaptureConfig,
+    /// 实验开关（名称 -> 是否启用），未设置的使用 EXPERIMENTS 中的默认值
+    pub experiments: HashMap<String, bool>,
+}
+
+static CONFIG: OnceLock<Mutex<CoreConfig>> = OnceLock::new();
+static CONFIG_PATH: OnceLock<PathBuf> = OnceLock::new();
+
+/// 加载配置（在 Tauri setup 中调用）
+///
+/// 文件不存在或解析失败时使用默认配置，不阻塞启动。
+pub fn init(app: &AppHandle) {
+    let path = match app.path().app_data_dir() {
+        // --profile 使用独立的配置档，互不影响
+        Ok(dir) => match &crate::cli::args().profile {
+            Some(profile) => dir.join("profiles").join(profile).join(CONFIG_FILE),
+            None => dir.join(CONFIG_FILE),
+        },
+        Err(e) => {
+            warn!(error = ?e, "failed to resolve app data dir, config will not persist");
+            let _ = CONFIG.set(Mutex::new(CoreConfig::default()));
+            return;
+        }
+    };
+
+    let config = match std::fs::read_to_string(&path) {
+        Ok(text) => match serde_json::from_str::<CoreConfig>(&text) {
+            Ok(config) => config,
+            Err(e) => {
+                warn!(error = %e, path = %path.display(), "invalid config file, using defaults");
+                CoreConfig::default()
+            }
+        },
+        Err(_) => CoreConfig::default(),
+    };
+
+    info!(
+        path = %path.display(),
+        clean_mode = config.safety.clean_mode,
+        safe_mode = crate::cli::safe_mode(),
+        "config loaded"
+    );
+    crate::wpe::set_clean_mode(effective_clean_mode(&config));
+    let _ = CONFIG_PATH.set(path);
+    let _ = CONFIG.set(Mutex::new(config));
+}
+
+/// 获取当前配置快照
+pub fn current() -> CoreConfig {
+    CONFIG
+        .get()
+        .map(|lock| lock.lock().expect("config lock").clone())
+        .unwrap_or_default()
+}
+
+/// --safe-mode 启动时本次运行按纯净模式处理，但不改写配置文件
+fn effective_clean_mode(config: &CoreConfig) -> bool {
+    config.safety.clean_mode || crate::cli::safe_mode()
+}
+
+/// 是否处于纯净模式
+pub fn clean_mode() -> bool {
+    effective_clean_mode(&current())
+}
+
+/// 会改动游戏的功能在执行前调用，纯净模式下返回错误
+pub fn ensure_game_modification_allowed(feature: &str) -> Result<(), String> {
+    if clean_mode() {
+        warn!(feature = feature, "blocked by clean mode");
+        return Err(format!("{feature} is disabled in clean mode."));
+    }
+    Ok(())
+}
+
+fn experiment_value(config: &CoreConfig, info: &ExperimentInfo) -> bool {
+    config
+        .experiments
+        .get(info.name)
+        .copied()
+        .unwrap_or(info.default)
+}
+
+/// 实验开关是否启用；未知名称和 --safe-mode 下视为关闭
+pub fn experiment_enabled(name: &str) -> bool {
+    if crate::cli::safe_mode() {
+        return false;
+    }
+    let config = current();
+    EXPERIMENTS
+        .iter()
+        .find(|info| info.name == name)
+        .is_some_and(|info| experiment_value(&config, info))
+}
+
+pub fn list_experiments() -> Vec<ExperimentState> {
+    let config = current();
+    EXPERIMENTS
+        .iter()
+        .map(|info| ExperimentState {
+            info: *info,
+            enabled: !crate::cli::safe_mode() && experiment_value(&config, info),
+        })
+        .collect()
+}
+
+/// 设置实验开关并落盘，未知名称返回错误
+pub fn set_experiment(name: &str, enabled: bool) -> Result<(), String> {
+    if !EXPERIMENTS.iter().any(|info| info.name == name) {
+        return Err(format!("Unknown experiment: {name}"));
+    }
+    update(|config| {
+        config.experiments.insert(name.to_string(), enabled);
+    })?;
+    info!(name = name, enabled = enabled, "experiment toggled");
+    Ok(())
+}
+
+/// 修改配置并立即落盘
+pub fn update(f: impl FnOnce(&mut CoreConfig)) -> Result<CoreConfig, String> {
+    let lock = CONFIG.get_or_init(|| Mutex::new(CoreConfig::default()));
+    let snapshot = {
+        let mut guard = lock.lock().expect("config lock");
+        f(&mut guard);
+        guard.clone()
+    };
+    crate::wpe::set_clean_mode(effective_clean_mode(&snapshot));
+    save(&snapshot)?;
+    Ok(snapshot)
+}
+
+fn save(config: &CoreConfig) -> Result<(), String> {
+    let Some(path) = CONFIG_PATH.get() else {
+        return Ok(());
+    };
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|_| "Failed to create config directory.".to_string())?;
+    }
+    let json = serde_json::to_string_pretty(config)
+        .map_err(|_| "Failed to serialize config.".to_string())?;
+    std::fs::write(path, json).map_err(|_| "Failed to write config file.".to_string())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn window_match_skips_helper_windows() {
+        let any = WindowMatchConfig::default();
+        assert!(any.matches("ShockwaveFlashSplash", "", 320, 200));
+
+        let game = WindowMatchConfig {
+            class_name: "ShockwaveFlash".to_string(),
+            min_width: 640,
+            ..Default::default()
+        };
+        assert!(!game.matches("ShockwaveFlashSplash", "", 800, 600));
+        assert!(!game.matches("shockwaveflash", "", 320, 200));
+        assert!(game.matches("shockwaveflash", "洛克王国", 1280, 720));
+    }
+}
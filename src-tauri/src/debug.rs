@@ -21,7 +21,7 @@ pub fn debug_log(level: &str, message: &str) {
 
     if let Some(app) = DEBUG_APP.get() {
         let _ = app.emit(
-            "debug_log",
+            crate::events::DEBUG_LOG,
             serde_json::json!({
               "level": level,
               "message": message
@@ -50,12 +50,14 @@ where
             }
         }
 
+        // 所有进入 Debug Console 与诊断包的日志都先脱敏
+        let message = crate::redact::redact_secrets(&message);
         let mut log_event = LogEvent::new(level, target, message);
 
         // 添加结构化字段（JSON 格式）
         if !visitor.fields.is_empty() {
             if let Ok(json) = serde_json::to_string(&visitor.fields) {
-                log_event.fields = Some(json);
+                log_event.fields = Some(crate::redact::redact_secrets(&json));
             }
         }
 
@@ -508,7 +508,7 @@ fn emit_batch(batch: Vec<LogEvent>) {
     let (tx, rx) = std::sync::mpsc::channel();
     let app_clone = app.clone();
     std::thread::spawn(move || {
-        let result = app_clone.emit("debug_log_batch", &batch);
+        let result = app_clone.emit(crate::events::DEBUG_LOG_BATCH, &batch);
         let _ = tx.send(result);
     });
 
@@ -581,7 +581,7 @@ fn emit_stats(stats: LogBusStats) {
     let (tx, rx) = std::sync::mpsc::channel();
     let app_clone = app.clone();
     std::thread::spawn(move || {
-        let result = app_clone.emit("debug_log_stats", &stats);
+        let result = app_clone.emit(crate::events::DEBUG_LOG_STATS, &stats);
         let _ = tx.send(result);
     });
 
@@ -0,0 +1,236 @@
+use std::path::{Path, PathBuf};
+
+use tauri::{AppHandle, Manager};
+use tracing::info;
+
+/// 显卡信息（同一块显卡接多个显示器时只记录一次）
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct GpuInfo {
+    pub name: String,
+    pub driver_version: Option<String>,
+}
+
+/// 显示器拓扑
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct MonitorInfo {
+    pub name: Option<String>,
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    pub scale_factor: f64,
+    pub primary: bool,
+}
+
+/// 环境信息（嵌入相关问题大多与显卡、DPI、WebView2 版本有关）
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct EnvironmentInfo {
+    pub app_version: String,
+    pub os: String,
+    pub arch: String,
+    pub gpus: Vec<GpuInfo>,
+    pub monitors: Vec<MonitorInfo>,
+    pub webview2_version: Option<String>,
+    pub projector_path: Option<String>,
+    pub projector_version: Option<String>,
+    /// Ruffle 路径（launcher.runtime 为 auto 或 ruffle 时使用）
+    pub ruffle_path: Option<String>,
+}
+
+pub fn collect(app: &AppHandle) -> EnvironmentInfo {
+    let primary = app.primary_monitor().ok().flatten();
+    let monitors = app
+        .available_monitors()
+        .unwrap_or_default()
+        .iter()
+        .map(|m| MonitorInfo {
+            name: m.name().cloned(),
+            x: m.position().x,
+            y: m.position().y,
+            width: m.size().width,
+            height: m.size().height,
+            scale_factor: m.scale_factor(),
+            primary: primary
+                .as_ref()
+                .is_some_and(|p| p.name() == m.name() && p.position() == m.position()),
+        })
+        .collect();
+
+    let projector_path = crate::projector::resolve_projector_path(app).ok();
+    let projector_version = projector_path.as_deref().and_then(file_version);
+    let ruffle_path = crate::projector::runtime::resolve_ruffle_path(app).ok();
+
+    EnvironmentInfo {
+        app_version: app.package_info().version.to_string(),
+        os: std::env::consts::OS.to_string(),
+        arch: std::env::consts::ARCH.to_string(),
+        gpus: gpus(),
+        monitors,
+        webview2_version: tauri::webview_version().ok(),
+        projector_path: projector_path.map(|p| p.display().to_string()),
+        projector_version,
+        ruffle_path: ruffle_path.map(|p| p.display().to_string()),
+    }
+}
+
+/// 导出诊断包（环境信息 + 最近日志 + 会话记录 + 上次退出记录），返回文件路径
+pub fn export_bundle(app: &AppHandle) -> Result<PathBuf, String> {
+    let bundle = serde_json::json!({
+        "environment": collect(app),
+        "log_stats": crate::debug_log_bus::get_stats(),
+        "recent_logs": crate::debug_log_bus::get_recent_logs(500),
+        "sessions": crate::session_history::recent(20),
+        "last_shutdown": crate::shutdown::previous(),
+        "login": crate::login_diagnostics::snapshot(),
+    });
+
+    let dir = app
+        .path()
+        .app_log_dir()
+        .map_err(|_| "Failed to resolve logs directory.".to_string())?;
+    std::fs::create_dir_all(&dir).map_err(|_| "Failed to create log directory.".to_string())?;
+    let path = dir.join("diagnostics.json");
+    let json = serde_json::to_string_pretty(&bundle)
+        .map_err(|_| "Failed to serialize diagnostics.".to_string())?;
+    // 日志在进入日志总线时已脱敏，这里对整个诊断包再过滤一遍
+    let json = crate::redact::redact_secrets(&json);
+    std::fs::write(&path, json).map_err(|_| "Failed to write diagnostics file.".to_string())?;
+    info!(path = %path.display(), "[Diagnostics] bundle exported");
+    Ok(path)
+}
+
+#[cfg(target_os = "windows")]
+fn wide(s: &str) -> Vec<u16> {
+    s.encode_utf16().chain(std::iter::once(0)).collect()
+}
+
+#[cfg(target_os = "windows")]
+fn gpus() -> Vec<GpuInfo> {
+    use windows::core::PCWSTR;
+    use windows::Win32::Graphics::Gdi::{EnumDisplayDevicesW, DISPLAY_DEVICEW};
+
+    let mut result: Vec<GpuInfo> = Vec::new();
+    let mut index = 0u32;
+    loop {
+        let mut device = DISPLAY_DEVICEW {
+            cb: std::mem::size_of::<DISPLAY_DEVICEW>() as u32,
+            ..Default::default()
+        };
+        if !unsafe { EnumDisplayDevicesW(PCWSTR::null(), index, &mut device, 0) }.as_bool() {
+            break;
+        }
+        index += 1;
+
+        let name = utf16_until_nul(&device.DeviceString);
+        if name.is_empty() || result.iter().any(|gpu| gpu.name == name) {
+            continue;
+        }
+        let driver_version = driver_version(&utf16_until_nul(&device.DeviceKey));
+        result.push(GpuInfo {
+            name,
+            driver_version,
+        });
+    }
+    result
+}
+
+/// DeviceKey 形如 \Registry\Machine\System\CurrentControlSet\Control\Video\{GUID}\0000
+#[cfg(target_os = "windows")]
+fn driver_version(device_key: &str) -> Option<String> {
+    use windows::core::PCWSTR;
+    use windows::Win32::Foundation::ERROR_SUCCESS;
+    use windows::Win32::System::Registry::{RegGetValueW, HKEY_LOCAL_MACHINE, RRF_RT_REG_SZ};
+
+    let prefix = "\\Registry\\Machine\\";
+    let subkey = device_key
+        .get(..prefix.len())
+        .filter(|head| head.eq_ignore_ascii_case(prefix))
+        .map(|_| &device_key[prefix.len()..])?;
+
+    let key = wide(subkey);
+    let name = wide("DriverVersion");
+    let mut buf = [0u16; 128];
+    let mut size = (buf.len() * 2) as u32;
+    let result = unsafe {
+        RegGetValueW(
+            HKEY_LOCAL_MACHINE,
+            PCWSTR(key.as_ptr()),
+            PCWSTR(name.as_ptr()),
+            RRF_RT_REG_SZ,
+            None,
+            Some(buf.as_mut_ptr() as *mut _),
+            Some(&mut size as *mut _),
+        )
+    };
+    if result != ERROR_SUCCESS {
+        return None;
+    }
+    Some(utf16_until_nul(&buf))
+}
+
+/// 读取可执行文件版本资源中的 FileVersion
+#[cfg(target_os = "windows")]
+fn file_version(path: &Path) -> Option<String> {
+    use windows::core::PCWSTR;
+    use windows::Win32::Storage::FileSystem::{
+        GetFileVersionInfoSizeW, GetFileVersionInfoW, VerQueryValueW, VS_FIXEDFILEINFO,
+    };
+
+    let path = wide(&path.to_string_lossy());
+    let size = unsafe { GetFileVersionInfoSizeW(PCWSTR(path.as_ptr()), None) };
+    if size == 0 {
+        return None;
+    }
+    let mut data = vec![0u8; size as usize];
+    unsafe {
+        GetFileVersionInfoW(
+            PCWSTR(path.as_ptr()),
+            None,
+            size,
+            data.as_mut_ptr() as *mut _,
+        )
+    }
+    .ok()?;
+
+    let root = wide("\\");
+    let mut info: *mut std::ffi::c_void = std::ptr::null_mut();
+    let mut len = 0u32;
+    let found = unsafe {
+        VerQueryValueW(
+            data.as_ptr() as *const _,
+            PCWSTR(root.as_ptr()),
+            &mut info,
+            &mut len,
+        )
+    };
+    if !found.as_bool()
+        || info.is_null()
+        || (len as usize) < std::mem::size_of::<VS_FIXEDFILEINFO>()
+    {
+        return None;
+    }
+    let info = unsafe { &*(info as *const VS_FIXEDFILEINFO) };
+    Some(format!(
+        "{}.{}.{}.{}",
+        info.dwFileVersionMS >> 16,
+        info.dwFileVersionMS & 0xFFFF,
+        info.dwFileVersionLS >> 16,
+        info.dwFileVersionLS & 0xFFFF
+    ))
+}
+
+#[cfg(target_os = "windows")]
+fn utf16_until_nul(buf: &[u16]) -> String {
+    let len = buf.iter().position(|&c| c == 0).unwrap_or(buf.len());
+    String::from_utf16_lossy(&buf[..len])
+}
+
+#[cfg(not(target_os = "windows"))]
+fn gpus() -> Vec<GpuInfo> {
+    Vec::new()
+}
+
+#[cfg(not(target_os = "windows"))]
+fn file_version(_path: &Path) -> Option<String> {
+    None
+}
@@ -0,0 +1,112 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
+
+use tauri::image::Image;
+use tauri::{AppHandle, Emitter, Manager};
+use tracing::{info, warn};
+
+// 隐私伪装：改主窗口标题、换任务栏图标、屏蔽提醒，配合全局快捷键一键切换。
+// 伪装状态只在本次运行内有效，不落盘。
+
+static ACTIVE: AtomicBool = AtomicBool::new(false);
+/// 伪装前的窗口标题，恢复时使用
+static ORIGINAL_TITLE: OnceLock<Mutex<Option<String>>> = OnceLock::new();
+
+const ICON_SIZE: u32 = 32;
+
+/// 伪装是否开启（开启时通知模块不弹提示、不响铃、不闪烁）
+pub fn is_active() -> bool {
+    ACTIVE.load(Ordering::Relaxed)
+}
+
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct DisguiseChanged {
+    pub active: bool,
+}
+
+/// 生成伪装用的图标：透明底上的白色文档和几行灰色文字
+fn disguise_icon() -> Image<'static> {
+    let mut rgba = vec![0u8; (ICON_SIZE * ICON_SIZE * 4) as usize];
+    for y in 0..ICON_SIZE {
+        for x in 0..ICON_SIZE {
+            let page = (6..26).contains(&x) && (2..30).contains(&y);
+            if !page {
+                continue;
+            }
+            let border = x == 6 || x == 25 || y == 2 || y == 29;
+            let line = (9..23).contains(&x) && (8..=24).contains(&y) && y % 4 == 0;
+            let color = if border {
+                [0x80, 0x80, 0x80]
+            } else if line {
+                [0x9A, 0xA4, 0xB0]
+            } else {
+                [0xFF, 0xFF, 0xFF]
+            };
+            let i = ((y * ICON_SIZE + x) * 4) as usize;
+            rgba[i..i + 3].copy_from_slice(&color);
+            rgba[i + 3] = 0xFF;
+        }
+    }
+    Image::new_owned(rgba, ICON_SIZE, ICON_SIZE)
+}
+
+/// 开启或关闭伪装
+pub fn set_active(app: &AppHandle, active: bool) -> Result<(), String> {
+    let window = app
+        .get_window("main")
+        .ok_or_else(|| "Main window missing.".to_string())?;
+    let original = ORIGINAL_TITLE.get_or_init(|| Mutex::new(None));
+
+    if active {
+        let mut original = original.lock().expect("disguise title lock");
+        if original.is_none() {
+            *original = window.title().ok();
+        }
+        let title = crate::config::current().privacy.disguise_title;
+        window
+            .set_title(&title)
+            .map_err(|_| "Failed to set window title.".to_string())?;
+        if let Err(e) = window.set_icon(disguise_icon()) {
+            warn!(error = %e, "[Disguise] failed to set icon");
+        }
+    } else {
+        let title = original
+            .lock()
+            .expect("disguise title lock")
+            .take()
+            .unwrap_or_else(|| app.package_info().name.clone());
+        window
+            .set_title(&title)
+            .map_err(|_| "Failed to restore window title.".to_string())?;
+        if let Some(icon) = app.default_window_icon().cloned() {
+            if let Err(e) = window.set_icon(icon) {
+                warn!(error = %e, "[Disguise] failed to restore icon");
+            }
+        }
+    }
+
+    ACTIVE.store(active, Ordering::Relaxed);
+    crate::pacing::refresh();
+    info!(active = active, "[Disguise] toggled");
+    let _ = app.emit(crate::events::DISGUISE_CHANGED, DisguiseChanged { active });
+    Ok(())
+}
+
+pub fn toggle(app: &AppHandle) -> Result<(), String> {
+    set_active(app, !is_active())
+}
+
+/// 按配置（重新）注册伪装快捷键，配置为空时取消注册
+pub fn apply_hotkey(app: &AppHandle) {
+    let text = crate::config::current().privacy.hotkey;
+    crate::hotkey::bind(
+        app,
+        "disguise",
+        &text,
+        std::sync::Arc::new(|app: &AppHandle| {
+            if let Err(e) = toggle(app) {
+                warn!(error = %e, "[Disguise] toggle failed");
+            }
+        }),
+    );
+}
@@ -1,5 +1,6 @@
 #[cfg(target_os = "windows")]
 mod win {
+    use crate::config::WindowMatchConfig;
     use std::time::{Duration, Instant};
     use windows::core::BOOL;
     use windows::Win32::Foundation::RECT;
@@ -8,17 +9,17 @@ mod win {
         SetProcessDpiAwarenessContext, DPI_AWARENESS_CONTEXT_PER_MONITOR_AWARE_V2,
     };
     use windows::Win32::UI::WindowsAndMessaging::{
-        EnumWindows, GetClientRect, GetWindow, GetWindowLongPtrW, GetWindowThreadProcessId,
-        IsWindowVisible, MoveWindow, SetParent, SetWindowLongPtrW, SetWindowPos, ShowWindow,
-        GWL_STYLE, GW_OWNER, HWND_TOP, SWP_FRAMECHANGED, SWP_NOMOVE, SWP_NOSIZE, SWP_NOZORDER,
-        SWP_SHOWWINDOW, SW_HIDE, WS_CHILD, WS_MAXIMIZEBOX, WS_OVERLAPPEDWINDOW, WS_POPUP,
-        WS_SIZEBOX, WS_VISIBLE,
+        EnumWindows, GetClassNameW, GetClientRect, GetWindow, GetWindowLongPtrW, GetWindowRect,
+        GetWindowTextW, GetWindowThreadProcessId, MoveWindow, SetParent, SetWindowLongPtrW,
+        SetWindowPos, ShowWindow, GWL_STYLE, GW_OWNER, HWND_TOP, SWP_FRAMECHANGED, SWP_NOMOVE,
+        SWP_NOSIZE, SWP_NOZORDER, SWP_SHOWWINDOW, SW_HIDE, WS_CHILD, WS_MAXIMIZEBOX,
+        WS_OVERLAPPEDWINDOW, WS_POPUP, WS_SIZEBOX, WS_VISIBLE,
     };
 
-    #[derive(Default)]
-    struct FindData {
+    struct FindData<'a> {
         pid: u32,
         hwnd: HWND,
+        criteria: &'a WindowMatchConfig,
     }
 
     unsafe extern "system" fn enum_windows_proc(hwnd: HWND, lparam: LPARAM) -> BOOL {
@@ -29,23 +30,35 @@ mod win {
             return BOOL(1);
         }
         let owner = GetWindow(hwnd, GW_OWNER).unwrap_or(HWND(std::ptr::null_mut()));
-        if owner.0.is_null() && IsWindowVisible(hwnd).as_bool() {
-            data.hwnd = hwnd;
-            return BOOL(0);
+        if !owner.0.is_null() {
+            return BOOL(1);
         }
-        if owner.0.is_null() {
-            data.hwnd = hwnd;
-            return BOOL(0);
+        let mut rect = RECT::default();
+        let _ = GetWindowRect(hwnd, &mut rect);
+        if !data.criteria.matches(
+            &window_class(hwnd),
+            &window_title(hwnd),
+            rect.right - rect.left,
+            rect.bottom - rect.top,
+        ) {
+            return BOOL(1);
         }
-        BOOL(1)
+        data.hwnd = hwnd;
+        BOOL(0)
     }
 
-    pub fn find_window_by_pid(pid: u32, timeout_ms: u64) -> Result<HWND, String> {
+    /// 查找进程的顶层窗口：取第一个没有 owner 且符合 criteria 的窗口
+    pub fn find_window_by_pid(
+        pid: u32,
+        timeout_ms: u64,
+        criteria: &WindowMatchConfig,
+    ) -> Result<HWND, String> {
         let start = Instant::now();
         loop {
             let mut data = FindData {
                 pid,
                 hwnd: HWND(std::ptr::null_mut()),
+                criteria,
             };
             unsafe {
                 let _ = EnumWindows(
@@ -161,6 +174,69 @@ mod win {
             let _ = ShowWindow(child_hwnd, SW_HIDE);
         }
     }
+
+    /// 闪烁任务栏按钮，直到窗口回到前台
+    pub fn flash_window(hwnd: HWND) {
+        use windows::Win32::UI::WindowsAndMessaging::{
+            FlashWindowEx, FLASHWINFO, FLASHW_ALL, FLASHW_TIMERNOFG,
+        };
+        let info = FLASHWINFO {
+            cbSize: std::mem::size_of::<FLASHWINFO>() as u32,
+            hwnd,
+            dwFlags: FLASHW_ALL | FLASHW_TIMERNOFG,
+            uCount: 0,
+            dwTimeout: 0,
+        };
+        unsafe {
+            let _ = FlashWindowEx(&info);
+        }
+    }
+
+    pub fn play_alert_sound() {
+        use windows::Win32::UI::WindowsAndMessaging::{MessageBeep, MB_ICONASTERISK};
+        unsafe {
+            let _ = MessageBeep(MB_ICONASTERISK);
+        }
+    }
+
+    /// 异步播放声音；alias 为 true 时 sound 是系统声音别名（如 "SystemAsterisk"），否则是 .wav 文件路径
+    pub fn play_sound(sound: &str, alias: bool) -> bool {
+        use windows::core::PCWSTR;
+        use windows::Win32::Media::Audio::{
+            PlaySoundW, SND_ALIAS, SND_ASYNC, SND_FILENAME, SND_NODEFAULT,
+        };
+        let wide: Vec<u16> = sound.encode_utf16().chain(std::iter::once(0)).collect();
+        let source = if alias { SND_ALIAS } else { SND_FILENAME };
+        unsafe {
+            PlaySoundW(
+                PCWSTR(wide.as_ptr()),
+                None,
+                source | SND_ASYNC | SND_NODEFAULT,
+            )
+            .as_bool()
+        }
+    }
+
+    /// 设置本进程的波形输出音量（0-100，左右声道相同）
+    pub fn set_sound_volume(volume: u8) {
+        use windows::Win32::Media::Audio::waveOutSetVolume;
+        let level = u32::from(volume.min(100)) * 0xFFFF / 100;
+        unsafe {
+            let _ = waveOutSetVolume(None, level | (level << 16));
+        }
+    }
+
+    pub fn window_title(hwnd: HWND) -> String {
+        let mut buf = [0u16; 512];
+        let len = unsafe { GetWindowTextW(hwnd, &mut buf) };
+        String::from_utf16_lossy(&buf[..len.max(0) as usize])
+    }
+
+    pub fn window_class(hwnd: HWND) -> String {
+        let mut buf = [0u16; 256];
+        let len = unsafe { GetClassNameW(hwnd, &mut buf) };
+        String::from_utf16_lossy(&buf[..len.max(0) as usize])
+    }
 }
 
 #[cfg(target_os = "windows")]
@@ -168,9 +244,14 @@ pub use win::*;
 
 #[cfg(not(target_os = "windows"))]
 mod non_win {
+    use crate::config::WindowMatchConfig;
     use windows::Win32::Foundation::HWND;
 
-    pub fn find_window_by_pid(_pid: u32, _timeout_ms: u64) -> Result<HWND, String> {
+    pub fn find_window_by_pid(
+        _pid: u32,
+        _timeout_ms: u64,
+        _criteria: &WindowMatchConfig,
+    ) -> Result<HWND, String> {
         Err("仅支持 Windows 平台。".to_string())
     }
 
@@ -195,6 +276,24 @@ mod non_win {
     pub fn bring_to_top(_child_hwnd: HWND) {}
 
     pub fn hide_window(_child_hwnd: HWND) {}
+
+    pub fn flash_window(_hwnd: HWND) {}
+
+    pub fn play_alert_sound() {}
+
+    pub fn play_sound(_sound: &str, _alias: bool) -> bool {
+        false
+    }
+
+    pub fn set_sound_volume(_volume: u8) {}
+
+    pub fn window_title(_hwnd: HWND) -> String {
+        String::new()
+    }
+
+    pub fn window_class(_hwnd: HWND) -> String {
+        String::new()
+    }
 }
 
 #[cfg(not(target_os = "windows"))]
@@ -0,0 +1,119 @@
+use tracing::warn;
+
+// 登录页与游戏资源地址
+//
+// 默认使用官方服务器，可以改为测试服、镜像或其他地区的地址而无需重新编译。
+// 取值优先级：命令行（--login-url/--swf-base）> 环境变量（ROCO_LOGIN_URL/ROCO_SWF_BASE）
+// > 配置 endpoints.login_url/swf_base > 默认值。无效的地址（不是 http(s) URL）记录警告后跳过。
+
+pub const DEFAULT_LOGIN_URL: &str = "https://17roco.qq.com/login.html";
+pub const DEFAULT_SWF_BASE: &str = "https://res.17roco.qq.com/main.swf";
+
+const LOGIN_URL_ENV: &str = "ROCO_LOGIN_URL";
+const SWF_BASE_ENV: &str = "ROCO_SWF_BASE";
+
+/// 是否为可用的 http(s) 地址
+pub fn valid_url(text: &str) -> bool {
+    url::Url::parse(text)
+        .is_ok_and(|url| matches!(url.scheme(), "http" | "https") && url.host_str().is_some())
+}
+
+/// 按优先级选出第一个有效的地址，都无效时使用默认值
+fn resolve(name: &str, candidates: [Option<String>; 3], default: &str) -> String {
+    for candidate in candidates.into_iter().flatten() {
+        let candidate = candidate.trim();
+        if candidate.is_empty() {
+            continue;
+        }
+        if valid_url(candidate) {
+            return candidate.to_string();
+        }
+        warn!(endpoint = name, value = %candidate, "[Endpoints] ignoring invalid URL");
+    }
+    default.to_string()
+}
+
+/// 当前使用的登录页地址
+pub fn login_url() -> String {
+    resolve(
+        "login_url",
+        [
+            crate::cli::args().login_url.clone(),
+            std::env::var(LOGIN_URL_ENV).ok(),
+            Some(crate::config::current().endpoints.login_url),
+        ],
+        DEFAULT_LOGIN_URL,
+    )
+}
+
+/// 当前使用的 main.swf 地址（不含查询参数）
+pub fn swf_base() -> String {
+    resolve(
+        "swf_base",
+        [
+            crate::cli::args().swf_base.clone(),
+            std::env::var(SWF_BASE_ENV).ok(),
+            Some(crate::config::current().endpoints.swf_base),
+        ],
+        DEFAULT_SWF_BASE,
+    )
+}
+
+/// 拼接最终的 main.swf 地址；swf_base 自带查询参数时追加在其后
+pub fn swf_url(base: &str, nonce: &str, flash_vars: &str) -> String {
+    let separator = if base.contains('?') { '&' } else { '?' };
+    format!("{base}{separator}{nonce}=&{flash_vars}")
+}
+
+/// 登录页与游戏资源所在的主机和端口（去重）
+pub fn hosts() -> Vec<(String, u16)> {
+    let mut hosts = Vec::new();
+    for url in [login_url(), swf_base()] {
+        let Ok(url) = url::Url::parse(&url) else {
+            continue;
+        };
+        if let (Some(host), Some(port)) = (url.host_str(), url.port_or_known_default()) {
+            let host = (host.to_string(), port);
+            if !hosts.contains(&host) {
+                hosts.push(host);
+            }
+        }
+    }
+    hosts
+}
+
+/// 导航地址是否为登录页（只比较路径，登录页可能带有不同的查询参数）
+pub fn is_login_page(url: &url::Url) -> bool {
+    url::Url::parse(&login_url()).is_ok_and(|login| url.path() == login.path())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolves_first_valid_candidate_and_builds_swf_url() {
+        let pick = |candidates| resolve("test", candidates, DEFAULT_LOGIN_URL);
+        assert_eq!(
+            pick([
+                None,
+                Some("https://mirror.example.com/login.html".to_string()),
+                Some("https://config.example.com/login.html".to_string()),
+            ]),
+            "https://mirror.example.com/login.html"
+        );
+        assert_eq!(
+            pick([Some("ftp://x".to_string()), Some(" ".to_string()), None]),
+            DEFAULT_LOGIN_URL
+        );
+
+        assert_eq!(
+            swf_url(DEFAULT_SWF_BASE, "0.5", "config=x"),
+            "https://res.17roco.qq.com/main.swf?0.5=&config=x"
+        );
+        assert_eq!(
+            swf_url("http://127.0.0.1:8080/main.swf?region=tw", "0.5", "a=1"),
+            "http://127.0.0.1:8080/main.swf?region=tw&0.5=&a=1"
+        );
+    }
+}
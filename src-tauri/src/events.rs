@@ -0,0 +1,111 @@
+// 前端事件约定
+//
+// 后端发给前端（toolbar、debug 窗口）的全部事件名集中在这里，各条注释写明载荷类型。
+// 事件改名、删除或载荷有不兼容的改动时递增 CONTRACT_VERSION；
+// 前端可以用 get_event_contract 检查版本，不再依赖散落在各模块中的字符串。
+
+/// 事件约定版本
+pub const CONTRACT_VERSION: u32 = 1;
+
+/// 状态栏状态（state::StatusPayload）
+pub const STATUS_CHANGED: &str = "status_changed";
+/// 状态详情（state::StatusDetail）
+pub const STATUS_DETAIL: &str = "status_detail";
+/// projector 窗口标题变化（launcher::TitleChanged）
+pub const PROJECTOR_TITLE_CHANGED: &str = "projector_title_changed";
+/// 自动重启倒计时（projector::auto_restart::RestartCountdown）
+pub const AUTO_RESTART_COUNTDOWN: &str = "auto_restart_countdown";
+/// 自动重启被取消（projector pid）
+pub const AUTO_RESTART_CANCELLED: &str = "auto_restart_cancelled";
+/// projector 工作集超过告警阈值（projector::metrics::MemoryWarning）
+pub const PROJECTOR_MEMORY_WARNING: &str = "projector_memory_warning";
+/// projector 下载进度（projector_manager::InstallProgress）
+pub const PROJECTOR_INSTALL_PROGRESS: &str = "projector_install_progress";
+/// 断线重连倒计时（projector::reconnect::ReconnectCountdown）
+pub const RECONNECT_COUNTDOWN: &str = "reconnect_countdown";
+/// 断线重连被取消（第几次重试）
+pub const RECONNECT_CANCELLED: &str = "reconnect_cancelled";
+/// 登录过期自动重新登录的进度（projector::relogin::ReloginProgress）
+pub const SESSION_RELOGIN: &str = "session_relogin";
+/// 客户端版本与命令表不一致（client_version::Compatibility）
+pub const CLIENT_VERSION_MISMATCH: &str = "client_version_mismatch";
+/// 抓取到新的 Global.xml（game_config::GameConfig）
+pub const GAME_CONFIG_UPDATED: &str = "game_config_updated";
+/// 捕获后端状态变化（wpe::BackendStatus）
+pub const WPE_BACKEND_STATUS: &str = "wpe_backend_status";
+/// 录制重放结束（wpe::replay::ReplaySummary）
+pub const WPE_REPLAY_FINISHED: &str = "wpe_replay_finished";
+/// 一批新捕获的封包（Vec<packet_stream::PacketEvent>）
+pub const PACKET_CAPTURED: &str = "packet_captured";
+/// 封包流统计（packet_stream::PacketStreamStats）
+pub const PACKET_STREAM_STATS: &str = "packet_stream_stats";
+/// 通知规则触发（notify::NotificationEvent）
+pub const NOTIFICATION: &str = "notification";
+/// 隐私伪装切换（disguise::DisguiseChanged）
+pub const DISGUISE_CHANGED: &str = "disguise_changed";
+/// 计时器每秒刷新（timers::TimersSnapshot）
+pub const TIMERS_TICK: &str = "timers_tick";
+/// 倒计时结束（timers::Countdown）
+pub const COUNTDOWN_FINISHED: &str = "countdown_finished";
+/// 单条调试日志（{ level, message }）
+pub const DEBUG_LOG: &str = "debug_log";
+/// 一批调试日志（Vec<debug_log_bus::LogEvent>）
+pub const DEBUG_LOG_BATCH: &str = "debug_log_batch";
+/// 调试日志总线统计（debug_log_bus::LogBusStats）
+pub const DEBUG_LOG_STATS: &str = "debug_log_stats";
+
+/// 全部事件名
+pub const ALL: &[&str] = &[
+    STATUS_CHANGED,
+    STATUS_DETAIL,
+    PROJECTOR_TITLE_CHANGED,
+    AUTO_RESTART_COUNTDOWN,
+    AUTO_RESTART_CANCELLED,
+    PROJECTOR_MEMORY_WARNING,
+    PROJECTOR_INSTALL_PROGRESS,
+    RECONNECT_COUNTDOWN,
+    RECONNECT_CANCELLED,
+    SESSION_RELOGIN,
+    CLIENT_VERSION_MISMATCH,
+    GAME_CONFIG_UPDATED,
+    WPE_BACKEND_STATUS,
+    WPE_REPLAY_FINISHED,
+    PACKET_CAPTURED,
+    PACKET_STREAM_STATS,
+    NOTIFICATION,
+    DISGUISE_CHANGED,
+    TIMERS_TICK,
+    COUNTDOWN_FINISHED,
+    DEBUG_LOG,
+    DEBUG_LOG_BATCH,
+    DEBUG_LOG_STATS,
+];
+
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct EventContract {
+    pub version: u32,
+    pub events: &'static [&'static str],
+}
+
+pub fn contract() -> EventContract {
+    EventContract {
+        version: CONTRACT_VERSION,
+        events: ALL,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn event_names_are_unique_snake_case() {
+        let mut names = ALL.to_vec();
+        names.sort();
+        names.dedup();
+        assert_eq!(names.len(), ALL.len());
+        assert!(ALL
+            .iter()
+            .all(|name| name.chars().all(|c| c.is_ascii_lowercase() || c == '_')));
+    }
+}
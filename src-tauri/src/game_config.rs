@@ -0,0 +1,285 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use tauri::{AppHandle, Emitter, Manager};
+use tracing::{info, warn};
+
+// 游戏配置（Global.xml）
+//
+// 登录过程中会加载 flashVars 里 config= 指向的 Global.xml。通过 webview_capture 抓取这个响应，
+// 原文缓存到 AppData/cache/Global.xml，解析出服务器列表和版本号，启动前就能在界面上显示；
+// 启动时先读取上次的缓存，抓取到新的响应后替换并发出 game_config_updated 事件。
+// Global.xml 没有公开的格式说明，这里只做宽松的解析：名称为 server 的元素视为服务器，
+// 名为 version/ver（或以 version 结尾）的属性视为版本号，其他内容保留在原文缓存中。
+
+const CACHE_DIR: &str = "cache";
+const CACHE_FILE: &str = "Global.xml";
+const MAX_CONFIG_BYTES: usize = 512 * 1024;
+
+#[derive(Clone, Debug, Default, PartialEq, serde::Serialize)]
+pub struct ServerEntry {
+    pub name: Option<String>,
+    pub host: Option<String>,
+    pub port: Option<u16>,
+    /// 元素上的全部属性
+    pub attributes: BTreeMap<String, String>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, serde::Serialize)]
+pub struct ParsedConfig {
+    pub servers: Vec<ServerEntry>,
+    /// 版本号：`元素名` 或 `元素名.属性名` -> 值
+    pub versions: BTreeMap<String, String>,
+}
+
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct GameConfig {
+    #[serde(flatten)]
+    pub parsed: ParsedConfig,
+    /// 抓取（或缓存文件写入）时间，Unix 毫秒
+    pub captured_at: u64,
+    /// 来自上次运行的缓存，本次登录尚未抓取到
+    pub from_cache: bool,
+    pub size_bytes: usize,
+}
+
+static CACHE_PATH: OnceLock<PathBuf> = OnceLock::new();
+static CURRENT: Mutex<Option<GameConfig>> = Mutex::new(None);
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// 读取缓存并注册抓取（在 Tauri setup 中、附加 webview_capture 之前调用）
+pub fn init(app: &AppHandle) {
+    match app.path().app_data_dir() {
+        Ok(dir) => {
+            let path = dir.join(CACHE_DIR).join(CACHE_FILE);
+            if let Ok(xml) = std::fs::read_to_string(&path) {
+                let captured_at = std::fs::metadata(&path)
+                    .and_then(|meta| meta.modified())
+                    .ok()
+                    .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+                    .map(|d| d.as_millis() as u64)
+                    .unwrap_or(0);
+                let config = GameConfig {
+                    parsed: parse(&xml),
+                    captured_at,
+                    from_cache: true,
+                    size_bytes: xml.len(),
+                };
+                info!(
+                    servers = config.parsed.servers.len(),
+                    versions = config.parsed.versions.len(),
+                    "[GameConfig] loaded cached Global.xml"
+                );
+                *CURRENT.lock().expect("game config lock") = Some(config);
+            }
+            let _ = CACHE_PATH.set(path);
+        }
+        Err(_) => {
+            warn!("[GameConfig] failed to resolve app data dir, Global.xml will not be cached")
+        }
+    }
+
+    crate::webview_capture::register(
+        "global_xml",
+        |url| url::Url::parse(url).is_ok_and(|url| url.path().ends_with("/global.xml")),
+        || MAX_CONFIG_BYTES,
+        |app, _url, body| match body {
+            Ok(xml) => update(app, &xml),
+            Err(e) => warn!(error = %e, "[GameConfig] failed to read Global.xml"),
+        },
+    );
+}
+
+/// 当前的游戏配置；本次登录尚未抓取且没有缓存时为 None
+pub fn current() -> Option<GameConfig> {
+    CURRENT.lock().expect("game config lock").clone()
+}
+
+fn update(app: &AppHandle, xml: &str) {
+    let parsed = parse(xml);
+    if parsed.servers.is_empty() && parsed.versions.is_empty() {
+        warn!(
+            size_bytes = xml.len(),
+            "[GameConfig] Global.xml contains no servers or versions, keeping previous config"
+        );
+        return;
+    }
+    if let Some(path) = CACHE_PATH.get() {
+        if let Some(parent) = path.parent() {
+            let _ = std::fs::create_dir_all(parent);
+        }
+        if let Err(e) = std::fs::write(path, xml) {
+            warn!(error = %e, "[GameConfig] failed to cache Global.xml");
+        }
+    }
+    let config = GameConfig {
+        parsed,
+        captured_at: now_ms(),
+        from_cache: false,
+        size_bytes: xml.len(),
+    };
+    info!(
+        servers = config.parsed.servers.len(),
+        versions = ?config.parsed.versions,
+        "[GameConfig] Global.xml captured"
+    );
+    *CURRENT.lock().expect("game config lock") = Some(config.clone());
+    let _ = app.emit(crate::events::GAME_CONFIG_UPDATED, config);
+}
+
+/// 从 Global.xml 中取出服务器列表和版本号
+pub fn parse(xml: &str) -> ParsedConfig {
+    let mut parsed = ParsedConfig::default();
+    for (tag, attributes) in elements(xml) {
+        let tag_lc = tag.to_ascii_lowercase();
+        for (key, value) in &attributes {
+            let key_lc = key.to_ascii_lowercase();
+            if key_lc == "version" || key_lc == "ver" {
+                parsed.versions.insert(tag.clone(), value.clone());
+            } else if key_lc.ends_with("version") {
+                parsed
+                    .versions
+                    .insert(format!("{tag}.{key}"), value.clone());
+            }
+        }
+        if tag_lc == "server" {
+            let find = |names: &[&str]| {
+                attributes
+                    .iter()
+                    .find(|(key, _)| names.contains(&key.to_ascii_lowercase().as_str()))
+                    .map(|(_, value)| value.clone())
+            };
+            parsed.servers.push(ServerEntry {
+                name: find(&["name", "title"]),
+                host: find(&["ip", "host", "addr", "address"]),
+                port: find(&["port"]).and_then(|port| port.parse().ok()),
+                attributes: attributes.into_iter().collect(),
+            });
+        }
+    }
+    parsed
+}
+
+/// 依次取出开始标签的名称和属性（跳过结束标签、注释、声明和 CDATA）
+fn elements(xml: &str) -> Vec<(String, Vec<(String, String)>)> {
+    let mut out = Vec::new();
+    let mut rest = xml;
+    while let Some(start) = rest.find('<') {
+        rest = &rest[start + 1..];
+        if rest.starts_with("!--") {
+            rest = rest.find("-->").map_or("", |end| &rest[end + 3..]);
+            continue;
+        }
+        if rest.starts_with("![CDATA[") {
+            rest = rest.find("]]>").map_or("", |end| &rest[end + 3..]);
+            continue;
+        }
+        let Some(end) = tag_end(rest) else {
+            break;
+        };
+        let body = &rest[..end];
+        rest = &rest[end + 1..];
+        if body.starts_with(['/', '?', '!']) {
+            continue;
+        }
+        let body = body.trim_end_matches('/');
+        let name_end = body.find(|c: char| c.is_whitespace()).unwrap_or(body.len());
+        let name = &body[..name_end];
+        if name.is_empty() {
+            continue;
+        }
+        out.push((name.to_string(), attributes(&body[name_end..])));
+    }
+    out
+}
+
+/// 标签结束的 `>`，忽略引号内的
+fn tag_end(text: &str) -> Option<usize> {
+    let mut quote = None;
+    for (i, ch) in text.char_indices() {
+        match (quote, ch) {
+            (None, '"' | '\'') => quote = Some(ch),
+            (Some(q), c) if c == q => quote = None,
+            (None, '>') => return Some(i),
+            _ => {}
+        }
+    }
+    None
+}
+
+fn attributes(text: &str) -> Vec<(String, String)> {
+    let mut out = Vec::new();
+    let mut rest = text;
+    while let Some(eq) = rest.find('=') {
+        let key = rest[..eq].trim();
+        let after = rest[eq + 1..].trim_start();
+        let Some(quote) = after.chars().next().filter(|c| *c == '"' || *c == '\'') else {
+            break;
+        };
+        let Some(len) = after[1..].find(quote) else {
+            break;
+        };
+        if !key.is_empty() {
+            out.push((key.to_string(), unescape(&after[1..1 + len])));
+        }
+        rest = &after[len + 2..];
+    }
+    out
+}
+
+fn unescape(value: &str) -> String {
+    value
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_servers_and_versions() {
+        let xml = r#"<?xml version="1.0" encoding="utf-8"?>
+<!-- <server name="注释" /> -->
+<config version="20240601">
+  <client mainVersion='3.1' />
+  <servers>
+    <server id="1" name="电信一区" ip="10.0.0.1" port="443"/>
+    <server id="2" name="A &amp; B" host="roco.example.com" port="x" />
+  </servers>
+</config>"#;
+        let parsed = parse(xml);
+        assert_eq!(
+            parsed.versions.get("config").map(String::as_str),
+            Some("20240601")
+        );
+        assert_eq!(
+            parsed
+                .versions
+                .get("client.mainVersion")
+                .map(String::as_str),
+            Some("3.1")
+        );
+        assert_eq!(parsed.servers.len(), 2);
+        assert_eq!(parsed.servers[0].name.as_deref(), Some("电信一区"));
+        assert_eq!(parsed.servers[0].host.as_deref(), Some("10.0.0.1"));
+        assert_eq!(parsed.servers[0].port, Some(443));
+        assert_eq!(parsed.servers[1].name.as_deref(), Some("A & B"));
+        assert_eq!(parsed.servers[1].port, None);
+        assert_eq!(
+            parsed.servers[1].attributes.get("id").map(String::as_str),
+            Some("2")
+        );
+    }
+}
@@ -0,0 +1,175 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use tauri::{AppHandle, Manager};
+use tracing::{info, warn};
+
+use crate::state::{emit_status, AppState, AppStatus};
+
+// 无界面登录
+//
+// 供脚本批量登录多个账号使用：不显示登录页，按顺序尝试
+//   1. 账号档案中该 QQ 号保存的凭据（accounts，DPAPI 加密）
+//   2. 记住登录保存的凭据（saved_login，未指定账号或账号一致时）
+//   3. 在隐藏的登录 webview 中打开登录页，依靠 QQ 登录态自动完成登录并抓取 login3
+// 只读取加密保存的登录凭据，不保存也不填写 QQ 密码。第 3 步超过 login.headless_timeout_secs
+// 仍未进入运行状态时进入 Error，登录页保持隐藏，需要交互登录一次刷新登录态。
+// 第 3 步的登录态可能属于另一个 QQ 号：指定了账号时，进入运行状态后核对账号，不一致则结束会话并进入 Error。
+// 需要开启 login.headless，或以 --headless 启动（本次运行视为开启）。
+
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HeadlessMethod {
+    /// 账号档案中保存的凭据
+    SavedAccount,
+    /// 记住登录保存的凭据
+    SavedLogin,
+    /// 隐藏的登录页（QQ 登录态）
+    WebSession,
+}
+
+static IN_PROGRESS: AtomicBool = AtomicBool::new(false);
+
+pub fn enabled() -> bool {
+    crate::config::current().login.headless || crate::cli::args().headless
+}
+
+/// 开始无界面登录，返回采用的方式；`qq_num` 为 None 时使用 --account
+pub fn start(app: &AppHandle, qq_num: Option<u64>) -> Result<HeadlessMethod, String> {
+    if !enabled() {
+        return Err("Headless login is disabled (login.headless).".to_string());
+    }
+    let state = app.state::<Mutex<AppState>>();
+    if state.lock().expect("state lock").projector.is_some() {
+        return Err("Projector is already running.".to_string());
+    }
+    let account = qq_num.or(crate::cli::args().account);
+    crate::usage::record("headless_login");
+
+    if let Some(qq_num) = account {
+        match crate::accounts::credentials(qq_num) {
+            Ok(flash_vars) => {
+                crate::saved_login::launch_with(app, &flash_vars, Some(qq_num))?;
+                info!(qq_num = qq_num, "[Headless] launching with saved account");
+                return Ok(HeadlessMethod::SavedAccount);
+            }
+            Err(e) => info!(qq_num = qq_num, reason = %e, "[Headless] no saved account"),
+        }
+    }
+    let saved_account = crate::saved_login::info().map(|info| info.account);
+    if saved_account.is_some_and(|saved| account.is_none() || saved == account)
+        && crate::saved_login::try_silent_login(app)?
+    {
+        info!(account = ?account, "[Headless] launching with saved login");
+        return Ok(HeadlessMethod::SavedLogin);
+    }
+
+    start_web_session(app, account)?;
+    Ok(HeadlessMethod::WebSession)
+}
+
+/// 在隐藏的登录页中抓取 login3，超时后进入 Error
+fn start_web_session(app: &AppHandle, account: Option<u64>) -> Result<(), String> {
+    if IN_PROGRESS.swap(true, Ordering::SeqCst) {
+        return Err("Headless login already in progress.".to_string());
+    }
+    let result = (|| {
+        crate::login3_capture::start(app.clone(), app.state::<Mutex<AppState>>())?;
+        let login = app
+            .get_webview("login")
+            .ok_or_else(|| "Login WebView not found.".to_string())?;
+        let _ = login.hide();
+        let url = crate::endpoints::login_url()
+            .parse()
+            .map_err(|_| "Invalid login URL.".to_string())?;
+        login
+            .navigate(url)
+            .map_err(|_| "Failed to navigate login webview.".to_string())
+    })();
+    if let Err(e) = result {
+        IN_PROGRESS.store(false, Ordering::SeqCst);
+        return Err(e);
+    }
+    info!(account = ?account, "[Headless] logging in with web session");
+
+    let app = app.clone();
+    std::thread::spawn(move || {
+        let timeout = Duration::from_secs(crate::config::current().login.headless_timeout_secs);
+        if wait_for_running(&app, timeout) {
+            if let Some(expected) = account {
+                verify_account(&app, expected);
+            }
+        } else {
+            warn!(
+                timeout_secs = timeout.as_secs(),
+                "[Headless] web session login did not complete"
+            );
+            let state = app.state::<Mutex<AppState>>();
+            crate::login3_capture::stop_timer_only(&state);
+            let mut guard = state.lock().expect("state lock");
+            if guard.status != AppStatus::Running {
+                guard.status = AppStatus::Error;
+                guard.message = Some(
+                    "Headless login did not complete, please log in interactively once."
+                        .to_string(),
+                );
+                emit_status(&app, &guard);
+            }
+        }
+        IN_PROGRESS.store(false, Ordering::SeqCst);
+    });
+    Ok(())
+}
+
+/// 运行中的账号与指定账号不一致（或无法识别）时结束会话并进入 Error
+fn verify_account(app: &AppHandle, expected: u64) {
+    if crate::EXITING.load(Ordering::Relaxed) {
+        return;
+    }
+    let state = app.state::<Mutex<AppState>>();
+    let actual = state
+        .lock()
+        .expect("state lock")
+        .qq_num
+        .filter(|&qq_num| qq_num != 0);
+    if actual == Some(expected) {
+        return;
+    }
+    warn!(
+        expected = expected,
+        actual = ?actual,
+        "[Headless] web session logged in a different account"
+    );
+    crate::session_history::end_current("account_mismatch", false);
+    crate::launcher::stop_projector(&state);
+    let mut guard = state.lock().expect("state lock");
+    guard.status = AppStatus::Error;
+    guard.message = Some(match actual {
+        Some(actual) => format!("Headless login used account {actual} instead of {expected}."),
+        None => format!("Headless login could not confirm account {expected}."),
+    });
+    emit_status(app, &guard);
+}
+
+fn wait_for_running(app: &AppHandle, timeout: Duration) -> bool {
+    let state = app.state::<Mutex<AppState>>();
+    let deadline = Instant::now() + timeout;
+    while Instant::now() < deadline {
+        if crate::EXITING.load(Ordering::Relaxed) {
+            return true;
+        }
+        std::thread::sleep(POLL_INTERVAL);
+        match state.lock().expect("state lock").status {
+            AppStatus::Running => {
+                info!("[Headless] web session login completed");
+                return true;
+            }
+            AppStatus::Error => return false,
+            _ => {}
+        }
+    }
+    false
+}
@@ -0,0 +1,214 @@
+use std::sync::{Arc, Mutex};
+
+use tauri::AppHandle;
+use tracing::warn;
+
+// 全局快捷键
+//
+// 各功能按名称登记自己的快捷键，所有快捷键在同一个线程上注册并处理消息。
+// 登记变化时结束旧线程，按当前登记表重新注册（回调里不能再调用 bind）。
+
+pub type HotkeyCallback = Arc<dyn Fn(&AppHandle) + Send + Sync>;
+
+struct Binding {
+    name: &'static str,
+    modifiers: u32,
+    vk: u32,
+    callback: HotkeyCallback,
+}
+
+static BINDINGS: Mutex<Vec<Binding>> = Mutex::new(Vec::new());
+
+/// 解析快捷键（如 "Ctrl+Alt+H"、"Ctrl+Shift+F12"），返回 (修饰键位, 虚拟键码)
+///
+/// 修饰键位与 RegisterHotKey 一致：Alt=1、Ctrl=2、Shift=4、Win=8。
+pub fn parse_hotkey(text: &str) -> Option<(u32, u32)> {
+    let mut modifiers = 0u32;
+    let mut key = None;
+    for part in text.split('+').map(str::trim) {
+        match part.to_ascii_lowercase().as_str() {
+            "alt" => modifiers |= 0x1,
+            "ctrl" | "control" => modifiers |= 0x2,
+            "shift" => modifiers |= 0x4,
+            "win" | "super" => modifiers |= 0x8,
+            other => {
+                if key.is_some() {
+                    return None;
+                }
+                key = Some(match other.as_bytes() {
+                    [c] if c.is_ascii_alphanumeric() => c.to_ascii_uppercase() as u32,
+                    [b'f', digits @ ..] => {
+                        let n: u32 = std::str::from_utf8(digits).ok()?.parse().ok()?;
+                        if !(1..=24).contains(&n) {
+                            return None;
+                        }
+                        0x70 + n - 1
+                    }
+                    _ => return None,
+                });
+            }
+        }
+    }
+    // 不带修饰键的全局快捷键会吞掉普通输入
+    if modifiers == 0 {
+        return None;
+    }
+    key.map(|key| (modifiers, key))
+}
+
+/// 按配置文本登记（或替换）快捷键；文本为空或无效时取消该名称的登记
+pub fn bind(app: &AppHandle, name: &'static str, text: &str, callback: HotkeyCallback) {
+    let parsed = if text.trim().is_empty() {
+        None
+    } else {
+        let parsed = parse_hotkey(text);
+        if parsed.is_none() {
+            warn!(name = name, hotkey = %text, "[Hotkey] invalid hotkey");
+        }
+        parsed
+    };
+
+    {
+        let mut bindings = BINDINGS.lock().expect("hotkey bindings lock");
+        bindings.retain(|binding| binding.name != name);
+        if let Some((modifiers, vk)) = parsed {
+            if let Some(other) = bindings
+                .iter()
+                .find(|b| b.modifiers == modifiers && b.vk == vk)
+            {
+                warn!(
+                    name = name,
+                    other = other.name,
+                    "[Hotkey] hotkey already in use"
+                );
+            } else {
+                bindings.push(Binding {
+                    name,
+                    modifiers,
+                    vk,
+                    callback,
+                });
+            }
+        }
+    }
+
+    #[cfg(target_os = "windows")]
+    listener::restart(app);
+    #[cfg(not(target_os = "windows"))]
+    let _ = app;
+}
+
+#[cfg(target_os = "windows")]
+mod listener {
+    use std::sync::mpsc;
+    use std::sync::Mutex;
+    use std::thread::JoinHandle;
+
+    use tauri::AppHandle;
+    use tracing::{info, warn};
+    use windows::Win32::Foundation::{LPARAM, WPARAM};
+    use windows::Win32::System::Threading::GetCurrentThreadId;
+    use windows::Win32::UI::Input::KeyboardAndMouse::{
+        RegisterHotKey, UnregisterHotKey, HOT_KEY_MODIFIERS, MOD_NOREPEAT,
+    };
+    use windows::Win32::UI::WindowsAndMessaging::{
+        GetMessageW, PostThreadMessageW, MSG, WM_HOTKEY, WM_QUIT,
+    };
+
+    use super::BINDINGS;
+
+    /// 热键 ID 从这里开始按登记顺序分配
+    const HOTKEY_ID_BASE: i32 = 0x5243;
+
+    /// 当前快捷键线程（重新注册前先结束旧线程并等待它注销热键）
+    static THREAD: Mutex<Option<(u32, JoinHandle<()>)>> = Mutex::new(None);
+
+    pub fn restart(app: &AppHandle) {
+        let mut thread = THREAD.lock().expect("hotkey thread lock");
+        if let Some((thread_id, handle)) = thread.take() {
+            unsafe {
+                let _ = PostThreadMessageW(thread_id, WM_QUIT, WPARAM(0), LPARAM(0));
+            }
+            let _ = handle.join();
+        }
+        let keys: Vec<(u32, u32)> = BINDINGS
+            .lock()
+            .expect("hotkey bindings lock")
+            .iter()
+            .map(|b| (b.modifiers, b.vk))
+            .collect();
+        if keys.is_empty() {
+            return;
+        }
+
+        let app = app.clone();
+        let (tx, rx) = mpsc::channel();
+        let handle = std::thread::spawn(move || {
+            // 热键绑定在注册它的线程上，消息循环必须在同一线程
+            let mut registered = Vec::new();
+            for (index, (modifiers, vk)) in keys.iter().enumerate() {
+                let id = HOTKEY_ID_BASE + index as i32;
+                let result = unsafe {
+                    RegisterHotKey(None, id, HOT_KEY_MODIFIERS(*modifiers) | MOD_NOREPEAT, *vk)
+                };
+                match result {
+                    Ok(()) => registered.push(id),
+                    Err(e) => warn!(
+                        error = %e,
+                        modifiers = modifiers,
+                        vk = vk,
+                        "[Hotkey] failed to register hotkey"
+                    ),
+                }
+            }
+            // 调用过 user32 后线程已有消息队列，此时才能接收 WM_QUIT
+            let _ = tx.send(unsafe { GetCurrentThreadId() });
+            if registered.is_empty() {
+                return;
+            }
+            info!(count = registered.len(), "[Hotkey] hotkeys registered");
+
+            let mut msg = MSG::default();
+            while unsafe { GetMessageW(&mut msg, None, 0, 0) }.as_bool() {
+                if msg.message != WM_HOTKEY {
+                    continue;
+                }
+                let index = (msg.wParam.0 as i32 - HOTKEY_ID_BASE) as usize;
+                let Some(&(modifiers, vk)) = keys.get(index) else {
+                    continue;
+                };
+                let callback = BINDINGS
+                    .lock()
+                    .expect("hotkey bindings lock")
+                    .iter()
+                    .find(|b| b.modifiers == modifiers && b.vk == vk)
+                    .map(|b| b.callback.clone());
+                if let Some(callback) = callback {
+                    callback(&app);
+                }
+            }
+            for id in registered {
+                unsafe {
+                    let _ = UnregisterHotKey(None, id);
+                }
+            }
+        });
+        if let Ok(thread_id) = rx.recv() {
+            *thread = Some((thread_id, handle));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_hotkey;
+
+    #[test]
+    fn parses_hotkeys() {
+        assert_eq!(parse_hotkey("Ctrl+Alt+H"), Some((0x3, b'H' as u32)));
+        assert_eq!(parse_hotkey("shift + ctrl + f12"), Some((0x6, 0x7B)));
+        assert_eq!(parse_hotkey("H"), None);
+        assert_eq!(parse_hotkey("Ctrl+H+J"), None);
+        assert_eq!(parse_hotkey("Ctrl+F30"), None);
+    }
+}
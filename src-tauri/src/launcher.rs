@@ -1,10 +1,11 @@
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::sync::Mutex;
-use std::time::Duration;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
 
 use tauri::PhysicalSize;
-use tauri::{AppHandle, Manager, State};
+use tauri::{AppHandle, Emitter, Manager, State};
 use windows::Win32::Foundation::HWND;
 
 // 全局退出标志，用于控制调度线程停止
@@ -12,15 +13,22 @@ static SHOULD_EXIT_SCHEDULES: AtomicBool = AtomicBool::new(false);
 
 use crate::embed_win32::{
     attach_child, bring_to_top, detach_child, find_window_by_pid, hide_window, move_child,
-    parent_client_size,
+    parent_client_size, window_class, window_title,
 };
-use crate::projector::{resolve_projector_path, stop_projector as kill_projector};
-use crate::state::{emit_status, AppState, AppStatus, ProjectorHandle};
-use crate::wpe::{PacketInjector, PacketInterceptor};
+use crate::pacing::Pace;
+use crate::projector::{close_projector, stop_projector as kill_projector};
+use crate::state::{
+    emit_status, AppState, AppStatus, LaunchGuard, Pipeline, ProjectorHandle, ProjectorProcess,
+};
+use crate::wpe::{BackendSpec, PacketInterceptor};
 use tracing::info;
 
 const LOGIN_ZOOM: f64 = 1.17;
 const UI_BAR_HEIGHT: i32 = 36;
+/// projector 存活检查间隔（启动中 / 正常 / 空闲）
+const EXIT_POLL: Pace = Pace::from_millis(250, 500, 3000);
+/// projector 标题轮询间隔
+const TITLE_POLL: Pace = Pace::from_millis(500, 1000, 5000);
 
 fn extract_qq_from_url(url: &str) -> Option<u64> {
     url::Url::parse(url).ok().and_then(|parsed| {
@@ -74,13 +82,17 @@ fn set_error(app: &AppHandle, state: &State<Mutex<AppState>>, msg: String) {
 }
 
 pub fn stop_projector(state: &State<Mutex<AppState>>) {
-    with_state(state, |s| {
-        if let Some(mut projector) = s.projector.take() {
-            detach_child(
-                HWND(projector.hwnd as *mut std::ffi::c_void),
-                projector.original_style,
-            );
-            kill_projector(&mut projector.process);
+    // 调用方未给出更具体原因时按普通停止记录
+    crate::session_history::end_current("stopped", false);
+    let projector = with_state(state, |s| {
+        let projector = s.projector.take();
+        if let Some(projector) = &projector {
+            let hwnd = HWND(projector.hwnd as *mut std::ffi::c_void);
+            if let Some(style) = projector.original_style {
+                detach_child(hwnd, style);
+            }
+            // 等待退出期间不显示已脱离主窗口的 projector
+            hide_window(hwnd);
         }
 
         if let Some(interceptor) = s.wpe_interceptor.take() {
@@ -92,18 +104,105 @@ pub fn stop_projector(state: &State<Mutex<AppState>>) {
         s.message = None;
         s.last_projector_rect = None;
         s.qq_num = None;
+        projector
     });
+
+    // 正常关闭在状态锁外进行；平时在后台等待，不阻塞界面，
+    // 退出时同步等待，但不超过兜底延迟的一半，避免程序退出后遗留 projector
+    let Some(mut projector) = projector else {
+        return;
+    };
+    let grace = crate::projector::close_grace();
+    if crate::EXITING.load(Ordering::Relaxed) {
+        let grace = grace.min(crate::shutdown::fallback_delay() / 2);
+        close_projector(&mut projector.process, projector.hwnd, grace);
+    } else {
+        std::thread::spawn(move || {
+            close_projector(&mut projector.process, projector.hwnd, grace);
+        });
+    }
+}
+
+fn init_wpe(pid: u32) -> Result<Arc<PacketInterceptor>, String> {
+    if !crate::config::experiment_enabled("windivert_capture") {
+        tracing::info!("windivert_capture experiment disabled, using mock capture backend");
+        return PacketInterceptor::with_backend(pid, BackendSpec::Mock)
+            .map_err(|e| format!("Failed to create packet interceptor: {}", e));
+    }
+
+    // WinDivert 不可用时按配置改用本地代理，不需要提权
+    let capture = crate::config::current().capture;
+    let windivert_ready =
+        crate::wpe::windivert::is_elevated() && crate::wpe::windivert::driver_available();
+    if capture.proxy_fallback && !capture.proxy_upstream.is_empty() && !windivert_ready {
+        tracing::info!(
+            upstream = %capture.proxy_upstream,
+            "WinDivert unavailable, using proxy capture backend"
+        );
+        let spec = BackendSpec::Proxy {
+            upstream: capture.proxy_upstream,
+            listen_port: capture.proxy_listen_port,
+        };
+        return PacketInterceptor::with_backend(pid, spec)
+            .map_err(|e| format!("Failed to create packet interceptor: {}", e));
+    }
+
+    match PacketInterceptor::new(pid) {
+        Ok(int) => {
+            tracing::info!("packet interceptor created");
+            Ok(int)
+        }
+        Err(e) => {
+            tracing::warn!(error = %e, "failed to create packet interceptor");
+            Err(format!("Failed to create packet interceptor: {}", e))
+        }
+    }
 }
 
+/// 启动失败时回收已经拉起的进程和并行中的 WPE 初始化任务
+fn abort_launch(
+    mut process: ProjectorProcess,
+    wpe_task: JoinHandle<Result<Arc<PacketInterceptor>, String>>,
+) {
+    if let Ok(Ok(interceptor)) = wpe_task.join() {
+        interceptor.stop();
+    }
+    kill_projector(&mut process);
+    tracing::info!("launch aborted, projector process terminated");
+}
+
+/// 启动流程中按顺序执行、报告进度的阶段（resolve_path、init_wpe 在后台线程中并行，不计入）
+const LAUNCH_STAGES: &[&str] = &[
+    "validate_state",
+    "launch_process",
+    "find_window",
+    "attach_window",
+    "resize_window",
+    "introspect_process",
+    "join_wpe",
+    "update_state",
+    "hide_login",
+];
+
 pub fn launch_projector_auto(
     app: &AppHandle,
     state: &State<Mutex<AppState>>,
 ) -> Result<(), String> {
     tracing::info!("launch_projector_auto started");
+    let pipeline_start = Instant::now();
+
+    // 防止登录回调与手动启动并发，导致两个 projector 争抢嵌入
+    let launch_flag = with_state(state, |s| s.launch_in_progress.clone());
+    let Some(_launch_guard) = LaunchGuard::acquire(&launch_flag) else {
+        tracing::warn!("launch already in progress, rejecting concurrent request");
+        return Err("Launch already in progress.".to_string());
+    };
+    crate::pacing::mark_launch();
+    let pipeline = Pipeline::begin(app, "launch", LAUNCH_STAGES);
 
     // 阶段 1：验证状态
     let (swf_url, existing) = {
-        let _stage = crate::request_context::StageTimer::new("validate_state");
+        let _stage = pipeline.stage("validate_state");
         let result = with_state(state, |s| (s.swf_url.clone(), s.projector.is_some()));
         tracing::info!(
             has_swf_url = result.0.is_some(),
@@ -113,8 +212,18 @@ pub fn launch_projector_auto(
         result
     };
 
+    // 阶段 2：解析投影器路径（与停止旧进程并行）
+    let path_task = {
+        let app = app.clone();
+        std::thread::spawn(move || {
+            let _stage = crate::request_context::StageTimer::new("resolve_path");
+            crate::projector::runtime::resolve(&app)
+        })
+    };
+
     if existing {
         tracing::info!("stopping existing projector");
+        crate::session_history::end_current("relaunch", false);
         stop_projector(state);
     }
 
@@ -126,31 +235,39 @@ pub fn launch_projector_auto(
         None => {
             let msg = "Missing main.swf URL.".to_string();
             tracing::error!("missing swf url");
+            // 等后台解析结束再返回，不留下脱离启动流程的线程
+            let _ = path_task.join();
             set_error(app, state, msg.clone());
             return Err(msg);
         }
     };
 
-    // 阶段 2：解析投影器路径
-    let projector_path = {
-        let _stage = crate::request_context::StageTimer::new("resolve_path");
-        match resolve_projector_path(app) {
-            Ok(path) => {
-                tracing::info!(path = %path.display(), "projector path resolved");
-                path
-            }
-            Err(msg) => {
-                tracing::error!(error = %msg, "failed to resolve projector path");
-                set_error(app, state, msg.clone());
-                return Err(msg);
-            }
+    let runtime = match path_task.join() {
+        Ok(Ok(runtime)) => {
+            tracing::info!(
+                runtime = ?runtime.runtime,
+                path = %runtime.path.display(),
+                "projector path resolved"
+            );
+            runtime
+        }
+        Ok(Err(msg)) => {
+            tracing::error!(error = %msg, "failed to resolve projector path");
+            set_error(app, state, msg.clone());
+            return Err(msg);
+        }
+        Err(_) => {
+            let msg = "Projector path resolution panicked.".to_string();
+            tracing::error!("resolve_path task panicked");
+            set_error(app, state, msg.clone());
+            return Err(msg);
         }
     };
 
     // 阶段 3：启动进程
     let (process, pid) = {
-        let _stage = crate::request_context::StageTimer::new("launch_process");
-        match crate::projector::launch_projector(&projector_path, &swf_url) {
+        let _stage = pipeline.stage("launch_process");
+        match crate::projector::runtime::launch(&runtime, &swf_url) {
             Ok(process) => {
                 let pid = process.pid;
                 tracing::info!(pid = pid, "process launched");
@@ -164,114 +281,173 @@ pub fn launch_projector_auto(
         }
     };
 
-    // 阶段 4：查找窗口
+    // 阶段 4：初始化 WPE（后台线程，与查找/嵌入窗口并行）
+    let wpe_task = std::thread::spawn(move || {
+        let _stage = crate::request_context::StageTimer::new("init_wpe");
+        init_wpe(pid)
+    });
+
+    // 阶段 5：查找窗口
     let child_hwnd = {
-        let _stage = crate::request_context::StageTimer::new("find_window");
-        match find_window_by_pid(pid, 6000) {
+        let _stage = pipeline.stage("find_window");
+        let criteria = crate::config::current().launcher.projector_window;
+        match find_window_by_pid(pid, 6000, &criteria) {
             Ok(hwnd) => {
-                tracing::info!(hwnd = hwnd.0 as usize, "window found");
+                tracing::info!(
+                    hwnd = hwnd.0 as usize,
+                    class = %window_class(hwnd),
+                    criteria = ?criteria,
+                    "window found"
+                );
                 hwnd
             }
             Err(msg) => {
-                tracing::error!(error = %msg, pid = pid, "failed to find window");
+                tracing::error!(
+                    error = %msg,
+                    pid = pid,
+                    criteria = ?criteria,
+                    "failed to find window"
+                );
+                abort_launch(process, wpe_task);
                 set_error(app, state, msg.clone());
                 return Err(msg);
             }
         }
     };
 
-    // 阶段 5：嵌入窗口
+    // 阶段 6：嵌入窗口（独立窗口模式下保留为顶层窗口）
+    let external = crate::config::current().launcher.external_window;
     let original_style = {
-        let _stage = crate::request_context::StageTimer::new("attach_window");
+        let _stage = pipeline.stage("attach_window");
 
-        hide_window(child_hwnd);
+        if external {
+            tracing::info!(
+                child_hwnd = child_hwnd.0 as usize,
+                "external window mode, skipping attach"
+            );
+            None
+        } else {
+            hide_window(child_hwnd);
+
+            let main_hwnd = match main_hwnd(app) {
+                Ok(hwnd) => hwnd,
+                Err(msg) => {
+                    tracing::error!(error = %msg, "failed to get main window handle");
+                    abort_launch(process, wpe_task);
+                    set_error(app, state, msg.clone());
+                    return Err(msg);
+                }
+            };
+
+            match attach_child(child_hwnd, main_hwnd) {
+                Ok(style) => {
+                    tracing::info!(
+                        child_hwnd = child_hwnd.0 as usize,
+                        parent_hwnd = main_hwnd.0 as usize,
+                        "window attached"
+                    );
+                    Some(style)
+                }
+                Err(msg) => {
+                    tracing::error!(error = %msg, "failed to attach window");
+                    abort_launch(process, wpe_task);
+                    set_error(app, state, msg.clone());
+                    return Err(msg);
+                }
+            }
+        }
+    };
 
-        let main_hwnd = match main_hwnd(app) {
-            Ok(hwnd) => hwnd,
-            Err(msg) => {
-                tracing::error!(error = %msg, "failed to get main window handle");
+    // 阶段 7：调整窗口大小（独立窗口由用户自行调整，只提到前台）
+    {
+        let _stage = pipeline.stage("resize_window");
+
+        if external {
+            bring_to_top(child_hwnd);
+        } else {
+            if let Err(msg) = fit_embedded(app, child_hwnd) {
+                tracing::error!(error = %msg, "failed to resize window");
+                if let Some(style) = original_style {
+                    detach_child(child_hwnd, style);
+                }
+                abort_launch(process, wpe_task);
                 set_error(app, state, msg.clone());
                 return Err(msg);
             }
-        };
+            bring_to_top(child_hwnd);
+            schedule_projector_fit(app.clone());
+        }
+    }
 
-        match attach_child(child_hwnd, main_hwnd) {
-            Ok(style) => {
+    // 窗口出现时主模块已加载完毕，此时采集进程信息
+    let process_info = {
+        let _stage = pipeline.stage("introspect_process");
+        match crate::projector::introspect::introspect(&process) {
+            Ok(info) => {
                 tracing::info!(
-                    child_hwnd = child_hwnd.0 as usize,
-                    parent_hwnd = main_hwnd.0 as usize,
-                    "window attached"
+                    architecture = %info.architecture,
+                    native_architecture = %info.native_architecture,
+                    is_32bit = info.is_32bit,
+                    modules = info.modules.len(),
+                    "projector process introspected"
                 );
-                style
+                Some(info)
             }
             Err(msg) => {
-                tracing::error!(error = %msg, "failed to attach window");
-                set_error(app, state, msg.clone());
-                return Err(msg);
+                tracing::warn!(error = %msg, "failed to introspect projector process");
+                None
             }
         }
     };
 
-    // 阶段 6：调整窗口大小
-    {
-        let _stage = crate::request_context::StageTimer::new("resize_window");
-
-        if let Some((w, h)) = parent_client_size(main_hwnd(app).unwrap()) {
-            let scale = main_window_scale(app);
-            let bar_h = ((UI_BAR_HEIGHT as f64) * scale).round() as i32;
-            let usable_h = (h - bar_h).max(1);
-            move_child(child_hwnd, 0, bar_h, w, usable_h);
-            tracing::info!(width = w, height = usable_h, "window resized");
-        } else {
-            let size = main_window_size_physical(app)?;
-            let scale = main_window_scale(app);
-            let bar_h = ((UI_BAR_HEIGHT as f64) * scale).round() as i32;
-            let usable_h = (size.height as i32 - bar_h).max(1);
-            move_child(child_hwnd, 0, bar_h, size.width as i32, usable_h);
-            tracing::info!(
-                width = size.width,
-                height = usable_h,
-                "window resized (fallback)"
+    let qq_num = extract_qq_from_url(&swf_url).unwrap_or(0);
+    tracing::info!(qq_num = qq_num, "qq number extracted");
+    if let Some(expected) = crate::cli::args().account {
+        if qq_num != 0 && qq_num != expected {
+            tracing::warn!(
+                expected = expected,
+                actual = qq_num,
+                "logged in account differs from --account"
             );
         }
-
-        bring_to_top(child_hwnd);
-        schedule_projector_fit(app.clone());
     }
 
-    // 阶段 7：初始化 WPE
-    let qq_num = extract_qq_from_url(&swf_url).unwrap_or(0);
-    tracing::info!(qq_num = qq_num, "qq number extracted");
-
-    let _interceptor = {
-        let _stage = crate::request_context::StageTimer::new("init_wpe");
-
-        let _injector = match PacketInjector::new(pid) {
-            Ok(inj) => {
-                tracing::info!("packet injector created");
-                Arc::new(inj)
-            }
-            Err(e) => {
-                tracing::warn!(error = %e, "failed to create packet injector");
-                return Err(format!("Failed to create packet injector: {}", e));
-            }
-        };
-
-        match PacketInterceptor::new(pid) {
-            Ok(int) => {
-                tracing::info!("packet interceptor created");
-                int
+    let client_version = crate::client_version::apply(app, &swf_url);
+
+    // 等待并行的 WPE 初始化完成
+    let interceptor = {
+        let _stage = pipeline.stage("join_wpe");
+        match wpe_task.join() {
+            Ok(Ok(interceptor)) => interceptor,
+            Ok(Err(msg)) => {
+                if let Some(style) = original_style {
+                    detach_child(child_hwnd, style);
+                }
+                let mut process = process;
+                kill_projector(&mut process);
+                set_error(app, state, msg.clone());
+                return Err(msg);
             }
-            Err(e) => {
-                tracing::warn!(error = %e, "failed to create packet interceptor");
-                return Err(format!("Failed to create packet interceptor: {}", e));
+            Err(_) => {
+                if let Some(style) = original_style {
+                    detach_child(child_hwnd, style);
+                }
+                let mut process = process;
+                kill_projector(&mut process);
+                let msg = "WPE initialization panicked.".to_string();
+                set_error(app, state, msg.clone());
+                return Err(msg);
             }
         }
     };
+    crate::notify::attach(app, &interceptor);
+    watch_wpe_status(app, &interceptor);
+    attach_wpe_rules(app, &interceptor);
+    crate::packet_stream::attach(&interceptor);
 
     // 阶段 8：更新状态
     {
-        let _stage = crate::request_context::StageTimer::new("update_state");
+        let _stage = pipeline.stage("update_state");
 
         with_state(state, |s| {
             tracing::info!(
@@ -284,20 +460,34 @@ pub fn launch_projector_auto(
                 process,
                 hwnd: child_hwnd.0 as isize,
                 original_style,
+                info: process_info,
+                title: None,
+                metrics: Default::default(),
             });
             s.status = AppStatus::Running;
             s.message = None;
             s.last_projector_rect = None;
             s.qq_num = Some(qq_num);
-            s.wpe_interceptor = Some(_interceptor);
+            // 旁观中的拦截器被新会话取代
+            if let Some(previous) = s.wpe_interceptor.replace(interceptor) {
+                previous.stop();
+            }
         });
 
         emit_status(app, &state.lock().expect("state lock"));
+        crate::sound::play(crate::sound::SoundEvent::LoginComplete);
+        crate::session_history::begin((qq_num != 0).then_some(qq_num), client_version);
+        watch_projector_exit(app.clone(), pid);
+        watch_projector_title(app.clone(), pid);
+        crate::projector::auto_restart::watch(app.clone(), pid);
+        crate::projector::metrics::watch(app.clone(), pid);
+        crate::projector::reconnect::watch(app.clone(), pid);
+        crate::projector::relogin::watch(app.clone(), pid);
     }
 
     // 阶段 9：隐藏登录窗口
     {
-        let _stage = crate::request_context::StageTimer::new("hide_login");
+        let _stage = pipeline.stage("hide_login");
 
         if let Some(login) = app.get_webview("login") {
             let _ = login.hide();
@@ -309,10 +499,129 @@ pub fn launch_projector_auto(
         }
     }
 
-    tracing::info!("launch_projector_auto completed successfully");
+    tracing::info!(
+        total_ms = pipeline_start.elapsed().as_millis() as u64,
+        "launch_projector_auto completed successfully"
+    );
     Ok(())
 }
 
+/// 监视 projector 进程是否自行退出（崩溃或被外部结束）
+fn watch_projector_exit(app: AppHandle, pid: u32) {
+    std::thread::spawn(move || loop {
+        crate::pacing::wait(&EXIT_POLL);
+        if SHOULD_EXIT_SCHEDULES.load(Ordering::Relaxed) || crate::EXITING.load(Ordering::Relaxed) {
+            break;
+        }
+
+        let state = app.state::<Mutex<AppState>>();
+        let exited = {
+            let mut guard = state.lock().expect("state lock");
+            match guard.projector.as_mut() {
+                Some(projector) if projector.process.pid == pid => {
+                    crate::projector::exit_code(&mut projector.process)
+                }
+                // 已被停止或替换为新进程
+                _ => break,
+            }
+        };
+        let Some(code) = exited else {
+            continue;
+        };
+
+        tracing::warn!(pid = pid, exit_code = code, "projector exited unexpectedly");
+        crate::session_history::end_current("projector_exited", code != 0);
+        stop_projector(&state);
+        set_error(
+            &app,
+            &state,
+            format!("Projector exited unexpectedly (code {code})."),
+        );
+        break;
+    });
+}
+
+/// projector 窗口标题变化事件
+#[derive(Clone, serde::Serialize)]
+pub struct TitleChanged {
+    pub pid: u32,
+    pub title: String,
+    pub previous: Option<String>,
+}
+
+/// 把捕获后端的状态变化（包括自动切换到备用后端）转发为 wpe_backend_status 事件
+pub fn watch_wpe_status(app: &AppHandle, interceptor: &PacketInterceptor) {
+    let app = app.clone();
+    interceptor.set_status_listener(Arc::new(move |status| {
+        tracing::info!(
+            backend = status.backend,
+            state = ?status.state,
+            reason = ?status.reason,
+            "wpe backend status"
+        );
+        let _ = app.emit(crate::events::WPE_BACKEND_STATUS, status);
+    }));
+}
+
+/// 封包规则文件（AppData/wpe_rules.json）
+pub fn wpe_rules_path(app: &AppHandle) -> Result<std::path::PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map(|dir| dir.join("wpe_rules.json"))
+        .map_err(|_| "Failed to resolve app data dir.".to_string())
+}
+
+/// 加载封包规则并在文件修改后自动重新加载
+pub fn attach_wpe_rules(app: &AppHandle, interceptor: &Arc<PacketInterceptor>) {
+    match wpe_rules_path(app) {
+        Ok(path) => crate::wpe::rules::watch_file(interceptor, path),
+        Err(e) => tracing::warn!(error = %e, "packet rules disabled"),
+    }
+}
+
+/// 轮询 projector 窗口标题，变化时记录到状态并发出 projector_title_changed 事件
+fn watch_projector_title(app: AppHandle, pid: u32) {
+    std::thread::spawn(move || loop {
+        if SHOULD_EXIT_SCHEDULES.load(Ordering::Relaxed) || crate::EXITING.load(Ordering::Relaxed) {
+            break;
+        }
+
+        let state = app.state::<Mutex<AppState>>();
+        let hwnd = match with_state(&state, |s| {
+            s.projector
+                .as_ref()
+                .filter(|p| p.process.pid == pid)
+                .map(|p| p.hwnd)
+        }) {
+            Some(hwnd) => hwnd,
+            // 已被停止或替换为新进程
+            None => break,
+        };
+        // 在锁外读取标题，避免窗口无响应时阻塞状态锁
+        let title = window_title(HWND(hwnd as *mut std::ffi::c_void));
+
+        let changed = with_state(&state, |s| {
+            let projector = s.projector.as_mut().filter(|p| p.process.pid == pid)?;
+            let previous = projector.title.as_ref().map(|(t, _)| t.clone());
+            if previous.as_deref() == Some(title.as_str()) {
+                return None;
+            }
+            projector.title = Some((title.clone(), Instant::now()));
+            Some(TitleChanged {
+                pid,
+                title,
+                previous,
+            })
+        });
+
+        if let Some(event) = changed {
+            tracing::debug!(pid = pid, title = %event.title, "projector title changed");
+            let _ = app.emit(crate::events::PROJECTOR_TITLE_CHANGED, event);
+        }
+        crate::pacing::wait(&TITLE_POLL);
+    });
+}
+
 fn schedule_projector_fit(app: AppHandle) {
     std::thread::spawn(move || {
         let delays_ms = [50u64, 150, 300, 600, 1200, 2000];
@@ -339,9 +648,94 @@ fn schedule_projector_fit(app: AppHandle) {
     });
 }
 
+/// 把嵌入的 projector 铺满主窗口工具栏以下的区域
+fn fit_embedded(app: &AppHandle, child_hwnd: HWND) -> Result<(), String> {
+    if let Some((w, h)) = main_hwnd(app).ok().and_then(parent_client_size) {
+        let scale = main_window_scale(app);
+        let bar_h = ((UI_BAR_HEIGHT as f64) * scale).round() as i32;
+        let usable_h = (h - bar_h).max(1);
+        move_child(child_hwnd, 0, bar_h, w, usable_h);
+        tracing::info!(width = w, height = usable_h, "window resized");
+    } else {
+        let size = main_window_size_physical(app)?;
+        let scale = main_window_scale(app);
+        let bar_h = ((UI_BAR_HEIGHT as f64) * scale).round() as i32;
+        let usable_h = (size.height as i32 - bar_h).max(1);
+        move_child(child_hwnd, 0, bar_h, size.width as i32, usable_h);
+        tracing::info!(
+            width = size.width,
+            height = usable_h,
+            "window resized (fallback)"
+        );
+    }
+    Ok(())
+}
+
+/// 把运行中的 projector 重新嵌入主窗口（`embedded` 为真）或弹出为独立窗口，返回是否有变化
+pub fn set_projector_embedded(
+    app: &AppHandle,
+    state: &State<Mutex<AppState>>,
+    embedded: bool,
+) -> Result<bool, String> {
+    let Some((hwnd, original_style, last_rect)) = with_state(state, |s| {
+        s.projector
+            .as_ref()
+            .map(|p| (p.hwnd, p.original_style, s.last_projector_rect))
+    }) else {
+        return Ok(false);
+    };
+    if original_style.is_some() == embedded {
+        return Ok(false);
+    }
+    let child_hwnd = HWND(hwnd as *mut std::ffi::c_void);
+
+    let original_style = match original_style {
+        None => {
+            let style = attach_child(child_hwnd, main_hwnd(app)?)?;
+            fit_embedded(app, child_hwnd)?;
+            bring_to_top(child_hwnd);
+            Some(style)
+        }
+        Some(style) => {
+            detach_child(child_hwnd, style);
+            // detach_child 会把窗口缩成 1x1，按嵌入时的大小放在主窗口附近
+            let position = main_window(app)?
+                .outer_position()
+                .map_err(|e| format!("Failed to get main window position: {e}"))?;
+            let size = main_window_size_physical(app)?;
+            let (w, h) = last_rect.map_or((size.width as i32, size.height as i32), |r| (r.2, r.3));
+            move_child(child_hwnd, position.x + 40, position.y + 40, w, h);
+            bring_to_top(child_hwnd);
+            None
+        }
+    };
+    with_state(state, |s| {
+        if let Some(projector) = s.projector.as_mut().filter(|p| p.hwnd == hwnd) {
+            projector.original_style = original_style;
+        }
+        s.last_projector_rect = None;
+    });
+    if embedded {
+        schedule_projector_fit(app.clone());
+    }
+    info!(
+        hwnd = hwnd,
+        embedded = embedded,
+        "[Launcher] projector window mode changed"
+    );
+    Ok(true)
+}
+
 pub fn resize_projector_to_window(app: &AppHandle, state: &State<Mutex<AppState>>) {
+    // 独立窗口不跟随主窗口调整
     let (projector, last_rect) = with_state(state, |s| {
-        (s.projector.as_ref().map(|p| p.hwnd), s.last_projector_rect)
+        (
+            s.projector
+                .as_ref()
+                .filter(|p| p.original_style.is_some())
+                .map(|p| p.hwnd),
+            s.last_projector_rect,
+        )
     });
     let Some(hwnd) = projector else {
         return;
@@ -382,6 +776,27 @@ pub fn resize_projector_to_window(app: &AppHandle, state: &State<Mutex<AppState>
     });
 }
 
+/// webview 缩放倍率：优先使用用户设置，否则 login 使用内置倍率，其余为 1.0
+pub fn webview_zoom(label: &str) -> f64 {
+    if let Some(zoom) = crate::config::current().ui.webview_zoom.get(label) {
+        return *zoom;
+    }
+    if label == "login" {
+        LOGIN_ZOOM
+    } else {
+        1.0
+    }
+}
+
+/// 按配置应用所有已保存的缩放（webview 创建后调用）
+pub fn apply_webview_zooms(app: &AppHandle) {
+    for label in crate::config::current().ui.webview_zoom.keys() {
+        if let Some(webview) = app.get_webview(label) {
+            let _ = webview.set_zoom(webview_zoom(label));
+        }
+    }
+}
+
 pub fn resize_login_to_window(app: &AppHandle) {
     if let Ok(window) = main_window(app) {
         if let Ok(size) = window.inner_size() {
@@ -392,11 +807,12 @@ pub fn resize_login_to_window(app: &AppHandle) {
                 let usable_h = (h - UI_BAR_HEIGHT).max(1);
                 let _ = login.set_position(tauri::LogicalPosition::new(0, UI_BAR_HEIGHT));
                 let _ = login.set_size(tauri::LogicalSize::new(w, usable_h));
-                let _ = login.set_zoom(LOGIN_ZOOM);
+                let _ = login.set_zoom(webview_zoom("login"));
             }
             if let Some(toolbar) = app.get_webview("toolbar") {
                 let _ = toolbar.set_position(tauri::LogicalPosition::new(0, 0));
                 let _ = toolbar.set_size(tauri::LogicalSize::new(w, UI_BAR_HEIGHT));
+                let _ = toolbar.set_zoom(webview_zoom("toolbar"));
             }
         }
     }
@@ -427,8 +843,31 @@ pub fn schedule_login_layout(app: AppHandle) {
     });
 }
 
+/// 重新布局所有子 webview 与内嵌游戏窗口
+///
+/// 在主题切换、缩放比例变化等会影响布局的事件后调用。
+pub fn relayout_all(app: &AppHandle) {
+    let state = app.state::<Mutex<AppState>>();
+    let has_projector = with_state(&state, |s| {
+        // 清除缓存的矩形，强制下一次 resize 真正移动窗口
+        s.last_projector_rect = None;
+        s.projector.is_some()
+    });
+
+    resize_login_to_window(app);
+    if has_projector {
+        resize_projector_to_window(app, &state);
+        schedule_projector_fit(app.clone());
+    } else {
+        schedule_login_layout(app.clone());
+    }
+    tracing::info!(has_projector = has_projector, "relayout_all completed");
+}
+
 /// 停止所有调度线程
 pub fn stop_schedule_threads() {
     tracing::info!("[Launcher] Stopping schedule threads");
     SHOULD_EXIT_SCHEDULES.store(true, Ordering::SeqCst);
+    // 唤醒按空闲节奏等待中的监视线程
+    crate::pacing::refresh();
 }
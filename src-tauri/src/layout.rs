@@ -0,0 +1,413 @@
+use std::collections::HashMap;
+use std::sync::atomic::Ordering;
+
+use tauri::{AppHandle, Manager, PhysicalPosition, PhysicalSize, Size};
+use tracing::{info, warn};
+
+// 窗口布局预设
+//
+// 把主窗口的位置、大小和各 webview 的缩放保存为命名预设，之后可以一键恢复。
+// 位置按所在显示器记录（相对显示器左上角，连同缩放比例）：恢复时该显示器仍存在就回到原显示器，
+// 否则放到主窗口当前所在的显示器上；尺寸按两台显示器的缩放比例换算，并限制在显示器范围内。
+// 工具栏固定在顶部、没有可拖动的叠加层组件，因此预设中只有窗口几何和缩放。
+//
+// 另有后台线程监视显示器变化（如笔记本拔下扩展坞）：主窗口所在显示器消失、窗口落到屏幕外
+// 或比剩余显示器还大时，把窗口移回最近的显示器并缩到可见范围内，再重新嵌入布局。
+
+/// 预设允许的最小窗口宽度（与首次启动时的默认尺寸下限一致）
+const MIN_WIDTH: u32 = 640;
+
+/// 窗口与某个显示器至少重叠这么多（物理像素，横纵都要满足）才视为可见、可以拖回
+const MIN_VISIBLE: i64 = 64;
+
+/// 显示器变化的检查间隔
+const DISPLAY_POLL: crate::pacing::Pace = crate::pacing::Pace::from_millis(2000, 2000, 5000);
+
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct LayoutPreset {
+    /// 保存时主窗口所在的显示器名称
+    pub monitor: Option<String>,
+    /// 相对显示器左上角的位置（物理像素）
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    /// 保存时显示器的缩放比例
+    pub scale_factor: f64,
+    /// 各 webview 的缩放（按 label）
+    pub webview_zoom: HashMap<String, f64>,
+}
+
+/// 显示器区域（物理像素）
+#[derive(Clone, Copy, Debug)]
+struct Area {
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+    scale_factor: f64,
+}
+
+impl Area {
+    fn of(monitor: &tauri::Monitor) -> Self {
+        Self {
+            x: monitor.position().x,
+            y: monitor.position().y,
+            width: monitor.size().width,
+            height: monitor.size().height,
+            scale_factor: monitor.scale_factor(),
+        }
+    }
+}
+
+/// 计算预设在目标显示器上的窗口矩形 (x, y, w, h)
+fn place(preset: &LayoutPreset, area: Area) -> (i32, i32, u32, u32) {
+    let ratio = if preset.scale_factor > 0.0 {
+        area.scale_factor / preset.scale_factor
+    } else {
+        1.0
+    };
+    let scale = |value: f64| (value * ratio).round();
+    let width = (scale(preset.width as f64) as u32)
+        .max(MIN_WIDTH)
+        .min(area.width)
+        .max(1);
+    let height = (scale(preset.height as f64) as u32).min(area.height).max(1);
+    let max_x = area.width.saturating_sub(width) as i32;
+    let max_y = area.height.saturating_sub(height) as i32;
+    (
+        area.x + (scale(preset.x as f64) as i32).clamp(0, max_x),
+        area.y + (scale(preset.y as f64) as i32).clamp(0, max_y),
+        width,
+        height,
+    )
+}
+
+/// 窗口矩形 (x, y, w, h) 是否需要移回：与所有显示器都几乎不重叠，或比所在显示器大
+fn needs_rescue(rect: (i32, i32, u32, u32), areas: &[Area]) -> bool {
+    let (x, y, w, h) = rect;
+    let (x, y, w, h) = (x as i64, y as i64, w as i64, h as i64);
+    let visible = areas.iter().find(|area| {
+        let (ax, ay) = (area.x as i64, area.y as i64);
+        let overlap_w = (x + w).min(ax + area.width as i64) - x.max(ax);
+        let overlap_h = (y + h).min(ay + area.height as i64) - y.max(ay);
+        overlap_w >= MIN_VISIBLE && overlap_h >= MIN_VISIBLE
+    });
+    match visible {
+        Some(area) => rect.2 > area.width || rect.3 > area.height,
+        None => true,
+    }
+}
+
+/// 把窗口矩形放进显示器：尺寸超出时缩小，位置限制在显示器范围内
+fn fit_into(rect: (i32, i32, u32, u32), area: Area) -> (i32, i32, u32, u32) {
+    let width = rect.2.min(area.width).max(1);
+    let height = rect.3.min(area.height).max(1);
+    let max_x = area.x + area.width.saturating_sub(width) as i32;
+    let max_y = area.y + area.height.saturating_sub(height) as i32;
+    (
+        rect.0.clamp(area.x, max_x),
+        rect.1.clamp(area.y, max_y),
+        width,
+        height,
+    )
+}
+
+fn main_window(app: &AppHandle) -> Result<tauri::Window, String> {
+    app.get_window("main")
+        .ok_or_else(|| "Main window missing.".to_string())
+}
+
+/// 按名称列出已保存的预设
+pub fn list() -> Vec<String> {
+    let mut names: Vec<String> = crate::config::current()
+        .ui
+        .layout_presets
+        .into_keys()
+        .collect();
+    names.sort();
+    names
+}
+
+/// 把当前布局保存为预设（同名覆盖）
+pub fn save(app: &AppHandle, name: &str) -> Result<LayoutPreset, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Preset name is required.".to_string());
+    }
+    let window = main_window(app)?;
+    let position = window
+        .outer_position()
+        .map_err(|e| format!("Failed to read window position: {e}"))?;
+    let size = window
+        .inner_size()
+        .map_err(|e| format!("Failed to read window size: {e}"))?;
+    let monitor = window.current_monitor().ok().flatten();
+    let origin = monitor
+        .as_ref()
+        .map(|m| *m.position())
+        .unwrap_or(PhysicalPosition::new(0, 0));
+    let webview_zoom = app
+        .webviews()
+        .into_keys()
+        .map(|label| {
+            let zoom = crate::launcher::webview_zoom(&label);
+            (label, zoom)
+        })
+        .collect();
+
+    let preset = LayoutPreset {
+        monitor: monitor.as_ref().and_then(|m| m.name().cloned()),
+        x: position.x - origin.x,
+        y: position.y - origin.y,
+        width: size.width,
+        height: size.height,
+        scale_factor: window.scale_factor().unwrap_or(1.0),
+        webview_zoom,
+    };
+    crate::config::update(|config| {
+        config
+            .ui
+            .layout_presets
+            .insert(name.to_string(), preset.clone());
+    })?;
+    info!(
+        name = %name,
+        monitor = ?preset.monitor,
+        width = preset.width,
+        height = preset.height,
+        "[Layout] preset saved"
+    );
+    Ok(preset)
+}
+
+/// 恢复预设的窗口位置、大小和 webview 缩放
+///
+/// 调用方负责按游戏比例校正窗口高度并重新布局。
+pub fn apply(app: &AppHandle, name: &str) -> Result<(), String> {
+    let preset = crate::config::current()
+        .ui
+        .layout_presets
+        .remove(name)
+        .ok_or_else(|| format!("Layout preset not found: {name}"))?;
+    let window = main_window(app)?;
+
+    let monitors = window.available_monitors().unwrap_or_default();
+    let saved = preset
+        .monitor
+        .as_ref()
+        .and_then(|wanted| monitors.iter().find(|m| m.name() == Some(wanted)));
+    let area = saved
+        .map(Area::of)
+        .or_else(|| {
+            window
+                .current_monitor()
+                .ok()
+                .flatten()
+                .map(|m| Area::of(&m))
+        })
+        .unwrap_or(Area {
+            x: 0,
+            y: 0,
+            width: 1920,
+            height: 1080,
+            scale_factor: 1.0,
+        });
+    let (x, y, width, height) = place(&preset, area);
+
+    // 主窗口固定尺寸（最小、最大尺寸相同），先解除限制再调整
+    let size = PhysicalSize::new(width, height);
+    let _ = window.set_min_size(None::<Size>);
+    let _ = window.set_max_size(None::<Size>);
+    let _ = window.set_size(Size::Physical(size));
+    let _ = window.set_min_size(Some(Size::Physical(size)));
+    let _ = window.set_max_size(Some(Size::Physical(size)));
+    let _ = window.set_position(PhysicalPosition::new(x, y));
+
+    for (label, zoom) in &preset.webview_zoom {
+        if let Some(webview) = app.get_webview(label) {
+            let _ = webview.set_zoom(*zoom);
+        }
+    }
+    crate::config::update(|config| {
+        config.ui.webview_zoom.extend(preset.webview_zoom.clone());
+    })?;
+
+    info!(
+        name = %name,
+        same_monitor = saved.is_some(),
+        x = x,
+        y = y,
+        width = width,
+        height = height,
+        "[Layout] preset applied"
+    );
+    Ok(())
+}
+
+/// 当前显示器布局（名称和区域），用于发现显示器变化
+fn monitor_layout(window: &tauri::Window) -> Vec<(Option<String>, i32, i32, u32, u32)> {
+    window
+        .available_monitors()
+        .unwrap_or_default()
+        .iter()
+        .map(|m| {
+            let area = Area::of(m);
+            (m.name().cloned(), area.x, area.y, area.width, area.height)
+        })
+        .collect()
+}
+
+/// 主窗口落到屏幕外或比显示器大时移回最近的显示器，返回是否移动了窗口
+fn rescue_window(window: &tauri::Window) -> bool {
+    let (Ok(position), Ok(size)) = (window.outer_position(), window.outer_size()) else {
+        return false;
+    };
+    let rect = (position.x, position.y, size.width, size.height);
+    let areas: Vec<Area> = window
+        .available_monitors()
+        .unwrap_or_default()
+        .iter()
+        .map(Area::of)
+        .collect();
+    if areas.is_empty() || !needs_rescue(rect, &areas) {
+        return false;
+    }
+    // current_monitor 在窗口不在任何显示器上时返回最近的显示器
+    let Some(target) = window
+        .current_monitor()
+        .ok()
+        .flatten()
+        .or_else(|| window.primary_monitor().ok().flatten())
+    else {
+        return false;
+    };
+    let (x, y, width, height) = fit_into(rect, Area::of(&target));
+    warn!(
+        from = ?rect,
+        to = ?(x, y, width, height),
+        monitor = ?target.name(),
+        "[Layout] main window was off-screen, moving it back"
+    );
+    if (width, height) != (size.width, size.height) {
+        // outer_size 含边框，按边框差值换算内部尺寸
+        let inner = window.inner_size().unwrap_or(size);
+        let inner = PhysicalSize::new(
+            width
+                .saturating_sub(size.width.saturating_sub(inner.width))
+                .max(1),
+            height
+                .saturating_sub(size.height.saturating_sub(inner.height))
+                .max(1),
+        );
+        let _ = window.set_min_size(None::<Size>);
+        let _ = window.set_max_size(None::<Size>);
+        let _ = window.set_size(Size::Physical(inner));
+        let _ = window.set_min_size(Some(Size::Physical(inner)));
+        let _ = window.set_max_size(Some(Size::Physical(inner)));
+    }
+    let _ = window.set_position(PhysicalPosition::new(x, y));
+    true
+}
+
+/// 监视显示器变化，必要时把主窗口移回可见范围；fit 在窗口尺寸改变后按游戏比例校正高度
+pub fn watch_displays(app: AppHandle, fit: fn(&tauri::Window)) {
+    std::thread::spawn(move || {
+        let mut last = app.get_window("main").map(|w| monitor_layout(&w));
+        while !crate::EXITING.load(Ordering::Relaxed) {
+            crate::pacing::wait(&DISPLAY_POLL);
+            let Some(window) = app.get_window("main") else {
+                continue;
+            };
+            let current = monitor_layout(&window);
+            if last.as_ref() == Some(&current) {
+                continue;
+            }
+            info!(
+                monitors = current.len(),
+                "[Layout] display configuration changed"
+            );
+            last = Some(current);
+            let app_for_task = app.clone();
+            let _ = app.run_on_main_thread(move || {
+                if rescue_window(&window) {
+                    fit(&window);
+                    crate::launcher::relayout_all(&app_for_task);
+                }
+            });
+        }
+    });
+}
+
+pub fn delete(name: &str) -> Result<bool, String> {
+    let mut removed = false;
+    crate::config::update(|config| {
+        removed = config.ui.layout_presets.remove(name).is_some();
+    })?;
+    Ok(removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn places_preset_on_other_monitors() {
+        let preset = LayoutPreset {
+            monitor: Some("\\\\.\\DISPLAY1".to_string()),
+            x: 100,
+            y: 50,
+            width: 1200,
+            height: 740,
+            scale_factor: 1.0,
+            webview_zoom: HashMap::new(),
+        };
+        let same = Area {
+            x: -1920,
+            y: 0,
+            width: 1920,
+            height: 1080,
+            scale_factor: 1.0,
+        };
+        assert_eq!(place(&preset, same), (-1820, 50, 1200, 740));
+
+        // 150% 缩放的显示器：尺寸和偏移一起放大
+        let hidpi = Area {
+            x: 0,
+            y: 0,
+            width: 2560,
+            height: 1440,
+            scale_factor: 1.5,
+        };
+        assert_eq!(place(&preset, hidpi), (150, 75, 1800, 1110));
+
+        // 放不下时缩到显示器大小并贴边
+        let small = Area {
+            x: 0,
+            y: 0,
+            width: 1024,
+            height: 768,
+            scale_factor: 1.0,
+        };
+        assert_eq!(place(&preset, small), (0, 28, 1024, 740));
+    }
+
+    #[test]
+    fn rescues_windows_left_on_a_removed_monitor() {
+        let laptop = Area {
+            x: 0,
+            y: 0,
+            width: 1920,
+            height: 1080,
+            scale_factor: 1.25,
+        };
+        // 原本在右侧 4K 显示器上的窗口
+        let rect = (2400, 300, 2200, 1300);
+        assert!(needs_rescue(rect, &[laptop]));
+        assert_eq!(fit_into(rect, laptop), (0, 0, 1920, 1080));
+
+        // 跨在两块屏幕边缘、仍有足够部分可见的窗口不动
+        assert!(!needs_rescue((1500, 200, 1200, 740), &[laptop]));
+        assert!(needs_rescue((1900, 200, 1200, 740), &[laptop]));
+    }
+}
@@ -6,79 +6,71 @@ use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use tauri::{AppHandle, Manager, State};
 use tracing::info;
 
+use crate::login_diagnostics::{self, FailureReason};
+use crate::redact;
 use crate::state::{emit_status, AppState, AppStatus};
 
 const LOGIN3_PATH_NEEDLE: &str = "/fcgi-bin/login3";
-const MAX_RESPONSE_BYTES: usize = 1_500_000;
-const TIMEOUT_SECS: u64 = 180;
 
-fn debug_log(message: &str) {
-    info!("[RocoKnight][login3] {message}");
+/// 抓取超时（login.capture_timeout_secs）的取值范围（秒）
+const MIN_TIMEOUT_SECS: u64 = 30;
+const MAX_TIMEOUT_SECS: u64 = 1800;
+/// 响应读取上限（login.max_response_bytes）的取值范围
+const MIN_RESPONSE_BYTES: usize = 64 * 1024;
+const MAX_RESPONSE_BYTES: usize = 16 * 1024 * 1024;
+
+/// 生效的抓取限制（配置值限制在合理范围内）
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize)]
+pub struct CaptureLimits {
+    pub timeout_secs: u64,
+    pub max_response_bytes: usize,
 }
 
-fn redact_url(url: &str) -> String {
-    match url.split_once('?') {
-        Some((base, _)) => format!("{base}?REDACTED"),
-        None => url.to_string(),
+impl CaptureLimits {
+    pub fn clamped(timeout_secs: u64, max_response_bytes: usize) -> Self {
+        Self {
+            timeout_secs: timeout_secs.clamp(MIN_TIMEOUT_SECS, MAX_TIMEOUT_SECS),
+            max_response_bytes: max_response_bytes.clamp(MIN_RESPONSE_BYTES, MAX_RESPONSE_BYTES),
+        }
     }
-}
 
-fn redact_tokens(text: &str) -> String {
-    let keys = ["angel_uin", "angel_key", "skey", "pskey"];
-    let bytes = text.as_bytes();
-    let mut out = String::with_capacity(text.len());
-    let mut i = 0usize;
-    while i < bytes.len() {
-        let mut matched: Option<&str> = None;
-        for key in keys {
-            let kb = key.as_bytes();
-            if bytes[i..].starts_with(kb)
-                && i + kb.len() < bytes.len()
-                && bytes[i + kb.len()] == b'='
-            {
-                matched = Some(key);
-                break;
-            }
-        }
-        if let Some(key) = matched {
-            out.push_str(key);
-            out.push('=');
-            i += key.len() + 1;
-            while i < bytes.len() {
-                let b = bytes[i];
-                if b == b'&' || b == b'"' || b == b'\'' || b == b'<' || b == b'>' {
-                    break;
-                }
-                i += 1;
-            }
-            out.push('*');
-            continue;
-        }
-        out.push(bytes[i] as char);
-        i += 1;
+    pub fn current() -> Self {
+        let login = crate::config::current().login;
+        Self::clamped(login.capture_timeout_secs, login.max_response_bytes)
     }
-    out
 }
 
-fn sample_response(html: &str) -> String {
-    let max_len = 600;
-    let mut sample = html.replace('\r', " ").replace('\n', " ");
-    if sample.len() > max_len {
-        sample.truncate(max_len);
-    }
-    redact_tokens(&sample)
+/// 修改抓取限制并保存，超出范围的值按边界处理；下一次抓取开始生效
+pub fn set_limits(
+    timeout_secs: Option<u64>,
+    max_response_bytes: Option<usize>,
+) -> Result<CaptureLimits, String> {
+    let current = CaptureLimits::current();
+    let limits = CaptureLimits::clamped(
+        timeout_secs.unwrap_or(current.timeout_secs),
+        max_response_bytes.unwrap_or(current.max_response_bytes),
+    );
+    crate::config::update(|config| {
+        config.login.capture_timeout_secs = limits.timeout_secs;
+        config.login.max_response_bytes = limits.max_response_bytes;
+    })?;
+    info!(
+        timeout_secs = limits.timeout_secs,
+        max_response_bytes = limits.max_response_bytes,
+        "[RocoKnight][login3] capture limits updated"
+    );
+    Ok(limits)
 }
 
-fn redact_value(value: &str) -> String {
-    redact_tokens(value)
+fn debug_log(message: &str) {
+    info!("[RocoKnight][login3] {message}");
 }
 
-fn redact_swf_url(url: &str) -> String {
-    if let Some((base, query)) = url.split_once('?') {
-        let redacted = redact_tokens(query);
-        return format!("{base}?{redacted}");
-    }
-    url.to_string()
+fn sample_response(html: &str) -> String {
+    let sample = html.replace('\r', " ").replace('\n', " ");
+    // 先脱敏再截断，避免截断点落在参数名中间导致漏匹配
+    let redacted = redact::redact_secrets(&sample);
+    redact::truncate_chars(&redacted, 600).to_string()
 }
 
 fn maybe_dump_response(html: &str) {
@@ -104,7 +96,8 @@ fn maybe_dump_response(html: &str) {
                 .truncate(true)
                 .open(&path)
             {
-                let _ = file.write_all(html.as_bytes());
+                // 转储文件位于日志目录，可能随诊断包一起分享，同样脱敏
+                let _ = file.write_all(redact::redact_secrets(html).as_bytes());
                 debug_log(&format!("login3 response dumped to {}", path.display()));
             }
         }
@@ -122,6 +115,7 @@ pub fn start(app: AppHandle, state: State<Mutex<AppState>>) -> Result<(), String
     });
     emit_status(&app, &state.lock().expect("state lock"));
 
+    login_diagnostics::begin();
     start_timeout(app, stop_flag);
     debug_log("capture started");
     Ok(())
@@ -148,7 +142,8 @@ fn stop_inner(state: &State<Mutex<AppState>>) {
 
 fn start_timeout(app: AppHandle, stop_flag: Arc<AtomicBool>) {
     std::thread::spawn(move || {
-        let deadline = std::time::Instant::now() + Duration::from_secs(TIMEOUT_SECS);
+        let timeout_secs = CaptureLimits::current().timeout_secs;
+        let deadline = std::time::Instant::now() + Duration::from_secs(timeout_secs);
         while std::time::Instant::now() < deadline {
             if stop_flag.load(Ordering::Relaxed) {
                 return;
@@ -162,8 +157,10 @@ fn start_timeout(app: AppHandle, stop_flag: Arc<AtomicBool>) {
             let state = app.state::<Mutex<AppState>>();
             if let Ok(mut guard) = state.lock() {
                 if matches!(guard.status, AppStatus::Capturing) && guard.swf_url.is_none() {
+                    login_diagnostics::timed_out();
                     guard.status = AppStatus::Error;
-                    guard.message = Some("Login timed out (180s). Please retry.".to_string());
+                    guard.message =
+                        Some(format!("Login timed out ({timeout_secs}s). Please retry."));
                     emit_status(&app, &guard);
                 }
             };
@@ -176,27 +173,37 @@ pub fn handle_login3_response(app: &AppHandle, state: &State<Mutex<AppState>>, h
         debug_log("login3 response parsed: flashVars not found; sample follows");
         debug_log(&sample_response(html));
         maybe_dump_response(html);
+        login_diagnostics::fail(
+            app,
+            FailureReason::NoFlashVars,
+            Some(format!("{} bytes", html.len())),
+        );
         return;
     };
 
-    if !value.contains("config=") || !value.contains("angel_uin=") {
+    if let Some(reason) = missing_param(&value) {
         debug_log(&format!(
             "login3 response parsed: missing required params; value sample: {}",
             sample_response(&value)
         ));
         maybe_dump_response(html);
+        login_diagnostics::fail(app, reason, Some(sample_response(&value)));
         return;
     }
 
     let Some(swf_url) = build_swf_url(&value) else {
         debug_log("login3 response parsed: failed to build swf url");
+        login_diagnostics::fail(app, FailureReason::InvalidSwfUrl, None);
         return;
     };
     debug_log(&format!(
         "flashVars captured (redacted): {}",
-        redact_value(&value)
+        redact::redact_secrets(&value)
+    ));
+    debug_log(&format!(
+        "swf url (redacted): {}",
+        redact::redact_query(&swf_url)
     ));
-    debug_log(&format!("swf url (redacted): {}", redact_swf_url(&swf_url)));
 
     let should_emit = with_state(state, |s| {
         if matches!(s.status, AppStatus::Running) {
@@ -213,6 +220,9 @@ pub fn handle_login3_response(app: &AppHandle, state: &State<Mutex<AppState>>, h
 
     if should_emit {
         debug_log("login3 response parsed: value accepted, moving to launch");
+        login_diagnostics::succeed();
+        crate::saved_login::remember(&value);
+        crate::accounts::remember(&value);
         emit_status(app, &state.lock().expect("state lock"));
         with_state(state, |s| {
             s.status = AppStatus::Launching;
@@ -230,6 +240,17 @@ pub fn handle_login3_response(app: &AppHandle, state: &State<Mutex<AppState>>, h
     }
 }
 
+/// flashVars 缺少的必要参数
+fn missing_param(value: &str) -> Option<FailureReason> {
+    if !value.contains("config=") {
+        Some(FailureReason::MissingConfig)
+    } else if !value.contains("angel_uin=") {
+        Some(FailureReason::MissingAccount)
+    } else {
+        None
+    }
+}
+
 pub fn stop_timer_only(state: &State<Mutex<AppState>>) {
     with_state(state, |s| {
         if let Some(stop) = &s.capture_stop {
@@ -365,14 +386,16 @@ fn unescape_source(text: &str) -> String {
     out
 }
 
-fn build_swf_url(value: &str) -> Option<String> {
+pub fn build_swf_url(value: &str) -> Option<String> {
     let trimmed = value.trim().trim_start_matches('?').trim_start_matches('&');
     if trimmed.is_empty() {
         return None;
     }
     let nonce = build_nonce_key();
-    Some(format!(
-        "https://res.17roco.qq.com/main.swf?{nonce}=&{trimmed}"
+    Some(crate::endpoints::swf_url(
+        &crate::endpoints::swf_base(),
+        &nonce,
+        trimmed,
     ))
 }
 
@@ -390,130 +413,45 @@ fn with_state<R>(state: &State<Mutex<AppState>>, f: impl FnOnce(&mut AppState) -
     f(&mut guard)
 }
 
-#[cfg(windows)]
-pub fn attach_webview2_capture(webview: tauri::webview::PlatformWebview, app: AppHandle) {
-    use webview2_com::Microsoft::Web::WebView2::Win32::{
-        ICoreWebView2_2, COREWEBVIEW2_WEB_RESOURCE_CONTEXT_ALL,
-    };
-    use webview2_com::{
-        take_pwstr, WebResourceResponseReceivedEventHandler,
-        WebResourceResponseViewGetContentCompletedHandler,
-    };
-    use windows::core::{w, Interface, PWSTR};
-    use windows::Win32::System::Com::IStream;
-
-    let controller = webview.controller();
-    let core = match unsafe { controller.CoreWebView2() } {
-        Ok(core) => core,
-        Err(_) => {
-            debug_log("attach failed: CoreWebView2 not available");
-            return;
-        }
-    };
-
-    let _ = unsafe {
-        core.AddWebResourceRequestedFilter(w!("*"), COREWEBVIEW2_WEB_RESOURCE_CONTEXT_ALL)
-    };
-    debug_log("attach ok: WebResourceRequestedFilter added");
-
-    let app_handle = app.clone();
-    let response_handler =
-        WebResourceResponseReceivedEventHandler::create(Box::new(move |_webview, args| {
-            let Some(args) = args else {
-                return Ok(());
-            };
-            let request = unsafe { args.Request() }?;
-            let mut uri_pw = PWSTR::null();
-            unsafe { request.Uri(&mut uri_pw) }?;
-            let url = take_pwstr(uri_pw);
-            let url_lc = url.to_ascii_lowercase();
-            if !url_lc.contains(LOGIN3_PATH_NEEDLE) {
-                return Ok(());
-            }
-            debug_log(&format!("login3 response event: {}", redact_url(&url)));
-            let response = unsafe { args.Response() }?;
-            let app_for_content = app_handle.clone();
-            let handler = WebResourceResponseViewGetContentCompletedHandler::create(Box::new(
-                move |result, stream: Option<IStream>| {
-                    if result.is_err() {
-                        debug_log("login3 response GetContent failed");
-                        return Ok(());
-                    }
-                    let Some(stream) = stream else {
-                        debug_log("login3 response GetContent empty stream");
-                        return Ok(());
-                    };
-                    let html = read_stream_to_string(&stream, MAX_RESPONSE_BYTES);
-                    if let Some(html) = html {
-                        debug_log(&format!("login3 response size: {} bytes", html.len()));
-                        let state = app_for_content.state::<Mutex<AppState>>();
-                        handle_login3_response(&app_for_content, &state, &html);
-                    } else {
-                        debug_log("login3 response read_stream_to_string failed");
-                    }
-                    Ok(())
-                },
+/// 注册 login3 响应的抓取（在附加 webview_capture 之前调用）
+pub fn register_capture() {
+    crate::webview_capture::register(
+        "login3",
+        |url| url.contains(LOGIN3_PATH_NEEDLE),
+        || CaptureLimits::current().max_response_bytes,
+        |app, url, body| {
+            debug_log(&format!(
+                "login3 response event: {}",
+                redact::redact_url(url)
             ));
-            let _ = unsafe { response.GetContent(&handler) };
-            Ok(())
-        }));
-
-    let core2: ICoreWebView2_2 = match core.cast() {
-        Ok(core2) => core2,
-        Err(_) => {
-            return;
-        }
-    };
-    let mut token: i64 = 0;
-    let _ = unsafe { core2.add_WebResourceResponseReceived(&response_handler, &mut token) };
-    std::mem::forget(response_handler);
-    debug_log("attach ok: WebResourceResponseReceived handler registered");
-}
-
-#[cfg(not(windows))]
-pub fn attach_webview2_capture(_webview: tauri::webview::PlatformWebview, _app: AppHandle) {}
-
-#[cfg(windows)]
-fn read_stream_to_string(
-    stream: &windows::Win32::System::Com::IStream,
-    limit: usize,
-) -> Option<String> {
-    let mut buf = Vec::new();
-    let mut chunk = [0u8; 4096];
-    let mut total = 0usize;
-    loop {
-        let mut read = 0u32;
-        let hr = unsafe {
-            stream.Read(
-                chunk.as_mut_ptr() as *mut _,
-                chunk.len() as u32,
-                Some(&mut read),
-            )
-        };
-        if hr.is_err() {
-            return None;
-        }
-        if read == 0 {
-            break;
-        }
-        let take = read as usize;
-        let remaining = limit.saturating_sub(total);
-        if remaining == 0 {
-            break;
-        }
-        let slice_len = take.min(remaining);
-        buf.extend_from_slice(&chunk[..slice_len]);
-        total += slice_len;
-        if total >= limit {
-            break;
-        }
-    }
-    Some(String::from_utf8_lossy(&buf).to_string())
+            match body {
+                Ok(html) => {
+                    debug_log(&format!("login3 response size: {} bytes", html.len()));
+                    let state = app.state::<Mutex<AppState>>();
+                    handle_login3_response(app, &state, &html);
+                }
+                Err(e) => {
+                    debug_log(&format!("login3 response read failed: {e}"));
+                    login_diagnostics::fail(app, FailureReason::StreamReadError, Some(e));
+                }
+            }
+        },
+    );
 }
 
 #[cfg(test)]
 mod tests {
-    use super::parse_login3_value;
+    use super::{parse_login3_value, CaptureLimits};
+
+    #[test]
+    fn capture_limits_are_clamped() {
+        let limits = CaptureLimits::clamped(5, usize::MAX);
+        assert_eq!(limits.timeout_secs, 30);
+        assert_eq!(limits.max_response_bytes, 16 * 1024 * 1024);
+        let limits = CaptureLimits::clamped(600, 2_000_000);
+        assert_eq!(limits.timeout_secs, 600);
+        assert_eq!(limits.max_response_bytes, 2_000_000);
+    }
 
     #[test]
     fn parse_value_from_script() {
@@ -0,0 +1,306 @@
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use tauri::{AppHandle, Manager};
+use tracing::{info, warn};
+
+use crate::state::{emit_status, AppState, AppStatus};
+
+// login3 抓取重试与诊断
+//
+// login3 响应无法解析（找不到 flashVars、缺少 config= 或 angel_uin=、读取响应内容失败）时，
+// 记录结构化的失败原因，并在抓取中自动刷新登录页重试，最多 login.capture_retries 次；
+// 重试用尽后直接进入 Error，不再等到抓取超时（login.capture_timeout_secs）。超时本身也记为一次失败。
+// 最近的失败记录通过 get_login_diagnostics 查询，也会写入诊断包，供排查登录问题。
+
+/// 最多保留的失败记录条数
+const MAX_FAILURES: usize = 20;
+/// 刷新登录页前的等待，避免服务器异常时连续请求
+const RETRY_DELAY: Duration = Duration::from_secs(2);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FailureReason {
+    /// 响应中没有 flashVars
+    NoFlashVars,
+    /// flashVars 中缺少 config=
+    MissingConfig,
+    /// flashVars 中缺少 angel_uin=
+    MissingAccount,
+    /// 读取响应内容失败
+    StreamReadError,
+    /// 无法拼接 main.swf 地址
+    InvalidSwfUrl,
+    /// 超时仍未抓取到有效响应
+    Timeout,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CaptureState {
+    /// 尚未开始抓取
+    Idle,
+    Capturing,
+    /// 已安排刷新登录页
+    Retrying,
+    Succeeded,
+    /// 重试用尽或超时
+    Failed,
+}
+
+/// 一次失败之后的处理
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Next {
+    /// 刷新登录页重试
+    Retry,
+    /// 放弃本轮抓取
+    GiveUp,
+    /// 只记录（已在重试中，或不在抓取中）
+    Ignore,
+}
+
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct LoginFailure {
+    /// 发生时间（Unix 毫秒）
+    pub at: u64,
+    pub reason: FailureReason,
+    /// 第几次尝试（从 0 开始，0 为首次加载登录页）
+    pub attempt: u32,
+    /// 已脱敏的补充信息
+    pub detail: Option<String>,
+}
+
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct LoginDiagnostics {
+    pub state: CaptureState,
+    /// 本轮抓取已经刷新登录页的次数
+    pub attempt: u32,
+    pub max_retries: u32,
+    pub last_success_at: Option<u64>,
+    /// 最近的失败记录，按时间顺序
+    pub failures: Vec<LoginFailure>,
+}
+
+impl LoginDiagnostics {
+    const fn new() -> Self {
+        Self {
+            state: CaptureState::Idle,
+            attempt: 0,
+            max_retries: 0,
+            last_success_at: None,
+            failures: Vec::new(),
+        }
+    }
+
+    /// 开始抓取；由重试触发的重新加载沿用本轮的计数
+    fn begin(&mut self, max_retries: u32) {
+        if self.state != CaptureState::Retrying {
+            self.attempt = 0;
+        }
+        self.max_retries = max_retries;
+        self.state = CaptureState::Capturing;
+    }
+
+    fn record(&mut self, reason: FailureReason, detail: Option<String>, at: u64) {
+        self.failures.push(LoginFailure {
+            at,
+            reason,
+            attempt: self.attempt,
+            detail,
+        });
+        if self.failures.len() > MAX_FAILURES {
+            self.failures.remove(0);
+        }
+    }
+
+    /// 记录一次失败并决定下一步；超时不重试
+    fn fail(&mut self, reason: FailureReason, detail: Option<String>, at: u64) -> Next {
+        self.record(reason, detail, at);
+        let timeout = reason == FailureReason::Timeout;
+        match self.state {
+            CaptureState::Capturing if !timeout && self.attempt < self.max_retries => {
+                self.attempt += 1;
+                self.state = CaptureState::Retrying;
+                Next::Retry
+            }
+            CaptureState::Capturing => {
+                self.state = CaptureState::Failed;
+                Next::GiveUp
+            }
+            CaptureState::Retrying if timeout => {
+                self.state = CaptureState::Failed;
+                Next::GiveUp
+            }
+            _ => Next::Ignore,
+        }
+    }
+
+    fn succeed(&mut self, at: u64) {
+        self.state = CaptureState::Succeeded;
+        self.last_success_at = Some(at);
+    }
+}
+
+static DIAGNOSTICS: Mutex<LoginDiagnostics> = Mutex::new(LoginDiagnostics::new());
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+fn diagnostics() -> MutexGuard<'static, LoginDiagnostics> {
+    DIAGNOSTICS.lock().expect("login diagnostics lock")
+}
+
+/// 当前的诊断信息
+pub fn snapshot() -> LoginDiagnostics {
+    diagnostics().clone()
+}
+
+/// login3 抓取开始时调用
+pub fn begin() {
+    diagnostics().begin(crate::config::current().login.capture_retries);
+}
+
+/// 抓取到有效的 login3 值
+pub fn succeed() {
+    diagnostics().succeed(now_ms());
+}
+
+/// 抓取超时（计时线程持有状态锁时调用，状态由调用方切换到 Error）
+pub fn timed_out() {
+    diagnostics().fail(FailureReason::Timeout, None, now_ms());
+    warn!("[Login] login3 capture timed out");
+}
+
+/// 记录一次抓取失败；仍在抓取且未用尽重试次数时刷新登录页，否则进入 Error
+pub fn fail(app: &AppHandle, reason: FailureReason, detail: Option<String>) {
+    let capturing = app
+        .state::<Mutex<AppState>>()
+        .lock()
+        .expect("state lock")
+        .status
+        == AppStatus::Capturing;
+    let (next, attempt, max_retries) = {
+        let mut diagnostics = diagnostics();
+        let next = if capturing {
+            diagnostics.fail(reason, detail, now_ms())
+        } else {
+            // 已经启动或已回到登录页时收到的响应只记录
+            diagnostics.record(reason, detail, now_ms());
+            Next::Ignore
+        };
+        (next, diagnostics.attempt, diagnostics.max_retries)
+    };
+    warn!(
+        reason = ?reason,
+        attempt = attempt,
+        max_retries = max_retries,
+        next = ?next,
+        "[Login] login3 capture failed"
+    );
+    if next == Next::Retry {
+        schedule_refresh(app.clone(), attempt, max_retries);
+    } else if next == Next::GiveUp {
+        let state = app.state::<Mutex<AppState>>();
+        crate::login3_capture::stop_timer_only(&state);
+        let mut guard = state.lock().expect("state lock");
+        guard.status = AppStatus::Error;
+        guard.message = Some(format!(
+            "Login failed after {attempt} retries ({}). Please retry.",
+            reason_label(reason)
+        ));
+        emit_status(app, &guard);
+    }
+}
+
+fn reason_label(reason: FailureReason) -> &'static str {
+    match reason {
+        FailureReason::NoFlashVars => "flashVars not found",
+        FailureReason::MissingConfig => "config= missing",
+        FailureReason::MissingAccount => "angel_uin= missing",
+        FailureReason::StreamReadError => "response read error",
+        FailureReason::InvalidSwfUrl => "invalid swf url",
+        FailureReason::Timeout => "timed out",
+    }
+}
+
+/// 稍后刷新登录页；重新加载会再次触发 login3_capture::start
+fn schedule_refresh(app: AppHandle, attempt: u32, max_retries: u32) {
+    {
+        let state = app.state::<Mutex<AppState>>();
+        let mut guard = state.lock().expect("state lock");
+        guard.message = Some(format!(
+            "Login response invalid, retrying ({attempt}/{max_retries})"
+        ));
+        emit_status(&app, &guard);
+    }
+    std::thread::spawn(move || {
+        std::thread::sleep(RETRY_DELAY);
+        if diagnostics().state != CaptureState::Retrying {
+            return;
+        }
+        info!(attempt = attempt, "[Login] refreshing login page");
+        let handle = app.clone();
+        let _ = app.run_on_main_thread(move || {
+            let Some(login) = handle.get_webview("login") else {
+                return;
+            };
+            match crate::endpoints::login_url().parse() {
+                Ok(url) => {
+                    if let Err(e) = login.navigate(url) {
+                        warn!(error = %e, "[Login] failed to refresh login page");
+                    }
+                }
+                Err(_) => warn!("[Login] invalid login URL, not refreshing"),
+            }
+        });
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn retries_until_exhausted_then_fails() {
+        let mut diagnostics = LoginDiagnostics::new();
+        diagnostics.begin(2);
+        assert_eq!(
+            diagnostics.fail(FailureReason::NoFlashVars, None, 1),
+            Next::Retry
+        );
+        // 等待刷新期间的失败只记录
+        assert_eq!(
+            diagnostics.fail(FailureReason::NoFlashVars, None, 1),
+            Next::Ignore
+        );
+        diagnostics.begin(2);
+        assert_eq!(
+            diagnostics.fail(FailureReason::MissingConfig, None, 2),
+            Next::Retry
+        );
+        diagnostics.begin(2);
+        assert_eq!(diagnostics.attempt, 2);
+        assert_eq!(
+            diagnostics.fail(FailureReason::StreamReadError, None, 3),
+            Next::GiveUp
+        );
+        assert_eq!(diagnostics.state, CaptureState::Failed);
+        let attempts: Vec<u32> = diagnostics.failures.iter().map(|f| f.attempt).collect();
+        assert_eq!(attempts, vec![0, 1, 1, 2]);
+
+        // 新一轮抓取重新计数，超时不触发重试
+        diagnostics.begin(2);
+        assert_eq!(diagnostics.attempt, 0);
+        assert_eq!(
+            diagnostics.fail(FailureReason::Timeout, None, 4),
+            Next::GiveUp
+        );
+        diagnostics.succeed(5);
+        assert_eq!(diagnostics.state, CaptureState::Succeeded);
+        assert_eq!(diagnostics.last_success_at, Some(5));
+    }
+}
@@ -1,15 +1,47 @@
 #![cfg_attr(target_os = "windows", windows_subsystem = "windows")]
 
+mod accounts;
+mod autostart;
+mod channel;
+mod cleanup;
+mod cli;
+mod client_version;
+mod config;
 mod debug;
 mod debug_console_layer;
 mod debug_log_bus;
+mod diagnostics;
+mod disguise;
 mod embed_win32;
+mod endpoints;
 mod error_handling;
+mod events;
+mod game_config;
+mod headless_login;
+mod hotkey;
 mod launcher;
+mod layout;
 mod login3_capture;
+mod login_diagnostics;
+mod notify;
+mod pacing;
+mod prefetch;
+mod preflight;
+mod packet_stream;
 mod projector;
+mod projector_manager;
+mod redact;
 mod request_context;
+mod saved_login;
+mod session_history;
+mod shutdown;
+mod snapshot;
+mod sound;
 mod state;
+mod timers;
+mod usage;
+mod webhook;
+mod webview_capture;
 mod wpe;
 
 use std::io::Write;
@@ -25,16 +57,16 @@ use tracing::{error, info};
 
 use crate::embed_win32::{disable_maximize_resize, parent_client_size, set_dpi_awareness};
 use crate::launcher::{
-    resize_login_to_window, resize_projector_to_window, schedule_login_layout,
+    relayout_all, resize_login_to_window, resize_projector_to_window, schedule_login_layout,
     stop_projector as stop_projector_state,
 };
-use crate::state::{emit_status, AppState, AppStatus, ThemeMode};
+use crate::state::{emit_status, AppState, AppStatus, Pipeline, ThemeMode};
 
 // 全局退出标志（所有模块可见）
 pub static EXITING: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
 
-/// 请求退出（必定在 100ms 内退出进程）
-fn request_exit() {
+/// 请求退出（清理超时后由兜底线程强制退出进程，见 shutdown 模块）
+fn request_exit(app: &AppHandle) {
     // 设置全局退出标志
     if EXITING.swap(true, std::sync::atomic::Ordering::SeqCst) {
         // 已经在退出中，直接返回
@@ -44,17 +76,30 @@ fn request_exit() {
 
     startup_log("request_exit: EXITING set to true");
 
-    // 立即启动兜底线程（100ms 后强制退出）
-    std::thread::spawn(|| {
-        std::thread::sleep(std::time::Duration::from_millis(100));
+    let fallback = shutdown::fallback_delay();
+    shutdown::begin(fallback);
+
+    // 先启动兜底线程，清理步骤卡住时同样能退出
+    std::thread::spawn(move || {
+        std::thread::sleep(fallback);
+        shutdown::force();
         startup_log("request_exit: fallback triggered -> process::exit(0)");
         std::process::exit(0);
     });
 
-    startup_log("request_exit: fallback thread spawned (will exit in 100ms)");
+    startup_log(&format!(
+        "request_exit: fallback thread spawned (will exit in {}ms)",
+        fallback.as_millis()
+    ));
+
+    session_history::end_on_exit("app_exit");
+    shutdown::step(shutdown::STEP_SESSION_HISTORY);
+    stop_projector_state(&app.state::<Mutex<AppState>>());
+    shutdown::step(shutdown::STEP_PROJECTOR);
+    shutdown::finish();
+    startup_log("request_exit: cleanup finished");
 }
 
-
 static LAST_WINDOW_SIZE: OnceLock<Mutex<Option<PhysicalSize<u32>>>> = OnceLock::new();
 const LOG_MAX_BYTES: u64 = 5 * 1024 * 1024;
 const LOG_TRIM_BYTES: usize = 1 * 1024 * 1024;
@@ -138,7 +183,12 @@ fn init_startup_log() {
 fn startup_log(message: &str) {
     if let Some(lock) = STARTUP_LOG.get() {
         if let Ok(mut file) = lock.lock() {
-            let _ = writeln!(file, "[{:?}] {}", std::time::SystemTime::now(), message);
+            let _ = writeln!(
+                file,
+                "[{:?}] {}",
+                std::time::SystemTime::now(),
+                redact::redact_secrets(message)
+            );
         }
     }
 }
@@ -274,10 +324,217 @@ fn set_theme_mode(
             s.theme_mode = mode;
         });
         apply_theme_to_app(&app, mode);
+        relayout_all(&app);
         Ok(mode.as_str().to_string())
     })
 }
 
+#[tauri::command]
+fn get_config() -> config::CoreConfig {
+    config::current()
+}
+
+#[tauri::command]
+fn set_config(app: AppHandle, config: config::CoreConfig) -> Result<config::CoreConfig, String> {
+    request_context::wrap_command("set_config", 200, || {
+        let updated = crate::config::update(|current| *current = config)?;
+        autostart::sync_with_config();
+        disguise::apply_hotkey(&app);
+        timers::apply_hotkey(&app);
+        Ok(updated)
+    })
+}
+
+#[tauri::command]
+fn list_experiments() -> Vec<config::ExperimentState> {
+    config::list_experiments()
+}
+
+/// 打开/关闭实验开关；多数子系统在下次启动对应功能时生效
+#[tauri::command]
+fn set_experiment(name: String, enabled: bool) -> Result<Vec<config::ExperimentState>, String> {
+    request_context::wrap_command("set_experiment", 200, || {
+        config::set_experiment(&name, enabled)?;
+        Ok(config::list_experiments())
+    })
+}
+
+/// 缩放倍率允许的范围
+const WEBVIEW_ZOOM_RANGE: std::ops::RangeInclusive<f64> = 0.5..=3.0;
+
+/// 设置指定 webview 的缩放倍率并持久化
+#[tauri::command]
+fn set_webview_zoom(app: AppHandle, label: String, factor: f64) -> Result<f64, String> {
+    request_context::wrap_command("set_webview_zoom", 200, || {
+        if !factor.is_finite() {
+            return Err("Invalid zoom factor.".to_string());
+        }
+        let factor = factor.clamp(*WEBVIEW_ZOOM_RANGE.start(), *WEBVIEW_ZOOM_RANGE.end());
+        let webview = app
+            .get_webview(&label)
+            .ok_or_else(|| format!("Webview not found: {label}"))?;
+        webview
+            .set_zoom(factor)
+            .map_err(|e| format!("Failed to set zoom: {e}"))?;
+        config::update(|config| {
+            config.ui.webview_zoom.insert(label.clone(), factor);
+        })?;
+        info!(label = %label, factor = factor, "webview zoom updated");
+        Ok(factor)
+    })
+}
+
+#[tauri::command]
+fn get_webview_zoom(label: String) -> f64 {
+    launcher::webview_zoom(&label)
+}
+
+/// 向指定 webhook 发送测试消息（等待请求完成，不在主线程执行）
+#[tauri::command(async)]
+fn test_webhook(name: String) -> Result<(), String> {
+    request_context::wrap_command("test_webhook", 6000, || webhook::test_fire(&name))
+}
+
+#[tauri::command]
+fn get_autostart() -> autostart::AutostartStatus {
+    autostart::status()
+}
+
+#[tauri::command]
+fn set_autostart(enabled: bool) -> Result<autostart::AutostartStatus, String> {
+    request_context::wrap_command("set_autostart", 500, || {
+        autostart::apply(enabled)?;
+        config::update(|config| config.launcher.autostart = enabled)?;
+        Ok(autostart::status())
+    })
+}
+
+/// 推迟当前会话的自动重启（默认 30 分钟，最多 24 小时）
+#[tauri::command]
+fn postpone_auto_restart(
+    minutes: Option<u64>,
+    state: State<Mutex<AppState>>,
+) -> Result<(), String> {
+    let duration = projector::auto_restart::postpone_duration(minutes)?;
+    let pid = with_state(&state, |s| s.projector.as_ref().map(|p| p.process.pid))
+        .ok_or_else(|| "Projector is not running.".to_string())?;
+    projector::auto_restart::postpone(pid, duration);
+    Ok(())
+}
+
+/// 当前 projector 的资源占用（CPU、工作集、GPU）及最近 5 分钟的采样
+#[tauri::command]
+fn get_projector_metrics(
+    state: State<Mutex<AppState>>,
+) -> Result<projector::metrics::ProjectorMetrics, String> {
+    with_state(&state, |s| s.projector.as_ref().map(projector::metrics::snapshot))
+        .ok_or_else(|| "Projector is not running.".to_string())
+}
+
+/// 取消断线后的自动重连倒计时（状态保持为 Disconnected）
+#[tauri::command]
+fn cancel_reconnect() -> Result<(), String> {
+    if projector::reconnect::cancel() {
+        Ok(())
+    } else {
+        Err("No reconnect is pending.".to_string())
+    }
+}
+
+#[tauri::command]
+fn list_layout_presets() -> Vec<String> {
+    layout::list()
+}
+
+/// 把当前窗口布局保存为命名预设
+#[tauri::command]
+fn save_layout_preset(app: AppHandle, name: String) -> Result<layout::LayoutPreset, String> {
+    request_context::wrap_command("save_layout_preset", 200, || layout::save(&app, &name))
+}
+
+/// 恢复命名的窗口布局预设
+#[tauri::command]
+fn apply_layout_preset(app: AppHandle, name: String) -> Result<(), String> {
+    request_context::wrap_command("apply_layout_preset", 500, || {
+        layout::apply(&app, &name)?;
+        if let Some(window) = app.get_window("main") {
+            align_window_height_for_game_ratio(&window);
+        }
+        relayout_all(&app);
+        Ok(())
+    })
+}
+
+#[tauri::command]
+fn delete_layout_preset(name: String) -> Result<bool, String> {
+    layout::delete(&name)
+}
+
+/// 内置提示音名称
+#[tauri::command]
+fn list_builtin_sounds() -> Vec<&'static str> {
+    sound::BUILTIN_SOUNDS.iter().map(|(name, _)| *name).collect()
+}
+
+/// 设置事件的提示音（内置名称或 .wav 文件路径），为空时该事件不播放
+#[tauri::command]
+fn set_event_sound(event: sound::SoundEvent, sound: Option<String>) -> Result<(), String> {
+    request_context::wrap_command("set_event_sound", 200, || sound::set_event_sound(event, sound))
+}
+
+#[tauri::command]
+fn set_sound_volume(volume: u8) -> Result<u8, String> {
+    let volume = volume.min(100);
+    config::update(|config| config.sounds.volume = volume)?;
+    Ok(volume)
+}
+
+#[tauri::command]
+fn set_sound_muted(muted: bool) -> Result<(), String> {
+    config::update(|config| config.sounds.muted = muted)?;
+    info!(muted = muted, "[Sound] mute toggled");
+    Ok(())
+}
+
+/// 试听提示音
+#[tauri::command]
+fn preview_sound(sound: String) -> Result<(), String> {
+    sound::preview(&sound)
+}
+
+#[tauri::command]
+fn get_disguise() -> bool {
+    disguise::is_active()
+}
+
+/// 开启/关闭隐私伪装（窗口标题、任务栏图标、提醒）
+#[tauri::command]
+fn set_disguise(app: AppHandle, active: bool) -> Result<bool, String> {
+    request_context::wrap_command("set_disguise", 200, || {
+        disguise::set_active(&app, active)?;
+        Ok(active)
+    })
+}
+
+/// 开始叠加层倒计时，返回倒计时 ID；未指定名称时使用配置中的默认名称
+#[tauri::command]
+fn start_countdown(label: Option<String>, secs: u64) -> Result<u32, String> {
+    let label = label
+        .filter(|l| !l.trim().is_empty())
+        .unwrap_or_else(|| config::current().timers.countdown_label);
+    timers::start_countdown(label, secs)
+}
+
+#[tauri::command]
+fn cancel_countdown(id: u32) -> bool {
+    timers::cancel_countdown(id)
+}
+
+#[tauri::command]
+fn get_timers() -> timers::TimersSnapshot {
+    timers::snapshot()
+}
+
 #[tauri::command]
 fn start_login3_capture(app: AppHandle, state: State<Mutex<AppState>>) -> Result<(), String> {
     request_context::wrap_command("start_login3_capture", 500, || {
@@ -351,11 +608,35 @@ fn stop_projector_command(state: &State<Mutex<AppState>>) {
 fn stop_projector(app: AppHandle, state: State<Mutex<AppState>>) {
     let _timer = request_context::CommandTimer::new("stop_projector", 500);
     tracing::info!("command invoked");
+    session_history::end_current("user_stop", false);
+    projector::reconnect::cancel();
     stop_projector_command(&state);
     emit_status(&app, &state.lock().expect("state lock"));
     tracing::info!("projector stopped and status emitted");
 }
 
+/// 当前是否为独立窗口模式
+#[tauri::command]
+fn get_external_window() -> bool {
+    config::current().launcher.external_window
+}
+
+/// 切换独立窗口模式（保存到配置），返回生效的值；projector 运行中时立即弹出或重新嵌入主窗口
+#[tauri::command]
+fn set_external_window(
+    app: AppHandle,
+    enabled: bool,
+    state: State<Mutex<AppState>>,
+) -> Result<bool, String> {
+    request_context::wrap_command("set_external_window", 500, || {
+        // 先切换运行中的窗口，失败时不改写配置
+        launcher::set_projector_embedded(&app, &state, !enabled)?;
+        config::update(|config| config.launcher.external_window = enabled)?;
+        info!(enabled = enabled, "[Launcher] external window mode toggled");
+        Ok(enabled)
+    })
+}
+
 #[tauri::command]
 fn restart_projector(
     app: AppHandle,
@@ -380,6 +661,393 @@ fn restart_projector(
     }
 }
 
+#[tauri::command]
+fn get_projector_info(
+    state: State<Mutex<AppState>>,
+) -> Option<projector::introspect::ProcessInfo> {
+    with_state(&state, |s| s.projector.as_ref().and_then(|p| p.info.clone()))
+}
+
+#[tauri::command]
+fn get_session_history(limit: Option<usize>) -> Vec<session_history::SessionRecord> {
+    session_history::recent(limit.unwrap_or(50))
+}
+
+/// 清除启动器的全部本地数据（日志、抓取结果、配置、会话记录、WebView 数据）
+///
+/// confirm 为 false 时只返回将被删除的目录，界面确认后再以 true 调用。
+#[tauri::command]
+fn cleanup_all_data(
+    app: AppHandle,
+    state: State<Mutex<AppState>>,
+    confirm: bool,
+) -> Result<cleanup::CleanupReport, String> {
+    request_context::wrap_command("cleanup_all_data", 5000, || {
+        let _timer = request_context::CommandTimer::new("cleanup_all_data", 5000);
+        if confirm {
+            // 先结束 projector，释放它占用的文件
+            session_history::end_current("cleanup", false);
+            if let Err(e) = autostart::apply(false) {
+                tracing::warn!(error = %e, "[Autostart] failed to remove autostart entry");
+            }
+            stop_projector_state(&state);
+            emit_status(&app, &state.lock().expect("state lock"));
+        }
+        Ok(cleanup::cleanup_all(&app, confirm))
+    })
+}
+
+/// 剪贴板导入结果
+#[derive(serde::Serialize)]
+struct ImportedPacketInfo {
+    name: String,
+    kind: &'static str,
+    command: Option<u16>,
+    command_name: Option<&'static str>,
+    length: usize,
+    warnings: Vec<String>,
+}
+
+/// 当前登录的账号（QQ 号未知时为 None）
+fn current_account(state: &State<Mutex<AppState>>) -> Option<u64> {
+    with_state(state, |s| s.qq_num.filter(|qq| *qq != 0))
+}
+
+/// 校验封包模板但不发送：展开 {qq} 占位符，按命令登记表检查，返回将要发送的确切字节
+#[tauri::command]
+fn validate_packet(
+    template: String,
+    state: State<Mutex<AppState>>,
+) -> Result<wpe::hexdump::PacketPreview, String> {
+    wpe::hexdump::preview(&template, current_account(&state)).map_err(|e| e.to_string())
+}
+
+/// 从粘贴的十六进制/WPE 转储导入封包并加入发送列表
+#[tauri::command]
+fn import_packet_from_hex(
+    text: String,
+    name: Option<String>,
+    state: State<Mutex<AppState>>,
+) -> Result<ImportedPacketInfo, String> {
+    let text = wpe::hexdump::expand_placeholders(&text, current_account(&state))
+        .map_err(|e| e.to_string())?;
+    let imported = wpe::hexdump::import(&text).map_err(|e| e.to_string())?;
+    let command = match &imported.packet {
+        wpe::GamePacket::Binary { command, .. } => Some(*command),
+        wpe::GamePacket::Text(_) => None,
+    };
+    let info = with_state(&state, |s| {
+        let name = name
+            .filter(|n| !n.trim().is_empty())
+            .unwrap_or_else(|| s.send_list.next_name("Imported"));
+        s.send_list.upsert(name.clone(), imported.bytes.clone());
+        ImportedPacketInfo {
+            name,
+            kind: if command.is_some() { "binary" } else { "text" },
+            command,
+            command_name: imported.command_name,
+            length: imported.bytes.len(),
+            warnings: imported.warnings,
+        }
+    });
+    info!(
+        name = %info.name,
+        bytes = info.length,
+        warnings = info.warnings.len(),
+        "packet imported into send list"
+    );
+    Ok(info)
+}
+
+/// 向发送列表添加（或替换同名）封包，text 为十六进制文本
+#[tauri::command]
+fn sendlist_add(name: String, text: String, state: State<Mutex<AppState>>) -> Result<(), String> {
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err("Send list entry name is empty.".to_string());
+    }
+    let text = wpe::hexdump::expand_placeholders(&text, current_account(&state))
+        .map_err(|e| e.to_string())?;
+    let bytes = wpe::hexdump::parse_hex(&text).map_err(|e| e.to_string())?;
+    wpe::GamePacket::parse(&bytes).map_err(|e| e.to_string())?;
+    with_state(&state, |s| s.send_list.upsert(name.clone(), bytes));
+    info!(name = %name, "send list entry added");
+    Ok(())
+}
+
+/// 按计划回放发送列表（names 为空时发送全部条目）
+#[tauri::command]
+fn sendlist_start(
+    names: Option<Vec<String>>,
+    schedule: wpe::sendlist::SendSchedule,
+    build: Option<wpe::BuildMode>,
+    state: State<Mutex<AppState>>,
+) -> Result<(), String> {
+    request_context::wrap_command("sendlist_start", 200, || {
+        config::ensure_game_modification_allowed("Send list")?;
+        with_state(&state, |s| {
+            let interceptor = s
+                .wpe_interceptor
+                .clone()
+                .ok_or_else(|| "WPE is not running.".to_string())?;
+            interceptor.check_send().map_err(|e| e.to_string())?;
+            s.send_list
+                .start(&names.unwrap_or_default(), schedule, move |data| {
+                    interceptor.send_with(data, build.unwrap_or_default())
+                })
+                .map_err(|e| e.to_string())
+        })
+    })
+}
+
+#[tauri::command]
+fn sendlist_stop(state: State<Mutex<AppState>>) -> bool {
+    with_state(&state, |s| s.send_list.stop())
+}
+
+/// 开始向前端推送 packet_captured 事件（批量，每 200ms 一次）
+#[tauri::command]
+fn wpe_start_stream(app: AppHandle) {
+    packet_stream::start(app);
+}
+
+#[tauri::command]
+fn wpe_stop_stream() {
+    packet_stream::stop();
+}
+
+#[tauri::command]
+fn get_packet_stream_stats() -> packet_stream::PacketStreamStats {
+    packet_stream::get_stats()
+}
+
+#[tauri::command]
+fn get_recent_packets(limit: usize) -> Vec<packet_stream::PacketEvent> {
+    packet_stream::get_recent(limit)
+}
+
+/// 读取封包规则（文件不存在时为空）
+#[tauri::command]
+fn get_wpe_rules(app: AppHandle) -> Result<Vec<wpe::rules::PacketRule>, String> {
+    let path = launcher::wpe_rules_path(&app)?;
+    match std::fs::read_to_string(&path) {
+        Ok(text) => wpe::rules::RuleSet::from_json(&text)
+            .map(|rules| rules.rules().to_vec())
+            .map_err(|e| e.to_string()),
+        Err(_) => Ok(Vec::new()),
+    }
+}
+
+/// 保存封包规则，运行中的拦截器会自动重新加载
+#[tauri::command]
+fn set_wpe_rules(app: AppHandle, rules: Vec<wpe::rules::PacketRule>) -> Result<(), String> {
+    request_context::wrap_command("set_wpe_rules", 200, || {
+        config::ensure_game_modification_allowed("Packet rules")?;
+        let path = launcher::wpe_rules_path(&app)?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|_| "Failed to create app data dir.".to_string())?;
+        }
+        let json = serde_json::to_string_pretty(&rules)
+            .map_err(|_| "Failed to serialize rules.".to_string())?;
+        std::fs::write(&path, json).map_err(|_| "Failed to write rules file.".to_string())?;
+        info!(count = rules.len(), "packet rules saved");
+        Ok(())
+    })
+}
+
+/// 开始录制封包到 AppData/recordings，返回文件路径
+#[tauri::command]
+fn wpe_start_recording(app: AppHandle, state: State<Mutex<AppState>>) -> Result<String, String> {
+    request_context::wrap_command("wpe_start_recording", 500, || {
+        let interceptor = with_state(&state, |s| s.wpe_interceptor.clone())
+            .ok_or_else(|| "WPE is not running.".to_string())?;
+        let stamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        let path = app
+            .path()
+            .app_data_dir()
+            .map_err(|_| "Failed to resolve app data dir.".to_string())?
+            .join("recordings")
+            .join(format!("wpe-{stamp}.pcapng"));
+        interceptor
+            .start_recording(&path)
+            .map_err(|e| format!("Failed to start recording: {e}"))?;
+        Ok(path.display().to_string())
+    })
+}
+
+/// 结束录制，返回录制的封包数（未在录制时为 None）
+#[tauri::command]
+fn wpe_stop_recording(state: State<Mutex<AppState>>) -> Result<Option<usize>, String> {
+    let Some(interceptor) = with_state(&state, |s| s.wpe_interceptor.clone()) else {
+        return Ok(None);
+    };
+    interceptor
+        .stop_recording()
+        .map_err(|e| format!("Failed to finish recording: {e}"))
+}
+
+/// 是否正在录制（WPE 未运行时为 false），前端据此恢复录制按钮状态
+#[tauri::command]
+fn wpe_is_recording(state: State<Mutex<AppState>>) -> bool {
+    with_state(&state, |s| {
+        s.wpe_interceptor
+            .as_ref()
+            .is_some_and(|interceptor| interceptor.is_recording())
+    })
+}
+
+/// 封包流量统计：按命令号的出入站封包数、字节数和最近的每秒速率
+#[tauri::command]
+fn get_wpe_stats(state: State<Mutex<AppState>>) -> Result<wpe::stats::WpeStats, String> {
+    let interceptor = with_state(&state, |s| s.wpe_interceptor.clone())
+        .ok_or_else(|| "WPE is not running.".to_string())?;
+    Ok(interceptor.stats())
+}
+
+/// 十六进制查看器：当前捕获会话可读取的封包序号范围
+#[tauri::command]
+fn get_packet_history(
+    state: State<Mutex<AppState>>,
+) -> Result<wpe::hexview::HistoryRange, String> {
+    let interceptor = with_state(&state, |s| s.wpe_interceptor.clone())
+        .ok_or_else(|| "WPE is not running.".to_string())?;
+    Ok(interceptor.history_range())
+}
+
+/// 十六进制查看器：分页读取封包字节（len 最多 4096），附带头部字段和命中规则的标注
+#[tauri::command]
+fn get_packet_bytes(
+    session: u64,
+    index: u64,
+    offset: usize,
+    len: usize,
+    state: State<Mutex<AppState>>,
+) -> Result<wpe::hexview::PacketBytes, String> {
+    let interceptor = with_state(&state, |s| s.wpe_interceptor.clone())
+        .ok_or_else(|| "WPE is not running.".to_string())?;
+    interceptor
+        .packet_bytes(session, index, offset, len)
+        .map_err(|e| format!("Failed to read packet: {e}"))
+}
+
+/// 把最近截获的封包导出为 pcapng（可用 Wireshark 打开），返回导出的封包数
+#[tauri::command]
+fn wpe_export_pcap(path: String, state: State<Mutex<AppState>>) -> Result<usize, String> {
+    request_context::wrap_command("wpe_export_pcap", 2000, || {
+        let interceptor = with_state(&state, |s| s.wpe_interceptor.clone())
+            .ok_or_else(|| "WPE is not running.".to_string())?;
+        interceptor
+            .export_pcap(std::path::Path::new(&path))
+            .map_err(|e| format!("Failed to export packets: {e}"))
+    })
+}
+
+/// 按原始时间间隔重放录制文件中的出站封包
+#[tauri::command]
+fn wpe_replay_recording(
+    app: AppHandle,
+    path: String,
+    build: Option<wpe::BuildMode>,
+    state: State<Mutex<AppState>>,
+) -> Result<(), String> {
+    request_context::wrap_command("wpe_replay_recording", 500, || {
+        config::ensure_game_modification_allowed("Packet replay")?;
+        let interceptor = with_state(&state, |s| s.wpe_interceptor.clone())
+            .ok_or_else(|| "WPE is not running.".to_string())?;
+        interceptor.check_send().map_err(|e| e.to_string())?;
+        let replayer = wpe::PacketReplayer::open(std::path::Path::new(&path))
+            .map_err(|e| format!("Failed to load recording: {e}"))?;
+
+        let stop = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+        if let Some(previous) = with_state(&state, |s| s.replay_stop.replace(stop.clone())) {
+            previous.store(true, std::sync::atomic::Ordering::Relaxed);
+        }
+        std::thread::spawn(move || {
+            let summary = replayer.replay(&interceptor, build.unwrap_or_default(), &stop);
+            let _ = app.emit(events::WPE_REPLAY_FINISHED, summary);
+        });
+        info!(path = %path, "[WPE] replay started");
+        Ok(())
+    })
+}
+
+#[tauri::command]
+fn wpe_stop_replay(state: State<Mutex<AppState>>) -> bool {
+    match with_state(&state, |s| s.replay_stop.take()) {
+        Some(stop) => {
+            stop.store(true, std::sync::atomic::Ordering::Relaxed);
+            true
+        }
+        None => false,
+    }
+}
+
+/// 解码粘贴的十六进制封包，返回命令名称与结构化字段
+#[tauri::command]
+fn decode_packet_hex(text: String) -> Result<wpe::decoder::DecodedPacket, String> {
+    let bytes = wpe::hexdump::parse_hex(&text).map_err(|e| e.to_string())?;
+    let packet = wpe::GamePacket::parse(&bytes).map_err(|e| e.to_string())?;
+    Ok(wpe::decoder::decode(&packet))
+}
+
+/// 以仿真模式启动 WPE：回放录制的 pcapng，无需游戏进程与驱动
+#[tauri::command]
+fn wpe_start_simulation(
+    app: AppHandle,
+    path: String,
+    looped: Option<bool>,
+    state: State<Mutex<AppState>>,
+) -> Result<(), String> {
+    let spec = wpe::BackendSpec::Simulation {
+        capture: std::path::PathBuf::from(&path),
+        looped: looped.unwrap_or(false),
+    };
+    // 仿真模式不绑定进程，pid 固定为 0
+    let interceptor = wpe::PacketInterceptor::with_backend(0, spec)
+        .map_err(|e| format!("Failed to start simulation: {e}"))?;
+    notify::attach(&app, &interceptor);
+    launcher::watch_wpe_status(&app, &interceptor);
+    launcher::attach_wpe_rules(&app, &interceptor);
+    packet_stream::attach(&interceptor);
+    let previous = with_state(&state, |s| s.wpe_interceptor.replace(interceptor));
+    if let Some(previous) = previous {
+        previous.stop();
+    }
+    info!(path = %path, "[WPE] simulation started");
+    Ok(())
+}
+
+#[tauri::command]
+fn wpe_stop_simulation(state: State<Mutex<AppState>>) {
+    if let Some(interceptor) = with_state(&state, |s| s.wpe_interceptor.take()) {
+        interceptor.stop();
+    }
+}
+
+/// 旁观模式：只读捕获一个手动启动的 projector（pid 省略时自动查找），返回附加的 pid
+#[tauri::command]
+fn wpe_attach_spectator(app: AppHandle, pid: Option<u32>) -> Result<u32, String> {
+    request_context::wrap_command("wpe_attach_spectator", 1000, || {
+        projector::spectator::attach(&app, pid)
+    })
+}
+
+/// 结束旁观，返回之前是否处于旁观模式
+#[tauri::command]
+fn wpe_detach_spectator(app: AppHandle) -> bool {
+    projector::spectator::detach(&app)
+}
+
+/// 当前捕获后端状态，WPE 未启动时为 None
+#[tauri::command]
+fn get_wpe_status(state: State<Mutex<AppState>>) -> Option<wpe::BackendStatus> {
+    with_state(&state, |s| s.wpe_interceptor.as_ref().map(|i| i.status()))
+}
+
 #[tauri::command]
 fn change_channel(app: AppHandle, state: State<Mutex<AppState>>) -> Result<(), String> {
     request_context::wrap_command("change_channel", 2000, || {
@@ -399,7 +1067,7 @@ fn change_channel(app: AppHandle, state: State<Mutex<AppState>>) -> Result<(), S
 
     if !has_projector {
         tracing::warn!("projector not running, fallback to relogin");
-        return reset_to_login(app, state);
+        return reset_to_login(app, None, state);
     }
 
     if !has_swf {
@@ -410,6 +1078,7 @@ fn change_channel(app: AppHandle, state: State<Mutex<AppState>>) -> Result<(), S
     // 阶段 2：重启投影器
     {
         let _stage = request_context::StageTimer::new("relaunch_projector");
+        session_history::end_current("channel_change", false);
         match crate::launcher::launch_projector_auto(&app, &state) {
             Ok(()) => {
                 tracing::info!("projector relaunched successfully");
@@ -426,25 +1095,76 @@ fn change_channel(app: AppHandle, state: State<Mutex<AppState>>) -> Result<(), S
     })
 }
 
+/// Global.xml 中的服务器列表，标记当前 main.swf 地址所在的频道
+#[tauri::command]
+fn list_channels(state: State<Mutex<AppState>>) -> Vec<channel::Channel> {
+    let swf_url = with_state(&state, |s| s.swf_url.clone());
+    channel::list(swf_url.as_deref())
+}
+
+/// 把 main.swf 地址改写为指定频道；projector 运行中时用新地址重新启动
 #[tauri::command]
-fn reset_to_login(app: AppHandle, state: State<Mutex<AppState>>) -> Result<(), String> {
+fn select_channel(
+    app: AppHandle,
+    id: String,
+    state: State<Mutex<AppState>>,
+) -> Result<(), String> {
+    let running = request_context::wrap_command("select_channel", 200, || {
+        let swf_url = with_state(&state, |s| s.swf_url.clone())
+            .ok_or_else(|| "Missing swf url.".to_string())?;
+        let swf_url = channel::select(&swf_url, &id)?;
+        Ok(with_state(&state, |s| {
+            s.swf_url = Some(swf_url);
+            s.projector.is_some()
+        }))
+    })?;
+    if running {
+        change_channel(app, state)
+    } else {
+        Ok(())
+    }
+}
+
+/// 回到登录页流程中报告进度的阶段（silent_login 成功时转入启动流程，不计入）
+const RESET_TO_LOGIN_STAGES: &[&str] = &[
+    "stop_projector",
+    "reset_state",
+    "show_login",
+    "navigate",
+    "adjust_layout",
+    "emit_status",
+];
+
+/// 回到登录页；`silent` 为真且有有效的保存登录时直接用它重新启动，不显示登录页
+#[tauri::command]
+fn reset_to_login(
+    app: AppHandle,
+    silent: Option<bool>,
+    state: State<Mutex<AppState>>,
+) -> Result<(), String> {
     request_context::wrap_command("reset_to_login", 1000, || {
         let _timer = request_context::CommandTimer::new("reset_to_login", 1000);
 
         let current_status = with_state(&state, |s| s.status.clone());
         tracing::info!(current_status = ?current_status, "command invoked");
+        let pipeline = Pipeline::begin(&app, "reset_to_login", RESET_TO_LOGIN_STAGES);
 
     // 阶段 1：停止投影器
     {
-        let _stage = request_context::StageTimer::new("stop_projector");
+        let _stage = pipeline.stage("stop_projector");
+        session_history::end_current("relogin", false);
         stop_projector_command(&state);
         login3_capture::stop_timer_only(&state);
         tracing::info!("projector and capture stopped");
+
+        if config::current().launcher.warm_pool {
+            projector::warm_pool::prewarm(app.clone());
+        }
     }
 
     // 阶段 2：重置状态
     {
-        let _stage = request_context::StageTimer::new("reset_state");
+        let _stage = pipeline.stage("reset_state");
         with_state(&state, |s| {
             tracing::info!(
               old_status = ?s.status,
@@ -458,9 +1178,18 @@ fn reset_to_login(app: AppHandle, state: State<Mutex<AppState>>) -> Result<(), S
         tracing::info!("state reset complete");
     }
 
+    if silent.unwrap_or(false) {
+        let _stage = request_context::StageTimer::new("silent_login");
+        if saved_login::try_silent_login(&app)? {
+            tracing::info!("relaunched with saved login");
+            return Ok(());
+        }
+        tracing::info!("no valid saved login, showing login page");
+    }
+
     // 阶段 3：显示登录窗口
     {
-        let _stage = request_context::StageTimer::new("show_login");
+        let _stage = pipeline.stage("show_login");
 
         if let Some(main) = app.get_webview("main") {
             let _ = main.show();
@@ -481,10 +1210,11 @@ fn reset_to_login(app: AppHandle, state: State<Mutex<AppState>>) -> Result<(), S
 
     // 阶段 4：导航到登录页
     {
-        let _stage = request_context::StageTimer::new("navigate");
+        let _stage = pipeline.stage("navigate");
 
         let login = app.get_webview("login").unwrap();
-        let url = "https://17roco.qq.com/login.html".parse().map_err(|e| {
+        let login_url = endpoints::login_url();
+        let url = login_url.parse().map_err(|e| {
             tracing::error!(error = ?e, "invalid login URL");
             "Invalid login URL.".to_string()
         })?;
@@ -494,15 +1224,12 @@ fn reset_to_login(app: AppHandle, state: State<Mutex<AppState>>) -> Result<(), S
             "Failed to navigate login webview.".to_string()
         })?;
 
-        tracing::info!(
-            url = "https://17roco.qq.com/login.html",
-            "navigation complete"
-        );
+        tracing::info!(url = %login_url, "navigation complete");
     }
 
     // 阶段 5：调整布局
     {
-        let _stage = request_context::StageTimer::new("adjust_layout");
+        let _stage = pipeline.stage("adjust_layout");
         resize_login_to_window(&app);
         schedule_login_layout(app.clone());
         tracing::info!("layout adjusted");
@@ -510,7 +1237,7 @@ fn reset_to_login(app: AppHandle, state: State<Mutex<AppState>>) -> Result<(), S
 
     // 阶段 6：发送状态
     {
-        let _stage = request_context::StageTimer::new("emit_status");
+        let _stage = pipeline.stage("emit_status");
         emit_status(&app, &state.lock().expect("state lock"));
         tracing::info!("status emitted");
     }
@@ -602,7 +1329,7 @@ fn toggle_debug_window(app: AppHandle) -> Result<bool, String> {
 #[tauri::command]
 fn debug_log(app: AppHandle, level: String, message: String) {
     let _ = app.emit(
-        "debug_log",
+        events::DEBUG_LOG,
         serde_json::json!({
           "level": level,
           "message": message
@@ -615,6 +1342,205 @@ fn get_debug_stats() -> debug_log_bus::LogBusStats {
     debug_log_bus::get_stats()
 }
 
+#[tauri::command]
+fn get_environment_info(app: AppHandle) -> diagnostics::EnvironmentInfo {
+    diagnostics::collect(&app)
+}
+
+/// 启动前检查播放器、WebView2、目录写入权限和 WinDivert，返回检查清单
+#[tauri::command]
+fn preflight_check(app: AppHandle) -> Result<preflight::PreflightReport, String> {
+    request_context::wrap_command("preflight_check", 1000, || Ok(preflight::run(&app)))
+}
+
+/// 导出诊断包，返回文件路径供用户附到问题反馈中
+#[tauri::command]
+fn export_diagnostics(app: AppHandle) -> Result<String, String> {
+    request_context::wrap_command("export_diagnostics", 2000, || {
+        diagnostics::export_bundle(&app).map(|path| path.display().to_string())
+    })
+}
+
+/// 用保存的登录静默启动，返回是否已开始启动（没有有效的保存登录时为 false）
+#[tauri::command]
+fn try_silent_login(app: AppHandle) -> Result<bool, String> {
+    request_context::wrap_command("try_silent_login", 500, || {
+        saved_login::try_silent_login(&app)
+    })
+}
+
+/// 保存的登录信息（账号、保存时间、过期时间），不含凭据
+#[tauri::command]
+fn get_saved_login() -> Option<saved_login::SavedLoginInfo> {
+    saved_login::info()
+}
+
+#[tauri::command]
+fn forget_saved_login() {
+    saved_login::forget();
+}
+
+/// 已保存的账号（显示名称、是否过期、是否为当前账号）
+#[tauri::command]
+fn list_accounts(app: AppHandle) -> Vec<accounts::AccountEntry> {
+    accounts::list(&app)
+}
+
+/// 切换到已保存的账号：停止当前 projector，用该账号保存的凭据重新启动
+#[tauri::command]
+fn switch_account(
+    app: AppHandle,
+    qq_num: u64,
+    state: State<Mutex<AppState>>,
+) -> Result<(), String> {
+    request_context::wrap_command("switch_account", 1000, || {
+        let flash_vars = accounts::credentials(qq_num)?;
+        session_history::end_current("account_switch", false);
+        projector::reconnect::cancel();
+        stop_projector_command(&state);
+        login3_capture::stop_timer_only(&state);
+        with_state(&state, |s| s.swf_url = None);
+        accounts::isolate_session(&app, qq_num);
+        info!(qq_num = qq_num, "[Accounts] switching account");
+        saved_login::launch_with(&app, &flash_vars, Some(qq_num))
+    })
+}
+
+/// 无界面登录：用保存的凭据或隐藏的登录页登录指定账号（默认 --account），返回采用的方式
+#[tauri::command]
+fn login_headless(
+    app: AppHandle,
+    qq_num: Option<u64>,
+) -> Result<headless_login::HeadlessMethod, String> {
+    request_context::wrap_command("login_headless", 1000, || {
+        headless_login::start(&app, qq_num)
+    })
+}
+
+/// 清除登录页的 QQ 登录态（cookie），下次打开登录页需要重新登录
+#[tauri::command]
+fn clear_login_session(app: AppHandle) -> Result<(), String> {
+    request_context::wrap_command("clear_login_session", 500, || {
+        accounts::clear_login_session(&app)
+    })
+}
+
+#[tauri::command]
+fn rename_account(qq_num: u64, display_name: String) -> Result<(), String> {
+    accounts::rename(qq_num, display_name)
+}
+
+/// 删除账号档案和保存的凭据，返回是否找到
+#[tauri::command]
+fn remove_account(qq_num: u64) -> bool {
+    accounts::remove(qq_num)
+}
+
+/// 上次运行的退出记录（是否被兜底强制结束、完成了哪些清理步骤）
+#[tauri::command]
+fn get_last_shutdown_report() -> Option<shutdown::ShutdownReport> {
+    shutdown::previous()
+}
+
+/// 解析后的 Global.xml（服务器列表、版本号）；尚未抓取且没有缓存时为 None
+#[tauri::command]
+fn get_game_config() -> Option<game_config::GameConfig> {
+    game_config::current()
+}
+
+/// 镜像清单中的 projector 版本和本地已下载的版本（请求镜像，不在主线程执行）
+#[tauri::command(async)]
+fn list_projector_versions() -> Result<Vec<projector_manager::ProjectorVersion>, String> {
+    request_context::wrap_command("list_projector_versions", 3000, projector_manager::list)
+}
+
+/// 在后台下载并校验指定版本的 projector（进度见 projector_install_progress 事件）
+#[tauri::command]
+fn install_projector(app: AppHandle, version: String) -> Result<(), String> {
+    projector_manager::install(&app, version)
+}
+
+/// 选择使用已下载的 projector 版本；不传版本时恢复使用随程序分发的 projector
+#[tauri::command]
+fn select_projector_version(version: Option<String>) -> Result<(), String> {
+    request_context::wrap_command("select_projector_version", 200, || {
+        projector_manager::select(version)
+    })
+}
+
+/// 修改 login3 抓取超时和响应读取上限（超出范围按边界处理），返回生效的值
+#[tauri::command]
+fn set_login_capture_limits(
+    timeout_secs: Option<u64>,
+    max_response_bytes: Option<usize>,
+) -> Result<login3_capture::CaptureLimits, String> {
+    request_context::wrap_command("set_login_capture_limits", 200, || {
+        login3_capture::set_limits(timeout_secs, max_response_bytes)
+    })
+}
+
+/// login3 抓取的重试状态与最近的失败原因
+#[tauri::command]
+fn get_login_diagnostics() -> login_diagnostics::LoginDiagnostics {
+    login_diagnostics::snapshot()
+}
+
+/// 导出状态快照（不含登录信息），返回文件路径
+#[tauri::command]
+fn export_state_snapshot(app: AppHandle) -> Result<String, String> {
+    request_context::wrap_command("export_state_snapshot", 500, || {
+        snapshot::export(&app).map(|path| path.display().to_string())
+    })
+}
+
+/// 载入用户导出的状态快照（仅调试构建）
+#[tauri::command]
+fn load_state_snapshot(app: AppHandle, path: String) -> Result<(), String> {
+    if !cfg!(debug_assertions) {
+        return Err("State snapshots can only be loaded in debug builds.".to_string());
+    }
+    request_context::wrap_command("load_state_snapshot", 500, || {
+        let snapshot = snapshot::load(&app, std::path::Path::new(&path))?;
+        apply_theme_to_app(&app, snapshot.theme_mode);
+        relayout_all(&app);
+        let state = app.state::<Mutex<AppState>>();
+        emit_status(&app, &state.lock().expect("state lock"));
+        Ok(())
+    })
+}
+
+/// 前端事件约定（版本与事件名）
+#[tauri::command]
+fn get_event_contract() -> events::EventContract {
+    events::contract()
+}
+
+#[tauri::command]
+fn get_usage_stats() -> usage::UsageStats {
+    usage::snapshot()
+}
+
+/// 开启/关闭本地功能使用统计（关闭时保留已有数据）
+#[tauri::command]
+fn set_usage_stats_enabled(enabled: bool) -> Result<(), String> {
+    config::update(|config| config.usage.enabled = enabled)?;
+    info!(enabled = enabled, "[Usage] usage stats toggled");
+    Ok(())
+}
+
+#[tauri::command]
+fn reset_usage_stats() {
+    usage::reset();
+}
+
+/// 导出功能使用汇总，返回文件路径（由用户自行决定是否提供给维护者）
+#[tauri::command]
+fn export_usage_summary(app: AppHandle) -> Result<String, String> {
+    request_context::wrap_command("export_usage_summary", 500, || {
+        usage::export(&app).map(|path| path.display().to_string())
+    })
+}
+
 #[tauri::command]
 fn debug_get_recent_logs(limit: usize) -> Vec<debug_log_bus::LogEvent> {
     debug_log_bus::get_recent_logs(limit)
@@ -654,7 +1580,7 @@ fn init_logging(app: &tauri::App) -> Result<std::path::PathBuf, String> {
     use tracing_subscriber::util::SubscriberInitExt;
 
     let file_layer = tracing_subscriber::fmt::layer()
-        .with_writer(non_blocking)
+        .with_writer(move || redact::RedactingWriter(non_blocking.clone()))
         .with_ansi(false);
 
     let debug_console_layer = debug_console_layer::DebugConsoleLayer::new();
@@ -705,6 +1631,7 @@ fn init_logging(app: &tauri::App) -> Result<std::path::PathBuf, String> {
 fn main() {
     let _ = set_dpi_awareness();
     init_startup_log();
+    cli::init();
 
     // 🔴 验证标记：如果看到这行，说明是新编译的版本
     startup_log("🔴🔴🔴 VERSION: 2026-02-12-PATCH-V2 🔴🔴🔴");
@@ -734,6 +1661,20 @@ fn main() {
                 Ok(path) => info!("log file at {}", path.display()),
                 Err(msg) => error!("logging init failed: {msg}"),
             }
+            cli::report_problems();
+            config::init(app.handle());
+            // 尽早开始，与下面的窗口、webview 创建并行
+            prefetch::start();
+            session_history::init(app.handle());
+            shutdown::init(app.handle());
+            saved_login::init(app.handle());
+            accounts::init(app.handle());
+            projector_manager::init(app.handle());
+            usage::init(app.handle());
+            autostart::sync_with_config();
+            disguise::apply_hotkey(app.handle());
+            timers::apply_hotkey(app.handle());
+            timers::start(app.handle().clone());
 
             let main_window = app.get_window("main").ok_or_else(|| {
                 error!("main window not found");
@@ -761,19 +1702,16 @@ fn main() {
             if let Ok(hwnd) = main_window.hwnd() {
                 disable_maximize_resize(hwnd);
             }
+            layout::watch_displays(app.handle().clone(), align_window_height_for_game_ratio);
             let _ = main_window.show();
+            if autostart::launched_minimized() {
+                let _ = main_window.minimize();
+            }
             startup_log("main window show called");
 
-            if let Ok(projector_path) = app.path().resolve("projector.exe", BaseDirectory::Resource)
-            {
-                if std::fs::metadata(&projector_path).is_err() {
-                    show_error_message(&format!(
-                        "projector.exe not found. resolved: {}",
-                        projector_path.display()
-                    ));
-                }
-            } else {
-                show_error_message("projector.exe resolve failed.");
+            // projector.exe 与 Ruffle 都找不到时提示（auto 时缺少 projector 会改用 Ruffle）
+            if let Err(msg) = projector::runtime::resolve(app.handle()) {
+                show_error_message(&msg);
             }
 
             let app_handle = app.handle().clone();
@@ -781,13 +1719,13 @@ fn main() {
             let login_builder = WebviewBuilder::new(
                 "login",
                 WebviewUrl::External(
-                    "https://17roco.qq.com/login.html"
+                    endpoints::login_url()
                         .parse()
                         .map_err(|_| "Invalid login URL.".to_string())?,
                 ),
             )
             .on_navigation(move |url| {
-                if url.path().contains("login.html") {
+                if endpoints::is_login_page(url) {
                     let _ = start_login3_capture(
                         nav_handle.clone(),
                         nav_handle.state::<Mutex<AppState>>(),
@@ -798,6 +1736,18 @@ fn main() {
                 true
             })
             .on_new_window(move |_url, _features| tauri::webview::NewWindowResponse::Allow);
+            // 按账号隔离登录页的 WebView2 用户数据（cookie），多开时会话互不影响
+            let login_builder = match (
+                cli::args().webview_data_key(),
+                app.path().app_local_data_dir(),
+            ) {
+                (Some(key), Ok(dir)) => {
+                    let data_dir = dir.join("webview").join(key);
+                    info!(path = %data_dir.display(), "login webview uses isolated user data");
+                    login_builder.data_directory(data_dir)
+                }
+                _ => login_builder,
+            };
 
             let scale = main_window.scale_factor().unwrap_or(1.0);
             let logical_w = ((size.width as f64) / scale).round() as i32;
@@ -826,13 +1776,17 @@ fn main() {
 
             startup_log("login webview created");
             startup_log("toolbar webview created");
+            login3_capture::register_capture();
+            game_config::init(app.handle());
             let _ = login_webview.with_webview(move |webview| {
-                login3_capture::attach_webview2_capture(webview, app_handle.clone());
+                webview_capture::attach(webview, app_handle.clone());
             });
 
             resize_login_to_window(&app.handle().clone());
             schedule_login_layout(app.handle().clone());
-            let _ = login_webview.show();
+            if !cli::args().headless {
+                let _ = login_webview.show();
+            }
             let _ = toolbar_webview.show();
             let app_handle_for_theme = app.handle().clone();
             let state_for_theme = app_handle_for_theme.state::<Mutex<AppState>>();
@@ -865,6 +1819,7 @@ fn main() {
             // [日志点 5] Debug 窗口创建成功
             dbglog!(INFO, "Debug window created successfully");
             debug::set_debug_window_state(false);
+            launcher::apply_webview_zooms(app.handle());
 
             // 全局退出标志（用于在退出时拒绝所有 debug 操作）
             static EXITING_GLOBAL: std::sync::atomic::AtomicBool =
@@ -937,6 +1892,40 @@ fn main() {
             debug::init_debug(app.handle().clone());
             debug_info!("Application initialized successfully");
 
+            if config::current().launcher.warm_pool {
+                projector::warm_pool::prewarm(app.handle().clone());
+            }
+
+            let cli_args = cli::args();
+            if cli_args.debug_console {
+                if let Err(e) = toggle_debug_window(app.handle().clone()) {
+                    tracing::warn!(error = %e, "[Cli] failed to open debug console");
+                }
+            }
+            if let Some(swf_url) = cli_args.swf_url.clone() {
+                // 跳过登录页，与 login3 捕获到地址后的流程相同
+                info!("[Cli] launching projector from --swf-url");
+                let state = app.state::<Mutex<AppState>>();
+                with_state(&state, |s| {
+                    s.swf_url = Some(swf_url);
+                    s.status = AppStatus::Launching;
+                    s.message = None;
+                });
+                emit_status(app.handle(), &state.lock().expect("state lock"));
+                let launch_handle = app.handle().clone();
+                let _ = app.handle().run_on_main_thread(move || {
+                    let state = launch_handle.state::<Mutex<AppState>>();
+                    if let Err(e) = launcher::launch_projector_auto(&launch_handle, &state) {
+                        error!(error = %e, "[Cli] failed to launch projector");
+                    }
+                });
+            } else if cli_args.headless {
+                match headless_login::start(app.handle(), None) {
+                    Ok(method) => info!(method = ?method, "[Cli] headless login started"),
+                    Err(e) => error!(error = %e, "[Cli] headless login failed"),
+                }
+            }
+
             Ok(())
         })
         .on_window_event(|window, event| {
@@ -947,10 +1936,11 @@ fn main() {
 
             if let WindowEvent::CloseRequested { .. } = event {
                 startup_log("MAIN_WINDOW_CLOSE: calling request_exit()");
-                request_exit();
-                // request_exit() 会在 100ms 内强制退出进程
-                // 不需要任何其他操作
+                request_exit(window.app_handle());
+                // 清理完成或超过兜底延迟后进程退出，不需要任何其他操作
             } else if let WindowEvent::Resized(size) = event {
+                // 最小化时收到 0x0 的 Resized
+                pacing::set_minimized(size.width == 0 || size.height == 0);
                 track_last_size(*size);
                 let state = window.state::<Mutex<AppState>>();
                 if let Ok(guard) = state.lock() {
@@ -961,6 +1951,8 @@ fn main() {
                     }
                 }
                 resize_projector_to_window(&window.app_handle(), &state);
+            } else if let WindowEvent::ScaleFactorChanged { .. } = event {
+                relayout_all(&window.app_handle());
             }
         })
         .invoke_handler(tauri::generate_handler![
@@ -969,17 +1961,102 @@ fn main() {
             hide_login_webview,
             get_theme_mode,
             set_theme_mode,
+            get_config,
+            set_config,
+            list_experiments,
+            set_experiment,
+            get_autostart,
+            get_disguise,
+            postpone_auto_restart,
+            get_projector_metrics,
+            cancel_reconnect,
+            list_builtin_sounds,
+            set_event_sound,
+            set_sound_volume,
+            set_sound_muted,
+            preview_sound,
+            set_disguise,
+            start_countdown,
+            cancel_countdown,
+            get_timers,
+            test_webhook,
+            set_webview_zoom,
+            list_layout_presets,
+            save_layout_preset,
+            apply_layout_preset,
+            delete_layout_preset,
+            get_webview_zoom,
+            set_autostart,
             start_login3_capture,
             stop_login3_capture,
             launch_projector,
             resize_projector,
             stop_projector,
+            get_external_window,
+            set_external_window,
             restart_projector,
+            get_projector_info,
+            get_session_history,
+            cleanup_all_data,
+            import_packet_from_hex,
+            validate_packet,
+            decode_packet_hex,
+            wpe_start_simulation,
+            wpe_stop_simulation,
+            wpe_attach_spectator,
+            wpe_detach_spectator,
+            get_wpe_status,
+            sendlist_add,
+            sendlist_start,
+            sendlist_stop,
+            wpe_start_stream,
+            wpe_stop_stream,
+            get_packet_stream_stats,
+            get_recent_packets,
+            get_wpe_rules,
+            set_wpe_rules,
+            wpe_start_recording,
+            wpe_stop_recording,
+            wpe_is_recording,
+            wpe_export_pcap,
+            get_wpe_stats,
+            get_packet_history,
+            get_packet_bytes,
+            wpe_replay_recording,
+            wpe_stop_replay,
             change_channel,
+            list_channels,
+            select_channel,
             reset_to_login,
+            try_silent_login,
+            get_saved_login,
+            forget_saved_login,
+            list_accounts,
+            switch_account,
+            rename_account,
+            clear_login_session,
+            login_headless,
+            remove_account,
             toggle_debug_window,
             debug_log,
             get_debug_stats,
+            get_environment_info,
+            preflight_check,
+            export_diagnostics,
+            get_last_shutdown_report,
+            get_login_diagnostics,
+            set_login_capture_limits,
+            get_game_config,
+            list_projector_versions,
+            install_projector,
+            select_projector_version,
+            export_state_snapshot,
+            get_event_contract,
+            get_usage_stats,
+            set_usage_stats_enabled,
+            reset_usage_stats,
+            export_usage_summary,
+            load_state_snapshot,
             debug_get_recent_logs
         ])
         .run(context);
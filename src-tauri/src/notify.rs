@@ -0,0 +1,228 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use tauri::{AppHandle, Emitter, Manager};
+use tracing::info;
+
+use crate::state::AppState;
+use crate::wpe::{Direction, GamePacket, PacketAction, PacketHandler, PacketInterceptor};
+
+/// 通知触发条件
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum NotificationTrigger {
+    /// 指定命令号的二进制封包（direction 为空时两个方向都匹配）
+    Command {
+        command: u16,
+        #[serde(default)]
+        direction: Option<Direction>,
+    },
+    /// 文本封包包含指定内容（如 "System_" 系统消息）
+    TextContains { pattern: String },
+}
+
+/// 通知动作
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum NotificationAction {
+    /// 向前端发出 notification 事件，由界面弹出提示
+    Toast {
+        #[serde(default)]
+        title: Option<String>,
+        #[serde(default)]
+        body: Option<String>,
+    },
+    /// 播放提示音（sounds 配置中 notification 事件的声音，未配置时为系统提示音）
+    Sound,
+    /// 闪烁任务栏按钮直到窗口获得焦点
+    FlashWindow,
+    /// 发送到订阅了 notification 事件的 webhook
+    Webhook,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// 一条通知规则
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct NotificationRule {
+    pub name: String,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// 只对指定账号生效，为空时对所有账号生效
+    #[serde(default)]
+    pub account: Option<u64>,
+    pub trigger: NotificationTrigger,
+    pub actions: Vec<NotificationAction>,
+    /// 只在主窗口处于后台时提醒
+    #[serde(default = "default_true")]
+    pub only_in_background: bool,
+    /// 同一规则两次触发的最小间隔（秒）
+    #[serde(default)]
+    pub cooldown_secs: u64,
+}
+
+/// 前端 notification 事件
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct NotificationEvent {
+    pub rule: String,
+    pub title: String,
+    pub body: String,
+}
+
+impl NotificationRule {
+    fn matches(&self, packet: &GamePacket, direction: Direction, account: Option<u64>) -> bool {
+        if !self.enabled {
+            return false;
+        }
+        if self.account.is_some() && self.account != account {
+            return false;
+        }
+        match (&self.trigger, packet) {
+            (
+                NotificationTrigger::Command {
+                    command,
+                    direction: wanted,
+                },
+                GamePacket::Binary {
+                    command: actual, ..
+                },
+            ) => command == actual && wanted.is_none_or(|d| d == direction),
+            (NotificationTrigger::TextContains { pattern }, GamePacket::Text(text)) => {
+                text.contains(pattern.as_str())
+            }
+            _ => false,
+        }
+    }
+}
+
+/// 按规则把封包转成通知的 PacketHandler（只观察，不修改封包）
+pub struct NotificationHandler {
+    app: AppHandle,
+    last_fired: Mutex<HashMap<String, Instant>>,
+}
+
+impl NotificationHandler {
+    pub fn new(app: AppHandle) -> Self {
+        Self {
+            app,
+            last_fired: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn evaluate(&self, packet: &GamePacket, direction: Direction) {
+        let rules = crate::config::current().notifications.rules;
+        if rules.is_empty() {
+            return;
+        }
+        let account = match packet {
+            GamePacket::Binary { qq_num, .. } if *qq_num != 0 => Some(*qq_num),
+            _ => {
+                self.app
+                    .state::<Mutex<AppState>>()
+                    .lock()
+                    .expect("state lock")
+                    .qq_num
+            }
+        };
+        let in_background = !self
+            .app
+            .get_window("main")
+            .and_then(|w| w.is_focused().ok())
+            .unwrap_or(false);
+
+        for rule in rules
+            .iter()
+            .filter(|rule| rule.matches(packet, direction, account))
+        {
+            if rule.only_in_background && !in_background {
+                continue;
+            }
+            if !self.take_cooldown(rule) {
+                continue;
+            }
+            info!(rule = %rule.name, "[Notify] rule triggered");
+            for action in &rule.actions {
+                self.run_action(rule, action, packet, account);
+            }
+        }
+    }
+
+    fn take_cooldown(&self, rule: &NotificationRule) -> bool {
+        let mut last_fired = self.last_fired.lock().expect("notify lock");
+        let now = Instant::now();
+        if let Some(last) = last_fired.get(&rule.name) {
+            if now.duration_since(*last) < Duration::from_secs(rule.cooldown_secs) {
+                return false;
+            }
+        }
+        last_fired.insert(rule.name.clone(), now);
+        true
+    }
+
+    fn run_action(
+        &self,
+        rule: &NotificationRule,
+        action: &NotificationAction,
+        packet: &GamePacket,
+        account: Option<u64>,
+    ) {
+        // 伪装状态下只保留不会在本机留下痕迹的动作
+        if crate::disguise::is_active() && !matches!(action, NotificationAction::Webhook) {
+            return;
+        }
+        match action {
+            NotificationAction::Toast { title, body } => {
+                let body = body.clone().unwrap_or_else(|| describe(packet));
+                let _ = self.app.emit(
+                    crate::events::NOTIFICATION,
+                    NotificationEvent {
+                        rule: rule.name.clone(),
+                        title: title.clone().unwrap_or_else(|| rule.name.clone()),
+                        body,
+                    },
+                );
+            }
+            NotificationAction::Sound => crate::sound::play_notification(),
+            NotificationAction::FlashWindow => {
+                if let Some(hwnd) = self.app.get_window("main").and_then(|w| w.hwnd().ok()) {
+                    crate::embed_win32::flash_window(hwnd);
+                }
+            }
+            NotificationAction::Webhook => crate::webhook::fire(
+                crate::webhook::WebhookEvent::Notification,
+                &format!("{}: {}", rule.name, describe(packet)),
+                account,
+            ),
+        }
+    }
+}
+
+fn describe(packet: &GamePacket) -> String {
+    match packet {
+        GamePacket::Binary { command, .. } => format!("cmd=0x{command:04X}"),
+        GamePacket::Text(text) => {
+            let text = crate::redact::redact_secrets(text);
+            crate::redact::truncate_chars(&text, 120).to_string()
+        }
+    }
+}
+
+impl PacketHandler for NotificationHandler {
+    fn handle_outbound(&self, packet: &GamePacket) -> PacketAction {
+        self.evaluate(packet, Direction::Outbound);
+        PacketAction::Forward
+    }
+
+    fn handle_inbound(&self, packet: &GamePacket) -> PacketAction {
+        self.evaluate(packet, Direction::Inbound);
+        PacketAction::Forward
+    }
+}
+
+/// 为新建的拦截器挂上通知规则处理器
+pub fn attach(app: &AppHandle, interceptor: &PacketInterceptor) {
+    interceptor.register_handler(Arc::new(NotificationHandler::new(app.clone())));
+}
@@ -0,0 +1,127 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Condvar, Mutex};
+use std::time::{Duration, Instant};
+
+// 后台监视线程的轮询节奏
+//
+// projector 存活检查、窗口标题轮询等监视线程原本按固定频率运行。这里按程序的活动状态调整间隔：
+// 启动后的一段时间内加快（尽早发现进程退出、加载中的标题变化），主窗口最小化或处于伪装状态时
+// 放慢到数秒，降低空闲时的 CPU 占用。活动状态变化时会唤醒正在等待的线程，恢复窗口后立即按新间隔运行。
+
+/// 启动开始后按"启动中"节奏运行的时长
+const LAUNCH_WINDOW: Duration = Duration::from_secs(30);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Activity {
+    Launching,
+    Active,
+    Idle,
+}
+
+/// 一个监视线程在各活动状态下的轮询间隔
+#[derive(Clone, Copy, Debug)]
+pub struct Pace {
+    pub launching: Duration,
+    pub active: Duration,
+    pub idle: Duration,
+}
+
+impl Pace {
+    pub const fn from_millis(launching: u64, active: u64, idle: u64) -> Self {
+        Self {
+            launching: Duration::from_millis(launching),
+            active: Duration::from_millis(active),
+            idle: Duration::from_millis(idle),
+        }
+    }
+
+    pub fn interval(&self, activity: Activity) -> Duration {
+        match activity {
+            Activity::Launching => self.launching,
+            Activity::Active => self.active,
+            Activity::Idle => self.idle,
+        }
+    }
+}
+
+static MINIMIZED: AtomicBool = AtomicBool::new(false);
+static LAUNCHED_AT: Mutex<Option<Instant>> = Mutex::new(None);
+/// 活动状态变化的计数，配合 Condvar 唤醒等待中的线程
+static CHANGES: Mutex<u64> = Mutex::new(0);
+static CHANGED: Condvar = Condvar::new();
+
+fn resolve(since_launch: Option<Duration>, minimized: bool, disguised: bool) -> Activity {
+    if since_launch.is_some_and(|elapsed| elapsed < LAUNCH_WINDOW) {
+        Activity::Launching
+    } else if minimized || disguised {
+        Activity::Idle
+    } else {
+        Activity::Active
+    }
+}
+
+pub fn activity() -> Activity {
+    let since_launch = LAUNCHED_AT
+        .lock()
+        .expect("pacing lock")
+        .map(|at| at.elapsed());
+    resolve(
+        since_launch,
+        MINIMIZED.load(Ordering::Relaxed),
+        crate::disguise::is_active(),
+    )
+}
+
+fn notify_changed() {
+    *CHANGES.lock().expect("pacing lock") += 1;
+    CHANGED.notify_all();
+}
+
+/// 开始启动 projector 时调用
+pub fn mark_launch() {
+    *LAUNCHED_AT.lock().expect("pacing lock") = Some(Instant::now());
+    notify_changed();
+}
+
+/// 主窗口最小化或还原时调用
+pub fn set_minimized(minimized: bool) {
+    if MINIMIZED.swap(minimized, Ordering::Relaxed) != minimized {
+        notify_changed();
+    }
+}
+
+/// 伪装状态切换时调用
+pub fn refresh() {
+    notify_changed();
+}
+
+/// 按当前活动状态等待一个间隔；期间活动状态变化时提前返回
+pub fn wait(pace: &Pace) {
+    let interval = pace.interval(activity());
+    let guard = CHANGES.lock().expect("pacing lock");
+    let seen = *guard;
+    let _ = CHANGED
+        .wait_timeout_while(guard, interval, |changes| *changes == seen)
+        .expect("pacing lock");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn launch_overrides_idle_until_window_ends() {
+        let pace = Pace::from_millis(250, 500, 3000);
+        let recent = Some(Duration::from_secs(5));
+        let old = Some(LAUNCH_WINDOW);
+
+        assert_eq!(resolve(recent, true, false), Activity::Launching);
+        assert_eq!(resolve(old, false, false), Activity::Active);
+        assert_eq!(resolve(old, true, false), Activity::Idle);
+        assert_eq!(resolve(None, false, true), Activity::Idle);
+        assert_eq!(
+            pace.interval(resolve(None, true, false)),
+            Duration::from_secs(3)
+        );
+    }
+}
@@ -0,0 +1,235 @@
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use tauri::{AppHandle, Emitter};
+use tracing::info;
+
+use crate::wpe::{Direction, GamePacket, PacketAction, PacketHandler, PacketInterceptor};
+
+// 封包实时流：把拦截到的封包批量推送给 debug 窗口显示十六进制视图。
+// 与 debug_log_bus 相同的做法：handler 只入队，由后台线程定时批量 emit，
+// 队列满时丢弃新封包，战斗中封包密集也不会压垮 webview。
+// 封包量远大于普通日志，使用独立的环形缓冲区和统计，不写入 debug_log_bus，
+// 避免挤掉日志总线里的正常日志。
+
+/// 批量发送间隔（毫秒）
+const BATCH_INTERVAL_MS: u64 = 200;
+
+/// 内存中保留的最近封包数量（用于 debug 窗口打开时回放）
+const RING_BUFFER_SIZE: usize = 2000;
+
+/// 队列最大容量
+const MAX_QUEUE_SIZE: usize = 1000;
+
+/// 单次批量发送的最大封包数
+const MAX_BATCH_SIZE: usize = 200;
+
+/// 单个封包最多展示的字节数
+const MAX_HEX_BYTES: usize = 512;
+
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct PacketEvent {
+    /// Unix 时间戳（毫秒）
+    pub timestamp: u64,
+    pub direction: Direction,
+    /// 二进制封包的命令号，文本封包为 None
+    pub command: Option<u16>,
+    /// 已知命令的名称
+    pub name: Option<&'static str>,
+    /// 解码器给出的结构化字段
+    pub fields: Option<serde_json::Value>,
+    /// 封包总长度（字节）
+    pub length: usize,
+    /// 十六进制内容（超过 MAX_HEX_BYTES 时截断）
+    pub hex: String,
+    pub truncated: bool,
+}
+
+impl PacketEvent {
+    fn new(packet: &GamePacket, direction: Direction) -> Self {
+        let bytes = packet.build().unwrap_or_default();
+        let decoded = crate::wpe::decoder::decode(packet);
+        let shown = &bytes[..bytes.len().min(MAX_HEX_BYTES)];
+        Self {
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_millis() as u64)
+                .unwrap_or(0),
+            direction,
+            command: decoded.command,
+            name: decoded.name,
+            fields: decoded.fields,
+            length: bytes.len(),
+            hex: shown
+                .iter()
+                .map(|b| format!("{b:02X}"))
+                .collect::<Vec<_>>()
+                .join(" "),
+            truncated: bytes.len() > shown.len(),
+        }
+    }
+}
+
+/// 封包流统计信息（前端 `packet_stream_stats`）
+#[derive(Clone, Debug, Default, serde::Serialize)]
+pub struct PacketStreamStats {
+    /// 总捕获封包数
+    pub total_captured: usize,
+    /// 总发送封包数
+    pub total_sent: usize,
+    /// 总丢弃封包数（队列已满）
+    pub total_dropped: usize,
+    /// 当前队列长度
+    pub queue_length: usize,
+    /// 当前环形缓冲区长度
+    pub ring_buffer_length: usize,
+}
+
+#[derive(Default)]
+struct StreamState {
+    /// 待发送队列
+    queue: VecDeque<PacketEvent>,
+    /// 最近封包环形缓冲区（用于回放）
+    ring_buffer: VecDeque<PacketEvent>,
+    stats: PacketStreamStats,
+}
+
+impl StreamState {
+    fn push(&mut self, event: PacketEvent) {
+        self.stats.total_captured += 1;
+
+        self.ring_buffer.push_back(event.clone());
+        if self.ring_buffer.len() > RING_BUFFER_SIZE {
+            self.ring_buffer.pop_front();
+        }
+
+        if self.queue.len() >= MAX_QUEUE_SIZE {
+            self.stats.total_dropped += 1;
+            return;
+        }
+        self.queue.push_back(event);
+    }
+
+    fn take_batch(&mut self) -> Vec<PacketEvent> {
+        let count = self.queue.len().min(MAX_BATCH_SIZE);
+        let batch: Vec<PacketEvent> = self.queue.drain(..count).collect();
+        self.stats.total_sent += batch.len();
+        batch
+    }
+
+    fn stats(&self) -> PacketStreamStats {
+        PacketStreamStats {
+            queue_length: self.queue.len(),
+            ring_buffer_length: self.ring_buffer.len(),
+            ..self.stats.clone()
+        }
+    }
+}
+
+static STREAM: OnceLock<Mutex<StreamState>> = OnceLock::new();
+static STREAMING: AtomicBool = AtomicBool::new(false);
+
+fn stream() -> &'static Mutex<StreamState> {
+    STREAM.get_or_init(|| Mutex::new(StreamState::default()))
+}
+
+fn push(event: PacketEvent) {
+    stream().lock().expect("packet stream lock").push(event);
+}
+
+/// 推送流的 handler：未开启推送时直接放行，不做任何处理
+struct StreamHandler;
+
+impl PacketHandler for StreamHandler {
+    fn handle_outbound(&self, packet: &GamePacket) -> PacketAction {
+        if STREAMING.load(Ordering::Relaxed) {
+            push(PacketEvent::new(packet, Direction::Outbound));
+        }
+        PacketAction::Forward
+    }
+
+    fn handle_inbound(&self, packet: &GamePacket) -> PacketAction {
+        if STREAMING.load(Ordering::Relaxed) {
+            push(PacketEvent::new(packet, Direction::Inbound));
+        }
+        PacketAction::Forward
+    }
+}
+
+/// 为新建的拦截器挂上推送 handler
+pub fn attach(interceptor: &PacketInterceptor) {
+    interceptor.register_handler(Arc::new(StreamHandler));
+}
+
+/// 开始推送 packet_captured 事件
+pub fn start(app: AppHandle) {
+    if STREAMING.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    info!("[PacketStream] started");
+    std::thread::spawn(move || {
+        while STREAMING.load(Ordering::Relaxed) && !crate::EXITING.load(Ordering::Relaxed) {
+            std::thread::sleep(Duration::from_millis(BATCH_INTERVAL_MS));
+            let (batch, stats) = {
+                let mut state = stream().lock().expect("packet stream lock");
+                (state.take_batch(), state.stats())
+            };
+            if !batch.is_empty() {
+                let _ = app.emit(crate::events::PACKET_CAPTURED, &batch);
+                let _ = app.emit(crate::events::PACKET_STREAM_STATS, &stats);
+            }
+        }
+        info!("[PacketStream] flush thread exiting");
+    });
+}
+
+/// 停止推送并清空未发送的封包
+pub fn stop() {
+    if !STREAMING.swap(false, Ordering::SeqCst) {
+        return;
+    }
+    let mut state = stream().lock().expect("packet stream lock");
+    state.queue.clear();
+    info!(
+        captured = state.stats.total_captured,
+        dropped = state.stats.total_dropped,
+        "[PacketStream] stopped"
+    );
+}
+
+/// 获取封包流统计信息
+pub fn get_stats() -> PacketStreamStats {
+    stream().lock().expect("packet stream lock").stats()
+}
+
+/// 获取最近的 N 个封包（按时间顺序，用于 debug 窗口初次打开）
+pub fn get_recent(limit: usize) -> Vec<PacketEvent> {
+    let state = stream().lock().expect("packet stream lock");
+    let skip = state.ring_buffer.len().saturating_sub(limit);
+    state.ring_buffer.iter().skip(skip).cloned().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ring_keeps_history_when_queue_is_full() {
+        let mut state = StreamState::default();
+        let packet = GamePacket::build_map_jump(10001, 5);
+        for _ in 0..MAX_QUEUE_SIZE + 5 {
+            state.push(PacketEvent::new(&packet, Direction::Outbound));
+        }
+
+        let stats = state.stats();
+        assert_eq!(stats.total_captured, MAX_QUEUE_SIZE + 5);
+        assert_eq!(stats.total_dropped, 5);
+        assert_eq!(stats.queue_length, MAX_QUEUE_SIZE);
+        assert_eq!(stats.ring_buffer_length, MAX_QUEUE_SIZE + 5);
+
+        assert_eq!(state.take_batch().len(), MAX_BATCH_SIZE);
+        assert_eq!(state.stats().total_sent, MAX_BATCH_SIZE);
+    }
+}
@@ -0,0 +1,45 @@
+use std::net::ToSocketAddrs;
+use std::time::{Duration, Instant};
+
+use tracing::{info, warn};
+
+// 登录页预热
+//
+// 在 setup 开头（主窗口和登录 webview 创建之前）由后台线程解析登录页相关域名并请求一次登录页，
+// 让系统 DNS 缓存和 CDN 边缘节点提前就绪，网络较慢时登录页出现得更快。
+// WebView2 有独立的连接池和 HTTP 缓存，这里无法把 TLS 会话或静态资源直接交给它；
+// 失败只记录日志，不影响正常启动。
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// 在后台开始预热；未启用或本次启动跳过登录页（--swf-url）时不做任何事
+pub fn start() {
+    if !crate::config::current().launcher.prefetch_login || crate::cli::args().swf_url.is_some() {
+        return;
+    }
+    // 登录页及游戏资源所在的域名（地址可配置，见 endpoints）
+    let hosts = crate::endpoints::hosts();
+    let login_url = crate::endpoints::login_url();
+    std::thread::spawn(move || {
+        let start = Instant::now();
+        for (host, port) in &hosts {
+            if let Err(e) = (host.as_str(), *port).to_socket_addrs() {
+                warn!(host = %host, error = %e, "[Prefetch] DNS lookup failed");
+            }
+        }
+        let resolved_ms = start.elapsed().as_millis() as u64;
+        match ureq::get(&login_url).timeout(REQUEST_TIMEOUT).call() {
+            Ok(response) => info!(
+                status = response.status(),
+                resolve_ms = resolved_ms,
+                total_ms = start.elapsed().as_millis() as u64,
+                "[Prefetch] login page warmed"
+            ),
+            Err(e) => warn!(
+                error = %e,
+                resolve_ms = resolved_ms,
+                "[Prefetch] login page request failed"
+            ),
+        }
+    });
+}
@@ -0,0 +1,190 @@
+use std::path::Path;
+
+use tauri::{AppHandle, Manager};
+use tracing::{info, warn};
+
+use crate::projector::runtime::FlashRuntime;
+
+// 启动前检查
+//
+// 启动 projector 依赖若干环境条件，过去只能在 launch_projector_auto 中途失败并得到笼统的错误。
+// preflight_check 逐项检查并返回清单：播放器（projector.exe 或 Ruffle）、WebView2 运行时、
+// 日志和缓存目录的写入权限，以及启用 windivert_capture 时的管理员权限和 WinDivert 驱动。
+// 每项给出结果、说明和可操作的建议，界面可以直接渲染；只有 fail 项会阻止启动，warn 项只影响部分功能。
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckStatus {
+    Pass,
+    /// 可以启动，但部分功能受影响
+    Warn,
+    /// 无法启动
+    Fail,
+    /// 未启用，不需要检查
+    Skipped,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize)]
+pub struct CheckItem {
+    pub id: &'static str,
+    pub status: CheckStatus,
+    pub detail: String,
+    /// 建议的处理方法
+    pub action: Option<String>,
+}
+
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct PreflightReport {
+    /// 没有 fail 项
+    pub ok: bool,
+    pub checks: Vec<CheckItem>,
+}
+
+impl CheckItem {
+    fn new(id: &'static str, status: CheckStatus, detail: impl Into<String>) -> Self {
+        Self {
+            id,
+            status,
+            detail: detail.into(),
+            action: None,
+        }
+    }
+
+    fn action(mut self, action: &str) -> Self {
+        self.action = Some(action.to_string());
+        self
+    }
+}
+
+/// 执行全部检查
+pub fn run(app: &AppHandle) -> PreflightReport {
+    let checks = vec![
+        player(app),
+        webview2(tauri::webview_version().ok()),
+        writable_dir(app, "logs_dir", "logs"),
+        writable_dir(app, "cache_dir", "cache"),
+        windivert(),
+    ];
+    let ok = !checks.iter().any(|check| check.status == CheckStatus::Fail);
+    for check in &checks {
+        if matches!(check.status, CheckStatus::Warn | CheckStatus::Fail) {
+            warn!(
+                check = check.id,
+                status = ?check.status,
+                detail = %check.detail,
+                "[Preflight] check not passed"
+            );
+        }
+    }
+    info!(ok = ok, "[Preflight] checks finished");
+    PreflightReport { ok, checks }
+}
+
+fn player(app: &AppHandle) -> CheckItem {
+    match crate::projector::runtime::resolve(app) {
+        Ok(resolved) if resolved.runtime == FlashRuntime::Projector => CheckItem::new(
+            "player",
+            CheckStatus::Pass,
+            format!("projector: {}", resolved.path.display()),
+        ),
+        Ok(resolved) => CheckItem::new(
+            "player",
+            CheckStatus::Warn,
+            format!("Ruffle: {}", resolved.path.display()),
+        )
+        .action("Ruffle 对 AS3 的支持不完整，需要完整功能时请放置或下载 projector"),
+        Err(e) => CheckItem::new("player", CheckStatus::Fail, e)
+            .action("把 projector.exe 放到 resources 目录，或在设置中下载 projector"),
+    }
+}
+
+fn webview2(version: Option<String>) -> CheckItem {
+    match version {
+        Some(version) => CheckItem::new("webview2", CheckStatus::Pass, version),
+        None => CheckItem::new("webview2", CheckStatus::Fail, "WebView2 runtime not found")
+            .action("安装 Microsoft Edge WebView2 运行时"),
+    }
+}
+
+fn writable_dir(app: &AppHandle, id: &'static str, name: &str) -> CheckItem {
+    let dir = match app.path().app_data_dir() {
+        Ok(dir) => dir.join(name),
+        Err(_) => {
+            return CheckItem::new(id, CheckStatus::Fail, "Failed to resolve app data dir.")
+                .action("检查用户目录（AppData）是否可用")
+        }
+    };
+    match probe_write(&dir) {
+        Ok(()) => CheckItem::new(id, CheckStatus::Pass, dir.display().to_string()),
+        Err(e) => CheckItem::new(id, CheckStatus::Fail, format!("{}: {e}", dir.display()))
+            .action("检查目录权限，或关闭占用该目录的程序（部分安全软件会拦截写入）"),
+    }
+}
+
+/// 在目录中创建并删除一个临时文件
+fn probe_write(dir: &Path) -> std::io::Result<()> {
+    std::fs::create_dir_all(dir)?;
+    let probe = dir.join(".preflight");
+    std::fs::write(&probe, b"ok")?;
+    std::fs::remove_file(&probe)
+}
+
+fn windivert() -> CheckItem {
+    let capture = crate::config::current().capture;
+    windivert_status(
+        crate::config::experiment_enabled("windivert_capture"),
+        crate::wpe::windivert::is_elevated(),
+        crate::wpe::windivert::driver_available(),
+        capture.proxy_fallback && !capture.proxy_upstream.is_empty(),
+    )
+}
+
+/// WinDivert 检查结果；不可用但配置了本地代理时改用代理，只给出警告
+fn windivert_status(enabled: bool, elevated: bool, driver: bool, proxy: bool) -> CheckItem {
+    const ID: &str = "windivert";
+    if !enabled {
+        return CheckItem::new(ID, CheckStatus::Skipped, "windivert_capture disabled");
+    }
+    let problem = match (elevated, driver) {
+        (true, true) => return CheckItem::new(ID, CheckStatus::Pass, "elevated, driver loaded"),
+        (false, _) => "not running as administrator",
+        (true, false) => "WinDivert driver not available",
+    };
+    let action = if elevated {
+        "确认 WinDivert.dll 与 WinDivert64.sys 在程序目录中，且未被安全软件拦截"
+    } else {
+        "以管理员身份运行启动器"
+    };
+    if proxy {
+        CheckItem::new(
+            ID,
+            CheckStatus::Warn,
+            format!("{problem}, using proxy capture"),
+        )
+        .action(action)
+    } else {
+        CheckItem::new(ID, CheckStatus::Fail, problem).action(action)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn windivert_needs_elevation_and_driver() {
+        let status = |enabled, elevated, driver, proxy| {
+            windivert_status(enabled, elevated, driver, proxy).status
+        };
+        assert_eq!(status(false, false, false, false), CheckStatus::Skipped);
+        assert_eq!(status(true, true, true, false), CheckStatus::Pass);
+        assert_eq!(status(true, false, true, false), CheckStatus::Fail);
+        assert_eq!(status(true, true, false, false), CheckStatus::Fail);
+        // 配置了本地代理时可以改用代理捕获
+        assert_eq!(status(true, false, false, true), CheckStatus::Warn);
+        assert_eq!(
+            windivert_status(true, false, true, false).action.as_deref(),
+            Some("以管理员身份运行启动器")
+        );
+    }
+}
@@ -0,0 +1,266 @@
+use std::sync::atomic::Ordering;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use tauri::{AppHandle, Emitter, Manager};
+use tracing::{info, warn};
+
+use crate::config::AutoRestartConfig;
+use crate::pacing::Pace;
+use crate::state::AppState;
+use crate::wpe::{GamePacket, PacketAction, PacketHandler};
+
+// 长会话自动重启
+//
+// 达到时长或内存阈值后，等到"安全时机"再重启 projector（沿用当前 swf 地址，无需重新登录）：
+// - 最近 idle_secs 内没有出站封包（玩家没有操作，不在战斗或交易中）
+// - 最近 idle_secs 内窗口标题没有变化（不在切换地图/加载中）
+// 开始重启前发出 auto_restart_countdown 倒计时事件，期间可以用 postpone_auto_restart 推迟。
+
+/// 检查间隔：最小化时玩家不在操作，放慢检查不影响重启时机
+const POLL: Pace = Pace::from_millis(5000, 5000, 15_000);
+/// 推迟的默认时长
+pub const DEFAULT_POSTPONE: Duration = Duration::from_secs(30 * 60);
+/// 一次最多推迟的分钟数
+pub const MAX_POSTPONE_MINUTES: u64 = 24 * 60;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RestartReason {
+    SessionLength,
+    WorkingSet,
+}
+
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct RestartCountdown {
+    pub pid: u32,
+    pub reason: RestartReason,
+    pub remaining_secs: u64,
+}
+
+/// 推迟到的时间点（按 pid 记录，新进程不继承）
+static POSTPONED: Mutex<Option<(u32, Instant)>> = Mutex::new(None);
+
+/// 记录最近一次出站封包时间的 handler
+#[derive(Default)]
+struct ActivityTracker {
+    last_outbound: Mutex<Option<Instant>>,
+}
+
+impl ActivityTracker {
+    fn idle_for(&self, duration: Duration) -> bool {
+        self.last_outbound
+            .lock()
+            .expect("activity lock")
+            .is_none_or(|last| last.elapsed() >= duration)
+    }
+}
+
+impl PacketHandler for ActivityTracker {
+    fn handle_outbound(&self, _packet: &GamePacket) -> PacketAction {
+        *self.last_outbound.lock().expect("activity lock") = Some(Instant::now());
+        PacketAction::Forward
+    }
+
+    fn handle_inbound(&self, _packet: &GamePacket) -> PacketAction {
+        PacketAction::Forward
+    }
+}
+
+/// 是否达到重启阈值
+fn due_reason(
+    config: &AutoRestartConfig,
+    elapsed: Duration,
+    working_set: Option<u64>,
+) -> Option<RestartReason> {
+    if config.max_session_hours > 0.0 && elapsed.as_secs_f64() >= config.max_session_hours * 3600.0
+    {
+        return Some(RestartReason::SessionLength);
+    }
+    let limit = config.max_working_set_mb * 1024 * 1024;
+    if limit > 0 && working_set.is_some_and(|bytes| bytes >= limit) {
+        return Some(RestartReason::WorkingSet);
+    }
+    None
+}
+
+/// 按分钟数计算推迟时长（None 为默认值），超出 1..=MAX_POSTPONE_MINUTES 时返回错误
+pub fn postpone_duration(minutes: Option<u64>) -> Result<Duration, String> {
+    match minutes {
+        None => Ok(DEFAULT_POSTPONE),
+        Some(m) if (1..=MAX_POSTPONE_MINUTES).contains(&m) => Ok(Duration::from_secs(m * 60)),
+        Some(m) => Err(format!(
+            "Postpone minutes must be between 1 and {MAX_POSTPONE_MINUTES}, got {m}."
+        )),
+    }
+}
+
+/// 推迟当前进程的自动重启
+pub fn postpone(pid: u32, duration: Duration) {
+    *POSTPONED.lock().expect("postpone lock") = Some((pid, Instant::now() + duration));
+    info!(
+        pid = pid,
+        secs = duration.as_secs(),
+        "[AutoRestart] postponed"
+    );
+}
+
+fn postponed(pid: u32) -> bool {
+    POSTPONED
+        .lock()
+        .expect("postpone lock")
+        .is_some_and(|(p, until)| p == pid && Instant::now() < until)
+}
+
+/// 读取当前 projector 的工作集与标题最近变化时间；进程已被替换时返回 None
+fn snapshot(app: &AppHandle, pid: u32) -> Option<(Option<u64>, Option<Instant>)> {
+    let state = app.state::<Mutex<AppState>>();
+    let guard = state.lock().expect("state lock");
+    let projector = guard.projector.as_ref().filter(|p| p.process.pid == pid)?;
+    Some((
+        super::introspect::working_set_bytes(&projector.process),
+        projector.title.as_ref().map(|(_, changed)| *changed),
+    ))
+}
+
+fn exiting() -> bool {
+    crate::EXITING.load(Ordering::Relaxed)
+}
+
+/// 倒计时；期间被推迟或进程被替换时返回 false
+fn countdown(app: &AppHandle, pid: u32, reason: RestartReason, secs: u64) -> bool {
+    for remaining in (1..=secs).rev() {
+        if exiting() || postponed(pid) || snapshot(app, pid).is_none() {
+            let _ = app.emit(crate::events::AUTO_RESTART_CANCELLED, pid);
+            return false;
+        }
+        let _ = app.emit(
+            crate::events::AUTO_RESTART_COUNTDOWN,
+            RestartCountdown {
+                pid,
+                reason,
+                remaining_secs: remaining,
+            },
+        );
+        std::thread::sleep(Duration::from_secs(1));
+    }
+    !exiting() && !postponed(pid) && snapshot(app, pid).is_some()
+}
+
+/// 为新启动的 projector 开始监视（launch_projector_auto 成功后调用）
+pub fn watch(app: AppHandle, pid: u32) {
+    let tracker = Arc::new(ActivityTracker::default());
+    let interceptor = app
+        .state::<Mutex<AppState>>()
+        .lock()
+        .expect("state lock")
+        .wpe_interceptor
+        .clone();
+    let registered = interceptor.map(|interceptor| {
+        let id = interceptor.register_handler_with(
+            tracker.clone(),
+            0,
+            Some(crate::wpe::Direction::Outbound),
+        );
+        (Arc::downgrade(&interceptor), id)
+    });
+
+    std::thread::spawn(move || {
+        monitor(&app, pid, &tracker);
+        // 不持有拦截器，避免延长已被替换的拦截器的生命周期
+        if let Some((interceptor, id)) = registered {
+            if let Some(interceptor) = interceptor.upgrade() {
+                interceptor.unregister_handler(id);
+            }
+        }
+    });
+}
+
+fn monitor(app: &AppHandle, pid: u32, tracker: &ActivityTracker) {
+    let started = Instant::now();
+    loop {
+        crate::pacing::wait(&POLL);
+        if exiting() {
+            return;
+        }
+        let Some((working_set, title_changed)) = snapshot(app, pid) else {
+            return;
+        };
+        let config = crate::config::current().launcher.auto_restart;
+        if !config.enabled || postponed(pid) {
+            continue;
+        }
+        let Some(reason) = due_reason(&config, started.elapsed(), working_set) else {
+            continue;
+        };
+
+        let idle = Duration::from_secs(config.idle_secs);
+        let title_settled = title_changed.is_none_or(|changed| changed.elapsed() >= idle);
+        if !tracker.idle_for(idle) || !title_settled {
+            continue;
+        }
+
+        info!(
+            pid = pid,
+            reason = ?reason,
+            working_set = ?working_set,
+            "[AutoRestart] restart due"
+        );
+        if !countdown(app, pid, reason, config.warning_secs) {
+            continue;
+        }
+
+        crate::session_history::end_current("auto_restart", false);
+        // 创建、嵌入窗口需要在主线程执行
+        let app_handle = app.clone();
+        let _ = app.run_on_main_thread(move || {
+            let state = app_handle.state::<Mutex<AppState>>();
+            if let Err(e) = crate::launcher::launch_projector_auto(&app_handle, &state) {
+                warn!(error = %e, "[AutoRestart] relaunch failed");
+            }
+        });
+        // 新进程会启动自己的监视线程
+        return;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn restart_due_by_length_or_memory() {
+        let config = AutoRestartConfig {
+            enabled: true,
+            max_session_hours: 2.0,
+            max_working_set_mb: 1024,
+            ..Default::default()
+        };
+        let hour = Duration::from_secs(3600);
+        assert_eq!(due_reason(&config, hour, Some(512 << 20)), None);
+        assert_eq!(
+            due_reason(&config, 2 * hour, None),
+            Some(RestartReason::SessionLength)
+        );
+        assert_eq!(
+            due_reason(&config, hour, Some(1024 << 20)),
+            Some(RestartReason::WorkingSet)
+        );
+
+        let unlimited = AutoRestartConfig {
+            max_session_hours: 0.0,
+            max_working_set_mb: 0,
+            ..config
+        };
+        assert_eq!(due_reason(&unlimited, 100 * hour, Some(u64::MAX)), None);
+    }
+
+    #[test]
+    fn postpone_minutes_are_bounded() {
+        assert_eq!(postpone_duration(None), Ok(DEFAULT_POSTPONE));
+        assert_eq!(postpone_duration(Some(5)), Ok(Duration::from_secs(300)));
+        assert!(postpone_duration(Some(0)).is_err());
+        assert!(postpone_duration(Some(MAX_POSTPONE_MINUTES + 1)).is_err());
+        assert!(postpone_duration(Some(u64::MAX)).is_err());
+    }
+}
@@ -0,0 +1,126 @@
+use std::path::PathBuf;
+
+use crate::config::LaunchConfig;
+
+// 播放器进程的启动参数
+//
+// 默认以 `可执行文件 [后端选项] main.swf地址` 启动，工作目录和环境变量继承启动器。配置 launcher.launch
+// 可以指定工作目录（working_dir）、追加参数（args）和额外的环境变量（env），用于包装脚本、兼容层等场景。
+// 三者都支持占位符 {swf_url}（main.swf 地址）和 {profile}（--profile 名称，未指定时为 default）；
+// args 中出现 {swf_url} 时由它决定地址的位置，不再在末尾追加地址。
+
+const SWF_URL: &str = "{swf_url}";
+const PROFILE: &str = "{profile}";
+const DEFAULT_PROFILE: &str = "default";
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LaunchCommand {
+    pub args: Vec<String>,
+    /// None 时继承启动器的工作目录
+    pub working_dir: Option<PathBuf>,
+    /// 在启动器环境变量之上追加或覆盖的变量
+    pub env: Vec<(String, String)>,
+}
+
+impl LaunchCommand {
+    /// 按 launcher.launch 生成启动参数；`options` 为后端自带的选项
+    pub fn from_config(options: &[&str], swf_url: &str) -> Self {
+        let profile = crate::cli::args().profile.clone();
+        Self::build(
+            &crate::config::current().launcher.launch,
+            options,
+            swf_url,
+            profile.as_deref().unwrap_or(DEFAULT_PROFILE),
+        )
+    }
+
+    fn build(config: &LaunchConfig, options: &[&str], swf_url: &str, profile: &str) -> Self {
+        let expand = |template: &str| template.replace(SWF_URL, swf_url).replace(PROFILE, profile);
+        let mut args: Vec<String> = options.iter().map(|option| option.to_string()).collect();
+        args.extend(config.args.iter().map(|arg| expand(arg)));
+        // 没有地址时不追加空参数
+        if !config.args.iter().any(|arg| arg.contains(SWF_URL)) && !swf_url.is_empty() {
+            args.push(swf_url.to_string());
+        }
+        let working_dir = config.working_dir.trim();
+        let mut env: Vec<(String, String)> = config
+            .env
+            .iter()
+            .filter(|(key, _)| !key.trim().is_empty())
+            .map(|(key, value)| (key.trim().to_string(), expand(value)))
+            .collect();
+        env.sort();
+        Self {
+            args,
+            working_dir: (!working_dir.is_empty()).then(|| PathBuf::from(expand(working_dir))),
+            env,
+        }
+    }
+}
+
+/// 按 Windows 命令行规则为参数加引号（含空白或引号时）
+#[cfg(target_os = "windows")]
+pub fn quote_arg(arg: &str) -> String {
+    if !arg.is_empty() && !arg.contains([' ', '\t', '"']) {
+        return arg.to_string();
+    }
+    let mut quoted = String::from("\"");
+    let mut backslashes = 0;
+    for ch in arg.chars() {
+        match ch {
+            '\\' => backslashes += 1,
+            '"' => {
+                quoted.push_str(&"\\".repeat(backslashes * 2 + 1));
+                backslashes = 0;
+            }
+            _ => {
+                quoted.push_str(&"\\".repeat(backslashes));
+                backslashes = 0;
+            }
+        }
+        if ch != '\\' {
+            quoted.push(ch);
+        }
+    }
+    quoted.push_str(&"\\".repeat(backslashes * 2));
+    quoted.push('"');
+    quoted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expands_placeholders() {
+        let url = "https://res.example.com/main.swf?1=&a=b";
+        let default = LaunchCommand::build(&LaunchConfig::default(), &["--no-gui"], url, "main");
+        assert_eq!(default.args, vec!["--no-gui", url]);
+        assert_eq!(default.working_dir, None);
+
+        let config = LaunchConfig {
+            working_dir: "D:\\roco\\{profile}".to_string(),
+            args: vec!["--movie={swf_url}".to_string(), "-v".to_string()],
+            env: [
+                ("ROCO_PROFILE".to_string(), "{profile}".to_string()),
+                (" ".to_string(), "ignored".to_string()),
+            ]
+            .into_iter()
+            .collect(),
+        };
+        let command = LaunchCommand::build(&config, &[], url, "alt");
+        assert_eq!(
+            command.args,
+            vec![format!("--movie={url}"), "-v".to_string()]
+        );
+        assert_eq!(command.working_dir, Some(PathBuf::from("D:\\roco\\alt")));
+        assert_eq!(
+            command.env,
+            vec![("ROCO_PROFILE".to_string(), "alt".to_string())]
+        );
+
+        // 没有地址
+        let empty = LaunchCommand::build(&LaunchConfig::default(), &[], "", "main");
+        assert!(empty.args.is_empty());
+    }
+}
@@ -0,0 +1,122 @@
+use crate::state::ProjectorProcess;
+
+/// projector 进程信息（启动后采集一次，供 WPE 等模块按位数选择后端）
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ProcessInfo {
+    /// 进程是否为 32 位（WOW64 或 32 位系统）
+    pub is_32bit: bool,
+    /// 进程架构：x86 / x64 / arm64 / unknown
+    pub architecture: String,
+    /// 系统原生架构
+    pub native_architecture: String,
+    /// 已加载模块名（小写）
+    pub modules: Vec<String>,
+}
+
+#[cfg(target_os = "windows")]
+fn machine_name(machine: u16) -> &'static str {
+    match machine {
+        0x014c => "x86",
+        0x8664 => "x64",
+        0xAA64 => "arm64",
+        _ => "unknown",
+    }
+}
+
+#[cfg(target_os = "windows")]
+pub fn introspect(process: &ProjectorProcess) -> Result<ProcessInfo, String> {
+    use windows::Win32::Foundation::HMODULE;
+    use windows::Win32::System::ProcessStatus::{
+        EnumProcessModulesEx, GetModuleBaseNameW, LIST_MODULES_ALL,
+    };
+    use windows::Win32::System::SystemInformation::{
+        IMAGE_FILE_MACHINE, IMAGE_FILE_MACHINE_UNKNOWN,
+    };
+    use windows::Win32::System::Threading::IsWow64Process2;
+
+    let mut process_machine = IMAGE_FILE_MACHINE::default();
+    let mut native_machine = IMAGE_FILE_MACHINE::default();
+    unsafe {
+        IsWow64Process2(
+            process.handle,
+            &mut process_machine,
+            Some(&mut native_machine as *mut _),
+        )
+    }
+    .map_err(|e| format!("IsWow64Process2 failed: {e}"))?;
+
+    // 非 WOW64 进程返回 UNKNOWN，此时进程架构与系统一致
+    let machine = if process_machine == IMAGE_FILE_MACHINE_UNKNOWN {
+        native_machine
+    } else {
+        process_machine
+    };
+    let architecture = machine_name(machine.0).to_string();
+    let native_architecture = machine_name(native_machine.0).to_string();
+
+    // 缓冲区不够时按返回的所需大小重新调用；两次调用之间可能有新模块加载，
+    // 重试几次仍不够时使用最后一次取到的部分
+    let mut handles = vec![HMODULE::default(); 512];
+    let mut count = None;
+    for _ in 0..4 {
+        let mut needed = 0u32;
+        let enum_result = unsafe {
+            EnumProcessModulesEx(
+                process.handle,
+                handles.as_mut_ptr(),
+                (handles.len() * std::mem::size_of::<HMODULE>()) as u32,
+                &mut needed,
+                LIST_MODULES_ALL,
+            )
+        };
+        if enum_result.is_err() {
+            break;
+        }
+        let needed = needed as usize / std::mem::size_of::<HMODULE>();
+        count = Some(needed.min(handles.len()));
+        if needed <= handles.len() {
+            break;
+        }
+        handles.resize(needed + 32, HMODULE::default());
+    }
+    let mut modules = Vec::new();
+    if let Some(count) = count {
+        for module in &handles[..count] {
+            let mut name = [0u16; 260];
+            let len = unsafe { GetModuleBaseNameW(process.handle, Some(*module), &mut name) };
+            if len > 0 {
+                modules.push(String::from_utf16_lossy(&name[..len as usize]).to_ascii_lowercase());
+            }
+        }
+    }
+
+    Ok(ProcessInfo {
+        is_32bit: architecture == "x86",
+        architecture,
+        native_architecture,
+        modules,
+    })
+}
+
+/// 进程当前工作集（字节）
+#[cfg(target_os = "windows")]
+pub fn working_set_bytes(process: &ProjectorProcess) -> Option<u64> {
+    use windows::Win32::System::ProcessStatus::{GetProcessMemoryInfo, PROCESS_MEMORY_COUNTERS};
+
+    let mut counters = PROCESS_MEMORY_COUNTERS {
+        cb: std::mem::size_of::<PROCESS_MEMORY_COUNTERS>() as u32,
+        ..Default::default()
+    };
+    unsafe { GetProcessMemoryInfo(process.handle, &mut counters, counters.cb) }.ok()?;
+    Some(counters.WorkingSetSize as u64)
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn working_set_bytes(_process: &ProjectorProcess) -> Option<u64> {
+    None
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn introspect(_process: &ProjectorProcess) -> Result<ProcessInfo, String> {
+    Err("仅支持 Windows 平台。".to_string())
+}
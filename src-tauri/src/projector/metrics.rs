@@ -0,0 +1,404 @@
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::atomic::Ordering;
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use tauri::{AppHandle, Emitter, Manager};
+use tracing::{info, warn};
+
+use crate::pacing::Pace;
+use crate::state::{AppState, ProjectorHandle, ProjectorProcess};
+
+// projector 资源监视
+//
+// 每个 projector 进程启动后由一个监视线程定期采样 CPU 占用、工作集和 GPU 占用，最近 5 分钟的采样保存在
+// ProjectorHandle 中，get_projector_metrics 直接返回可用于绘图的序列。Flash 长时间运行会持续泄漏内存，
+// 工作集超过 launcher.metrics.warn_working_set_mb 时发出 projector_memory_warning 事件（每次越过阈值一次，
+// 回落到阈值的 90% 以下后重新计数），需要自动处理时配合 launcher.auto_restart 使用。
+// CPU 占用按全部逻辑核心折算（100% 为占满所有核心）；GPU 占用来自性能计数器 GPU Engine，
+// 取各引擎类型（3D、视频解码等）中占用最高的一类，与任务管理器一致，系统不提供该计数器时为 null。
+
+/// 保留的采样时长
+pub const HISTORY_WINDOW: Duration = Duration::from_secs(5 * 60);
+/// 采样间隔：最小化时放慢，曲线仍然连续
+const POLL: Pace = Pace::from_millis(2000, 2000, 5000);
+/// 回落到阈值的该比例以下后重新允许告警
+const REARM_RATIO: f64 = 0.9;
+
+#[derive(Clone, Copy, Debug, PartialEq, serde::Serialize)]
+pub struct MetricsSample {
+    /// 采样时间（Unix 毫秒）
+    pub at: u64,
+    pub cpu_percent: Option<f32>,
+    pub working_set_bytes: Option<u64>,
+    pub gpu_percent: Option<f32>,
+}
+
+/// 最近 HISTORY_WINDOW 内的采样
+#[derive(Clone, Debug, Default)]
+pub struct MetricsHistory {
+    samples: VecDeque<MetricsSample>,
+    peak_working_set_bytes: u64,
+    /// 已越过内存阈值，尚未回落
+    memory_warned: bool,
+}
+
+impl MetricsHistory {
+    fn push(&mut self, sample: MetricsSample) {
+        let window = HISTORY_WINDOW.as_millis() as u64;
+        while self
+            .samples
+            .front()
+            .is_some_and(|first| first.at + window < sample.at)
+        {
+            self.samples.pop_front();
+        }
+        if let Some(bytes) = sample.working_set_bytes {
+            self.peak_working_set_bytes = self.peak_working_set_bytes.max(bytes);
+        }
+        self.samples.push_back(sample);
+    }
+
+    /// 工作集刚越过阈值时返回 true
+    fn memory_crossed(&mut self, working_set: Option<u64>, threshold: u64) -> bool {
+        let Some(bytes) = working_set.filter(|_| threshold > 0) else {
+            return false;
+        };
+        if !self.memory_warned && bytes >= threshold {
+            self.memory_warned = true;
+            return true;
+        }
+        if self.memory_warned && (bytes as f64) < threshold as f64 * REARM_RATIO {
+            self.memory_warned = false;
+        }
+        false
+    }
+}
+
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct ProjectorMetrics {
+    pub pid: u32,
+    pub latest: Option<MetricsSample>,
+    /// 本次运行的最大工作集
+    pub peak_working_set_bytes: u64,
+    pub warn_working_set_bytes: u64,
+    /// 工作集当前高于告警阈值
+    pub memory_warning: bool,
+    /// 最近 5 分钟的采样，按时间顺序
+    pub history: Vec<MetricsSample>,
+}
+
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct MemoryWarning {
+    pub pid: u32,
+    pub working_set_bytes: u64,
+    pub threshold_bytes: u64,
+}
+
+fn warn_threshold_bytes() -> u64 {
+    let mb = crate::config::current()
+        .launcher
+        .metrics
+        .warn_working_set_mb;
+    mb * 1024 * 1024
+}
+
+/// 当前 projector 的资源占用
+pub fn snapshot(projector: &ProjectorHandle) -> ProjectorMetrics {
+    let metrics = &projector.metrics;
+    ProjectorMetrics {
+        pid: projector.process.pid,
+        latest: metrics.samples.back().copied(),
+        peak_working_set_bytes: metrics.peak_working_set_bytes,
+        warn_working_set_bytes: warn_threshold_bytes(),
+        memory_warning: metrics.memory_warned,
+        history: metrics.samples.iter().copied().collect(),
+    }
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// 两次采样间的 CPU 占用；`cpu_time` 为进程累计的内核 + 用户时间（100ns）
+fn cpu_percent(previous: u64, current: u64, elapsed: Duration, cpus: usize) -> Option<f32> {
+    let wall = elapsed.as_nanos() as f64 / 100.0 * cpus.max(1) as f64;
+    if wall <= 0.0 || current < previous {
+        return None;
+    }
+    Some(((current - previous) as f64 / wall * 100.0).min(100.0) as f32)
+}
+
+/// 各 GPU 引擎实例的占用按引擎类型求和，取最高的一类
+fn gpu_percent(engines: &[(String, f64)]) -> Option<f32> {
+    if engines.is_empty() {
+        return None;
+    }
+    let mut by_type: BTreeMap<&str, f64> = BTreeMap::new();
+    for (instance, value) in engines {
+        let engine_type = instance
+            .rsplit_once("engtype_")
+            .map_or(instance.as_str(), |(_, engine_type)| engine_type);
+        *by_type.entry(engine_type).or_default() += value;
+    }
+    let busiest = by_type.values().copied().fold(0.0, f64::max);
+    Some(busiest.clamp(0.0, 100.0) as f32)
+}
+
+/// 为新启动的 projector 开始采样（launch_projector_auto 成功后调用）
+pub fn watch(app: AppHandle, pid: u32) {
+    std::thread::spawn(move || monitor(&app, pid));
+}
+
+fn monitor(app: &AppHandle, pid: u32) {
+    let cpus = std::thread::available_parallelism().map_or(1, |n| n.get());
+    let mut gpu = GpuQuery::open(pid);
+    let mut previous_cpu: Option<(u64, Instant)> = None;
+    loop {
+        crate::pacing::wait(&POLL);
+        if crate::EXITING.load(Ordering::Relaxed) {
+            return;
+        }
+        let config = crate::config::current().launcher.metrics;
+        let gpu_percent = if config.enabled {
+            gpu.as_mut().and_then(GpuQuery::sample)
+        } else {
+            None
+        };
+
+        let state = app.state::<Mutex<AppState>>();
+        let mut guard = state.lock().expect("state lock");
+        let Some(projector) = guard.projector.as_mut().filter(|p| p.process.pid == pid) else {
+            info!(pid = pid, "[Metrics] projector gone, stopping");
+            return;
+        };
+        if !config.enabled {
+            continue;
+        }
+        let now = Instant::now();
+        let cpu_total = cpu_time(&projector.process);
+        let cpu = match (previous_cpu, cpu_total) {
+            (Some((previous, at)), Some(current)) => cpu_percent(previous, current, now - at, cpus),
+            _ => None,
+        };
+        previous_cpu = cpu_total.map(|time| (time, now));
+        let working_set = super::introspect::working_set_bytes(&projector.process);
+        projector.metrics.push(MetricsSample {
+            at: now_ms(),
+            cpu_percent: cpu,
+            working_set_bytes: working_set,
+            gpu_percent,
+        });
+        let threshold = warn_threshold_bytes();
+        let crossed = projector.metrics.memory_crossed(working_set, threshold);
+        drop(guard);
+
+        if crossed {
+            let working_set_bytes = working_set.unwrap_or_default();
+            warn!(
+                pid = pid,
+                working_set_bytes = working_set_bytes,
+                threshold_bytes = threshold,
+                "[Metrics] projector working set above threshold"
+            );
+            let _ = app.emit(
+                crate::events::PROJECTOR_MEMORY_WARNING,
+                MemoryWarning {
+                    pid,
+                    working_set_bytes,
+                    threshold_bytes: threshold,
+                },
+            );
+        }
+    }
+}
+
+/// 进程累计的内核 + 用户 CPU 时间（100ns）
+#[cfg(target_os = "windows")]
+fn cpu_time(process: &ProjectorProcess) -> Option<u64> {
+    use windows::Win32::Foundation::FILETIME;
+    use windows::Win32::System::Threading::GetProcessTimes;
+
+    let mut creation = FILETIME::default();
+    let mut exit = FILETIME::default();
+    let mut kernel = FILETIME::default();
+    let mut user = FILETIME::default();
+    unsafe {
+        GetProcessTimes(
+            process.handle,
+            &mut creation,
+            &mut exit,
+            &mut kernel,
+            &mut user,
+        )
+    }
+    .ok()?;
+    let ticks =
+        |time: FILETIME| (u64::from(time.dwHighDateTime) << 32) | u64::from(time.dwLowDateTime);
+    Some(ticks(kernel) + ticks(user))
+}
+
+#[cfg(not(target_os = "windows"))]
+fn cpu_time(_process: &ProjectorProcess) -> Option<u64> {
+    None
+}
+
+/// 该进程的 GPU Engine 性能计数器
+#[cfg(target_os = "windows")]
+struct GpuQuery {
+    query: windows::Win32::System::Performance::PDH_HQUERY,
+    counter: windows::Win32::System::Performance::PDH_HCOUNTER,
+}
+
+#[cfg(target_os = "windows")]
+impl GpuQuery {
+    fn open(pid: u32) -> Option<Self> {
+        use windows::core::{HSTRING, PCWSTR};
+        use windows::Win32::System::Performance::{
+            PdhAddEnglishCounterW, PdhCloseQuery, PdhCollectQueryData, PdhOpenQueryW, PDH_HCOUNTER,
+            PDH_HQUERY,
+        };
+
+        let mut query = PDH_HQUERY::default();
+        if unsafe { PdhOpenQueryW(PCWSTR::null(), 0, &mut query) } != 0 {
+            warn!("[Metrics] PdhOpenQuery failed, GPU usage unavailable");
+            return None;
+        }
+        let path = HSTRING::from(format!("\\GPU Engine(pid_{pid}_*)\\Utilization Percentage"));
+        let mut counter = PDH_HCOUNTER::default();
+        if unsafe { PdhAddEnglishCounterW(query, &path, 0, &mut counter) } != 0 {
+            warn!("[Metrics] GPU Engine counter unavailable");
+            unsafe {
+                let _ = PdhCloseQuery(query);
+            }
+            return None;
+        }
+        // 利用率是差值计数器，先采集一次作为基准
+        unsafe {
+            let _ = PdhCollectQueryData(query);
+        }
+        Some(Self { query, counter })
+    }
+
+    fn sample(&mut self) -> Option<f32> {
+        use windows::Win32::System::Performance::{
+            PdhCollectQueryData, PdhGetFormattedCounterArrayW, PDH_FMT_COUNTERVALUE_ITEM_W,
+            PDH_FMT_DOUBLE, PDH_MORE_DATA,
+        };
+
+        if unsafe { PdhCollectQueryData(self.query) } != 0 {
+            return None;
+        }
+        let mut size = 0u32;
+        let mut count = 0u32;
+        let status = unsafe {
+            PdhGetFormattedCounterArrayW(self.counter, PDH_FMT_DOUBLE, &mut size, &mut count, None)
+        };
+        if status != PDH_MORE_DATA || size == 0 {
+            return None;
+        }
+        // 缓冲区在条目之后还存放实例名，按 8 字节对齐分配
+        let mut buffer = vec![0u64; (size as usize).div_ceil(8)];
+        let items = buffer.as_mut_ptr() as *mut PDH_FMT_COUNTERVALUE_ITEM_W;
+        let status = unsafe {
+            PdhGetFormattedCounterArrayW(
+                self.counter,
+                PDH_FMT_DOUBLE,
+                &mut size,
+                &mut count,
+                Some(items),
+            )
+        };
+        if status != 0 {
+            return None;
+        }
+        let items = unsafe { std::slice::from_raw_parts(items, count as usize) };
+        let engines: Vec<(String, f64)> = items
+            .iter()
+            .filter(|item| item.FmtValue.CStatus == 0)
+            .map(|item| {
+                let name = unsafe { item.szName.to_string() }.unwrap_or_default();
+                (name, unsafe { item.FmtValue.Anonymous.doubleValue })
+            })
+            .collect();
+        gpu_percent(&engines)
+    }
+}
+
+#[cfg(target_os = "windows")]
+impl Drop for GpuQuery {
+    fn drop(&mut self) {
+        unsafe {
+            let _ = windows::Win32::System::Performance::PdhCloseQuery(self.query);
+        }
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+struct GpuQuery;
+
+#[cfg(not(target_os = "windows"))]
+impl GpuQuery {
+    fn open(_pid: u32) -> Option<Self> {
+        None
+    }
+
+    fn sample(&mut self) -> Option<f32> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn computes_cpu_and_gpu_usage() {
+        // 1 秒内占用 0.5 秒 CPU 时间，4 个逻辑核心
+        let second = Duration::from_secs(1);
+        assert_eq!(cpu_percent(0, 5_000_000, second, 4), Some(12.5));
+        assert_eq!(cpu_percent(10, 5, second, 4), None);
+
+        let engines = [
+            ("pid_1_luid_0x0_phys_0_eng_0_engtype_3D".to_string(), 20.0),
+            ("pid_1_luid_0x0_phys_0_eng_1_engtype_3D".to_string(), 15.0),
+            (
+                "pid_1_luid_0x0_phys_0_eng_4_engtype_VideoDecode".to_string(),
+                30.0,
+            ),
+        ];
+        assert_eq!(gpu_percent(&engines), Some(35.0));
+        assert_eq!(gpu_percent(&[]), None);
+    }
+
+    #[test]
+    fn keeps_five_minutes_and_warns_once_per_crossing() {
+        let mb = 1024 * 1024;
+        let sample = |at_secs: u64, mb_used: u64| MetricsSample {
+            at: at_secs * 1000,
+            cpu_percent: None,
+            working_set_bytes: Some(mb_used * mb),
+            gpu_percent: None,
+        };
+        let mut history = MetricsHistory::default();
+        history.push(sample(0, 800));
+        history.push(sample(200, 1300));
+        history.push(sample(301, 900));
+        assert_eq!(history.samples.len(), 2);
+        assert_eq!(history.peak_working_set_bytes, 1300 * mb);
+
+        let threshold = 1000 * mb;
+        assert!(!history.memory_crossed(Some(900 * mb), threshold));
+        assert!(history.memory_crossed(Some(1000 * mb), threshold));
+        assert!(!history.memory_crossed(Some(1200 * mb), threshold));
+        // 回落到 95% 仍不重新计数，降到 90% 以下后再次越过才告警
+        assert!(!history.memory_crossed(Some(950 * mb), threshold));
+        assert!(!history.memory_crossed(Some(1100 * mb), threshold));
+        assert!(!history.memory_crossed(Some(850 * mb), threshold));
+        assert!(history.memory_crossed(Some(1100 * mb), threshold));
+        assert!(!history.memory_crossed(None, threshold));
+    }
+}
@@ -1,26 +1,49 @@
+pub mod auto_restart;
+pub mod command;
+pub mod introspect;
+pub mod metrics;
+pub mod reconnect;
+pub mod relogin;
+pub mod runtime;
+pub mod spectator;
+pub mod warm_pool;
+
 #[cfg(target_os = "windows")]
 use std::ffi::OsStr;
 use std::fs;
 #[cfg(target_os = "windows")]
 use std::os::windows::ffi::OsStrExt;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 #[cfg(not(target_os = "windows"))]
 use std::process::{Command, Stdio};
+use std::time::Duration;
 
 use tauri::path::BaseDirectory;
 use tauri::{AppHandle, Manager};
-use tracing::{error, info};
-use url::Url;
+use tracing::{error, info, warn};
 
 use crate::state::ProjectorProcess;
 
+/// 正常关闭的最长等待时间
+const MAX_CLOSE_GRACE_MS: u64 = 15_000;
+
 pub fn resolve_projector_path(app: &AppHandle) -> Result<PathBuf, String> {
+    // 配置选定的已下载版本优先于随程序分发的 projector
+    if let Some(path) = crate::projector_manager::selected_path() {
+        info!("projector path resolved (downloaded): {}", path.display());
+        return Ok(path);
+    }
+    resolve_resource(app, Path::new("projector.exe"))
+}
+
+/// 在资源目录（及开发时的 resources 目录）中查找随程序分发的文件
+pub fn resolve_resource(app: &AppHandle, name: &Path) -> Result<PathBuf, String> {
     let resolved = app
         .path()
-        .resolve("projector.exe", BaseDirectory::Resource)
+        .resolve(name, BaseDirectory::Resource)
         .map_err(|_| "Failed to resolve resource directory.".to_string())?;
     if fs::metadata(&resolved).is_ok() {
-        info!("projector path resolved: {}", resolved.display());
+        info!("{} path resolved: {}", name.display(), resolved.display());
         return Ok(resolved);
     }
 
@@ -28,36 +51,38 @@ pub fn resolve_projector_path(app: &AppHandle) -> Result<PathBuf, String> {
         .path()
         .resource_dir()
         .map_err(|_| "Failed to get resource directory.".to_string())?;
-    let fallback = resource_dir.join("projector.exe");
+    let fallback = resource_dir.join(name);
     if fs::metadata(&fallback).is_ok() {
-        info!("projector path resolved (fallback): {}", fallback.display());
+        info!(
+            "{} path resolved (fallback): {}",
+            name.display(),
+            fallback.display()
+        );
         return Ok(fallback);
     }
 
     if let Ok(mut exe) = std::env::current_exe() {
         exe.pop();
         let candidates = [
-            exe.join("resources").join("projector.exe"),
-            exe.join("..").join("resources").join("projector.exe"),
-            exe.join("..")
-                .join("..")
-                .join("resources")
-                .join("projector.exe"),
+            exe.join("resources").join(name),
+            exe.join("..").join("resources").join(name),
+            exe.join("..").join("..").join("resources").join(name),
             exe.join("..")
                 .join("..")
                 .join("debug")
                 .join("resources")
-                .join("projector.exe"),
+                .join(name),
             exe.join("..")
                 .join("..")
                 .join("release")
                 .join("resources")
-                .join("projector.exe"),
+                .join(name),
         ];
         for candidate in candidates {
             if fs::metadata(&candidate).is_ok() {
                 info!(
-                    "projector path resolved (exe fallback): {}",
+                    "{} path resolved (exe fallback): {}",
+                    name.display(),
                     candidate.display()
                 );
                 return Ok(candidate);
@@ -66,37 +91,78 @@ pub fn resolve_projector_path(app: &AppHandle) -> Result<PathBuf, String> {
     }
 
     Err(format!(
-        "Failed to locate projector.exe. Checked: {}, {}, and dev resources.",
+        "Failed to locate {}. Checked: {}, {}, and dev resources.",
+        name.display(),
         resolved.display(),
         fallback.display()
     ))
 }
 
+/// 以 `path 选项... swf_url` 启动播放器进程（Flash projector 或 Ruffle），
+/// 工作目录、追加参数和环境变量见 launcher.launch
 #[cfg(target_os = "windows")]
-pub fn launch_projector(path: &PathBuf, swf_url: &str) -> Result<ProjectorProcess, String> {
+pub fn launch_process(
+    path: &PathBuf,
+    options: &[&str],
+    swf_url: &str,
+) -> Result<ProjectorProcess, String> {
     use windows::core::{PCWSTR, PWSTR};
     use windows::Win32::Foundation::CloseHandle;
     use windows::Win32::System::Threading::{
-        CreateProcessW, CREATE_NO_WINDOW, PROCESS_CREATION_FLAGS, PROCESS_INFORMATION,
+        CreateProcessW, ResumeThread, CREATE_NO_WINDOW, CREATE_SUSPENDED,
+        CREATE_UNICODE_ENVIRONMENT, PROCESS_CREATION_FLAGS, PROCESS_INFORMATION,
         STARTF_USESHOWWINDOW, STARTUPINFOW,
     };
     use windows::Win32::UI::WindowsAndMessaging::SW_HIDE;
 
-    info!(
-        "launching projector: {} {}",
-        path.display(),
-        sanitize_url_for_log(swf_url)
-    );
+    let command = command::LaunchCommand::from_config(options, swf_url);
+    log_launch(path, &command, swf_url);
 
     let app_w: Vec<u16> = OsStr::new(path)
         .encode_wide()
         .chain(std::iter::once(0))
         .collect();
-    let cmd = format!("\"{}\" {}", path.display(), swf_url);
+    let mut cmd = format!("\"{}\"", path.display());
+    for arg in &command.args {
+        cmd.push(' ');
+        cmd.push_str(&command::quote_arg(arg));
+    }
     let mut cmd_w: Vec<u16> = OsStr::new(&cmd)
         .encode_wide()
         .chain(std::iter::once(0))
         .collect();
+    let dir_w: Option<Vec<u16>> = command.working_dir.as_ref().map(|dir| {
+        OsStr::new(dir)
+            .encode_wide()
+            .chain(std::iter::once(0))
+            .collect()
+    });
+    // 环境块：继承的变量加上配置的变量（同名时覆盖），每项以 0 结尾，整体再以 0 结尾
+    let env_w: Option<Vec<u16>> = (!command.env.is_empty()).then(|| {
+        let mut vars: Vec<(std::ffi::OsString, std::ffi::OsString)> = std::env::vars_os()
+            .filter(|(key, _)| {
+                !command
+                    .env
+                    .iter()
+                    .any(|(name, _)| OsStr::new(name).eq_ignore_ascii_case(key))
+            })
+            .collect();
+        vars.extend(
+            command
+                .env
+                .iter()
+                .map(|(key, value)| (key.into(), value.into())),
+        );
+        let mut block = Vec::new();
+        for (key, value) in vars {
+            block.extend(key.encode_wide());
+            block.push(u16::from(b'='));
+            block.extend(value.encode_wide());
+            block.push(0);
+        }
+        block.push(0);
+        block
+    });
 
     let mut si = STARTUPINFOW::default();
     si.cb = std::mem::size_of::<STARTUPINFOW>() as u32;
@@ -111,9 +177,16 @@ pub fn launch_projector(path: &PathBuf, swf_url: &str) -> Result<ProjectorProces
             None,
             None,
             false,
-            PROCESS_CREATION_FLAGS(CREATE_NO_WINDOW.0),
-            None,
-            PCWSTR::null(),
+            // 先挂起，加入 Job Object 后再运行，避免在此之前派生的子进程不受约束
+            PROCESS_CREATION_FLAGS(
+                CREATE_NO_WINDOW.0 | CREATE_UNICODE_ENVIRONMENT.0 | CREATE_SUSPENDED.0,
+            ),
+            env_w
+                .as_ref()
+                .map(|env| env.as_ptr() as *const std::ffi::c_void),
+            dir_w
+                .as_ref()
+                .map_or(PCWSTR::null(), |dir| PCWSTR(dir.as_ptr())),
             &si,
             &mut pi,
         )
@@ -123,7 +196,9 @@ pub fn launch_projector(path: &PathBuf, swf_url: &str) -> Result<ProjectorProces
         return Err("Failed to launch projector.".to_string());
     }
 
+    assign_to_job(pi.hProcess, pi.dwProcessId);
     unsafe {
+        ResumeThread(pi.hThread);
         let _ = CloseHandle(pi.hThread);
     }
 
@@ -133,27 +208,114 @@ pub fn launch_projector(path: &PathBuf, swf_url: &str) -> Result<ProjectorProces
     })
 }
 
+/// 启动器持有的 Job Object（JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE）
+///
+/// 句柄在启动器进程结束时由系统关闭，无论正常退出、崩溃还是被强制结束，
+/// 其中的 projector 都会随之结束，不会遗留在后台的不可见进程。
+#[cfg(target_os = "windows")]
+fn kill_on_close_job() -> Option<windows::Win32::Foundation::HANDLE> {
+    use windows::core::PCWSTR;
+    use windows::Win32::Foundation::{CloseHandle, HANDLE};
+    use windows::Win32::System::JobObjects::{
+        CreateJobObjectW, JobObjectExtendedLimitInformation, SetInformationJobObject,
+        JOBOBJECT_EXTENDED_LIMIT_INFORMATION, JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE,
+    };
+
+    // HANDLE 不是 Sync，按整数保存
+    static JOB: std::sync::OnceLock<Option<isize>> = std::sync::OnceLock::new();
+    let job = JOB.get_or_init(|| {
+        let job = match unsafe { CreateJobObjectW(None, PCWSTR::null()) } {
+            Ok(job) => job,
+            Err(err) => {
+                warn!("create job object failed: {err}");
+                return None;
+            }
+        };
+        let mut limits = JOBOBJECT_EXTENDED_LIMIT_INFORMATION::default();
+        limits.BasicLimitInformation.LimitFlags = JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE;
+        let result = unsafe {
+            SetInformationJobObject(
+                job,
+                JobObjectExtendedLimitInformation,
+                &limits as *const _ as *const std::ffi::c_void,
+                std::mem::size_of::<JOBOBJECT_EXTENDED_LIMIT_INFORMATION>() as u32,
+            )
+        };
+        if let Err(err) = result {
+            warn!("set job object limits failed: {err}");
+            unsafe {
+                let _ = CloseHandle(job);
+            }
+            return None;
+        }
+        info!("job object created (kill on close)");
+        Some(job.0 as isize)
+    });
+    job.map(|job| HANDLE(job as *mut std::ffi::c_void))
+}
+
+/// 把新进程加入 Job Object；失败时只记录日志，进程照常运行
+#[cfg(target_os = "windows")]
+fn assign_to_job(process: windows::Win32::Foundation::HANDLE, pid: u32) {
+    use windows::Win32::System::JobObjects::AssignProcessToJobObject;
+
+    let Some(job) = kill_on_close_job() else {
+        return;
+    };
+    if let Err(err) = unsafe { AssignProcessToJobObject(job, process) } {
+        warn!("assign projector to job object failed: pid {pid}: {err}");
+    }
+}
+
 #[cfg(not(target_os = "windows"))]
-pub fn launch_projector(path: &PathBuf, swf_url: &str) -> Result<ProjectorProcess, String> {
-    info!(
-        "launching projector: {} {}",
-        path.display(),
-        sanitize_url_for_log(swf_url)
-    );
-    let mut child = Command::new(path)
-        .arg(swf_url)
+pub fn launch_process(
+    path: &PathBuf,
+    options: &[&str],
+    swf_url: &str,
+) -> Result<ProjectorProcess, String> {
+    let command = command::LaunchCommand::from_config(options, swf_url);
+    log_launch(path, &command, swf_url);
+    let mut process = Command::new(path);
+    process
+        .args(&command.args)
+        .envs(command.env.iter().cloned())
         .stdin(Stdio::null())
         .stdout(Stdio::null())
-        .stderr(Stdio::null())
-        .spawn()
-        .map_err(|err| {
-            error!("launch projector failed: {err}");
-            "Failed to launch projector.".to_string()
-        })?;
+        .stderr(Stdio::null());
+    if let Some(dir) = &command.working_dir {
+        process.current_dir(dir);
+    }
+    let mut child = process.spawn().map_err(|err| {
+        error!("launch projector failed: {err}");
+        "Failed to launch projector.".to_string()
+    })?;
     let pid = child.id();
     Ok(ProjectorProcess { child, pid })
 }
 
+/// 记录启动命令（地址只保留参数名）
+fn log_launch(path: &Path, command: &command::LaunchCommand, swf_url: &str) {
+    let redacted = crate::redact::url_keys_only(swf_url);
+    let args: Vec<String> = command
+        .args
+        .iter()
+        .map(|arg| {
+            if swf_url.is_empty() {
+                arg.clone()
+            } else {
+                arg.replace(swf_url, &redacted)
+            }
+        })
+        .collect();
+    info!(
+        "launching projector: {} {} (cwd: {:?}, env: {:?})",
+        path.display(),
+        args.join(" "),
+        command.working_dir,
+        command.env.iter().map(|(key, _)| key).collect::<Vec<_>>()
+    );
+}
+
 #[cfg(target_os = "windows")]
 pub fn stop_projector(process: &mut ProjectorProcess) {
     use windows::Win32::Foundation::CloseHandle;
@@ -170,21 +332,72 @@ pub fn stop_projector(process: &mut ProjectorProcess) {
     let _ = process.child.wait();
 }
 
-fn sanitize_url_for_log(url: &str) -> String {
-    let Ok(parsed) = Url::parse(url) else {
-        return "<invalid-url>".to_string();
-    };
-    let origin = parsed.origin().ascii_serialization();
-    let path = parsed.path();
-    let mut keys: Vec<String> = Vec::new();
-    for (k, _v) in parsed.query_pairs() {
-        keys.push(k.to_string());
+/// 关闭 projector 前等待其自行退出的时间；未开启 launcher.graceful_close 时为 0
+pub fn close_grace() -> Duration {
+    let launcher = crate::config::current().launcher;
+    if !launcher.graceful_close {
+        return Duration::ZERO;
     }
-    keys.sort();
-    keys.dedup();
-    if keys.is_empty() {
-        format!("{origin}{path}")
+    Duration::from_millis(launcher.close_grace_ms.min(MAX_CLOSE_GRACE_MS))
+}
+
+/// 先向 projector 窗口发送 WM_CLOSE，让 Flash 写回本地共享对象（存档）后自行退出；
+/// 超过 `grace` 仍未退出时再强制结束。`grace` 为 0 时直接结束进程
+#[cfg(target_os = "windows")]
+pub fn close_projector(process: &mut ProjectorProcess, hwnd: isize, grace: Duration) {
+    use windows::Win32::Foundation::{CloseHandle, HWND, LPARAM, WAIT_OBJECT_0, WPARAM};
+    use windows::Win32::System::Threading::WaitForSingleObject;
+    use windows::Win32::UI::WindowsAndMessaging::{PostMessageW, WM_CLOSE};
+
+    if !grace.is_zero() {
+        let hwnd = HWND(hwnd as *mut std::ffi::c_void);
+        match unsafe { PostMessageW(Some(hwnd), WM_CLOSE, WPARAM(0), LPARAM(0)) } {
+            Ok(()) => {
+                let waited =
+                    unsafe { WaitForSingleObject(process.handle, grace.as_millis() as u32) };
+                if waited == WAIT_OBJECT_0 {
+                    info!("projector closed gracefully: pid {}", process.pid);
+                    unsafe {
+                        let _ = CloseHandle(process.handle);
+                    }
+                    return;
+                }
+                warn!(
+                    "projector did not exit within {}ms after WM_CLOSE, terminating: pid {}",
+                    grace.as_millis(),
+                    process.pid
+                );
+            }
+            Err(err) => warn!("post WM_CLOSE failed: {err}, terminating projector"),
+        }
+    }
+    stop_projector(process);
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn close_projector(process: &mut ProjectorProcess, _hwnd: isize, _grace: Duration) {
+    stop_projector(process);
+}
+
+/// 进程已退出时返回退出码，仍在运行时返回 None
+#[cfg(target_os = "windows")]
+pub fn exit_code(process: &mut ProjectorProcess) -> Option<u32> {
+    use windows::Win32::System::Threading::GetExitCodeProcess;
+    const STILL_ACTIVE: u32 = 259;
+    let mut code = 0u32;
+    unsafe { GetExitCodeProcess(process.handle, &mut code) }.ok()?;
+    if code == STILL_ACTIVE {
+        None
     } else {
-        format!("{origin}{path} ?keys={}", keys.join(","))
+        Some(code)
     }
 }
+
+#[cfg(not(target_os = "windows"))]
+pub fn exit_code(process: &mut ProjectorProcess) -> Option<u32> {
+    match process.child.try_wait() {
+        Ok(Some(status)) => Some(status.code().unwrap_or(-1) as u32),
+        _ => None,
+    }
+}
+
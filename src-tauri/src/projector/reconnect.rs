@@ -0,0 +1,261 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use tauri::{AppHandle, Emitter, Manager};
+use tracing::{info, warn};
+
+use crate::config::ReconnectConfig;
+use crate::state::{emit_status, AppState, AppStatus};
+
+// 断线重连
+//
+// 捕获层报告服务器断开了游戏连接（RST/FIN、代理上游关闭）后，状态切换为 Disconnected，
+// 按指数退避倒计时，再用缓存的 swf 地址重新启动 projector（无需重新登录）。
+// 倒计时期间每秒发出 reconnect_countdown 事件，可以用 cancel_reconnect 取消。
+// 重连后稳定运行 STABLE_AFTER 才清零重试次数，连续重试超过 max_attempts 次后放弃。
+
+/// 重连后运行超过该时长再断线，重新从第一次重试算起
+const STABLE_AFTER: Duration = Duration::from_secs(120);
+
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct ReconnectCountdown {
+    /// 第几次重试（从 1 开始）
+    pub attempt: u32,
+    pub max_attempts: u32,
+    pub remaining_secs: u64,
+    pub reason: String,
+}
+
+struct Progress {
+    /// 进行中的倒计时的取消标志
+    cancel: Option<Arc<AtomicBool>>,
+    /// 连续重试次数
+    attempts: u32,
+    last_attempt: Option<Instant>,
+}
+
+static PROGRESS: Mutex<Progress> = Mutex::new(Progress {
+    cancel: None,
+    attempts: 0,
+    last_attempt: None,
+});
+
+/// 第 attempt 次（从 1 开始）重连前的等待时间：从 initial_delay_secs 起每次翻倍，不超过 max_delay_secs
+fn backoff(config: &ReconnectConfig, attempt: u32) -> Duration {
+    let factor = 1u64 << attempt.saturating_sub(1).min(16);
+    Duration::from_secs(
+        config
+            .initial_delay_secs
+            .saturating_mul(factor)
+            .min(config.max_delay_secs),
+    )
+}
+
+fn exiting() -> bool {
+    crate::EXITING.load(Ordering::Relaxed)
+}
+
+/// 更新状态栏（只在仍处于断线状态时，用户可能已经停止或重新登录）
+fn set_disconnected_message(app: &AppHandle, message: String) {
+    let state = app.state::<Mutex<AppState>>();
+    let mut guard = state.lock().expect("state lock");
+    if guard.status == AppStatus::Disconnected {
+        guard.message = Some(message);
+        emit_status(app, &guard);
+    }
+}
+
+/// 为新启动的 projector 监听断线（launch_projector_auto 成功后调用）
+pub fn watch(app: AppHandle, pid: u32) {
+    let interceptor = app
+        .state::<Mutex<AppState>>()
+        .lock()
+        .expect("state lock")
+        .wpe_interceptor
+        .clone();
+    if let Some(interceptor) = interceptor {
+        interceptor
+            .set_disconnect_listener(Arc::new(move |reason| on_disconnect(&app, pid, reason)));
+    }
+}
+
+fn on_disconnect(app: &AppHandle, pid: u32, reason: &str) {
+    let state = app.state::<Mutex<AppState>>();
+    {
+        let mut guard = state.lock().expect("state lock");
+        let current = guard
+            .projector
+            .as_ref()
+            .is_some_and(|p| p.process.pid == pid);
+        // 同一次断线可能报告多次；进程已被替换时也不处理
+        if !current || guard.status != AppStatus::Running {
+            return;
+        }
+        info!(pid = pid, reason = %reason, "[Reconnect] connection lost");
+        guard.status = AppStatus::Disconnected;
+        guard.message = Some(format!("Connection lost: {reason}"));
+        emit_status(app, &guard);
+    }
+    crate::sound::play(crate::sound::SoundEvent::Disconnect);
+    schedule(app, reason.to_string());
+}
+
+/// 安排下一次重连；未启用、没有缓存的 swf 地址或次数用尽时只保留断线状态
+fn schedule(app: &AppHandle, reason: String) {
+    let config = crate::config::current().launcher.reconnect;
+    let has_swf_url = app
+        .state::<Mutex<AppState>>()
+        .lock()
+        .expect("state lock")
+        .swf_url
+        .is_some();
+    if !config.enabled || !has_swf_url {
+        info!(
+            enabled = config.enabled,
+            has_swf_url = has_swf_url,
+            "[Reconnect] auto reconnect skipped"
+        );
+        return;
+    }
+
+    let (attempt, cancel) = {
+        let mut progress = PROGRESS.lock().expect("reconnect lock");
+        if progress.cancel.is_some() {
+            return;
+        }
+        if progress
+            .last_attempt
+            .is_none_or(|at| at.elapsed() >= STABLE_AFTER)
+        {
+            progress.attempts = 0;
+        }
+        progress.attempts += 1;
+        if progress.attempts > config.max_attempts {
+            progress.attempts = 0;
+            progress.last_attempt = None;
+            drop(progress);
+            warn!(max_attempts = config.max_attempts, "[Reconnect] giving up");
+            let state = app.state::<Mutex<AppState>>();
+            let mut guard = state.lock().expect("state lock");
+            if guard.status == AppStatus::Disconnected {
+                guard.status = AppStatus::Error;
+                guard.message = Some(format!(
+                    "Reconnect failed after {} attempts.",
+                    config.max_attempts
+                ));
+                emit_status(app, &guard);
+            }
+            return;
+        }
+        let cancel = Arc::new(AtomicBool::new(false));
+        progress.cancel = Some(cancel.clone());
+        (progress.attempts, cancel)
+    };
+
+    let event = ReconnectCountdown {
+        attempt,
+        max_attempts: config.max_attempts,
+        remaining_secs: backoff(&config, attempt).as_secs(),
+        reason,
+    };
+    let app = app.clone();
+    std::thread::spawn(move || run(&app, event, &cancel));
+}
+
+/// 倒计时；期间被取消或程序退出时返回 false
+fn countdown(app: &AppHandle, mut event: ReconnectCountdown, cancel: &AtomicBool) -> bool {
+    for remaining in (1..=event.remaining_secs).rev() {
+        if exiting() || cancel.load(Ordering::Relaxed) {
+            return false;
+        }
+        event.remaining_secs = remaining;
+        set_disconnected_message(
+            app,
+            format!(
+                "Reconnecting in {}s (attempt {}/{})",
+                remaining, event.attempt, event.max_attempts
+            ),
+        );
+        let _ = app.emit(crate::events::RECONNECT_COUNTDOWN, event.clone());
+        std::thread::sleep(Duration::from_secs(1));
+    }
+    !exiting() && !cancel.load(Ordering::Relaxed)
+}
+
+fn run(app: &AppHandle, event: ReconnectCountdown, cancel: &Arc<AtomicBool>) {
+    let attempt = event.attempt;
+    let proceed = countdown(app, event, cancel);
+    {
+        let mut progress = PROGRESS.lock().expect("reconnect lock");
+        if progress
+            .cancel
+            .as_ref()
+            .is_some_and(|c| Arc::ptr_eq(c, cancel))
+        {
+            progress.cancel = None;
+        }
+        if proceed {
+            progress.last_attempt = Some(Instant::now());
+        }
+    }
+    if !proceed {
+        info!(attempt = attempt, "[Reconnect] cancelled");
+        let _ = app.emit(crate::events::RECONNECT_CANCELLED, attempt);
+        set_disconnected_message(app, "Reconnect cancelled.".to_string());
+        return;
+    }
+
+    let state = app.state::<Mutex<AppState>>();
+    if state.lock().expect("state lock").status != AppStatus::Disconnected {
+        // 倒计时期间用户已经手动重启或停止
+        return;
+    }
+    info!(attempt = attempt, "[Reconnect] relaunching projector");
+    crate::usage::record("auto_reconnect");
+    crate::session_history::end_current("disconnected", true);
+    // 创建、嵌入窗口需要在主线程执行
+    let app_handle = app.clone();
+    let _ = app.run_on_main_thread(move || {
+        let state = app_handle.state::<Mutex<AppState>>();
+        if let Err(e) = crate::launcher::launch_projector_auto(&app_handle, &state) {
+            warn!(error = %e, "[Reconnect] relaunch failed");
+            {
+                let mut guard = state.lock().expect("state lock");
+                guard.status = AppStatus::Disconnected;
+                guard.message = Some(format!("Reconnect failed: {e}"));
+                emit_status(&app_handle, &guard);
+            }
+            schedule(&app_handle, format!("relaunch failed: {e}"));
+        }
+        // 成功时新进程会重新注册自己的断线监听
+    });
+}
+
+/// 取消进行中的重连倒计时，没有倒计时时返回 false
+pub fn cancel() -> bool {
+    match PROGRESS.lock().expect("reconnect lock").cancel.take() {
+        Some(flag) => {
+            flag.store(true, Ordering::Relaxed);
+            true
+        }
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff_doubles_up_to_the_limit() {
+        let config = ReconnectConfig {
+            initial_delay_secs: 5,
+            max_delay_secs: 60,
+            ..Default::default()
+        };
+        let delays: Vec<u64> = (1..=6).map(|n| backoff(&config, n).as_secs()).collect();
+        assert_eq!(delays, [5, 10, 20, 40, 60, 60]);
+        assert_eq!(backoff(&config, u32::MAX).as_secs(), 60);
+    }
+}
@@ -0,0 +1,243 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use tauri::{AppHandle, Emitter, Manager};
+use tracing::{info, warn};
+
+use crate::state::{emit_status, AppState, AppStatus};
+use crate::wpe::{Direction, GamePacket, PacketAction, PacketHandler};
+
+// 登录过期自动重新登录
+//
+// 服务器发来的文本封包包含 launcher.relogin.patterns 中的内容（登录失效、被挤下线等提示）时，
+// 认为本次会话的登录凭据已经失效：停止 projector，在隐藏的登录 webview 中重新打开登录页并抓取 login3，
+// 拿到新的 swf 地址后按正常流程重新启动。QQ 登录态仍然有效时登录页会自动完成登录，无需操作；
+// 超过 timeout_secs 仍未完成时显示登录页交给用户。各阶段通过 session_relogin 事件通知前端。
+// 命令表中没有收录过期提示的原文，默认 patterns 只是常见写法，需要按实际情况调整。
+
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReloginStage {
+    /// 检测到登录过期
+    Detected,
+    /// 正在隐藏的登录页中抓取 login3
+    Capturing,
+    /// 已拿到新的 swf 地址，正在启动 projector
+    Launching,
+    Completed,
+    /// 超时或出错，已显示登录页
+    Failed,
+}
+
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct ReloginProgress {
+    pub stage: ReloginStage,
+    pub message: String,
+}
+
+static IN_PROGRESS: AtomicBool = AtomicBool::new(false);
+
+/// 命中的过期提示；只检查文本封包，空的 pattern 忽略
+fn expiry_pattern<'a>(packet: &GamePacket, patterns: &'a [String]) -> Option<&'a str> {
+    let GamePacket::Text(text) = packet else {
+        return None;
+    };
+    patterns
+        .iter()
+        .find(|pattern| !pattern.is_empty() && text.contains(pattern.as_str()))
+        .map(String::as_str)
+}
+
+struct ExpiryDetector {
+    app: AppHandle,
+    pid: u32,
+    /// 启动时的配置快照，避免每个封包都读取配置
+    patterns: Vec<String>,
+}
+
+impl PacketHandler for ExpiryDetector {
+    fn handle_outbound(&self, _packet: &GamePacket) -> PacketAction {
+        PacketAction::Forward
+    }
+
+    fn handle_inbound(&self, packet: &GamePacket) -> PacketAction {
+        if let Some(pattern) = expiry_pattern(packet, &self.patterns) {
+            on_expired(&self.app, self.pid, pattern);
+        }
+        PacketAction::Forward
+    }
+}
+
+/// 为新启动的 projector 监听登录过期（launch_projector_auto 成功后调用）
+pub fn watch(app: AppHandle, pid: u32) {
+    let config = crate::config::current().launcher.relogin;
+    if !config.enabled {
+        return;
+    }
+    let interceptor = app
+        .state::<Mutex<AppState>>()
+        .lock()
+        .expect("state lock")
+        .wpe_interceptor
+        .clone();
+    if let Some(interceptor) = interceptor {
+        let detector = ExpiryDetector {
+            app,
+            pid,
+            patterns: config.patterns,
+        };
+        interceptor.register_handler_with(Arc::new(detector), 0, Some(Direction::Inbound));
+    }
+}
+
+fn progress(app: &AppHandle, stage: ReloginStage, message: &str) {
+    let _ = app.emit(
+        crate::events::SESSION_RELOGIN,
+        ReloginProgress {
+            stage,
+            message: message.to_string(),
+        },
+    );
+}
+
+fn on_expired(app: &AppHandle, pid: u32, pattern: &str) {
+    {
+        let state = app.state::<Mutex<AppState>>();
+        let guard = state.lock().expect("state lock");
+        let current = guard
+            .projector
+            .as_ref()
+            .is_some_and(|p| p.process.pid == pid);
+        // 提示可能连续出现多条；进程已被替换或不在运行中时不处理
+        if !current || guard.status != AppStatus::Running {
+            return;
+        }
+    }
+    if IN_PROGRESS.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    info!(pid = pid, pattern = %pattern, "[Relogin] session expired");
+    crate::usage::record("auto_relogin");
+    progress(app, ReloginStage::Detected, "Session expired");
+    // handler 在封包工作线程上调用，停止拦截器前先离开它
+    let app = app.clone();
+    std::thread::spawn(move || {
+        let timeout = Duration::from_secs(crate::config::current().launcher.relogin.timeout_secs);
+        if !run(&app, timeout) {
+            show_login_page(&app);
+        }
+        IN_PROGRESS.store(false, Ordering::SeqCst);
+    });
+}
+
+/// 重新抓取 login3 并等待新的 projector 进入运行状态，成功时返回 true
+fn run(app: &AppHandle, timeout: Duration) -> bool {
+    let state = app.state::<Mutex<AppState>>();
+    crate::session_history::end_current("session_expired", false);
+    super::reconnect::cancel();
+    crate::launcher::stop_projector(&state);
+    state.lock().expect("state lock").swf_url = None;
+
+    if let Err(e) = crate::login3_capture::start(app.clone(), app.state::<Mutex<AppState>>()) {
+        warn!(error = %e, "[Relogin] failed to start login capture");
+        progress(app, ReloginStage::Failed, &e);
+        return false;
+    }
+    {
+        let mut guard = state.lock().expect("state lock");
+        guard.message = Some("Session expired, logging in again".to_string());
+        emit_status(app, &guard);
+    }
+    let navigated = app
+        .get_webview("login")
+        .ok_or_else(|| "Login WebView not found.".to_string())
+        .and_then(|login| {
+            let url = crate::endpoints::login_url()
+                .parse()
+                .map_err(|_| "Invalid login URL.".to_string())?;
+            login
+                .navigate(url)
+                .map_err(|_| "Failed to navigate login webview.".to_string())
+        });
+    if let Err(e) = navigated {
+        warn!(error = %e, "[Relogin] failed to open login page");
+        progress(app, ReloginStage::Failed, &e);
+        return false;
+    }
+    progress(app, ReloginStage::Capturing, "Logging in again");
+
+    let deadline = Instant::now() + timeout;
+    let mut launching = false;
+    while Instant::now() < deadline {
+        if crate::EXITING.load(Ordering::Relaxed) {
+            return true;
+        }
+        std::thread::sleep(POLL_INTERVAL);
+        let status = state.lock().expect("state lock").status.clone();
+        match status {
+            AppStatus::Running => {
+                info!("[Relogin] projector relaunched with fresh login");
+                progress(app, ReloginStage::Completed, "Logged in again");
+                return true;
+            }
+            AppStatus::Launching | AppStatus::FoundValue if !launching => {
+                launching = true;
+                progress(app, ReloginStage::Launching, "Relaunching projector");
+            }
+            AppStatus::Error => break,
+            _ => {}
+        }
+    }
+    warn!(
+        timeout_secs = timeout.as_secs(),
+        "[Relogin] automatic login did not complete"
+    );
+    progress(
+        app,
+        ReloginStage::Failed,
+        "Automatic login did not complete, please log in manually",
+    );
+    false
+}
+
+/// 自动登录失败：停止抓取计时并显示登录页，让用户手动登录
+fn show_login_page(app: &AppHandle) {
+    let state = app.state::<Mutex<AppState>>();
+    if state.lock().expect("state lock").status == AppStatus::Running {
+        return;
+    }
+    crate::login3_capture::stop_timer_only(&state);
+    {
+        let mut guard = state.lock().expect("state lock");
+        guard.status = AppStatus::Login;
+        guard.message = Some("Session expired, please log in again.".to_string());
+        emit_status(app, &guard);
+    }
+    let handle = app.clone();
+    let _ = app.run_on_main_thread(move || {
+        if let Some(login) = handle.get_webview("login") {
+            let _ = login.show();
+        }
+        crate::launcher::resize_login_to_window(&handle);
+        crate::launcher::schedule_login_layout(handle.clone());
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_text_packets_match_patterns() {
+        let patterns = vec![String::new(), "重新登录".to_string()];
+        let expired = GamePacket::Text("System_登录已失效，请重新登录".to_string());
+        assert_eq!(expiry_pattern(&expired, &patterns), Some("重新登录"));
+        let other = GamePacket::Text("System_宠物逃跑".to_string());
+        assert_eq!(expiry_pattern(&other, &patterns), None);
+        let binary = GamePacket::build_map_jump(10001, 5);
+        assert_eq!(expiry_pattern(&binary, &patterns), None);
+    }
+}
@@ -0,0 +1,190 @@
+use std::path::{Path, PathBuf};
+
+use tauri::AppHandle;
+use tracing::{info, warn};
+
+use crate::projector::{launch_process, resolve_projector_path, resolve_resource};
+use crate::state::ProjectorProcess;
+
+// 播放器后端
+//
+// 默认用随程序分发的 Flash projector（resources/projector.exe）运行 main.swf。很多用户无法获取或分发
+// Adobe 的 projector，因此也支持 Ruffle 桌面版：放在 resources/ruffle/ruffle.exe，或在配置
+// launcher.ruffle_path 中指定路径。launcher.runtime 为 auto（默认）时优先使用 projector，找不到时改用 Ruffle；
+// projector / ruffle 则只使用指定的后端。两者都以 `可执行文件 [选项] main.swf地址` 启动，
+// 窗口查找、嵌入、退出监视等流程相同。Ruffle 对 AS3 的支持仍不完整，部分界面可能无法正常显示，
+// 因此使用 Ruffle（包括 auto 的回退）还需要打开实验开关 ruffle_runtime。
+
+/// Ruffle 的启动选项：隐藏菜单栏，窗口嵌入后只显示游戏画面
+const RUFFLE_OPTIONS: &[&str] = &["--no-gui"];
+
+/// 配置中的后端选择
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RuntimePreference {
+    /// 优先 projector，找不到时使用 Ruffle
+    #[default]
+    Auto,
+    Projector,
+    Ruffle,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FlashRuntime {
+    Projector,
+    Ruffle,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ResolvedRuntime {
+    pub runtime: FlashRuntime,
+    pub path: PathBuf,
+}
+
+/// 按配置选择后端并解析可执行文件路径
+pub fn resolve(app: &AppHandle) -> Result<ResolvedRuntime, String> {
+    let preference = crate::config::current().launcher.runtime;
+    let resolved = select(
+        preference,
+        crate::config::experiment_enabled("ruffle_runtime"),
+        || resolve_projector_path(app),
+        || resolve_ruffle_path(app),
+    )?;
+    info!(
+        preference = ?preference,
+        runtime = ?resolved.runtime,
+        path = %resolved.path.display(),
+        "[Runtime] flash runtime selected"
+    );
+    Ok(resolved)
+}
+
+/// Ruffle 路径：配置的 launcher.ruffle_path，未配置时在资源目录中查找 ruffle/ruffle.exe
+pub fn resolve_ruffle_path(app: &AppHandle) -> Result<PathBuf, String> {
+    let configured = crate::config::current().launcher.ruffle_path;
+    let configured = configured.trim();
+    if configured.is_empty() {
+        return resolve_resource(app, &Path::new("ruffle").join("ruffle.exe"));
+    }
+    let path = PathBuf::from(configured);
+    if std::fs::metadata(&path).is_ok() {
+        Ok(path)
+    } else {
+        warn!(path = %path.display(), "[Runtime] configured ruffle_path not found");
+        Err(format!("Ruffle not found at {}.", path.display()))
+    }
+}
+
+/// `ruffle_allowed` 为 ruffle_runtime 实验开关，关闭时不使用 Ruffle
+fn select(
+    preference: RuntimePreference,
+    ruffle_allowed: bool,
+    projector: impl FnOnce() -> Result<PathBuf, String>,
+    ruffle: impl FnOnce() -> Result<PathBuf, String>,
+) -> Result<ResolvedRuntime, String> {
+    const RUFFLE_DISABLED: &str = "Ruffle runtime is disabled (experiment ruffle_runtime).";
+    let with = |runtime| move |path| ResolvedRuntime { runtime, path };
+    match preference {
+        RuntimePreference::Projector => projector().map(with(FlashRuntime::Projector)),
+        RuntimePreference::Ruffle if !ruffle_allowed => Err(RUFFLE_DISABLED.to_string()),
+        RuntimePreference::Ruffle => ruffle().map(with(FlashRuntime::Ruffle)),
+        RuntimePreference::Auto => match projector() {
+            Ok(path) => Ok(with(FlashRuntime::Projector)(path)),
+            Err(projector_err) if !ruffle_allowed => {
+                Err(format!("{projector_err} {RUFFLE_DISABLED}"))
+            }
+            Err(projector_err) => ruffle()
+                .map(with(FlashRuntime::Ruffle))
+                .map_err(|ruffle_err| format!("{projector_err} {ruffle_err}")),
+        },
+    }
+}
+
+/// 用选定的后端启动 main.swf
+pub fn launch(resolved: &ResolvedRuntime, swf_url: &str) -> Result<ProjectorProcess, String> {
+    match resolved.runtime {
+        FlashRuntime::Projector => launch_process(&resolved.path, &[], swf_url),
+        FlashRuntime::Ruffle => launch_process(&resolved.path, RUFFLE_OPTIONS, swf_url),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn auto_falls_back_to_ruffle() {
+        let found = |name: &str| {
+            let path = PathBuf::from(name);
+            move || Ok(path)
+        };
+        let missing = |name: &str| {
+            let msg = format!("Failed to locate {name}.");
+            move || Err(msg)
+        };
+
+        let resolved = select(
+            RuntimePreference::Auto,
+            true,
+            found("projector.exe"),
+            found("ruffle.exe"),
+        );
+        assert_eq!(resolved.unwrap().runtime, FlashRuntime::Projector);
+        let resolved = select(
+            RuntimePreference::Auto,
+            true,
+            missing("projector.exe"),
+            found("ruffle.exe"),
+        );
+        assert_eq!(resolved.unwrap().runtime, FlashRuntime::Ruffle);
+        assert_eq!(
+            select(
+                RuntimePreference::Auto,
+                true,
+                missing("projector.exe"),
+                missing("ruffle.exe")
+            ),
+            Err("Failed to locate projector.exe. Failed to locate ruffle.exe.".to_string())
+        );
+
+        // 指定后端时不回退
+        assert!(select(
+            RuntimePreference::Projector,
+            true,
+            missing("projector.exe"),
+            found("ruffle.exe")
+        )
+        .is_err());
+        let resolved = select(
+            RuntimePreference::Ruffle,
+            true,
+            found("projector.exe"),
+            found("ruffle.exe"),
+        );
+        assert_eq!(resolved.unwrap().path, PathBuf::from("ruffle.exe"));
+
+        // 实验开关关闭时既不回退也不能指定 Ruffle
+        assert!(select(
+            RuntimePreference::Auto,
+            false,
+            missing("projector.exe"),
+            found("ruffle.exe")
+        )
+        .is_err());
+        assert!(select(
+            RuntimePreference::Ruffle,
+            false,
+            found("projector.exe"),
+            found("ruffle.exe")
+        )
+        .is_err());
+        let resolved = select(
+            RuntimePreference::Auto,
+            false,
+            found("projector.exe"),
+            found("ruffle.exe"),
+        );
+        assert_eq!(resolved.unwrap().runtime, FlashRuntime::Projector);
+    }
+}
@@ -0,0 +1,200 @@
+use std::sync::atomic::Ordering;
+use std::sync::{Arc, Mutex};
+
+use tauri::{AppHandle, Manager};
+use tracing::{info, warn};
+
+use crate::pacing::Pace;
+use crate::state::AppState;
+use crate::wpe::PacketInterceptor;
+
+// 旁观模式
+//
+// 附加到用户手动启动、并非由启动器拉起的 projector 进程，只做封包捕获：
+// 拦截器以只读方式创建（SNIFF 模式的 WinDivert），发送一律拒绝，规则和 handler 的修改、丢弃、注入
+// 在拦截器内部直接忽略。封包控制台、通知等派生事件照常工作；不嵌入窗口、不自动重连，
+// 目标进程退出后自动结束旁观。
+
+const PROCESS_POLL: Pace = Pace::from_millis(1000, 1000, 5000);
+
+/// 附加到指定进程；`pid` 为 None 时选择第一个正在运行的 projector.exe
+pub fn attach(app: &AppHandle, pid: Option<u32>) -> Result<u32, String> {
+    let state = app.state::<Mutex<AppState>>();
+    if state.lock().expect("state lock").projector.is_some() {
+        return Err("Projector launched by the launcher is running.".to_string());
+    }
+    let pid = match pid {
+        Some(pid) => pid,
+        None => projector_pids()
+            .into_iter()
+            .next()
+            .ok_or_else(|| "No running projector found.".to_string())?,
+    };
+    if !process_alive(pid) {
+        return Err(format!("Process {pid} is not running."));
+    }
+
+    let interceptor = PacketInterceptor::spectator(pid)
+        .map_err(|e| format!("Failed to create packet interceptor: {e}"))?;
+    crate::notify::attach(app, &interceptor);
+    crate::launcher::watch_wpe_status(app, &interceptor);
+    crate::packet_stream::attach(&interceptor);
+    let previous = state
+        .lock()
+        .expect("state lock")
+        .wpe_interceptor
+        .replace(interceptor.clone());
+    if let Some(previous) = previous {
+        previous.stop();
+    }
+    info!(pid = pid, "[Spectator] attached");
+    watch_exit(app.clone(), pid, Arc::downgrade(&interceptor));
+    Ok(pid)
+}
+
+/// 结束旁观，返回之前是否处于旁观模式
+pub fn detach(app: &AppHandle) -> bool {
+    let state = app.state::<Mutex<AppState>>();
+    let interceptor = {
+        let mut guard = state.lock().expect("state lock");
+        match guard.wpe_interceptor.as_ref() {
+            Some(interceptor) if interceptor.is_read_only() => guard.wpe_interceptor.take(),
+            _ => None,
+        }
+    };
+    match interceptor {
+        Some(interceptor) => {
+            interceptor.stop();
+            info!("[Spectator] detached");
+            true
+        }
+        None => false,
+    }
+}
+
+/// 目标进程退出后结束旁观；拦截器已被替换或停止时退出监视
+fn watch_exit(app: AppHandle, pid: u32, interceptor: std::sync::Weak<PacketInterceptor>) {
+    std::thread::spawn(move || loop {
+        crate::pacing::wait(&PROCESS_POLL);
+        if crate::EXITING.load(Ordering::Relaxed) {
+            break;
+        }
+        let Some(watched) = interceptor.upgrade() else {
+            break;
+        };
+        let current = app
+            .state::<Mutex<AppState>>()
+            .lock()
+            .expect("state lock")
+            .wpe_interceptor
+            .clone();
+        if !current.is_some_and(|current| Arc::ptr_eq(&current, &watched)) {
+            break;
+        }
+        if !process_alive(pid) {
+            warn!(pid = pid, "[Spectator] target process exited");
+            detach(&app);
+            break;
+        }
+    });
+}
+
+fn is_projector_image(path: &str) -> bool {
+    path.rsplit(['\\', '/'])
+        .next()
+        .is_some_and(|name| name.eq_ignore_ascii_case("projector.exe"))
+}
+
+/// 正在运行的 projector.exe 进程
+#[cfg(target_os = "windows")]
+fn projector_pids() -> Vec<u32> {
+    use windows::Win32::System::ProcessStatus::EnumProcesses;
+
+    let mut pids = vec![0u32; 4096];
+    let mut needed = 0u32;
+    if unsafe {
+        EnumProcesses(
+            pids.as_mut_ptr(),
+            (pids.len() * std::mem::size_of::<u32>()) as u32,
+            &mut needed,
+        )
+    }
+    .is_err()
+    {
+        return Vec::new();
+    }
+    pids.truncate(needed as usize / std::mem::size_of::<u32>());
+    pids.into_iter()
+        .filter(|pid| *pid != 0)
+        .filter(|pid| image_path(*pid).is_some_and(|path| is_projector_image(&path)))
+        .collect()
+}
+
+#[cfg(target_os = "windows")]
+fn image_path(pid: u32) -> Option<String> {
+    use windows::core::PWSTR;
+    use windows::Win32::Foundation::CloseHandle;
+    use windows::Win32::System::Threading::{
+        OpenProcess, QueryFullProcessImageNameW, PROCESS_NAME_WIN32,
+        PROCESS_QUERY_LIMITED_INFORMATION,
+    };
+
+    let handle = unsafe { OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid) }.ok()?;
+    let mut buffer = [0u16; 1024];
+    let mut len = buffer.len() as u32;
+    let result = unsafe {
+        QueryFullProcessImageNameW(
+            handle,
+            PROCESS_NAME_WIN32,
+            PWSTR(buffer.as_mut_ptr()),
+            &mut len,
+        )
+    };
+    unsafe {
+        let _ = CloseHandle(handle);
+    }
+    result.ok()?;
+    Some(String::from_utf16_lossy(&buffer[..len as usize]))
+}
+
+#[cfg(target_os = "windows")]
+fn process_alive(pid: u32) -> bool {
+    use windows::Win32::Foundation::CloseHandle;
+    use windows::Win32::System::Threading::{
+        GetExitCodeProcess, OpenProcess, PROCESS_QUERY_LIMITED_INFORMATION,
+    };
+    const STILL_ACTIVE: u32 = 259;
+
+    let Ok(handle) = (unsafe { OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid) }) else {
+        return false;
+    };
+    let mut code = 0u32;
+    let result = unsafe { GetExitCodeProcess(handle, &mut code) };
+    unsafe {
+        let _ = CloseHandle(handle);
+    }
+    result.is_ok() && code == STILL_ACTIVE
+}
+
+#[cfg(not(target_os = "windows"))]
+fn projector_pids() -> Vec<u32> {
+    Vec::new()
+}
+
+#[cfg(not(target_os = "windows"))]
+fn process_alive(_pid: u32) -> bool {
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_projector_image_name() {
+        assert!(is_projector_image(r"D:\Games\Roco\Projector.exe"));
+        assert!(is_projector_image("projector.exe"));
+        assert!(!is_projector_image(r"C:\Tools\flashplayer_projector.exe"));
+        assert!(!is_projector_image(r"C:\projector.exe\game.exe"));
+    }
+}
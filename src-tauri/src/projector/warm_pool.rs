@@ -0,0 +1,53 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use tauri::AppHandle;
+use tracing::{info, warn};
+
+use crate::projector::runtime::FlashRuntime;
+
+// projector 预热
+//
+// Flash projector 无法在运行后切换到新的 swf URL，预先拉起的进程不能复用；
+// 登录后结束它再以真实 URL 重新拉起，反而多花一次进程启动和窗口查找。
+// 因此预热不创建进程：后台解析 projector 路径并完整读取一次可执行文件，
+// 真实启动时加载可执行文件直接命中系统磁盘缓存。
+
+/// 正在预热（启动与重新登录同时触发时只读一次）
+static WARMING: AtomicBool = AtomicBool::new(false);
+
+/// 后台预读 projector 可执行文件（已在预热时不重复执行）
+pub fn prewarm(app: AppHandle) {
+    if !crate::config::experiment_enabled("warm_pool") {
+        return;
+    }
+    if WARMING.swap(true, Ordering::AcqRel) {
+        return;
+    }
+
+    std::thread::spawn(move || {
+        let _stage = crate::request_context::StageTimer::new("warm_pool_prefetch");
+        if let Err(msg) = prefetch(&app) {
+            warn!(error = %msg, "[WarmPool] failed to prefetch projector");
+        }
+        WARMING.store(false, Ordering::Release);
+    });
+}
+
+fn prefetch(app: &AppHandle) -> Result<(), String> {
+    let resolved = crate::projector::runtime::resolve(app)?;
+    if resolved.runtime != FlashRuntime::Projector {
+        // Ruffle 启动很快，不需要预热
+        info!("[WarmPool] runtime is not projector, skipping");
+        return Ok(());
+    }
+    let mut file = std::fs::File::open(&resolved.path)
+        .map_err(|e| format!("Failed to open {}: {e}", resolved.path.display()))?;
+    let bytes = std::io::copy(&mut file, &mut std::io::sink())
+        .map_err(|e| format!("Failed to read {}: {e}", resolved.path.display()))?;
+    info!(
+        path = %resolved.path.display(),
+        bytes = bytes,
+        "[WarmPool] projector executable cached"
+    );
+    Ok(())
+}
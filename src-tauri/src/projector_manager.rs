@@ -0,0 +1,555 @@
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use tauri::{AppHandle, Emitter, Manager};
+use tracing::{info, warn};
+
+// Flash projector 版本管理
+//
+// 很多用户找不到可用的 projector.exe。配置 launcher.projector_manager.mirror 指向一个镜像后，
+// 可以从镜像的 projectors.json 清单中列出经过验证的独立版 projector，下载到 AppData/projectors/<版本>/，
+// 按 SHA-256 校验通过后才会保留。launcher.projector_manager.version 指定使用的版本，
+// resolve_projector_path 优先使用该版本，未指定或尚未下载时使用随程序分发的 resources/projector.exe。
+//
+// 清单格式：{ "builds": [{ "version": "32.0.0.465", "file": "flashplayer_32_sa.exe", "sha256": "…" }] }
+// file 为相对镜像地址的路径（也可以是完整 URL）。
+//
+// 镜像本身不可信：清单中的摘要必须与固定的摘要一致（程序内置的 PINNED_SHA256，
+// 以及配置 launcher.projector_manager.pinned_sha256 中用户自己确认过的版本），
+// 没有固定摘要或摘要不一致的版本直接忽略。镜像和下载地址只接受 https。
+
+const MANIFEST_FILE: &str = "projectors.json";
+const INSTALL_DIR: &str = "projectors";
+const EXE_NAME: &str = "projector.exe";
+const MANIFEST_TIMEOUT: Duration = Duration::from_secs(10);
+const DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(300);
+/// 下载大小上限，防止镜像返回异常内容
+const MAX_DOWNLOAD_BYTES: u64 = 64 * 1024 * 1024;
+/// 下载进度事件的间隔
+const PROGRESS_STEP: u64 = 1024 * 1024;
+/// 程序内置的已验证版本（版本, SHA-256），核对过官方发布的文件后才加入
+///
+/// 目前还没有核对过的版本，列表为空：用户需要自己核对文件并写入配置 pinned_sha256。
+const PINNED_SHA256: &[(&str, &str)] = &[];
+
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ProjectorBuild {
+    pub version: String,
+    pub file: String,
+    pub sha256: String,
+    #[serde(default)]
+    pub size: Option<u64>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct Manifest {
+    builds: Vec<ProjectorBuild>,
+}
+
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct ProjectorVersion {
+    pub version: String,
+    /// 镜像清单中有该版本
+    pub available: bool,
+    /// 已下载到本地
+    pub installed: bool,
+    /// 当前配置使用的版本
+    pub selected: bool,
+    pub size: Option<u64>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InstallStage {
+    Downloading,
+    Verifying,
+    Installed,
+    Failed,
+}
+
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct InstallProgress {
+    pub version: String,
+    pub stage: InstallStage,
+    pub downloaded_bytes: u64,
+    pub total_bytes: Option<u64>,
+    pub message: Option<String>,
+}
+
+static DIR: OnceLock<PathBuf> = OnceLock::new();
+static INSTALLING: AtomicBool = AtomicBool::new(false);
+
+/// 记录下载目录（在 Tauri setup 中调用）
+pub fn init(app: &AppHandle) {
+    match app.path().app_data_dir() {
+        Ok(dir) => {
+            let _ = DIR.set(dir.join(INSTALL_DIR));
+        }
+        Err(_) => warn!("[ProjectorManager] failed to resolve app data dir, downloads disabled"),
+    }
+}
+
+/// 版本号只允许字母、数字、点、下划线和连字符（用作目录名）
+fn valid_version(version: &str) -> bool {
+    !version.is_empty()
+        && version.len() <= 64
+        && version != "."
+        && version != ".."
+        && version
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-'))
+}
+
+fn install_path(version: &str) -> Option<PathBuf> {
+    if !valid_version(version) {
+        return None;
+    }
+    DIR.get().map(|dir| dir.join(version).join(EXE_NAME))
+}
+
+/// 配置选定且已下载的 projector；未选定或文件不存在时返回 None
+pub fn selected_path() -> Option<PathBuf> {
+    let version = crate::config::current().launcher.projector_manager.version;
+    let version = version.trim();
+    if version.is_empty() {
+        return None;
+    }
+    match install_path(version) {
+        Some(path) if path.is_file() => Some(path),
+        _ => {
+            warn!(
+                version = %version,
+                "[ProjectorManager] selected projector version not installed, using bundled"
+            );
+            None
+        }
+    }
+}
+
+/// 已下载的版本
+fn installed_versions() -> Vec<String> {
+    let Some(dir) = DIR.get() else {
+        return Vec::new();
+    };
+    let mut versions: Vec<String> = std::fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter(|entry| entry.path().join(EXE_NAME).is_file())
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .filter(|version| valid_version(version))
+        .collect();
+    versions.sort();
+    versions
+}
+
+fn mirror() -> Result<String, String> {
+    let mirror = crate::config::current().launcher.projector_manager.mirror;
+    let mirror = mirror.trim().trim_end_matches('/');
+    if mirror.is_empty() {
+        return Err(
+            "No projector mirror configured (launcher.projector_manager.mirror).".to_string(),
+        );
+    }
+    if !https_url(mirror) {
+        return Err(format!(
+            "Invalid projector mirror (https required): {mirror}"
+        ));
+    }
+    Ok(mirror.to_string())
+}
+
+fn https_url(text: &str) -> bool {
+    url::Url::parse(text).is_ok_and(|url| url.scheme() == "https" && url.host_str().is_some())
+}
+
+/// 固定的摘要：内置列表优先，配置只能补充内置列表中没有的版本
+fn pins() -> HashMap<String, String> {
+    let mut pins = crate::config::current()
+        .launcher
+        .projector_manager
+        .pinned_sha256;
+    for (version, sha256) in PINNED_SHA256 {
+        pins.insert(version.to_string(), sha256.to_string());
+    }
+    pins
+}
+
+/// 只保留摘要与固定摘要一致的版本
+fn pinned(builds: Vec<ProjectorBuild>, pins: &HashMap<String, String>) -> Vec<ProjectorBuild> {
+    builds
+        .into_iter()
+        .filter(|build| match pins.get(&build.version) {
+            Some(sha256) if sha256.eq_ignore_ascii_case(&build.sha256) => true,
+            Some(_) => {
+                warn!(
+                    version = %build.version,
+                    "[ProjectorManager] manifest digest does not match the pinned digest"
+                );
+                false
+            }
+            None => {
+                warn!(
+                    version = %build.version,
+                    "[ProjectorManager] skipping build without a pinned digest"
+                );
+                false
+            }
+        })
+        .collect()
+}
+
+fn parse_manifest(json: &str) -> Result<Vec<ProjectorBuild>, String> {
+    let manifest: Manifest =
+        serde_json::from_str(json).map_err(|e| format!("Invalid projector manifest: {e}"))?;
+    Ok(manifest
+        .builds
+        .into_iter()
+        .filter(|build| {
+            let usable = valid_version(&build.version)
+                && build.sha256.len() == 64
+                && build.sha256.chars().all(|c| c.is_ascii_hexdigit());
+            if !usable {
+                warn!(version = %build.version, "[ProjectorManager] skipping invalid manifest entry");
+            }
+            usable
+        })
+        .collect())
+}
+
+/// 没有任何固定摘要时清单中的版本全部会被忽略，直接说明原因，不必请求镜像
+fn ensure_pinned(pins: &HashMap<String, String>) -> Result<(), String> {
+    if pins.is_empty() {
+        return Err(
+            "No projector digests are pinned. Verify a build yourself and add its SHA-256 to \
+             launcher.projector_manager.pinned_sha256."
+                .to_string(),
+        );
+    }
+    Ok(())
+}
+
+fn fetch_manifest(mirror: &str) -> Result<Vec<ProjectorBuild>, String> {
+    let pins = pins();
+    ensure_pinned(&pins)?;
+    let json = ureq::get(&format!("{mirror}/{MANIFEST_FILE}"))
+        .timeout(MANIFEST_TIMEOUT)
+        .call()
+        .map_err(|e| format!("Failed to fetch projector manifest: {e}"))?
+        .into_string()
+        .map_err(|e| format!("Failed to read projector manifest: {e}"))?;
+    Ok(pinned(parse_manifest(&json)?, &pins))
+}
+
+/// 镜像清单与本地已下载版本的合集；未配置镜像时只列出本地版本
+pub fn list() -> Result<Vec<ProjectorVersion>, String> {
+    let builds = match mirror() {
+        Ok(mirror) => fetch_manifest(&mirror)?,
+        Err(_) => Vec::new(),
+    };
+    let installed = installed_versions();
+    let selected = crate::config::current().launcher.projector_manager.version;
+    let mut versions: Vec<ProjectorVersion> = builds
+        .iter()
+        .map(|build| ProjectorVersion {
+            version: build.version.clone(),
+            available: true,
+            installed: installed.contains(&build.version),
+            selected: build.version == selected.trim(),
+            size: build.size,
+        })
+        .collect();
+    for version in installed {
+        if !versions.iter().any(|v| v.version == version) {
+            versions.push(ProjectorVersion {
+                selected: version == selected.trim(),
+                version,
+                available: false,
+                installed: true,
+                size: None,
+            });
+        }
+    }
+    Ok(versions)
+}
+
+/// 选择使用的版本（None 时恢复使用随程序分发的 projector），只接受已下载的版本
+pub fn select(version: Option<String>) -> Result<(), String> {
+    let version = version.map(|v| v.trim().to_string()).unwrap_or_default();
+    if !version.is_empty() && !install_path(&version).is_some_and(|path| path.is_file()) {
+        return Err(format!("Projector {version} is not installed."));
+    }
+    crate::config::update(|c| c.launcher.projector_manager.version = version.clone())?;
+    info!(version = %version, "[ProjectorManager] projector version selected");
+    Ok(())
+}
+
+/// 在后台下载并校验指定版本，进度通过 projector_install_progress 事件报告
+pub fn install(app: &AppHandle, version: String) -> Result<(), String> {
+    if !valid_version(&version) {
+        return Err(format!("Invalid projector version: {version}"));
+    }
+    let mirror = mirror()?;
+    ensure_pinned(&pins())?;
+    if DIR.get().is_none() {
+        return Err("App data dir unavailable.".to_string());
+    }
+    if INSTALLING.swap(true, Ordering::SeqCst) {
+        return Err("A projector download is already in progress.".to_string());
+    }
+    let app = app.clone();
+    std::thread::spawn(move || {
+        let result = download_and_install(&app, &mirror, &version);
+        INSTALLING.store(false, Ordering::SeqCst);
+        let (stage, message) = match result {
+            Ok(path) => {
+                info!(version = %version, path = %path.display(), "[ProjectorManager] installed");
+                (InstallStage::Installed, None)
+            }
+            Err(e) => {
+                warn!(version = %version, error = %e, "[ProjectorManager] install failed");
+                (InstallStage::Failed, Some(e))
+            }
+        };
+        emit(&app, &version, stage, 0, None, message);
+    });
+    Ok(())
+}
+
+fn emit(
+    app: &AppHandle,
+    version: &str,
+    stage: InstallStage,
+    downloaded_bytes: u64,
+    total_bytes: Option<u64>,
+    message: Option<String>,
+) {
+    let _ = app.emit(
+        crate::events::PROJECTOR_INSTALL_PROGRESS,
+        InstallProgress {
+            version: version.to_string(),
+            stage,
+            downloaded_bytes,
+            total_bytes,
+            message,
+        },
+    );
+}
+
+fn download_and_install(app: &AppHandle, mirror: &str, version: &str) -> Result<PathBuf, String> {
+    let build = fetch_manifest(mirror)?
+        .into_iter()
+        .find(|build| build.version == version)
+        .ok_or_else(|| format!("Projector {version} is not listed on the mirror."))?;
+    let url = if crate::endpoints::valid_url(&build.file) {
+        build.file.clone()
+    } else {
+        format!("{mirror}/{}", build.file.trim_start_matches('/'))
+    };
+    if !https_url(&url) {
+        return Err(format!("Refusing to download over an insecure url: {url}"));
+    }
+    info!(version = %version, url = %url, "[ProjectorManager] downloading");
+
+    let path = install_path(version).ok_or_else(|| "App data dir unavailable.".to_string())?;
+    let dir = path.parent().expect("install path has a parent");
+    std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {e}", dir.display()))?;
+    let mut hasher = Sha256::new()?;
+
+    let response = ureq::get(&url)
+        .timeout(DOWNLOAD_TIMEOUT)
+        .call()
+        .map_err(|e| format!("Download failed: {e}"))?;
+    let total = response
+        .header("Content-Length")
+        .and_then(|len| len.parse().ok())
+        .or(build.size);
+
+    // 边下载边写入临时文件并计算摘要，校验通过的文件才替换正式文件
+    let partial = path.with_extension("exe.part");
+    let download = || -> Result<(), String> {
+        let mut file = std::fs::File::create(&partial)
+            .map_err(|e| format!("Failed to write projector: {e}"))?;
+        let mut reader = response.into_reader().take(MAX_DOWNLOAD_BYTES + 1);
+        let mut chunk = [0u8; 64 * 1024];
+        let mut downloaded = 0u64;
+        let mut reported = 0u64;
+        loop {
+            let read = reader
+                .read(&mut chunk)
+                .map_err(|e| format!("Download failed: {e}"))?;
+            if read == 0 {
+                break;
+            }
+            downloaded += read as u64;
+            if downloaded > MAX_DOWNLOAD_BYTES {
+                return Err("Download exceeds the size limit.".to_string());
+            }
+            hasher.update(&chunk[..read])?;
+            file.write_all(&chunk[..read])
+                .map_err(|e| format!("Failed to write projector: {e}"))?;
+            if downloaded - reported >= PROGRESS_STEP {
+                reported = downloaded;
+                emit(
+                    app,
+                    version,
+                    InstallStage::Downloading,
+                    downloaded,
+                    total,
+                    None,
+                );
+            }
+        }
+        file.sync_all()
+            .map_err(|e| format!("Failed to write projector: {e}"))?;
+
+        emit(
+            app,
+            version,
+            InstallStage::Verifying,
+            downloaded,
+            total,
+            None,
+        );
+        let digest = hasher.finish()?;
+        if !digest.eq_ignore_ascii_case(&build.sha256) {
+            return Err(format!(
+                "SHA-256 mismatch: expected {}, got {digest}.",
+                build.sha256
+            ));
+        }
+        Ok(())
+    };
+    if let Err(e) = download() {
+        let _ = std::fs::remove_file(&partial);
+        return Err(e);
+    }
+
+    std::fs::rename(&partial, &path).map_err(|e| {
+        let _ = std::fs::remove_file(&partial);
+        format!("Failed to install projector: {e}")
+    })?;
+    Ok(path)
+}
+
+/// 增量计算 SHA-256（BCrypt 哈希对象），下载时不必把整个文件留在内存中
+#[cfg(target_os = "windows")]
+struct Sha256(windows::Win32::Security::Cryptography::BCRYPT_HASH_HANDLE);
+
+#[cfg(target_os = "windows")]
+impl Sha256 {
+    fn new() -> Result<Self, String> {
+        use windows::Win32::Security::Cryptography::{
+            BCryptCreateHash, BCRYPT_HASH_HANDLE, BCRYPT_SHA256_ALG_HANDLE,
+        };
+
+        let mut handle = BCRYPT_HASH_HANDLE::default();
+        unsafe { BCryptCreateHash(BCRYPT_SHA256_ALG_HANDLE, &mut handle, None, None, 0) }
+            .ok()
+            .map_err(|e| format!("BCryptCreateHash failed: {e}"))?;
+        Ok(Self(handle))
+    }
+
+    fn update(&mut self, data: &[u8]) -> Result<(), String> {
+        use windows::Win32::Security::Cryptography::BCryptHashData;
+
+        unsafe { BCryptHashData(self.0, data, 0) }
+            .ok()
+            .map_err(|e| format!("BCryptHashData failed: {e}"))
+    }
+
+    fn finish(self) -> Result<String, String> {
+        use windows::Win32::Security::Cryptography::BCryptFinishHash;
+
+        let mut digest = [0u8; 32];
+        unsafe { BCryptFinishHash(self.0, &mut digest, 0) }
+            .ok()
+            .map_err(|e| format!("BCryptFinishHash failed: {e}"))?;
+        Ok(digest.iter().map(|b| format!("{b:02x}")).collect())
+    }
+}
+
+#[cfg(target_os = "windows")]
+impl Drop for Sha256 {
+    fn drop(&mut self) {
+        use windows::Win32::Security::Cryptography::BCryptDestroyHash;
+
+        let _ = unsafe { BCryptDestroyHash(self.0) };
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+struct Sha256;
+
+#[cfg(not(target_os = "windows"))]
+impl Sha256 {
+    fn new() -> Result<Self, String> {
+        Err("仅支持 Windows 平台。".to_string())
+    }
+
+    fn update(&mut self, _data: &[u8]) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn finish(self) -> Result<String, String> {
+        Err("仅支持 Windows 平台。".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_manifest_and_rejects_unsafe_versions() {
+        let sha = "ab".repeat(32);
+        let json = format!(
+            r#"{{ "builds": [
+                {{ "version": "32.0.0.465", "file": "sa/32.exe", "sha256": "{sha}", "size": 100 }},
+                {{ "version": "../evil", "file": "x.exe", "sha256": "{sha}" }},
+                {{ "version": "11.1", "file": "y.exe", "sha256": "not-a-digest" }}
+            ] }}"#
+        );
+        let builds = parse_manifest(&json).unwrap();
+        assert_eq!(builds.len(), 1);
+        assert_eq!(builds[0].version, "32.0.0.465");
+        assert_eq!(builds[0].size, Some(100));
+
+        assert!(valid_version("32.0.0.465"));
+        assert!(valid_version("debug_32-x86"));
+        assert!(!valid_version(".."));
+        assert!(!valid_version("a/b"));
+        assert!(!valid_version(""));
+        assert!(parse_manifest("{}").is_err());
+    }
+
+    #[test]
+    fn keeps_only_pinned_builds() {
+        let build = |version: &str, sha256: &str| ProjectorBuild {
+            version: version.to_string(),
+            file: format!("{version}.exe"),
+            sha256: sha256.to_string(),
+            size: None,
+        };
+        let pins: HashMap<String, String> = [("32.0.0.465".to_string(), "AB".repeat(32))]
+            .into_iter()
+            .collect();
+        let builds = pinned(
+            vec![
+                build("32.0.0.465", &"ab".repeat(32)),
+                build("32.0.0.465", &"cd".repeat(32)),
+                build("11.1", &"ab".repeat(32)),
+            ],
+            &pins,
+        );
+        assert_eq!(builds, vec![build("32.0.0.465", &"ab".repeat(32))]);
+
+        assert!(https_url("https://mirror.example.com/flash"));
+        assert!(!https_url("http://mirror.example.com/flash"));
+        assert!(!https_url("ftp://mirror.example.com/flash"));
+    }
+}
@@ -0,0 +1,335 @@
+// 日志脱敏
+//
+// 登录凭据（skey / pskey / angel_key 等）会出现在 login3 响应、flashVars、swf URL，
+// 也可能出现在封包文本中。所有写日志、导出诊断包的路径都必须经过这里，
+// 测试中的随机语料保证任何拼写、编码、分隔方式下凭据值都不会原样输出。
+
+/// 需要脱敏的参数名（按长度从长到短排列，保证 pskey 先于 skey 匹配）
+pub const SECRET_KEYS: &[&str] = &["angel_key", "angel_uin", "p_skey", "pskey", "skey"];
+
+const MASK: u8 = b'*';
+
+/// 在 key 与分隔符之间允许出现的字符（JSON 引号、转义、空白）
+fn is_key_padding(b: u8) -> bool {
+    matches!(b, b'"' | b'\'' | b'\\' | b' ' | b'\t')
+}
+
+/// 凭据值的结束字符
+fn is_value_end(b: u8) -> bool {
+    matches!(
+        b,
+        b'&' | b'"' | b'\'' | b'<' | b'>' | b';' | b',' | b'}' | b']' | b')' | b'\\'
+    ) || b.is_ascii_whitespace()
+}
+
+fn starts_with_ignore_case(haystack: &[u8], needle: &[u8]) -> bool {
+    haystack.len() >= needle.len() && haystack[..needle.len()].eq_ignore_ascii_case(needle)
+}
+
+/// 若 `at` 处是一个凭据参数，返回其值所在的字节区间
+fn match_secret(bytes: &[u8], at: usize) -> Option<(usize, usize)> {
+    let key = SECRET_KEYS
+        .iter()
+        .find(|key| starts_with_ignore_case(&bytes[at..], key.as_bytes()))?;
+
+    let mut i = at + key.len();
+    while i < bytes.len() && is_key_padding(bytes[i]) {
+        i += 1;
+    }
+    if i < bytes.len() && (bytes[i] == b'=' || bytes[i] == b':') {
+        i += 1;
+    } else if starts_with_ignore_case(&bytes[i..], b"%3d") {
+        i += 3;
+    } else {
+        return None;
+    }
+    while i < bytes.len() && is_key_padding(bytes[i]) {
+        i += 1;
+    }
+
+    let start = i;
+    while i < bytes.len() && !is_value_end(bytes[i]) {
+        // URL 编码的 & 同样视为结束
+        if starts_with_ignore_case(&bytes[i..], b"%26") {
+            break;
+        }
+        i += 1;
+    }
+    Some((start, i))
+}
+
+/// 找出所有凭据值的字节区间
+fn secret_spans(bytes: &[u8]) -> Vec<(usize, usize)> {
+    let mut spans = Vec::new();
+    let mut i = 0usize;
+    while i < bytes.len() {
+        match match_secret(bytes, i) {
+            Some((start, end)) => {
+                spans.push((start, end));
+                i = end.max(i + 1);
+            }
+            None => i += 1,
+        }
+    }
+    spans
+}
+
+/// 将文本中的凭据值替换为 `*`（保留参数名，便于排查是哪一项）
+pub fn redact_secrets(text: &str) -> String {
+    let spans = secret_spans(text.as_bytes());
+    if spans.is_empty() {
+        return text.to_string();
+    }
+    let mut out = String::with_capacity(text.len());
+    let mut copied = 0usize;
+    for (start, end) in spans {
+        // 区间端点都落在 ASCII 字符上，一定是字符边界
+        out.push_str(&text[copied..start]);
+        out.push(MASK as char);
+        copied = end;
+    }
+    out.push_str(&text[copied..]);
+    out
+}
+
+/// 只保留 URL 的 origin + path，整个 query 替换为 REDACTED
+pub fn redact_url(url: &str) -> String {
+    match url.split_once('?') {
+        Some((base, _)) => format!("{base}?REDACTED"),
+        None => url.to_string(),
+    }
+}
+
+/// 保留 query 结构，只脱敏凭据值
+pub fn redact_query(url: &str) -> String {
+    match url.split_once('?') {
+        Some((base, query)) => format!("{base}?{}", redact_secrets(query)),
+        None => redact_secrets(url),
+    }
+}
+
+/// 只输出 origin + path 与参数名列表（不含任何参数值）
+pub fn url_keys_only(url: &str) -> String {
+    let Ok(parsed) = url::Url::parse(url) else {
+        return "<invalid-url>".to_string();
+    };
+    let origin = parsed.origin().ascii_serialization();
+    let path = parsed.path();
+    let mut keys: Vec<String> = parsed.query_pairs().map(|(k, _)| k.to_string()).collect();
+    keys.sort();
+    keys.dedup();
+    if keys.is_empty() {
+        format!("{origin}{path}")
+    } else {
+        format!("{origin}{path} ?keys={}", keys.join(","))
+    }
+}
+
+/// 按字符边界截断（直接 String::truncate 在多字节字符中间会 panic）
+pub fn truncate_chars(text: &str, max_bytes: usize) -> &str {
+    if text.len() <= max_bytes {
+        return text;
+    }
+    let mut end = max_bytes;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    &text[..end]
+}
+
+/// 封包十六进制转储，凭据值所在字节先替换为 `*` 再输出
+pub fn packet_dump(data: &[u8]) -> String {
+    let mut masked = data.to_vec();
+    for (start, end) in secret_spans(data) {
+        masked[start..end].fill(MASK);
+    }
+    let hex = masked
+        .iter()
+        .map(|b| format!("{b:02X}"))
+        .collect::<Vec<_>>()
+        .join(" ");
+    let ascii: String = masked
+        .iter()
+        .map(|&b| {
+            if b.is_ascii_graphic() || b == b' ' {
+                b as char
+            } else {
+                '.'
+            }
+        })
+        .collect();
+    format!("{hex} | {ascii}")
+}
+
+/// 写日志文件的 writer 包装：每次写入的内容先经过脱敏
+pub struct RedactingWriter<W: std::io::Write>(pub W);
+
+impl<W: std::io::Write> std::io::Write for RedactingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let text = String::from_utf8_lossy(buf);
+        self.0.write_all(redact_secrets(&text).as_bytes())?;
+        // 对调用方而言整块都已消费
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.0.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 固定种子的 xorshift，保证语料可复现
+    struct Rng(u64);
+
+    impl Rng {
+        fn next(&mut self) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0
+        }
+
+        fn below(&mut self, n: usize) -> usize {
+            (self.next() % n as u64) as usize
+        }
+
+        fn pick<'a>(&mut self, items: &[&'a str]) -> &'a str {
+            items[self.below(items.len())]
+        }
+    }
+
+    const VALUE_CHARS: &[u8] =
+        b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789@*_-+/.=";
+
+    fn random_secret(rng: &mut Rng) -> String {
+        let len = 10 + rng.below(30);
+        (0..len)
+            .map(|_| VALUE_CHARS[rng.below(VALUE_CHARS.len())] as char)
+            .collect()
+    }
+
+    fn random_case(rng: &mut Rng, key: &str) -> String {
+        key.chars()
+            .map(|c| {
+                if rng.below(2) == 0 {
+                    c.to_ascii_uppercase()
+                } else {
+                    c
+                }
+            })
+            .collect()
+    }
+
+    /// (key 后缀 + 分隔符 + 值前缀, 值后缀)
+    const SEPARATORS: &[(&str, &str)] = &[
+        ("=", ""),
+        ("%3D", ""),
+        ("%3d", ""),
+        (" = ", ""),
+        ("\":\"", "\""),
+        ("\": \"", "\""),
+        ("\\\":\\\"", "\\\""),
+        ("':'", "'"),
+        (":", ""),
+    ];
+
+    const TERMINATORS: &[&str] = &["", "&", "%26", "\"", "'", " ", ";", ",", "<", "\n", "}"];
+
+    const PREFIXES: &[&str] = &[
+        "",
+        "https://17roco.qq.com/fcgi-bin/login3?uin=1&",
+        "<param name=\"FlashVars\" value=\"config=//res.17roco.qq.com/Global.xml&",
+        "{\"",
+        "flashVars captured (redacted): ",
+        "Cookie: uin=o123; ",
+        "封包内容 System_",
+        "swf url: https://res.17roco.qq.com/main.swf?",
+    ];
+
+    const SUFFIXES: &[&str] = &["", "&angel_uin=1", "\" />", "}", " 完成", "; path=/"];
+
+    #[test]
+    fn redacts_known_formats() {
+        assert_eq!(redact_secrets("a=1&skey=abc&b=2"), "a=1&skey=*&b=2");
+        assert_eq!(redact_secrets("pskey=abc"), "pskey=*");
+        assert_eq!(redact_secrets("SKEY=abc"), "SKEY=*");
+        assert_eq!(redact_secrets("skey%3Dabc%26x%3D1"), "skey%3D*%26x%3D1");
+        assert_eq!(redact_secrets(r#"{"skey":"abc"}"#), r#"{"skey":"*"}"#);
+        assert_eq!(redact_secrets("skey = abc def"), "skey = * def");
+        assert_eq!(redact_secrets("无关文本"), "无关文本");
+    }
+
+    #[test]
+    fn keeps_non_ascii_text_intact() {
+        let text = "登录成功 skey=@AbC 用户名=骑士";
+        assert_eq!(redact_secrets(text), "登录成功 skey=* 用户名=骑士");
+    }
+
+    #[test]
+    fn truncate_respects_char_boundary() {
+        let text = "洛克王国";
+        assert_eq!(truncate_chars(text, 4), "洛");
+        assert_eq!(truncate_chars(text, 100), text);
+    }
+
+    #[test]
+    fn packet_dump_masks_secret_bytes() {
+        let dump = packet_dump(b"System_skey=SECRETVALUE&x");
+        assert!(!dump.contains("SECRETVALUE"));
+        // 十六进制部分同样不能包含原值
+        let secret_hex = b"SECRETVALUE"
+            .iter()
+            .map(|b| format!("{b:02X}"))
+            .collect::<Vec<_>>()
+            .join(" ");
+        assert!(!dump.contains(&secret_hex));
+    }
+
+    #[test]
+    fn fuzz_corpus_never_leaks_secret_values() {
+        let mut rng = Rng(0x5EED_1234_ABCD_0001);
+        for _ in 0..20_000 {
+            let secret = random_secret(&mut rng);
+            let key = rng_pick_key(&mut rng);
+            let key = random_case(&mut rng, key);
+            let (sep, close) = SEPARATORS[rng.below(SEPARATORS.len())];
+            let input = format!(
+                "{}{}{}{}{}{}{}",
+                rng.pick(PREFIXES),
+                key,
+                sep,
+                secret,
+                close,
+                rng.pick(TERMINATORS),
+                rng.pick(SUFFIXES),
+            );
+
+            for (path, output) in [
+                ("text", redact_secrets(&input)),
+                ("query", redact_query(&input)),
+                ("packet", packet_dump(input.as_bytes())),
+                ("truncated", redact_secrets(truncate_chars(&input, 64))),
+            ] {
+                assert!(
+                    !output.contains(&secret),
+                    "secret leaked via {path}: input={input:?} output={output:?}"
+                );
+            }
+
+            let mut writer = RedactingWriter(Vec::new());
+            std::io::Write::write_all(&mut writer, input.as_bytes()).expect("write");
+            let written = String::from_utf8(writer.0).expect("utf8");
+            assert!(
+                !written.contains(&secret),
+                "secret leaked via writer: {input:?}"
+            );
+        }
+    }
+
+    fn rng_pick_key(rng: &mut Rng) -> &'static str {
+        SECRET_KEYS[rng.below(SECRET_KEYS.len())]
+    }
+}
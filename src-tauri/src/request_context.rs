@@ -196,7 +196,12 @@ pub fn cmd_log(message: &str) {
                 .append(true)
                 .open(&path)
             {
-                let _ = writeln!(file, "[{:?}] {}", SystemTime::now(), message);
+                let _ = writeln!(
+                    file,
+                    "[{:?}] {}",
+                    SystemTime::now(),
+                    crate::redact::redact_secrets(message)
+                );
             }
         }
     }
@@ -211,6 +216,7 @@ where
     let start = std::time::Instant::now();
 
     cmd_log(&format!("CMD_ENTER name={} seq={}", name, seq));
+    crate::usage::record(name);
 
     let result = match std::panic::catch_unwind(std::panic::AssertUnwindSafe(f)) {
         Ok(result) => result,
@@ -0,0 +1,268 @@
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use tauri::{AppHandle, Manager};
+use tracing::{info, warn};
+
+use crate::state::{emit_status, AppState, AppStatus};
+
+// 记住登录
+//
+// 开启配置 login.remember 后，login3 抓取成功时把 flashVars（含 angel_key/skey 等登录凭据）
+// 用 DPAPI 加密（只有当前 Windows 用户能解密）保存到 AppData/saved_login.bin。
+// 重新登录时可以先用保存的凭据静默启动 projector，不必再次扫码或输入密码。
+// 凭据在服务器端的有效期无法查询，超过 login.max_age_hours 视为过期并删除；
+// 其他平台没有 DPAPI，不保存（不落盘明文）。
+
+const SAVED_LOGIN_FILE: &str = "saved_login.bin";
+
+#[derive(serde::Serialize, serde::Deserialize)]
+struct SavedLogin {
+    account: Option<u64>,
+    /// 抓取时间（Unix 毫秒）
+    captured_at: u64,
+    flash_vars: String,
+}
+
+/// 保存的登录信息（不含凭据），供前端显示
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct SavedLoginInfo {
+    pub account: Option<u64>,
+    pub captured_at: u64,
+    pub expires_at: u64,
+}
+
+static SAVED_LOGIN_PATH: OnceLock<PathBuf> = OnceLock::new();
+// 文件读写串行化，抓取回调与命令可能同时访问
+static FILE_LOCK: Mutex<()> = Mutex::new(());
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// 在 Tauri setup 中调用
+pub fn init(app: &AppHandle) {
+    match app.path().app_data_dir() {
+        Ok(dir) => {
+            let _ = SAVED_LOGIN_PATH.set(dir.join(SAVED_LOGIN_FILE));
+        }
+        Err(_) => warn!("[SavedLogin] failed to resolve app data dir, remember login disabled"),
+    }
+}
+
+/// flashVars 中的 angel_uin
+pub fn account_of(flash_vars: &str) -> Option<u64> {
+    flash_vars
+        .split('&')
+        .find_map(|pair| pair.strip_prefix("angel_uin="))
+        .and_then(|uin| uin.parse().ok())
+}
+
+pub fn expires_at(captured_at: u64, max_age_hours: u64) -> u64 {
+    captured_at.saturating_add(max_age_hours.saturating_mul(3_600_000))
+}
+
+/// login3 抓取成功后调用；未开启记住登录时忽略
+pub fn remember(flash_vars: &str) {
+    if !crate::config::current().login.remember {
+        return;
+    }
+    let Some(path) = SAVED_LOGIN_PATH.get() else {
+        return;
+    };
+    let saved = SavedLogin {
+        account: account_of(flash_vars),
+        captured_at: now_ms(),
+        flash_vars: flash_vars.to_string(),
+    };
+    let Ok(json) = serde_json::to_vec(&saved) else {
+        return;
+    };
+    let encrypted = match protect(&json) {
+        Ok(data) => data,
+        Err(e) => {
+            warn!(error = %e, "[SavedLogin] failed to encrypt credentials, not saved");
+            return;
+        }
+    };
+    let _guard = FILE_LOCK.lock().expect("saved login lock");
+    if let Some(parent) = path.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+    match std::fs::write(path, encrypted) {
+        Ok(()) => info!(account = ?saved.account, "[SavedLogin] login saved"),
+        Err(e) => warn!(error = %e, "[SavedLogin] failed to write saved login"),
+    }
+}
+
+/// 读取仍然有效的保存登录；已关闭记住登录、过期或无法解密时删除文件并返回 None
+fn load() -> Option<SavedLogin> {
+    let path = SAVED_LOGIN_PATH.get()?;
+    let _guard = FILE_LOCK.lock().expect("saved login lock");
+    let encrypted = std::fs::read(path).ok()?;
+    let login = crate::config::current().login;
+    let saved = if login.remember {
+        unprotect(&encrypted)
+            .ok()
+            .and_then(|json| serde_json::from_slice::<SavedLogin>(&json).ok())
+            .filter(|saved| now_ms() < expires_at(saved.captured_at, login.max_age_hours))
+    } else {
+        None
+    };
+    if saved.is_none() {
+        let _ = std::fs::remove_file(path);
+        info!("[SavedLogin] saved login discarded (disabled, expired or unreadable)");
+    }
+    saved
+}
+
+/// 保存的登录信息；没有有效的保存登录时为 None
+pub fn info() -> Option<SavedLoginInfo> {
+    let max_age_hours = crate::config::current().login.max_age_hours;
+    load().map(|saved| SavedLoginInfo {
+        account: saved.account,
+        captured_at: saved.captured_at,
+        expires_at: expires_at(saved.captured_at, max_age_hours),
+    })
+}
+
+/// 删除保存的登录
+pub fn forget() {
+    let Some(path) = SAVED_LOGIN_PATH.get() else {
+        return;
+    };
+    let _guard = FILE_LOCK.lock().expect("saved login lock");
+    if std::fs::remove_file(path).is_ok() {
+        info!("[SavedLogin] saved login removed");
+    }
+}
+
+/// 用保存的登录静默启动 projector，返回是否已开始启动（没有有效的保存登录时为 false）
+pub fn try_silent_login(app: &AppHandle) -> Result<bool, String> {
+    let Some(saved) = load() else {
+        return Ok(false);
+    };
+    launch_with(app, &saved.flash_vars, saved.account)?;
+    Ok(true)
+}
+
+/// 用保存的 flashVars 启动 projector，与 login3 捕获到地址后的流程相同
+pub fn launch_with(app: &AppHandle, flash_vars: &str, account: Option<u64>) -> Result<(), String> {
+    let swf_url = crate::login3_capture::build_swf_url(flash_vars)
+        .ok_or_else(|| "Saved login is invalid.".to_string())?;
+
+    let state = app.state::<Mutex<AppState>>();
+    {
+        let mut guard = state.lock().expect("state lock");
+        if guard.projector.is_some() {
+            return Err("Projector is already running.".to_string());
+        }
+        guard.swf_url = Some(swf_url);
+        guard.status = AppStatus::Launching;
+        guard.message = None;
+        emit_status(app, &guard);
+    }
+    info!(account = ?account, "[SavedLogin] launching with saved login");
+
+    let launch_handle = app.clone();
+    let _ = app.run_on_main_thread(move || {
+        let state = launch_handle.state::<Mutex<AppState>>();
+        if let Err(e) = crate::launcher::launch_projector_auto(&launch_handle, &state) {
+            warn!(error = %e, "[SavedLogin] silent login launch failed");
+        }
+    });
+    Ok(())
+}
+
+/// 用当前 Windows 用户的 DPAPI 密钥加密
+#[cfg(target_os = "windows")]
+pub fn protect(data: &[u8]) -> Result<Vec<u8>, String> {
+    use windows::core::PCWSTR;
+    use windows::Win32::Security::Cryptography::{
+        CryptProtectData, CRYPTPROTECT_UI_FORBIDDEN, CRYPT_INTEGER_BLOB,
+    };
+
+    let input = CRYPT_INTEGER_BLOB {
+        cbData: data.len() as u32,
+        pbData: data.as_ptr() as *mut u8,
+    };
+    let mut output = CRYPT_INTEGER_BLOB::default();
+    unsafe {
+        CryptProtectData(
+            &input,
+            PCWSTR::null(),
+            None,
+            None,
+            None,
+            CRYPTPROTECT_UI_FORBIDDEN,
+            &mut output,
+        )
+    }
+    .map_err(|e| format!("CryptProtectData failed: {e}"))?;
+    Ok(take_blob(output))
+}
+
+#[cfg(target_os = "windows")]
+pub fn unprotect(data: &[u8]) -> Result<Vec<u8>, String> {
+    use windows::Win32::Security::Cryptography::{
+        CryptUnprotectData, CRYPTPROTECT_UI_FORBIDDEN, CRYPT_INTEGER_BLOB,
+    };
+
+    let input = CRYPT_INTEGER_BLOB {
+        cbData: data.len() as u32,
+        pbData: data.as_ptr() as *mut u8,
+    };
+    let mut output = CRYPT_INTEGER_BLOB::default();
+    unsafe {
+        CryptUnprotectData(
+            &input,
+            None,
+            None,
+            None,
+            None,
+            CRYPTPROTECT_UI_FORBIDDEN,
+            &mut output,
+        )
+    }
+    .map_err(|e| format!("CryptUnprotectData failed: {e}"))?;
+    Ok(take_blob(output))
+}
+
+/// 复制 DPAPI 分配的输出缓冲区并释放
+#[cfg(target_os = "windows")]
+fn take_blob(blob: windows::Win32::Security::Cryptography::CRYPT_INTEGER_BLOB) -> Vec<u8> {
+    use windows::Win32::Foundation::{LocalFree, HLOCAL};
+
+    let data = unsafe { std::slice::from_raw_parts(blob.pbData, blob.cbData as usize) }.to_vec();
+    unsafe {
+        let _ = LocalFree(Some(HLOCAL(blob.pbData as *mut std::ffi::c_void)));
+    }
+    data
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn protect(_data: &[u8]) -> Result<Vec<u8>, String> {
+    Err("仅支持 Windows 平台。".to_string())
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn unprotect(_data: &[u8]) -> Result<Vec<u8>, String> {
+    Err("仅支持 Windows 平台。".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn account_and_expiry_from_flash_vars() {
+        let flash_vars = "config=//res.17roco.qq.com/Global.xml&angel_uin=10001&angel_key=abc";
+        assert_eq!(account_of(flash_vars), Some(10001));
+        assert_eq!(account_of("config=x"), None);
+        assert_eq!(expires_at(1_000, 12), 1_000 + 12 * 3_600_000);
+    }
+}
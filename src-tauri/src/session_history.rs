@@ -0,0 +1,202 @@
+use std::collections::VecDeque;
+use std::path::PathBuf;
+use std::sync::{Mutex, MutexGuard, OnceLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use tauri::{AppHandle, Manager};
+use tracing::{info, warn};
+
+const HISTORY_FILE: &str = "session_history.json";
+
+/// 最多保留的会话条数（超出后丢弃最旧的记录）
+const MAX_SESSIONS: usize = 200;
+
+/// 一次游戏会话（从 projector 启动成功到停止/退出）
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct SessionRecord {
+    /// 账号 QQ 号
+    pub account: Option<u64>,
+    /// 开始时间（Unix 毫秒）
+    pub started_at: u64,
+    /// 结束时间（Unix 毫秒），进行中为 None
+    pub ended_at: Option<u64>,
+    /// 结束原因：user_stop / relogin / relaunch / app_exit / projector_exited ...
+    pub exit_reason: Option<String>,
+    /// projector 是否异常退出
+    pub crashed: bool,
+    /// 游戏客户端版本指纹，main.swf 地址中没有版本参数时为 None
+    #[serde(default)]
+    pub client_version: Option<String>,
+}
+
+impl SessionRecord {
+    pub fn duration_ms(&self) -> Option<u64> {
+        self.ended_at.map(|end| end.saturating_sub(self.started_at))
+    }
+}
+
+struct SessionHistory {
+    records: VecDeque<SessionRecord>,
+    /// 当前会话是否仍在进行（指向 records 的最后一条）
+    open: bool,
+    path: Option<PathBuf>,
+}
+
+static HISTORY: OnceLock<Mutex<SessionHistory>> = OnceLock::new();
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+fn history() -> &'static Mutex<SessionHistory> {
+    HISTORY.get_or_init(|| {
+        Mutex::new(SessionHistory {
+            records: VecDeque::new(),
+            open: false,
+            path: None,
+        })
+    })
+}
+
+/// 加载历史记录（在 Tauri setup 中调用）
+pub fn init(app: &AppHandle) {
+    let Ok(dir) = app.path().app_data_dir() else {
+        warn!("[SessionHistory] failed to resolve app data dir, history will not persist");
+        return;
+    };
+    let path = dir.join(HISTORY_FILE);
+    let mut records: VecDeque<SessionRecord> = std::fs::read_to_string(&path)
+        .ok()
+        .and_then(|text| serde_json::from_str(&text).ok())
+        .unwrap_or_default();
+
+    // 上次运行未正常结束的会话（进程被强杀等），标记为异常
+    if let Some(last) = records.back_mut() {
+        if last.ended_at.is_none() {
+            last.exit_reason = Some("unterminated".to_string());
+            last.crashed = true;
+        }
+    }
+
+    info!(count = records.len(), "[SessionHistory] loaded");
+    let mut guard = history().lock().expect("session history lock");
+    guard.records = records;
+    guard.path = Some(path);
+}
+
+/// 记录新会话开始（projector 启动成功时调用）
+pub fn begin(account: Option<u64>, client_version: Option<String>) {
+    let mut guard = history().lock().expect("session history lock");
+    if guard.open {
+        close_last(&mut guard, "superseded", false);
+    }
+    guard.records.push_back(SessionRecord {
+        account,
+        started_at: now_ms(),
+        ended_at: None,
+        exit_reason: None,
+        crashed: false,
+        client_version,
+    });
+    while guard.records.len() > MAX_SESSIONS {
+        guard.records.pop_front();
+    }
+    guard.open = true;
+    save(&guard);
+    drop(guard);
+    crate::webhook::fire(
+        crate::webhook::WebhookEvent::SessionStart,
+        "Game session started",
+        account,
+    );
+}
+
+/// 结束当前会话（没有进行中的会话时忽略）
+pub fn end_current(reason: &str, crashed: bool) {
+    end(
+        history().lock().expect("session history lock"),
+        reason,
+        crashed,
+    );
+}
+
+/// 进程退出时结束当前会话；锁被其他线程占用时放弃，不阻塞退出流程
+pub fn end_on_exit(reason: &str) {
+    match history().try_lock() {
+        Ok(guard) => end(guard, reason, false),
+        Err(_) => warn!("[SessionHistory] history busy, session left open on exit"),
+    }
+}
+
+fn end(mut guard: MutexGuard<'_, SessionHistory>, reason: &str, crashed: bool) {
+    if !guard.open {
+        return;
+    }
+    close_last(&mut guard, reason, crashed);
+    save(&guard);
+    let account = guard.records.back().and_then(|r| r.account);
+    drop(guard);
+
+    let (event, message) = if crashed {
+        (
+            crate::webhook::WebhookEvent::Crash,
+            format!("Game crashed ({reason})"),
+        )
+    } else {
+        (
+            crate::webhook::WebhookEvent::SessionEnd,
+            format!("Game session ended ({reason})"),
+        )
+    };
+    crate::webhook::fire(event, &message, account);
+}
+
+/// 进行中会话的开始时间（Unix 毫秒），没有进行中的会话时为 None
+pub fn current_started_at() -> Option<u64> {
+    let guard = history().lock().expect("session history lock");
+    if !guard.open {
+        return None;
+    }
+    guard.records.back().map(|r| r.started_at)
+}
+
+/// 获取最近的会话（最新的在前）
+pub fn recent(limit: usize) -> Vec<SessionRecord> {
+    let guard = history().lock().expect("session history lock");
+    guard.records.iter().rev().take(limit).cloned().collect()
+}
+
+fn close_last(history: &mut SessionHistory, reason: &str, crashed: bool) {
+    if let Some(last) = history.records.back_mut() {
+        last.ended_at = Some(now_ms());
+        last.exit_reason = Some(reason.to_string());
+        last.crashed = crashed;
+        info!(
+            reason = reason,
+            crashed = crashed,
+            duration_ms = last.duration_ms().unwrap_or(0),
+            "[SessionHistory] session ended"
+        );
+    }
+    history.open = false;
+}
+
+fn save(history: &SessionHistory) {
+    let Some(path) = history.path.as_ref() else {
+        return;
+    };
+    if let Some(parent) = path.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+    match serde_json::to_string(&history.records) {
+        Ok(json) => {
+            if let Err(e) = std::fs::write(path, json) {
+                warn!(error = %e, "[SessionHistory] failed to save");
+            }
+        }
+        Err(e) => warn!(error = %e, "[SessionHistory] failed to serialize"),
+    }
+}
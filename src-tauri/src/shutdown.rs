@@ -0,0 +1,192 @@
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use tauri::{AppHandle, Manager};
+use tracing::{info, warn};
+
+// 退出流程记录
+//
+// request_exit 依次执行下面的清理步骤，同时启动兜底线程：超过 launcher.exit_fallback_ms 仍未退出时强制结束进程。
+// 开始退出和每完成一步都把进度写入 AppData/shutdown_report.json，强制退出前也会写一次；
+// 下次启动时读取上次的记录，写一条 shutdown report 日志（正常完成、被兜底强制结束，或中途崩溃）后删除文件。
+
+const REPORT_FILE: &str = "shutdown_report.json";
+
+/// 兜底延迟的取值范围（毫秒）
+const MIN_FALLBACK_MS: u64 = 100;
+const MAX_FALLBACK_MS: u64 = 10_000;
+
+/// 结束当前会话记录
+pub const STEP_SESSION_HISTORY: &str = "session_history";
+/// 停止 projector 和 WPE 拦截器（结束录制）
+pub const STEP_PROJECTOR: &str = "projector";
+
+/// 全部清理步骤，按执行顺序
+pub const STEPS: &[&str] = &[STEP_SESSION_HISTORY, STEP_PROJECTOR];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ShutdownOutcome {
+    /// 退出流程尚未结束（下次启动时仍是这个值说明进程中途崩溃或被强杀）
+    InProgress,
+    /// 清理步骤全部完成
+    Completed,
+    /// 兜底线程强制结束了进程
+    Forced,
+}
+
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ShutdownReport {
+    /// 开始退出的时间（Unix 毫秒）
+    pub started_at: u64,
+    pub fallback_ms: u64,
+    /// 已完成的步骤，按完成顺序
+    pub completed: Vec<String>,
+    pub outcome: ShutdownOutcome,
+    /// 从开始退出到完成或被强制结束的耗时
+    pub elapsed_ms: Option<u64>,
+}
+
+impl ShutdownReport {
+    /// 尚未完成的步骤
+    pub fn pending(&self) -> Vec<&'static str> {
+        STEPS
+            .iter()
+            .copied()
+            .filter(|step| !self.completed.iter().any(|done| done == step))
+            .collect()
+    }
+}
+
+static CURRENT: Mutex<Option<ShutdownReport>> = Mutex::new(None);
+static REPORT_PATH: OnceLock<PathBuf> = OnceLock::new();
+/// 上次运行的退出记录
+static PREVIOUS: OnceLock<Option<ShutdownReport>> = OnceLock::new();
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// 读取上次运行的退出记录并写入日志（在 Tauri setup 中调用）
+pub fn init(app: &AppHandle) {
+    let Ok(dir) = app.path().app_data_dir() else {
+        warn!("[Shutdown] failed to resolve app data dir, shutdown report disabled");
+        let _ = PREVIOUS.set(None);
+        return;
+    };
+    let path = dir.join(REPORT_FILE);
+    let previous = std::fs::read_to_string(&path)
+        .ok()
+        .and_then(|text| serde_json::from_str::<ShutdownReport>(&text).ok());
+    if let Some(report) = &previous {
+        let pending = report.pending();
+        match report.outcome {
+            ShutdownOutcome::Completed => info!(
+                elapsed_ms = report.elapsed_ms,
+                "[Shutdown] shutdown report: previous exit completed cleanly"
+            ),
+            ShutdownOutcome::Forced => warn!(
+                fallback_ms = report.fallback_ms,
+                completed = ?report.completed,
+                pending = ?pending,
+                "[Shutdown] shutdown report: previous exit was forced by the fallback"
+            ),
+            ShutdownOutcome::InProgress => warn!(
+                completed = ?report.completed,
+                pending = ?pending,
+                "[Shutdown] shutdown report: previous exit was interrupted"
+            ),
+        }
+        let _ = std::fs::remove_file(&path);
+    }
+    let _ = PREVIOUS.set(previous);
+    let _ = REPORT_PATH.set(path);
+}
+
+/// 上次运行的退出记录（上次正常退出前没有记录或文件缺失时为 None）
+pub fn previous() -> Option<ShutdownReport> {
+    PREVIOUS.get().cloned().flatten()
+}
+
+/// 配置的兜底延迟，限制在合理范围内
+pub fn fallback_delay() -> Duration {
+    let ms = crate::config::current().launcher.exit_fallback_ms;
+    Duration::from_millis(ms.clamp(MIN_FALLBACK_MS, MAX_FALLBACK_MS))
+}
+
+fn save(report: &ShutdownReport) {
+    let Some(path) = REPORT_PATH.get() else {
+        return;
+    };
+    if let Ok(json) = serde_json::to_string(report) {
+        let _ = std::fs::write(path, json);
+    }
+}
+
+/// 开始退出流程
+pub fn begin(fallback: Duration) {
+    let report = ShutdownReport {
+        started_at: now_ms(),
+        fallback_ms: fallback.as_millis() as u64,
+        completed: Vec::new(),
+        outcome: ShutdownOutcome::InProgress,
+        elapsed_ms: None,
+    };
+    save(&report);
+    *CURRENT.lock().expect("shutdown lock") = Some(report);
+}
+
+fn update(f: impl FnOnce(&mut ShutdownReport)) {
+    // 兜底线程与清理步骤可能同时到达，使用 try_lock 避免卡住强制退出
+    let Ok(mut guard) = CURRENT.try_lock() else {
+        return;
+    };
+    if let Some(report) = guard.as_mut() {
+        f(report);
+        save(report);
+    }
+}
+
+/// 记录一个清理步骤已完成
+pub fn step(name: &str) {
+    update(|report| report.completed.push(name.to_string()));
+}
+
+/// 清理步骤全部完成
+pub fn finish() {
+    update(|report| {
+        report.outcome = ShutdownOutcome::Completed;
+        report.elapsed_ms = Some(now_ms().saturating_sub(report.started_at));
+    });
+}
+
+/// 兜底线程即将强制退出；清理已经完成时保留 Completed
+pub fn force() {
+    update(|report| {
+        if report.outcome == ShutdownOutcome::InProgress {
+            report.outcome = ShutdownOutcome::Forced;
+            report.elapsed_ms = Some(now_ms().saturating_sub(report.started_at));
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pending_lists_unfinished_steps_in_order() {
+        let report = ShutdownReport {
+            started_at: 0,
+            fallback_ms: 1000,
+            completed: vec![STEP_SESSION_HISTORY.to_string()],
+            outcome: ShutdownOutcome::Forced,
+            elapsed_ms: Some(1000),
+        };
+        assert_eq!(report.pending(), vec![STEP_PROJECTOR]);
+    }
+}
@@ -0,0 +1,172 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use tauri::{AppHandle, Manager};
+use tracing::info;
+
+use crate::state::{AppState, AppStatus, ThemeMode};
+
+// 状态快照（开发用）
+//
+// 把 AppState 中不敏感的部分和主窗口、内嵌窗口的几何信息写成 JSON，用户附到问题反馈中，
+// 开发者在调试构建中载入以复现布局、状态栏相关的问题。
+// 不包含 swf 地址（含登录 token）和账号；载入时不会启动 projector，只恢复状态和窗口布局。
+
+/// 快照格式版本，不兼容的改动时递增
+const SNAPSHOT_VERSION: u32 = 1;
+
+/// 主窗口位置与大小（物理像素）
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct WindowGeometry {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    pub scale_factor: f64,
+    pub maximized: bool,
+}
+
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct StateSnapshot {
+    pub version: u32,
+    pub status: AppStatus,
+    pub message: Option<String>,
+    pub theme_mode: ThemeMode,
+    pub has_swf_url: bool,
+    pub has_projector: bool,
+    /// 内嵌 projector 最近一次布局的矩形 (x, y, w, h)
+    pub last_projector_rect: Option<(i32, i32, i32, i32)>,
+    pub window: Option<WindowGeometry>,
+    /// 生效中的 webview 缩放（按 label）
+    pub webview_zoom: HashMap<String, f64>,
+}
+
+fn window_geometry(app: &AppHandle) -> Option<WindowGeometry> {
+    let window = app.get_window("main")?;
+    let position = window.outer_position().ok()?;
+    let size = window.inner_size().ok()?;
+    Some(WindowGeometry {
+        x: position.x,
+        y: position.y,
+        width: size.width,
+        height: size.height,
+        scale_factor: window.scale_factor().unwrap_or(1.0),
+        maximized: window.is_maximized().unwrap_or(false),
+    })
+}
+
+pub fn capture(app: &AppHandle) -> StateSnapshot {
+    let webview_zoom = app
+        .webviews()
+        .into_keys()
+        .map(|label| {
+            let zoom = crate::launcher::webview_zoom(&label);
+            (label, zoom)
+        })
+        .collect();
+    let state = app.state::<Mutex<AppState>>();
+    let guard = state.lock().expect("state lock");
+    StateSnapshot {
+        version: SNAPSHOT_VERSION,
+        status: guard.status.clone(),
+        message: guard.message.clone(),
+        theme_mode: guard.theme_mode,
+        has_swf_url: guard.swf_url.is_some(),
+        has_projector: guard.projector.is_some(),
+        last_projector_rect: guard.last_projector_rect,
+        window: window_geometry(app),
+        webview_zoom,
+    }
+}
+
+/// 导出快照到日志目录，返回文件路径
+pub fn export(app: &AppHandle) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_log_dir()
+        .map_err(|_| "Failed to resolve logs directory.".to_string())?;
+    std::fs::create_dir_all(&dir).map_err(|_| "Failed to create log directory.".to_string())?;
+    let path = dir.join("state_snapshot.json");
+    let json = serde_json::to_string_pretty(&capture(app))
+        .map_err(|_| "Failed to serialize state snapshot.".to_string())?;
+    // 状态消息可能带有错误详情，与诊断包一样再过滤一遍
+    let json = crate::redact::redact_secrets(&json);
+    std::fs::write(&path, json).map_err(|_| "Failed to write state snapshot.".to_string())?;
+    info!(path = %path.display(), "[Snapshot] state exported");
+    Ok(path)
+}
+
+fn parse(text: &str) -> Result<StateSnapshot, String> {
+    let snapshot: StateSnapshot =
+        serde_json::from_str(text).map_err(|e| format!("Invalid state snapshot: {e}"))?;
+    if snapshot.version != SNAPSHOT_VERSION {
+        return Err(format!(
+            "Unsupported state snapshot version {} (expected {}).",
+            snapshot.version, SNAPSHOT_VERSION
+        ));
+    }
+    Ok(snapshot)
+}
+
+/// 载入快照：恢复状态栏内容、主题、窗口几何和 webview 缩放（不写入配置）
+///
+/// 调用方负责应用主题、重新布局并发出状态事件。
+pub fn load(app: &AppHandle, path: &Path) -> Result<StateSnapshot, String> {
+    let text =
+        std::fs::read_to_string(path).map_err(|e| format!("Failed to read state snapshot: {e}"))?;
+    let snapshot = parse(&text)?;
+
+    {
+        let state = app.state::<Mutex<AppState>>();
+        let mut guard = state.lock().expect("state lock");
+        guard.status = snapshot.status.clone();
+        guard.message = snapshot.message.clone();
+        guard.theme_mode = snapshot.theme_mode;
+        // 强制下一次布局真正移动窗口
+        guard.last_projector_rect = None;
+    }
+
+    if let (Some(geometry), Some(window)) = (&snapshot.window, app.get_window("main")) {
+        let _ = window.unmaximize();
+        let _ = window.set_position(tauri::PhysicalPosition::new(geometry.x, geometry.y));
+        let _ = window.set_size(tauri::PhysicalSize::new(geometry.width, geometry.height));
+        if geometry.maximized {
+            let _ = window.maximize();
+        }
+    }
+    for (label, zoom) in &snapshot.webview_zoom {
+        if let Some(webview) = app.get_webview(label) {
+            let _ = webview.set_zoom(*zoom);
+        }
+    }
+
+    info!(
+        path = %path.display(),
+        status = ?snapshot.status,
+        "[Snapshot] state loaded"
+    );
+    Ok(snapshot)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_other_versions() {
+        let json = r#"{
+            "version": 1, "status": "Running", "message": null, "theme_mode": "Light",
+            "has_swf_url": true, "has_projector": true, "last_projector_rect": [0, 40, 1280, 680],
+            "window": { "x": 10, "y": 20, "width": 1280, "height": 720, "scale_factor": 1.5, "maximized": false },
+            "webview_zoom": { "login": 0.8 }
+        }"#;
+        let snapshot = parse(json).unwrap();
+        assert_eq!(snapshot.status, AppStatus::Running);
+        assert_eq!(snapshot.last_projector_rect, Some((0, 40, 1280, 680)));
+        assert_eq!(snapshot.window.unwrap().scale_factor, 1.5);
+
+        let newer = json.replacen("\"version\": 1", "\"version\": 2", 1);
+        assert!(parse(&newer).unwrap_err().contains("version 2"));
+    }
+}
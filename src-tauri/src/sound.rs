@@ -0,0 +1,147 @@
+use std::path::Path;
+
+use tracing::{info, warn};
+
+// 提示音
+//
+// 登录完成、断线、倒计时结束等事件按配置播放提示音。由主进程直接调用系统接口播放，
+// 不经过 webview，主窗口最小化或 webview 隐藏时同样有效。
+// 声音可以是内置名称（对应 Windows 系统声音），也可以是用户提供的 .wav 文件路径；
+// 音量只影响本程序播放的提示音，不影响 projector 的游戏音效。
+
+/// 可以配置提示音的事件
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SoundEvent {
+    /// 登录完成、projector 进入运行状态
+    LoginComplete,
+    /// 服务器断开游戏连接
+    Disconnect,
+    /// 叠加层倒计时结束
+    CountdownFinished,
+    /// 通知规则中的 sound 动作（未配置时播放系统提示音）
+    Notification,
+}
+
+/// 内置声音名称及对应的系统声音别名
+pub const BUILTIN_SOUNDS: &[(&str, &str)] = &[
+    ("chime", "SystemAsterisk"),
+    ("alert", "SystemExclamation"),
+    ("error", "SystemHand"),
+    ("notify", "SystemNotification"),
+    ("default", "SystemDefault"),
+];
+
+#[derive(Debug, PartialEq)]
+enum Source<'a> {
+    Alias(&'static str),
+    File(&'a Path),
+}
+
+fn resolve(sound: &str) -> Result<Source<'_>, String> {
+    let sound = sound.trim();
+    if let Some((_, alias)) = BUILTIN_SOUNDS
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(sound))
+    {
+        return Ok(Source::Alias(alias));
+    }
+    let path = Path::new(sound);
+    if !path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("wav"))
+    {
+        return Err(format!(
+            "Unknown sound: {sound} (use a built-in name or a .wav file)."
+        ));
+    }
+    Ok(Source::File(path))
+}
+
+fn play_source(source: &Source, volume: u8) -> Result<(), String> {
+    crate::embed_win32::set_sound_volume(volume);
+    let played = match source {
+        Source::Alias(alias) => crate::embed_win32::play_sound(alias, true),
+        Source::File(path) => {
+            if !path.is_file() {
+                return Err(format!("Sound file not found: {}", path.display()));
+            }
+            crate::embed_win32::play_sound(&path.to_string_lossy(), false)
+        }
+    };
+    if played {
+        Ok(())
+    } else {
+        Err("Failed to play sound.".to_string())
+    }
+}
+
+/// 播放事件对应的提示音，返回是否已播放；静音、伪装中或该事件未配置声音时不播放
+pub fn play(event: SoundEvent) -> bool {
+    let config = crate::config::current().sounds;
+    if config.muted || crate::disguise::is_active() {
+        return false;
+    }
+    let Some(sound) = config.events.get(&event) else {
+        return false;
+    };
+    match resolve(sound).and_then(|source| play_source(&source, config.volume)) {
+        Ok(()) => true,
+        Err(e) => {
+            warn!(event = ?event, error = %e, "[Sound] playback failed");
+            false
+        }
+    }
+}
+
+/// 通知规则的 sound 动作：配置了 notification 声音时播放它，否则播放系统提示音
+pub fn play_notification() {
+    if crate::config::current().sounds.muted {
+        return;
+    }
+    if !play(SoundEvent::Notification) {
+        crate::embed_win32::play_alert_sound();
+    }
+}
+
+/// 试听声音（设置界面使用），不受静音和事件配置影响
+pub fn preview(sound: &str) -> Result<(), String> {
+    let source = resolve(sound)?;
+    play_source(&source, crate::config::current().sounds.volume)
+}
+
+/// 设置事件的提示音并落盘，sound 为 None 时不再为该事件播放
+pub fn set_event_sound(event: SoundEvent, sound: Option<String>) -> Result<(), String> {
+    let sound = sound
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty());
+    if let Some(sound) = &sound {
+        resolve(sound)?;
+    }
+    crate::config::update(|config| match &sound {
+        Some(sound) => {
+            config.sounds.events.insert(event, sound.clone());
+        }
+        None => {
+            config.sounds.events.remove(&event);
+        }
+    })?;
+    info!(event = ?event, sound = ?sound, "[Sound] event sound updated");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolves_builtin_names_and_wav_files() {
+        assert_eq!(resolve(" Chime "), Ok(Source::Alias("SystemAsterisk")));
+        assert_eq!(
+            resolve(r"C:\sounds\ding.WAV"),
+            Ok(Source::File(Path::new(r"C:\sounds\ding.WAV")))
+        );
+        assert!(resolve("ding.mp3").is_err());
+        assert!(resolve("bell").is_err());
+    }
+}
@@ -0,0 +1,146 @@
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use tauri::{AppHandle, Emitter};
+
+use super::{StatusDetail, StatusPayload};
+
+// 状态事件合并发送
+//
+// 启动过程中 emit_status 会被密集调用（有的阶段连续两次），逐条发送会刷屏前端。
+// 与上次发出的内容相同的状态直接跳过；距上次发送不足 MIN_INTERVAL 时只保留最新状态，
+// 到期后由一次性线程补发，保证最终状态一定送达。
+
+/// 两次状态事件之间的最小间隔
+const MIN_INTERVAL: Duration = Duration::from_millis(100);
+
+pub(super) enum Offer<T> {
+    /// 立即发送
+    Emit(T),
+    /// 与上次发送的内容相同，跳过
+    Skip,
+    /// 已暂存，需要在给定时间后调用 take_pending 补发
+    Schedule(Duration),
+    /// 已暂存，补发已经安排过
+    Queued,
+}
+
+pub(super) struct Coalescer<T> {
+    interval: Duration,
+    last: Option<T>,
+    last_emit: Option<Instant>,
+    pending: Option<T>,
+    flush_scheduled: bool,
+}
+
+impl<T: Clone + PartialEq> Coalescer<T> {
+    pub(super) const fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            last: None,
+            last_emit: None,
+            pending: None,
+            flush_scheduled: false,
+        }
+    }
+
+    pub(super) fn offer(&mut self, value: T, now: Instant) -> Offer<T> {
+        if self.last.as_ref() == Some(&value) {
+            // 暂存的中间状态已被撤回，最终状态就是上次发出的状态
+            self.pending = None;
+            return Offer::Skip;
+        }
+        let wait = self
+            .last_emit
+            .map(|at| {
+                self.interval
+                    .saturating_sub(now.saturating_duration_since(at))
+            })
+            .unwrap_or_default();
+        if wait.is_zero() && !self.flush_scheduled {
+            self.mark(value.clone(), now);
+            return Offer::Emit(value);
+        }
+        self.pending = Some(value);
+        if self.flush_scheduled {
+            Offer::Queued
+        } else {
+            self.flush_scheduled = true;
+            Offer::Schedule(wait)
+        }
+    }
+
+    /// 取出需要补发的最新状态（没有时返回 None）
+    pub(super) fn take_pending(&mut self, now: Instant) -> Option<T> {
+        self.flush_scheduled = false;
+        let value = self.pending.take()?;
+        self.mark(value.clone(), now);
+        Some(value)
+    }
+
+    fn mark(&mut self, value: T, now: Instant) {
+        self.last = Some(value);
+        self.last_emit = Some(now);
+    }
+}
+
+type Status = (StatusPayload, StatusDetail);
+
+static STATUS: Mutex<Coalescer<Status>> = Mutex::new(Coalescer::new(MIN_INTERVAL));
+
+fn send(app: &AppHandle, (payload, detail): Status) {
+    let _ = app.emit(crate::events::STATUS_CHANGED, payload);
+    let _ = app.emit(crate::events::STATUS_DETAIL, detail);
+}
+
+pub(super) fn emit(app: &AppHandle, status: Status) {
+    // 持锁发送，保证事件顺序与状态变化顺序一致
+    let mut coalescer = STATUS.lock().expect("status emitter lock");
+    match coalescer.offer(status, Instant::now()) {
+        Offer::Emit(status) => send(app, status),
+        Offer::Skip | Offer::Queued => {}
+        Offer::Schedule(wait) => {
+            let app = app.clone();
+            std::thread::spawn(move || {
+                std::thread::sleep(wait);
+                let mut coalescer = STATUS.lock().expect("status emitter lock");
+                if let Some(status) = coalescer.take_pending(Instant::now()) {
+                    send(&app, status);
+                }
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bursts_are_coalesced_to_the_latest_value() {
+        let mut coalescer = Coalescer::new(Duration::from_millis(100));
+        let start = Instant::now();
+
+        assert!(matches!(coalescer.offer(1, start), Offer::Emit(1)));
+        assert!(matches!(coalescer.offer(1, start), Offer::Skip));
+
+        let at = start + Duration::from_millis(30);
+        assert!(
+            matches!(coalescer.offer(2, at), Offer::Schedule(wait) if wait == Duration::from_millis(70))
+        );
+        assert!(matches!(coalescer.offer(3, at), Offer::Queued));
+        assert_eq!(
+            coalescer.take_pending(start + Duration::from_millis(100)),
+            Some(3)
+        );
+
+        // 中间状态被撤回时不补发
+        let later = start + Duration::from_millis(150);
+        assert!(matches!(coalescer.offer(4, later), Offer::Schedule(_)));
+        assert!(matches!(coalescer.offer(3, later), Offer::Skip));
+        assert_eq!(coalescer.take_pending(later), None);
+
+        let idle = start + Duration::from_millis(400);
+        assert!(matches!(coalescer.offer(5, idle), Offer::Emit(5)));
+    }
+}
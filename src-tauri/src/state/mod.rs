@@ -1,21 +1,42 @@
 #[cfg(not(target_os = "windows"))]
 use std::process::Child;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
-use tauri::{AppHandle, Emitter};
+use tauri::AppHandle;
 #[cfg(target_os = "windows")]
 use windows::Win32::Foundation::HANDLE;
 
-#[derive(Debug, Clone, serde::Serialize)]
+mod emitter;
+mod progress;
+
+pub use progress::{Pipeline, StageProgress};
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 pub enum AppStatus {
     Login,
     Capturing,
     FoundValue,
     Launching,
     Running,
+    /// 服务器断开了游戏连接，等待自动重连或用户处理
+    Disconnected,
     Error,
 }
 
-#[derive(Clone, Copy, serde::Serialize)]
+impl AppStatus {
+    /// 粗粒度的整体进度（0.0 ~ 1.0），供状态栏显示
+    pub fn progress(&self) -> f32 {
+        match self {
+            AppStatus::Login | AppStatus::Disconnected | AppStatus::Error => 0.0,
+            AppStatus::Capturing => 0.25,
+            AppStatus::FoundValue => 0.5,
+            AppStatus::Launching => 0.75,
+            AppStatus::Running => 1.0,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, serde::Serialize, serde::Deserialize)]
 pub enum ThemeMode {
     Dark,
     Light,
@@ -30,16 +51,52 @@ impl ThemeMode {
     }
 }
 
-#[derive(Clone, serde::Serialize)]
+#[derive(Clone, PartialEq, serde::Serialize)]
 pub struct StatusPayload {
     pub status: AppStatus,
     pub message: Option<String>,
+    /// 多阶段流程（启动、回到登录页）进行中时的当前阶段
+    pub stage: Option<StageProgress>,
+}
+
+/// 状态栏详细信息（通过 `status_detail` 事件发送）
+#[derive(Clone, PartialEq, serde::Serialize)]
+pub struct StatusDetail {
+    pub phase: AppStatus,
+    pub message: Option<String>,
+    /// 整体进度（0.0 ~ 1.0）；有阶段进度时为当前流程的进度
+    pub progress: f32,
+    pub stage: Option<StageProgress>,
+    /// 当前账号 QQ 号
+    pub account: Option<u64>,
+}
+
+impl StatusDetail {
+    pub fn from_state(state: &AppState, stage: Option<StageProgress>) -> Self {
+        Self {
+            phase: state.status.clone(),
+            message: state.message.clone(),
+            progress: stage
+                .as_ref()
+                .map_or_else(|| state.status.progress(), StageProgress::fraction),
+            stage,
+            // 登录前显示 --account 指定的期望账号
+            account: state.qq_num.or(crate::cli::args().account),
+        }
+    }
 }
 
 pub struct ProjectorHandle {
     pub process: ProjectorProcess,
     pub hwnd: isize,
-    pub original_style: isize,
+    /// 嵌入前的窗口样式；独立窗口模式（未嵌入主窗口）时为 None
+    pub original_style: Option<isize>,
+    /// 启动后采集的进程信息（位数、架构、模块列表）
+    pub info: Option<crate::projector::introspect::ProcessInfo>,
+    /// 最近一次观察到的窗口标题及其变化时间（标题会反映加载/地图状态）
+    pub title: Option<(String, std::time::Instant)>,
+    /// 最近 5 分钟的资源占用采样
+    pub metrics: crate::projector::metrics::MetricsHistory,
 }
 
 #[cfg(target_os = "windows")]
@@ -57,6 +114,28 @@ pub struct ProjectorProcess {
     pub pid: u32,
 }
 
+/// 启动互斥守卫：同一时刻只允许一个 launch_projector_auto 执行
+///
+/// 离开作用域时自动释放。
+pub struct LaunchGuard {
+    flag: Arc<AtomicBool>,
+}
+
+impl LaunchGuard {
+    /// 尝试占用启动标志，已有启动在进行时返回 None
+    pub fn acquire(flag: &Arc<AtomicBool>) -> Option<Self> {
+        flag.compare_exchange(false, true, Ordering::AcqRel, Ordering::Acquire)
+            .ok()
+            .map(|_| Self { flag: flag.clone() })
+    }
+}
+
+impl Drop for LaunchGuard {
+    fn drop(&mut self) {
+        self.flag.store(false, Ordering::Release);
+    }
+}
+
 pub struct AppState {
     pub status: AppStatus,
     pub message: Option<String>,
@@ -67,6 +146,12 @@ pub struct AppState {
     pub last_projector_rect: Option<(i32, i32, i32, i32)>,
     pub qq_num: Option<u64>,
     pub wpe_interceptor: Option<Arc<crate::wpe::PacketInterceptor>>,
+    /// WPE 发送列表
+    pub send_list: crate::wpe::sendlist::SendList,
+    /// 正在进行的录制回放的停止标志
+    pub replay_stop: Option<Arc<AtomicBool>>,
+    /// 启动进行中标志（配合 LaunchGuard 使用）
+    pub launch_in_progress: Arc<AtomicBool>,
 }
 
 impl AppState {
@@ -81,14 +166,68 @@ impl AppState {
             last_projector_rect: None,
             qq_num: None,
             wpe_interceptor: None,
+            send_list: Default::default(),
+            replay_stop: None,
+            launch_in_progress: Arc::new(AtomicBool::new(false)),
         }
     }
 }
 
 pub fn emit_status(app: &AppHandle, state: &AppState) {
+    let stage = progress::current();
     let payload = StatusPayload {
         status: state.status.clone(),
         message: state.message.clone(),
+        stage: stage.clone(),
     };
-    let _ = app.emit("status_changed", payload);
+    emitter::emit(app, (payload, StatusDetail::from_state(state, stage)));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LaunchGuard;
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+    use std::sync::{Arc, Barrier};
+
+    #[test]
+    fn only_one_racer_acquires_launch_guard() {
+        let flag = Arc::new(AtomicBool::new(false));
+        let barrier = Arc::new(Barrier::new(8));
+        let winners = Arc::new(AtomicUsize::new(0));
+        let release = Arc::new(Barrier::new(8));
+
+        let handles: Vec<_> = (0..8)
+            .map(|_| {
+                let flag = flag.clone();
+                let barrier = barrier.clone();
+                let winners = winners.clone();
+                let release = release.clone();
+                std::thread::spawn(move || {
+                    barrier.wait();
+                    let guard = LaunchGuard::acquire(&flag);
+                    if guard.is_some() {
+                        winners.fetch_add(1, Ordering::SeqCst);
+                    }
+                    // 所有线程都尝试过后才释放守卫
+                    release.wait();
+                    drop(guard);
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().expect("racer thread");
+        }
+
+        assert_eq!(winners.load(Ordering::SeqCst), 1);
+        assert!(!flag.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn launch_guard_is_released_on_drop() {
+        let flag = Arc::new(AtomicBool::new(false));
+        let first = LaunchGuard::acquire(&flag).expect("first acquire");
+        assert!(LaunchGuard::acquire(&flag).is_none());
+        drop(first);
+        assert!(LaunchGuard::acquire(&flag).is_some());
+    }
 }
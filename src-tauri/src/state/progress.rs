@@ -0,0 +1,110 @@
+use std::sync::Mutex;
+
+use tauri::{AppHandle, Manager};
+
+use super::{emit_status, AppState};
+use crate::request_context::StageTimer;
+
+// 多阶段流程的进度
+//
+// 启动 projector、回到登录页等流程由若干 StageTimer 阶段组成。用 Pipeline::begin 声明流程的阶段列表，
+// 再用 pipeline.stage(name) 代替 StageTimer::new：除了计时，还会把当前阶段和序号写入状态事件，
+// 状态栏据此显示"启动中 3/9 find_window"和进度条。流程结束（Pipeline 离开作用域）时恢复外层流程的阶段；
+// 不在列表中的阶段（例如在后台线程中并行的阶段）只计时，不改变进度。
+
+#[derive(Clone, Debug, PartialEq, serde::Serialize)]
+pub struct StageProgress {
+    /// 流程名称（launch、reset_to_login）
+    pub flow: &'static str,
+    pub stage: String,
+    /// 当前阶段序号，从 1 开始
+    pub index: u32,
+    pub total: u32,
+}
+
+impl StageProgress {
+    /// 流程进度（0.0 ~ 1.0），当前阶段视为进行到一半
+    pub fn fraction(&self) -> f32 {
+        if self.total == 0 {
+            return 0.0;
+        }
+        (self.index as f32 - 0.5).max(0.0) / self.total as f32
+    }
+}
+
+static CURRENT: Mutex<Option<StageProgress>> = Mutex::new(None);
+
+/// 当前进行中的阶段
+pub fn current() -> Option<StageProgress> {
+    CURRENT.lock().expect("stage progress lock").clone()
+}
+
+fn set(progress: Option<StageProgress>) {
+    *CURRENT.lock().expect("stage progress lock") = progress;
+}
+
+pub struct Pipeline {
+    app: AppHandle,
+    flow: &'static str,
+    stages: &'static [&'static str],
+    /// 外层流程的阶段（流程嵌套时恢复）
+    outer: Option<StageProgress>,
+}
+
+impl Pipeline {
+    pub fn begin(app: &AppHandle, flow: &'static str, stages: &'static [&'static str]) -> Self {
+        Self {
+            app: app.clone(),
+            flow,
+            stages,
+            outer: current(),
+        }
+    }
+
+    /// 开始一个阶段：记录耗时，并在状态事件中报告进度
+    pub fn stage(&self, stage: &str) -> StageTimer {
+        if let Some(position) = self.stages.iter().position(|s| *s == stage) {
+            set(Some(StageProgress {
+                flow: self.flow,
+                stage: stage.to_string(),
+                index: position as u32 + 1,
+                total: self.stages.len() as u32,
+            }));
+            self.emit();
+        }
+        StageTimer::new(stage)
+    }
+
+    fn emit(&self) {
+        // 调用方持有状态锁时跳过，下一次状态事件会带上最新进度
+        let state = self.app.state::<Mutex<AppState>>();
+        if let Ok(guard) = state.try_lock() {
+            emit_status(&self.app, &guard);
+        }
+    }
+}
+
+impl Drop for Pipeline {
+    fn drop(&mut self) {
+        set(self.outer.take());
+        self.emit();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fraction_counts_current_stage_as_half_done() {
+        let progress = |index, total| StageProgress {
+            flow: "launch",
+            stage: String::new(),
+            index,
+            total,
+        };
+        assert_eq!(progress(1, 4).fraction(), 0.125);
+        assert_eq!(progress(4, 4).fraction(), 0.875);
+        assert_eq!(progress(0, 0).fraction(), 0.0);
+    }
+}
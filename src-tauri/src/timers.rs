@@ -0,0 +1,216 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use tauri::{AppHandle, Emitter};
+use tracing::{info, warn};
+
+// 叠加层计时器：会话计时、实时时钟和用户开始的倒计时（不依赖插件）
+//
+// 后台线程每秒发出一次 timers_tick，toolbar 叠加层据此渲染；
+// 倒计时结束时发出 countdown_finished。倒计时只在本次运行内有效，不落盘。
+
+const TICK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// 同时进行的倒计时上限
+const MAX_COUNTDOWNS: usize = 16;
+
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize)]
+pub struct Countdown {
+    pub id: u32,
+    pub label: String,
+    pub total_secs: u64,
+    pub remaining_secs: u64,
+}
+
+/// 前端 `timers_tick` 事件
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct TimersSnapshot {
+    /// 当前时间（Unix 毫秒），叠加层时钟使用
+    pub now: u64,
+    /// 当前游戏会话已进行的秒数，没有进行中的会话时为 None
+    pub session_secs: Option<u64>,
+    pub countdowns: Vec<Countdown>,
+}
+
+struct Entry {
+    id: u32,
+    label: String,
+    total: Duration,
+    deadline: Instant,
+}
+
+impl Entry {
+    fn view(&self, now: Instant) -> Countdown {
+        Countdown {
+            id: self.id,
+            label: self.label.clone(),
+            total_secs: self.total.as_secs(),
+            // 向上取整，剩余 0.4 秒时仍显示 1
+            remaining_secs: self
+                .deadline
+                .saturating_duration_since(now)
+                .as_millis()
+                .div_ceil(1000) as u64,
+        }
+    }
+}
+
+struct Timers {
+    next_id: u32,
+    countdowns: Vec<Entry>,
+}
+
+impl Timers {
+    const fn new() -> Self {
+        Self {
+            next_id: 1,
+            countdowns: Vec::new(),
+        }
+    }
+
+    fn start(&mut self, label: String, total: Duration, now: Instant) -> Result<u32, String> {
+        if self.countdowns.len() >= MAX_COUNTDOWNS {
+            return Err("Too many countdowns.".to_string());
+        }
+        let id = self.next_id;
+        self.next_id += 1;
+        self.countdowns.push(Entry {
+            id,
+            label,
+            total,
+            deadline: now + total,
+        });
+        Ok(id)
+    }
+
+    fn cancel(&mut self, id: u32) -> bool {
+        let before = self.countdowns.len();
+        self.countdowns.retain(|entry| entry.id != id);
+        self.countdowns.len() != before
+    }
+
+    /// 移除并返回已经结束的倒计时
+    fn take_finished(&mut self, now: Instant) -> Vec<Countdown> {
+        let (finished, running): (Vec<Entry>, Vec<Entry>) = self
+            .countdowns
+            .drain(..)
+            .partition(|entry| entry.deadline <= now);
+        self.countdowns = running;
+        finished.iter().map(|entry| entry.view(now)).collect()
+    }
+
+    fn views(&self, now: Instant) -> Vec<Countdown> {
+        self.countdowns
+            .iter()
+            .map(|entry| entry.view(now))
+            .collect()
+    }
+}
+
+static TIMERS: Mutex<Timers> = Mutex::new(Timers::new());
+static TICKING: AtomicBool = AtomicBool::new(false);
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// 开始一个倒计时，返回其 ID
+pub fn start_countdown(label: String, secs: u64) -> Result<u32, String> {
+    if secs == 0 {
+        return Err("Countdown must be at least 1 second.".to_string());
+    }
+    let id = TIMERS.lock().expect("timers lock").start(
+        label.clone(),
+        Duration::from_secs(secs),
+        Instant::now(),
+    )?;
+    info!(id = id, label = %label, secs = secs, "[Timers] countdown started");
+    Ok(id)
+}
+
+pub fn cancel_countdown(id: u32) -> bool {
+    TIMERS.lock().expect("timers lock").cancel(id)
+}
+
+pub fn snapshot() -> TimersSnapshot {
+    let now = now_ms();
+    TimersSnapshot {
+        now,
+        session_secs: crate::session_history::current_started_at()
+            .map(|started| now.saturating_sub(started) / 1000),
+        countdowns: TIMERS.lock().expect("timers lock").views(Instant::now()),
+    }
+}
+
+/// 启动计时器线程（在 Tauri setup 中调用）
+pub fn start(app: AppHandle) {
+    if TICKING.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    std::thread::spawn(move || {
+        while !crate::EXITING.load(Ordering::Relaxed) {
+            std::thread::sleep(TICK_INTERVAL);
+            let finished = TIMERS
+                .lock()
+                .expect("timers lock")
+                .take_finished(Instant::now());
+            for countdown in finished {
+                info!(id = countdown.id, label = %countdown.label, "[Timers] countdown finished");
+                let _ = app.emit(crate::events::COUNTDOWN_FINISHED, &countdown);
+                crate::sound::play(crate::sound::SoundEvent::CountdownFinished);
+            }
+            let _ = app.emit(crate::events::TIMERS_TICK, snapshot());
+        }
+        TICKING.store(false, Ordering::SeqCst);
+    });
+}
+
+/// 按配置（重新）注册快速倒计时快捷键
+pub fn apply_hotkey(app: &AppHandle) {
+    let text = crate::config::current().timers.countdown_hotkey;
+    crate::hotkey::bind(
+        app,
+        "countdown",
+        &text,
+        Arc::new(|_app: &AppHandle| {
+            let config = crate::config::current().timers;
+            if let Err(e) = start_countdown(config.countdown_label, config.countdown_secs) {
+                warn!(error = %e, "[Timers] hotkey countdown failed");
+            }
+        }),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn countdowns_finish_and_cancel() {
+        let mut timers = Timers::new();
+        let start = Instant::now();
+        let short = timers
+            .start("boss".to_string(), Duration::from_secs(10), start)
+            .unwrap();
+        let long = timers
+            .start("egg".to_string(), Duration::from_secs(60), start)
+            .unwrap();
+
+        let views = timers.views(start + Duration::from_millis(4_500));
+        assert_eq!(views[0].remaining_secs, 6);
+        assert_eq!(views[1].remaining_secs, 56);
+
+        let finished = timers.take_finished(start + Duration::from_secs(10));
+        assert_eq!(finished.len(), 1);
+        assert_eq!(finished[0].id, short);
+        assert_eq!(finished[0].remaining_secs, 0);
+
+        assert!(timers.cancel(long));
+        assert!(!timers.cancel(long));
+        assert!(timers.views(start).is_empty());
+    }
+}
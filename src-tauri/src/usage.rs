@@ -0,0 +1,144 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use tauri::{AppHandle, Manager};
+use tracing::{info, warn};
+
+// 功能使用统计（仅本地，需要用户开启）
+//
+// 开启配置 usage.enabled 后，按功能名累计使用次数（命令调用、断线重连等），保存在本机 AppData 中。
+// 不会自动上传：用户可以导出汇总文件，在反馈时自愿提供给维护者，用于判断各功能的优先级。
+// 只记录次数，不记录账号、参数和时间线。
+
+const USAGE_FILE: &str = "usage_stats.json";
+
+#[derive(Clone, Debug, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct UsageStats {
+    /// 开始统计的时间（Unix 毫秒），尚未记录时为 0
+    pub since: u64,
+    /// 功能名 -> 使用次数
+    pub counters: BTreeMap<String, u64>,
+}
+
+/// 导出的汇总
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct UsageSummary {
+    pub app_version: String,
+    /// 导出时间（Unix 毫秒）
+    pub exported_at: u64,
+    #[serde(flatten)]
+    pub stats: UsageStats,
+}
+
+static USAGE: Mutex<UsageStats> = Mutex::new(UsageStats {
+    since: 0,
+    counters: BTreeMap::new(),
+});
+static USAGE_PATH: OnceLock<PathBuf> = OnceLock::new();
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+fn bump(stats: &mut UsageStats, feature: &str, now: u64) {
+    if stats.since == 0 {
+        stats.since = now;
+    }
+    *stats.counters.entry(feature.to_string()).or_insert(0) += 1;
+}
+
+/// 加载已有统计（在 Tauri setup 中调用）
+pub fn init(app: &AppHandle) {
+    let Ok(dir) = app.path().app_data_dir() else {
+        warn!("[Usage] failed to resolve app data dir, usage stats will not persist");
+        return;
+    };
+    let path = dir.join(USAGE_FILE);
+    if let Some(stats) = std::fs::read_to_string(&path)
+        .ok()
+        .and_then(|text| serde_json::from_str::<UsageStats>(&text).ok())
+    {
+        *USAGE.lock().expect("usage lock") = stats;
+    }
+    let _ = USAGE_PATH.set(path);
+}
+
+fn save(stats: &UsageStats) {
+    let Some(path) = USAGE_PATH.get() else {
+        return;
+    };
+    if let Some(parent) = path.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+    match serde_json::to_string(stats) {
+        Ok(json) => {
+            if let Err(e) = std::fs::write(path, json) {
+                warn!(error = %e, "[Usage] failed to save");
+            }
+        }
+        Err(e) => warn!(error = %e, "[Usage] failed to serialize"),
+    }
+}
+
+/// 记录一次功能使用；未开启统计时忽略
+pub fn record(feature: &str) {
+    if !crate::config::current().usage.enabled {
+        return;
+    }
+    let mut stats = USAGE.lock().expect("usage lock");
+    bump(&mut stats, feature, now_ms());
+    save(&stats);
+}
+
+pub fn snapshot() -> UsageStats {
+    USAGE.lock().expect("usage lock").clone()
+}
+
+/// 清空统计
+pub fn reset() {
+    let mut stats = USAGE.lock().expect("usage lock");
+    *stats = UsageStats::default();
+    save(&stats);
+    info!("[Usage] stats reset");
+}
+
+/// 导出汇总到日志目录，返回文件路径
+pub fn export(app: &AppHandle) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_log_dir()
+        .map_err(|_| "Failed to resolve logs directory.".to_string())?;
+    std::fs::create_dir_all(&dir).map_err(|_| "Failed to create log directory.".to_string())?;
+    let path = dir.join("usage_summary.json");
+    let summary = UsageSummary {
+        app_version: app.package_info().version.to_string(),
+        exported_at: now_ms(),
+        stats: snapshot(),
+    };
+    let json = serde_json::to_string_pretty(&summary)
+        .map_err(|_| "Failed to serialize usage summary.".to_string())?;
+    std::fs::write(&path, json).map_err(|_| "Failed to write usage summary.".to_string())?;
+    info!(path = %path.display(), "[Usage] summary exported");
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bump_counts_and_keeps_first_timestamp() {
+        let mut stats = UsageStats::default();
+        bump(&mut stats, "launch_projector", 1_000);
+        bump(&mut stats, "launch_projector", 2_000);
+        bump(&mut stats, "sendlist_start", 3_000);
+        assert_eq!(stats.since, 1_000);
+        assert_eq!(stats.counters["launch_projector"], 2);
+        assert_eq!(stats.counters["sendlist_start"], 1);
+    }
+}
@@ -0,0 +1,225 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use tracing::{info, warn};
+
+/// 可触发 webhook 的事件
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookEvent {
+    SessionStart,
+    SessionEnd,
+    Crash,
+    /// 通知规则中配置了 webhook 动作
+    Notification,
+    /// test_webhook 命令手动触发
+    Test,
+}
+
+/// 请求体格式
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookFormat {
+    /// Discord：{"content": "..."}
+    Discord,
+    /// 通用 JSON（QQ 机器人等）：{"event", "content", "account", "timestamp"}
+    #[default]
+    Generic,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_min_interval() -> u64 {
+    2
+}
+
+fn default_max_per_minute() -> u32 {
+    20
+}
+
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct WebhookEndpoint {
+    pub name: String,
+    pub url: String,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    #[serde(default)]
+    pub format: WebhookFormat,
+    /// 订阅的事件；Test 事件总是会发送
+    #[serde(default)]
+    pub events: Vec<WebhookEvent>,
+    /// 两次发送的最小间隔（秒）
+    #[serde(default = "default_min_interval")]
+    pub min_interval_secs: u64,
+    /// 每分钟最多发送次数
+    #[serde(default = "default_max_per_minute")]
+    pub max_per_minute: u32,
+}
+
+/// 单个 endpoint 的限流状态（最小间隔 + 一分钟滑动窗口）
+#[derive(Default)]
+struct RateLimiter {
+    sent: VecDeque<Instant>,
+}
+
+impl RateLimiter {
+    fn try_acquire(&mut self, now: Instant, min_interval: Duration, max_per_minute: u32) -> bool {
+        while let Some(front) = self.sent.front() {
+            if now.duration_since(*front) >= Duration::from_secs(60) {
+                self.sent.pop_front();
+            } else {
+                break;
+            }
+        }
+        if let Some(last) = self.sent.back() {
+            if now.duration_since(*last) < min_interval {
+                return false;
+            }
+        }
+        if self.sent.len() >= max_per_minute as usize {
+            return false;
+        }
+        self.sent.push_back(now);
+        true
+    }
+}
+
+struct Job {
+    endpoint: WebhookEndpoint,
+    body: String,
+}
+
+static LIMITERS: OnceLock<Mutex<HashMap<String, RateLimiter>>> = OnceLock::new();
+static WORKER: OnceLock<Mutex<Sender<Job>>> = OnceLock::new();
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// 后台发送线程：webhook 请求不能阻塞调用方（可能在封包处理或退出流程中）
+fn worker() -> &'static Mutex<Sender<Job>> {
+    WORKER.get_or_init(|| {
+        let (tx, rx) = mpsc::channel::<Job>();
+        std::thread::spawn(move || {
+            for job in rx {
+                if let Err(e) = post(&job.endpoint, &job.body) {
+                    warn!(endpoint = %job.endpoint.name, error = %e, "[Webhook] delivery failed");
+                }
+            }
+        });
+        Mutex::new(tx)
+    })
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+fn build_body(
+    endpoint: &WebhookEndpoint,
+    event: WebhookEvent,
+    message: &str,
+    account: Option<u64>,
+) -> String {
+    let content = crate::redact::redact_secrets(message);
+    let body = match endpoint.format {
+        WebhookFormat::Discord => serde_json::json!({ "content": content }),
+        WebhookFormat::Generic => serde_json::json!({
+            "event": event,
+            "content": content,
+            "account": account,
+            "timestamp": now_ms(),
+        }),
+    };
+    body.to_string()
+}
+
+fn post(endpoint: &WebhookEndpoint, body: &str) -> Result<(), String> {
+    let response = ureq::post(&endpoint.url)
+        .timeout(REQUEST_TIMEOUT)
+        .set("Content-Type", "application/json")
+        .send_string(body)
+        .map_err(|e| format!("Webhook request failed: {e}"))?;
+    info!(
+        endpoint = %endpoint.name,
+        status = response.status(),
+        "[Webhook] delivered"
+    );
+    Ok(())
+}
+
+fn acquire(endpoint: &WebhookEndpoint) -> bool {
+    let mut limiters = LIMITERS
+        .get_or_init(|| Mutex::new(HashMap::new()))
+        .lock()
+        .expect("webhook limiter lock");
+    limiters
+        .entry(endpoint.name.clone())
+        .or_default()
+        .try_acquire(
+            Instant::now(),
+            Duration::from_secs(endpoint.min_interval_secs),
+            endpoint.max_per_minute,
+        )
+}
+
+/// 触发事件：发送到所有订阅了该事件的 endpoint（超出限流的直接丢弃）
+pub fn fire(event: WebhookEvent, message: &str, account: Option<u64>) {
+    let endpoints = crate::config::current().webhooks.endpoints;
+    for endpoint in endpoints
+        .into_iter()
+        .filter(|e| e.enabled && e.events.contains(&event))
+    {
+        if !acquire(&endpoint) {
+            warn!(endpoint = %endpoint.name, event = ?event, "[Webhook] rate limited");
+            continue;
+        }
+        let body = build_body(&endpoint, event, message, account);
+        let _ = worker()
+            .lock()
+            .expect("webhook worker lock")
+            .send(Job { endpoint, body });
+    }
+}
+
+/// 向指定 endpoint 同步发送一条测试消息（不受限流影响，便于配置时验证）
+pub fn test_fire(name: &str) -> Result<(), String> {
+    let endpoint = crate::config::current()
+        .webhooks
+        .endpoints
+        .into_iter()
+        .find(|e| e.name == name)
+        .ok_or_else(|| format!("Webhook not found: {name}"))?;
+    let body = build_body(
+        &endpoint,
+        WebhookEvent::Test,
+        "RocoKnight webhook test",
+        None,
+    );
+    post(&endpoint, &body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn limiter_enforces_interval_and_window() {
+        let mut limiter = RateLimiter::default();
+        let start = Instant::now();
+        let interval = Duration::from_secs(2);
+
+        assert!(limiter.try_acquire(start, interval, 2));
+        assert!(!limiter.try_acquire(start + Duration::from_secs(1), interval, 2));
+        assert!(limiter.try_acquire(start + Duration::from_secs(3), interval, 2));
+        // 一分钟内已达上限
+        assert!(!limiter.try_acquire(start + Duration::from_secs(10), interval, 2));
+        // 窗口滑过后恢复
+        assert!(limiter.try_acquire(start + Duration::from_secs(61), interval, 2));
+    }
+}
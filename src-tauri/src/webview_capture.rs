@@ -0,0 +1,209 @@
+use std::sync::{Arc, Mutex};
+
+use tauri::AppHandle;
+use tracing::{info, warn};
+
+// WebView2 响应抓取
+//
+// 在 webview 上注册一次 WebResourceResponseReceived，按 URL 分发给已注册的匹配器，
+// 各功能（login3、游戏配置等）只需调用 register 提供匹配规则和回调，不必各自重复 COM 调用。
+// 匹配时 URL 已转为小写；同一响应命中多个匹配器时只读取一次内容，按各自的上限截断后分别回调。
+// 回调在 WebView2 的完成回调中执行，耗时的处理应另开线程。
+
+/// 响应内容：读取成功时为文本，否则为失败原因
+pub type ResponseBody = Result<String, String>;
+
+type Callback = Box<dyn Fn(&AppHandle, &str, ResponseBody) + Send + Sync>;
+
+struct ResponseMatcher {
+    name: &'static str,
+    matches: fn(&str) -> bool,
+    /// 每次读取时取值，允许运行中调整
+    max_bytes: fn() -> usize,
+    callback: Callback,
+}
+
+static MATCHERS: Mutex<Vec<Arc<ResponseMatcher>>> = Mutex::new(Vec::new());
+
+/// 注册匹配器；`matches` 收到小写的 URL，`callback` 收到原始 URL 和响应内容（最多 `max_bytes()` 字节）
+pub fn register(
+    name: &'static str,
+    matches: fn(&str) -> bool,
+    max_bytes: fn() -> usize,
+    callback: impl Fn(&AppHandle, &str, ResponseBody) + Send + Sync + 'static,
+) {
+    MATCHERS
+        .lock()
+        .expect("capture matchers lock")
+        .push(Arc::new(ResponseMatcher {
+            name,
+            matches,
+            max_bytes,
+            callback: Box::new(callback),
+        }));
+    info!(matcher = name, "[WebViewCapture] matcher registered");
+}
+
+/// 命中 URL 的匹配器
+fn matching(url_lc: &str) -> Vec<Arc<ResponseMatcher>> {
+    MATCHERS
+        .lock()
+        .expect("capture matchers lock")
+        .iter()
+        .filter(|matcher| (matcher.matches)(url_lc))
+        .cloned()
+        .collect()
+}
+
+/// 把读取到的内容分发给各匹配器
+fn dispatch(
+    app: &AppHandle,
+    url: &str,
+    matchers: &[Arc<ResponseMatcher>],
+    body: Result<Vec<u8>, String>,
+) {
+    for matcher in matchers {
+        let body = match &body {
+            Ok(bytes) => {
+                let max_bytes = (matcher.max_bytes)();
+                if bytes.len() > max_bytes {
+                    warn!(
+                        matcher = matcher.name,
+                        max_bytes = max_bytes,
+                        "[WebViewCapture] response truncated"
+                    );
+                }
+                Ok(truncated_text(bytes, max_bytes))
+            }
+            Err(e) => {
+                warn!(
+                    matcher = matcher.name,
+                    error = %e,
+                    "[WebViewCapture] failed to read response"
+                );
+                Err(e.clone())
+            }
+        };
+        (matcher.callback)(app, url, body);
+    }
+}
+
+fn truncated_text(bytes: &[u8], max_bytes: usize) -> String {
+    String::from_utf8_lossy(&bytes[..bytes.len().min(max_bytes)]).to_string()
+}
+
+#[cfg(windows)]
+pub fn attach(webview: tauri::webview::PlatformWebview, app: AppHandle) {
+    use webview2_com::Microsoft::Web::WebView2::Win32::{
+        ICoreWebView2_2, COREWEBVIEW2_WEB_RESOURCE_CONTEXT_ALL,
+    };
+    use webview2_com::{
+        take_pwstr, WebResourceResponseReceivedEventHandler,
+        WebResourceResponseViewGetContentCompletedHandler,
+    };
+    use windows::core::{w, Interface, PWSTR};
+    use windows::Win32::System::Com::IStream;
+
+    let controller = webview.controller();
+    let core = match unsafe { controller.CoreWebView2() } {
+        Ok(core) => core,
+        Err(_) => {
+            warn!("[WebViewCapture] attach failed: CoreWebView2 not available");
+            return;
+        }
+    };
+
+    let _ = unsafe {
+        core.AddWebResourceRequestedFilter(w!("*"), COREWEBVIEW2_WEB_RESOURCE_CONTEXT_ALL)
+    };
+
+    let app_handle = app.clone();
+    let response_handler =
+        WebResourceResponseReceivedEventHandler::create(Box::new(move |_webview, args| {
+            let Some(args) = args else {
+                return Ok(());
+            };
+            let request = unsafe { args.Request() }?;
+            let mut uri_pw = PWSTR::null();
+            unsafe { request.Uri(&mut uri_pw) }?;
+            let url = take_pwstr(uri_pw);
+            let matchers = matching(&url.to_ascii_lowercase());
+            if matchers.is_empty() {
+                return Ok(());
+            }
+            // 多读一个字节，用于判断响应是否被截断
+            let limit = matchers.iter().map(|m| (m.max_bytes)()).max().unwrap_or(0) + 1;
+            let response = unsafe { args.Response() }?;
+            let app_for_content = app_handle.clone();
+            let handler = WebResourceResponseViewGetContentCompletedHandler::create(Box::new(
+                move |result, stream: Option<IStream>| {
+                    let body = match (result, stream) {
+                        (Err(e), _) => Err(format!("GetContent failed: {e}")),
+                        (Ok(()), None) => Err("empty stream".to_string()),
+                        (Ok(()), Some(stream)) => read_stream(&stream, limit)
+                            .ok_or_else(|| "stream read failed".to_string()),
+                    };
+                    dispatch(&app_for_content, &url, &matchers, body);
+                    Ok(())
+                },
+            ));
+            let _ = unsafe { response.GetContent(&handler) };
+            Ok(())
+        }));
+
+    let core2: ICoreWebView2_2 = match core.cast() {
+        Ok(core2) => core2,
+        Err(_) => {
+            warn!("[WebViewCapture] attach failed: ICoreWebView2_2 not available");
+            return;
+        }
+    };
+    let mut token: i64 = 0;
+    let _ = unsafe { core2.add_WebResourceResponseReceived(&response_handler, &mut token) };
+    std::mem::forget(response_handler);
+    info!("[WebViewCapture] WebResourceResponseReceived handler registered");
+}
+
+#[cfg(not(windows))]
+pub fn attach(_webview: tauri::webview::PlatformWebview, _app: AppHandle) {}
+
+#[cfg(windows)]
+fn read_stream(stream: &windows::Win32::System::Com::IStream, limit: usize) -> Option<Vec<u8>> {
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 4096];
+    loop {
+        let mut read = 0u32;
+        let hr = unsafe {
+            stream.Read(
+                chunk.as_mut_ptr() as *mut _,
+                chunk.len() as u32,
+                Some(&mut read),
+            )
+        };
+        if hr.is_err() {
+            return None;
+        }
+        if read == 0 {
+            break;
+        }
+        let remaining = limit.saturating_sub(buf.len());
+        buf.extend_from_slice(&chunk[..(read as usize).min(remaining)]);
+        if buf.len() >= limit {
+            break;
+        }
+    }
+    Some(buf)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn truncates_to_each_matcher_limit() {
+        assert_eq!(truncated_text(b"<xml>abc</xml>", 5), "<xml>");
+        assert_eq!(truncated_text(b"abc", 100), "abc");
+        // 截断点落在多字节字符中间时按有损解码处理
+        assert_eq!(truncated_text("洛克".as_bytes(), 4), "洛\u{FFFD}");
+    }
+}
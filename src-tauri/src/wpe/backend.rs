@@ -0,0 +1,143 @@
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::info;
+
+use crate::wpe::proxy::ProxyBackend;
+use crate::wpe::simulation::SimulationBackend;
+use crate::wpe::windivert::WinDivertHandle;
+use crate::wpe::{CapturedPacket, Direction, WpeError};
+
+/// 服务器断开连接时的回调，参数为原因
+pub type DisconnectListener = Arc<dyn Fn(&str) + Send + Sync>;
+
+/// 封包捕获后端（WinDivert、仿真回放等）
+pub trait CaptureBackend: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    /// 阻塞读取下一条封包；后端关闭后返回 `WpeError::NotRunning`
+    fn recv(&self) -> Result<CapturedPacket, WpeError>;
+
+    /// 向 `direction` 方向发出原始字节：Outbound 发往服务器，Inbound 发往游戏
+    fn send(&self, direction: Direction, data: &[u8]) -> Result<(), WpeError>;
+
+    /// 拦截型后端放行截获的封包，`data` 为原封包或改写后的内容
+    ///
+    /// 默认按原方向 `send`；需要把报文放回原连接的后端（WinDivert）覆盖它。
+    fn forward(&self, captured: &CapturedPacket, data: &[u8]) -> Result<(), WpeError> {
+        self.send(captured.direction, data)
+    }
+
+    /// 拦截型后端丢弃截获的封包；默认不发出即丢弃
+    fn discard(&self, _captured: &CapturedPacket) -> Result<(), WpeError> {
+        Ok(())
+    }
+
+    /// 是否只旁路读取：原封包已经送达，不需要（也无法）由拦截器转发
+    ///
+    /// 拦截型后端返回 false，处理完的封包必须通过 `send` 重新发出，否则相当于被丢弃。
+    /// 旁路后端上修改和丢弃都不会生效，只能额外注入封包。
+    fn passive(&self) -> bool {
+        false
+    }
+
+    /// 能否凭空发出新封包（发送列表、重放、注入）；不持有连接状态的后端返回 false
+    fn can_send(&self) -> bool {
+        true
+    }
+
+    /// 设置断线回调：能识别断线的后端（服务器发来 RST/FIN、代理的上游连接关闭）在断线时调用
+    ///
+    /// 默认忽略，仿真回放和空后端不会报告断线。
+    fn set_disconnect_listener(&self, _listener: DisconnectListener) {}
+
+    fn close(&self);
+}
+
+/// 后端选择
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum BackendSpec {
+    #[default]
+    WinDivert,
+    /// 回放录制的 pcapng 文件，当作实时流量驱动 handler
+    Simulation {
+        capture: PathBuf,
+        #[serde(default)]
+        looped: bool,
+    },
+    /// 本地 TCP 代理：监听 127.0.0.1 并转发到 `upstream`（ip:port），不需要管理员权限
+    Proxy {
+        upstream: String,
+        /// 监听端口，0 表示与 upstream 相同
+        #[serde(default)]
+        listen_port: u16,
+    },
+    /// 空后端：不产生流量，发送只记录日志；真实后端失效后的兜底
+    Mock,
+}
+
+impl BackendSpec {
+    /// 打开后端；`inline` 为 false 时（旁观模式）WinDivert 只以 SNIFF 模式打开
+    pub fn open(&self, pid: u32, inline: bool) -> Result<Arc<dyn CaptureBackend>, WpeError> {
+        match self {
+            BackendSpec::WinDivert => {
+                // 纯净模式下规则和 handler 不会改动流量，不需要拦截
+                let inline = inline
+                    && crate::config::current().capture.windivert_inline
+                    && !crate::wpe::clean_mode();
+                Ok(Arc::new(WinDivertHandle::open(pid, inline)?))
+            }
+            BackendSpec::Simulation { capture, looped } => {
+                Ok(Arc::new(SimulationBackend::open(capture, *looped)?))
+            }
+            BackendSpec::Proxy {
+                upstream,
+                listen_port,
+            } => Ok(Arc::new(ProxyBackend::open(upstream, *listen_port)?)),
+            BackendSpec::Mock => Ok(Arc::new(MockBackend::default())),
+        }
+    }
+
+    /// 当前后端失效时切换到的备用后端
+    ///
+    /// 仿真回放是用户显式选择的，失效时不自动替换。
+    pub fn fallback(&self) -> Option<BackendSpec> {
+        match self {
+            BackendSpec::WinDivert | BackendSpec::Proxy { .. } => Some(BackendSpec::Mock),
+            BackendSpec::Simulation { .. } | BackendSpec::Mock => None,
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct MockBackend {
+    closed: AtomicBool,
+}
+
+impl CaptureBackend for MockBackend {
+    fn name(&self) -> &'static str {
+        "mock"
+    }
+
+    fn recv(&self) -> Result<CapturedPacket, WpeError> {
+        while !self.closed.load(Ordering::Relaxed) {
+            std::thread::sleep(Duration::from_millis(100));
+        }
+        Err(WpeError::NotRunning)
+    }
+
+    fn send(&self, direction: Direction, data: &[u8]) -> Result<(), WpeError> {
+        info!(
+            "[WPE] Mock send: {} bytes {:?} (not sent)",
+            data.len(),
+            direction
+        );
+        Ok(())
+    }
+
+    fn close(&self) {
+        self.closed.store(true, Ordering::Relaxed);
+    }
+}
@@ -0,0 +1,37 @@
+// 已知命令号登记表
+//
+// 来源于 docs/wpe.md 中整理的封包格式，用于导入/校验封包时给出命令名称和长度提示。
+
+#[derive(Debug, Clone, Copy, serde::Serialize)]
+pub struct CommandInfo {
+    pub id: u16,
+    pub name: &'static str,
+    /// payload（16 字节头之后）的预期长度，变长命令为 None
+    pub payload_len: Option<usize>,
+}
+
+/// 整理本表时所用客户端的版本指纹（见 client_version），None 表示未绑定版本；
+/// 配置 capture.catalog_client 可以记录实际核对过的客户端，优先于这里的值
+pub const CATALOG_CLIENT: Option<&str> = None;
+
+pub const KNOWN_COMMANDS: &[CommandInfo] = &[
+    CommandInfo {
+        id: 0x0003,
+        name: "map_jump",
+        payload_len: Some(11),
+    },
+    CommandInfo {
+        id: 0x0014,
+        name: "pet_storage",
+        payload_len: Some(10),
+    },
+    CommandInfo {
+        id: 0x0052,
+        name: "home_training",
+        payload_len: Some(10),
+    },
+];
+
+pub fn lookup(id: u16) -> Option<&'static CommandInfo> {
+    KNOWN_COMMANDS.iter().find(|info| info.id == id)
+}
@@ -0,0 +1,148 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+
+use serde_json::{json, Value};
+
+use crate::wpe::commands;
+use crate::wpe::GamePacket;
+
+// 封包协议解码
+//
+// 按命令号登记解码器，把二进制封包的 payload 解析成结构化字段，供 debug 窗口等处显示。
+// 内置解码器覆盖 docs/wpe.md 中整理的命令；文本封包（如战斗中的 System_ 指令）单独处理。
+
+/// 单个命令的 payload 解码器
+pub trait ProtocolDecoder: Send + Sync {
+    /// 解析 payload（16 字节头之后），格式不符时返回 None
+    fn decode(&self, payload: &[u8]) -> Option<Value>;
+}
+
+impl<F> ProtocolDecoder for F
+where
+    F: Fn(&[u8]) -> Option<Value> + Send + Sync,
+{
+    fn decode(&self, payload: &[u8]) -> Option<Value> {
+        self(payload)
+    }
+}
+
+/// 解码结果
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct DecodedPacket {
+    /// 二进制封包的命令号，文本封包为 None
+    pub command: Option<u16>,
+    /// 已知命令的名称
+    pub name: Option<&'static str>,
+    pub qq_num: Option<u64>,
+    /// 结构化字段；没有对应解码器或解码失败时为 None
+    pub fields: Option<Value>,
+}
+
+static DECODERS: OnceLock<Mutex<HashMap<u16, Arc<dyn ProtocolDecoder>>>> = OnceLock::new();
+
+fn decoders() -> &'static Mutex<HashMap<u16, Arc<dyn ProtocolDecoder>>> {
+    DECODERS.get_or_init(|| Mutex::new(builtin()))
+}
+
+fn builtin() -> HashMap<u16, Arc<dyn ProtocolDecoder>> {
+    let mut map: HashMap<u16, Arc<dyn ProtocolDecoder>> = HashMap::new();
+    map.insert(0x0003, Arc::new(decode_map_jump));
+    map.insert(0x0014, Arc::new(decode_spirit_pos));
+    map.insert(0x0052, Arc::new(decode_spirit_pos));
+    map
+}
+
+/// 跳转地图：payload[9..11] 为地图编号（小端）
+fn decode_map_jump(payload: &[u8]) -> Option<Value> {
+    let bytes = payload.get(9..11)?;
+    Some(json!({ "map_no": u16::from_le_bytes([bytes[0], bytes[1]]) }))
+}
+
+/// 宠物放入仓库 / 家园锻炼：payload[9] 为宠物在背包中的位置（1~6）
+fn decode_spirit_pos(payload: &[u8]) -> Option<Value> {
+    let spirit_pos = *payload.get(9)?;
+    (1..=6)
+        .contains(&spirit_pos)
+        .then(|| json!({ "spirit_pos": spirit_pos }))
+}
+
+/// 文本封包：`System_` 前缀的是战斗等系统指令
+fn decode_text(text: &str) -> Option<Value> {
+    let message = text.strip_prefix("System_")?;
+    Some(match message {
+        "宠物逃跑" => json!({ "kind": "battle", "action": "escape" }),
+        _ => json!({ "kind": "system", "message": message }),
+    })
+}
+
+/// 登记（或替换）某个命令号的解码器
+pub fn register(command: u16, decoder: Arc<dyn ProtocolDecoder>) {
+    decoders()
+        .lock()
+        .expect("decoders lock")
+        .insert(command, decoder);
+}
+
+pub fn decode(packet: &GamePacket) -> DecodedPacket {
+    match packet {
+        GamePacket::Binary {
+            command,
+            qq_num,
+            data,
+            ..
+        } => {
+            let decoder = decoders()
+                .lock()
+                .expect("decoders lock")
+                .get(command)
+                .cloned();
+            DecodedPacket {
+                command: Some(*command),
+                name: commands::lookup(*command).map(|info| info.name),
+                qq_num: Some(*qq_num),
+                fields: decoder.and_then(|decoder| decoder.decode(data)),
+            }
+        }
+        GamePacket::Text(text) => DecodedPacket {
+            command: None,
+            name: None,
+            qq_num: None,
+            fields: decode_text(text),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_builtin_commands() {
+        let jump = decode(&GamePacket::build_map_jump(10001, 0x0123));
+        assert_eq!(jump.name, Some("map_jump"));
+        assert_eq!(jump.qq_num, Some(10001));
+        assert_eq!(jump.fields.unwrap()["map_no"], 0x0123);
+
+        let storage = decode(&GamePacket::build_pet_storage(10001, 3));
+        assert_eq!(storage.fields.unwrap()["spirit_pos"], 3);
+
+        let escape = decode(&GamePacket::build_pet_escape());
+        assert_eq!(escape.command, None);
+        assert_eq!(escape.fields.unwrap()["action"], "escape");
+
+        // 自定义解码器：未知命令没有名称，字段由登记的解码器给出
+        register(
+            0x7FFF,
+            Arc::new(|payload: &[u8]| Some(json!({ "len": payload.len() }))),
+        );
+        let custom = decode(&GamePacket::Binary {
+            magic: 0x9527,
+            length: 0,
+            command: 0x7FFF,
+            qq_num: 1,
+            data: vec![0; 4],
+        });
+        assert_eq!(custom.name, None);
+        assert_eq!(custom.fields.unwrap()["len"], 4);
+    }
+}
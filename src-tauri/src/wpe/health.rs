@@ -0,0 +1,110 @@
+use std::time::{Duration, Instant};
+
+// 捕获后端健康检查
+//
+// 两种情况判定后端失效：
+// 1. 已经收到过封包（说明游戏在线、有流量），之后超过 stall_after 没有新封包
+//    游戏客户端有心跳包，在线期间长时间无流量只可能是捕获失效
+// 2. 连续 recv 出错超过 max_errors 次
+
+/// 无封包多久判定为卡死
+pub const DEFAULT_STALL_AFTER: Duration = Duration::from_secs(30);
+/// 连续出错多少次判定为失效
+pub const DEFAULT_MAX_ERRORS: u32 = 10;
+
+#[derive(Debug)]
+pub struct BackendHealth {
+    stall_after: Duration,
+    max_errors: u32,
+    last_packet: Option<Instant>,
+    consecutive_errors: u32,
+}
+
+impl Default for BackendHealth {
+    fn default() -> Self {
+        Self::new(DEFAULT_STALL_AFTER, DEFAULT_MAX_ERRORS)
+    }
+}
+
+impl BackendHealth {
+    pub fn new(stall_after: Duration, max_errors: u32) -> Self {
+        Self {
+            stall_after,
+            max_errors,
+            last_packet: None,
+            consecutive_errors: 0,
+        }
+    }
+
+    pub fn record_packet(&mut self, now: Instant) {
+        self.last_packet = Some(now);
+        self.consecutive_errors = 0;
+    }
+
+    /// 记录一次错误，返回是否已达到失效阈值
+    pub fn record_error(&mut self) -> bool {
+        self.consecutive_errors += 1;
+        self.consecutive_errors >= self.max_errors
+    }
+
+    /// 期待有流量（收到过封包）但已超时无新封包
+    pub fn is_stalled(&self, now: Instant) -> bool {
+        self.last_packet
+            .is_some_and(|last| now.duration_since(last) >= self.stall_after)
+    }
+
+    /// 切换后端后重新计时
+    pub fn reset(&mut self) {
+        self.last_packet = None;
+        self.consecutive_errors = 0;
+    }
+}
+
+/// 后端运行状态
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BackendState {
+    /// 正在打开
+    Starting,
+    Active,
+    /// 原后端失效，已切换到备用后端
+    FailedOver,
+    /// 没有可用的备用后端，封包功能已停止
+    Failed,
+    Stopped,
+}
+
+/// 后端状态事件（前端 `wpe_backend_status`）
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct BackendStatus {
+    pub backend: &'static str,
+    pub state: BackendState,
+    pub reason: Option<String>,
+    /// 当前能否发送封包（发送列表、重放）；WinDivert、旁观模式、纯净模式下为 false
+    pub can_send: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_stall_and_error_bursts() {
+        let mut health = BackendHealth::new(Duration::from_secs(30), 3);
+        let start = Instant::now();
+
+        // 还没有流量时不判定卡死
+        assert!(!health.is_stalled(start + Duration::from_secs(120)));
+
+        health.record_packet(start);
+        assert!(!health.is_stalled(start + Duration::from_secs(29)));
+        assert!(health.is_stalled(start + Duration::from_secs(30)));
+
+        assert!(!health.record_error());
+        assert!(!health.record_error());
+        health.record_packet(start);
+        assert!(!health.record_error());
+        assert!(!health.record_error());
+        assert!(health.record_error());
+    }
+}
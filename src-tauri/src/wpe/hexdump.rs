@@ -0,0 +1,267 @@
+use crate::wpe::decoder::{self, DecodedPacket};
+use crate::wpe::{commands, GamePacket, WpeError};
+
+// 解析从剪贴板粘贴的十六进制封包
+//
+// 支持以下写法（可混用）：
+// - 空格分隔：95 27 00 00 0B 00
+// - 连续十六进制：9527000000 0B00
+// - C 风格：0x95, 0x27, 0x00
+// - 经典 WPE 转储：每行以偏移开头（0000 或 0000:），行尾附带 ASCII 列
+//
+// 模板中可以用 {qq} 代替当前账号，展开为 8 字节小端 QQ 号（与封包头中的 qq_num 字段格式相同）。
+
+fn is_hex(token: &str) -> bool {
+    !token.is_empty() && token.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+fn is_offset(token: &str) -> bool {
+    let digits = token.trim_end_matches(':');
+    (token.ends_with(':') || digits.len() == 4 || digits.len() == 8) && is_hex(digits)
+}
+
+fn parse_line(line: &str, out: &mut Vec<u8>) -> Result<(), WpeError> {
+    // 经典转储中偏移、字节列、ASCII 列之间以两个以上空格分隔
+    let segments: Vec<&str> = line
+        .split("  ")
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .collect();
+
+    let line_start = out.len();
+    for (seg_index, segment) in segments.iter().enumerate() {
+        let tokens: Vec<&str> = segment
+            .split(|c: char| c.is_whitespace() || c == ',')
+            .filter(|t| !t.is_empty())
+            .collect();
+
+        for (index, raw) in tokens.iter().enumerate() {
+            let line_head = seg_index == 0 && index == 0;
+            let followed_by_bytes = tokens.get(index + 1).is_some_and(|t| t.len() == 2)
+                || (tokens.len() == 1 && segments.len() > 1);
+            if (raw.ends_with(':') && is_hex(raw.trim_end_matches(':')))
+                || (line_head && followed_by_bytes && is_offset(raw))
+            {
+                continue;
+            }
+
+            let token = raw
+                .strip_prefix("0x")
+                .or_else(|| raw.strip_prefix("0X"))
+                .unwrap_or(raw);
+            if !is_hex(token) {
+                if out.len() > line_start {
+                    // 字节列之后的内容视为 ASCII 列
+                    return Ok(());
+                }
+                return Err(WpeError::PacketParse(format!("Invalid hex token: {raw}")));
+            }
+            if token.len() % 2 != 0 {
+                return Err(WpeError::PacketParse(format!(
+                    "Odd number of hex digits: {raw}"
+                )));
+            }
+            for i in (0..token.len()).step_by(2) {
+                let byte = u8::from_str_radix(&token[i..i + 2], 16)
+                    .map_err(|_| WpeError::PacketParse(format!("Invalid hex token: {raw}")))?;
+                out.push(byte);
+            }
+        }
+
+        // 字节列所在的段处理完后，其余段（ASCII 列）忽略
+        if out.len() > line_start {
+            return Ok(());
+        }
+    }
+    Ok(())
+}
+
+/// 把粘贴的文本解析为原始字节
+pub fn parse_hex(text: &str) -> Result<Vec<u8>, WpeError> {
+    let mut out = Vec::new();
+    for line in text.lines() {
+        parse_line(line, &mut out)?;
+    }
+    if out.is_empty() {
+        return Err(WpeError::PacketParse("No hex bytes found".to_string()));
+    }
+    Ok(out)
+}
+
+/// 当前账号占位符
+pub const QQ_PLACEHOLDER: &str = "{qq}";
+
+/// 把模板中的 {qq} 展开为账号的十六进制字节，模板使用了占位符但没有已登录账号时返回错误
+pub fn expand_placeholders(text: &str, account: Option<u64>) -> Result<String, WpeError> {
+    if !text.contains(QQ_PLACEHOLDER) {
+        return Ok(text.to_string());
+    }
+    let account = account.ok_or_else(|| {
+        WpeError::PacketBuild(format!(
+            "Template uses {QQ_PLACEHOLDER} but no account is logged in"
+        ))
+    })?;
+    // 连续写出，不改变模板原有的空白（经典转储中两个空格用于分隔 ASCII 列）
+    let hex: String = account
+        .to_le_bytes()
+        .iter()
+        .map(|b| format!("{b:02X}"))
+        .collect();
+    Ok(text.replace(QQ_PLACEHOLDER, &hex))
+}
+
+/// 十六进制显示（空格分隔，大写）
+pub fn format_hex(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|b| format!("{b:02X}"))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// 模板校验结果：展开占位符后将要发送的确切字节
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct PacketPreview {
+    pub hex: String,
+    pub length: usize,
+    pub decoded: DecodedPacket,
+    pub warnings: Vec<String>,
+}
+
+/// 校验模板（不发送）：展开占位符，按命令登记表检查结构，返回最终字节
+pub fn preview(text: &str, account: Option<u64>) -> Result<PacketPreview, WpeError> {
+    let imported = import(&expand_placeholders(text, account)?)?;
+    let mut warnings = imported.warnings;
+    if let (GamePacket::Binary { qq_num, .. }, Some(account)) = (&imported.packet, account) {
+        if *qq_num != account {
+            warnings.push(format!(
+                "Packet qq_num is {qq_num} but the current account is {account}"
+            ));
+        }
+    }
+    Ok(PacketPreview {
+        hex: format_hex(&imported.bytes),
+        length: imported.bytes.len(),
+        decoded: decoder::decode(&imported.packet),
+        warnings,
+    })
+}
+
+/// 导入结果：解析出的封包及校验提示
+#[derive(Debug, Clone)]
+pub struct ImportedPacket {
+    pub packet: GamePacket,
+    pub bytes: Vec<u8>,
+    pub command_name: Option<&'static str>,
+    pub warnings: Vec<String>,
+}
+
+/// 解析并按命令登记表校验（校验问题作为提示返回，不阻止导入）
+pub fn import(text: &str) -> Result<ImportedPacket, WpeError> {
+    let bytes = parse_hex(text)?;
+    let packet = GamePacket::parse(&bytes)?;
+    let mut warnings = Vec::new();
+    let mut command_name = None;
+
+    match &packet {
+        GamePacket::Binary {
+            length,
+            command,
+            data,
+            ..
+        } => {
+            if *length as usize != data.len() {
+                warnings.push(format!(
+                    "Length field is {} but payload has {} bytes",
+                    length,
+                    data.len()
+                ));
+            }
+            match commands::lookup(*command) {
+                Some(info) => {
+                    command_name = Some(info.name);
+                    if let Some(expected) = info.payload_len.filter(|len| *len != data.len()) {
+                        warnings.push(format!(
+                            "Command {} expects {} payload bytes, got {}",
+                            info.name,
+                            expected,
+                            data.len()
+                        ));
+                    }
+                }
+                None => warnings.push(format!("Unknown command 0x{command:04X}")),
+            }
+        }
+        GamePacket::Text(_) => {
+            if bytes.starts_with(&[0x95, 0x27]) {
+                warnings.push(
+                    "Header bytes are 95 27 (big-endian); binary packets start with 27 95"
+                        .to_string(),
+                );
+            }
+        }
+    }
+
+    Ok(ImportedPacket {
+        packet,
+        bytes,
+        command_name,
+        warnings,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_common_formats() {
+        assert_eq!(parse_hex("27 95 0b").unwrap(), vec![0x27, 0x95, 0x0B]);
+        assert_eq!(
+            parse_hex("2795 0B00").unwrap(),
+            vec![0x27, 0x95, 0x0B, 0x00]
+        );
+        assert_eq!(
+            parse_hex("0x27, 0x95,0x0b").unwrap(),
+            vec![0x27, 0x95, 0x0B]
+        );
+        assert!(parse_hex("27 9").is_err());
+        assert!(parse_hex("zz").is_err());
+    }
+
+    #[test]
+    fn skips_offsets_and_ascii_column() {
+        let dump = "0000  53 79 73 74 65 6D 5F  System_\n0007: 41 42  AB";
+        assert_eq!(parse_hex(dump).unwrap(), b"System_AB".to_vec());
+    }
+
+    #[test]
+    fn validates_against_registry() {
+        let packet = GamePacket::build_map_jump(10001, 5);
+        let hex: String = packet
+            .build()
+            .unwrap()
+            .iter()
+            .map(|b| format!("{b:02x} "))
+            .collect();
+        let imported = import(&hex).unwrap();
+        assert_eq!(imported.command_name, Some("map_jump"));
+        assert!(imported.warnings.is_empty(), "{:?}", imported.warnings);
+    }
+
+    #[test]
+    fn previews_templates_with_account_placeholder() {
+        let bytes = GamePacket::build_map_jump(10001, 5).build().unwrap();
+        let template = format!(
+            "{} {{qq}} {}",
+            format_hex(&bytes[..8]),
+            format_hex(&bytes[16..])
+        );
+
+        assert!(preview(&template, None).is_err());
+        let shown = preview(&template, Some(10001)).unwrap();
+        assert_eq!(shown.hex, format_hex(&bytes));
+        assert_eq!(shown.decoded.qq_num, Some(10001));
+        assert!(shown.warnings.is_empty(), "{:?}", shown.warnings);
+    }
+}
@@ -0,0 +1,224 @@
+use std::collections::VecDeque;
+
+use crate::wpe::rules::RuleSet;
+use crate::wpe::{CapturedPacket, Direction, GamePacket, WpeError};
+
+// 十六进制查看器的数据服务
+//
+// 拦截器按捕获顺序给封包编号（index 在一次捕获会话内单调递增，不随旧封包淘汰而变化），
+// 前端按 (session, index, offset, len) 分页读取字节，并附带头部字段和命中规则的字节范围标注，
+// 大封包不必在每个事件里整段发送。
+
+/// 单次最多读取的字节数
+pub const MAX_PAGE_BYTES: usize = 4096;
+
+/// 二进制封包头部长度
+const HEADER_LEN: usize = 16;
+
+/// 最近截获的封包，带会话内序号
+#[derive(Debug)]
+pub struct PacketHistory {
+    capacity: usize,
+    /// packets 第一条的序号
+    first_index: u64,
+    packets: VecDeque<CapturedPacket>,
+}
+
+impl PacketHistory {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            first_index: 0,
+            packets: VecDeque::new(),
+        }
+    }
+
+    pub fn push(&mut self, packet: CapturedPacket) {
+        if self.packets.len() >= self.capacity {
+            self.packets.pop_front();
+            self.first_index += 1;
+        }
+        self.packets.push_back(packet);
+    }
+
+    pub fn get(&self, index: u64) -> Option<&CapturedPacket> {
+        let offset = index.checked_sub(self.first_index)?;
+        self.packets.get(usize::try_from(offset).ok()?)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &CapturedPacket> {
+        self.packets.iter()
+    }
+
+    /// 仍可读取的序号范围 [first, next)
+    pub fn range(&self) -> (u64, u64) {
+        (
+            self.first_index,
+            self.first_index + self.packets.len() as u64,
+        )
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AnnotationKind {
+    /// 二进制封包头字段
+    Header,
+    /// 头部之后的 payload
+    Payload,
+    /// 封包规则的 bytes 条件命中的范围
+    Rule,
+}
+
+/// 字节范围标注，范围为整个封包内的 [start, end)
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize)]
+pub struct Annotation {
+    pub start: usize,
+    pub end: usize,
+    pub kind: AnnotationKind,
+    pub label: String,
+}
+
+/// `get_packet_bytes` 的返回值
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct PacketBytes {
+    pub session: u64,
+    pub index: u64,
+    /// Unix 时间戳（微秒）
+    pub timestamp_us: u64,
+    pub direction: Direction,
+    /// 封包总长度
+    pub total_len: usize,
+    pub offset: usize,
+    /// 本页字节的十六进制（空格分隔）
+    pub hex: String,
+    /// 与本页有重叠的标注
+    pub annotations: Vec<Annotation>,
+}
+
+/// `get_packet_history` 的返回值：当前会话可读取的序号范围
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct HistoryRange {
+    pub session: u64,
+    pub first_index: u64,
+    pub next_index: u64,
+}
+
+fn annotation(start: usize, end: usize, kind: AnnotationKind, label: &str) -> Annotation {
+    Annotation {
+        start,
+        end,
+        kind,
+        label: label.to_string(),
+    }
+}
+
+/// 整个封包的标注：头部字段、payload，以及命中规则的 bytes 条件
+pub fn annotate(data: &[u8], direction: Direction, rules: &RuleSet) -> Vec<Annotation> {
+    let mut annotations = Vec::new();
+    let payload_start = match GamePacket::parse(data) {
+        Ok(ref packet @ GamePacket::Binary { .. }) => {
+            for (start, end, label) in [
+                (0, 2, "magic"),
+                (2, 6, "length"),
+                (6, 8, "command"),
+                (8, HEADER_LEN, "qq_num"),
+            ] {
+                annotations.push(annotation(start, end, AnnotationKind::Header, label));
+            }
+            if data.len() > HEADER_LEN {
+                annotations.push(annotation(
+                    HEADER_LEN,
+                    data.len(),
+                    AnnotationKind::Payload,
+                    "payload",
+                ));
+            }
+            Some((packet.clone(), HEADER_LEN))
+        }
+        Ok(packet) => Some((packet, 0)),
+        Err(_) => None,
+    };
+
+    if let Some((packet, start)) = payload_start {
+        for rule in rules
+            .rules()
+            .iter()
+            .filter(|rule| rule.matches(&packet, direction))
+        {
+            for pattern in &rule.bytes {
+                let begin = start + pattern.offset;
+                annotations.push(annotation(
+                    begin,
+                    begin + pattern.value.len(),
+                    AnnotationKind::Rule,
+                    &rule.name,
+                ));
+            }
+        }
+    }
+    annotations
+}
+
+/// 读取一页字节；offset 超出封包长度时返回错误
+pub fn page(
+    packet: &CapturedPacket,
+    rules: &RuleSet,
+    offset: usize,
+    len: usize,
+) -> Result<(String, Vec<Annotation>), WpeError> {
+    let total = packet.data.len();
+    if offset > total {
+        return Err(WpeError::PacketParse(format!(
+            "Offset {offset} is beyond packet length {total}"
+        )));
+    }
+    let end = offset + len.min(MAX_PAGE_BYTES).min(total - offset);
+    let hex = packet.data[offset..end]
+        .iter()
+        .map(|b| format!("{b:02X}"))
+        .collect::<Vec<_>>()
+        .join(" ");
+    let annotations = annotate(&packet.data, packet.direction, rules)
+        .into_iter()
+        .filter(|a| a.start < end && a.end > offset)
+        .collect();
+    Ok((hex, annotations))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pages_bytes_with_header_and_rule_annotations() {
+        let mut history = PacketHistory::new(2);
+        for map_no in [1u16, 5, 7] {
+            let data = GamePacket::build_map_jump(10001, map_no).build().unwrap();
+            history.push(CapturedPacket::now(Direction::Outbound, data));
+        }
+        assert_eq!(history.range(), (1, 3));
+        assert!(history.get(0).is_none());
+
+        let rules = RuleSet::from_json(
+            r#"[{ "name": "map5", "command": 3, "bytes": [{ "offset": 9, "value": "05 00" }],
+                  "action": { "kind": "drop" } }]"#,
+        )
+        .unwrap();
+        let packet = history.get(1).unwrap();
+
+        let (hex, annotations) = page(packet, &rules, 0, 4).unwrap();
+        assert_eq!(hex, "27 95 0B 00");
+        let labels: Vec<&str> = annotations.iter().map(|a| a.label.as_str()).collect();
+        assert_eq!(labels, ["magic", "length"]);
+
+        let (hex, annotations) = page(packet, &rules, 24, 100).unwrap();
+        assert_eq!(hex, "00 05 00");
+        assert!(annotations.contains(&annotation(25, 27, AnnotationKind::Rule, "map5")));
+
+        // 其他地图的跳转不命中规则
+        let (_, annotations) = page(history.get(2).unwrap(), &rules, 0, 64).unwrap();
+        assert!(annotations.iter().all(|a| a.kind != AnnotationKind::Rule));
+        assert!(page(packet, &rules, 100, 1).is_err());
+    }
+}
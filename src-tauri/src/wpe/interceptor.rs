@@ -1,97 +1,703 @@
-use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::{Arc, Mutex};
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::mpsc::Receiver;
+use std::sync::{Arc, Mutex, Weak};
 use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use tracing::{error, info, warn};
 
-use crate::wpe::windivert::WinDivertHandle;
-use crate::wpe::{GamePacket, PacketAction, PacketHandler, WpeError};
+use crate::wpe::health::BackendHealth;
+use crate::wpe::hexview::{self, HistoryRange, PacketBytes, PacketHistory};
+use crate::wpe::pcapng::{self, PcapngWriter};
+use crate::wpe::pipeline::{Job, Pipeline, PipelineCounters, QUEUE_CAPACITY};
+use crate::wpe::rules::RuleSet;
+use crate::wpe::stats::{PacketStats, WpeStats};
+use crate::wpe::{
+    BackendSpec, BackendState, BackendStatus, BuildMode, CaptureBackend, CapturedPacket, Direction,
+    DisconnectListener, GamePacket, PacketAction, PacketHandler, WpeError,
+};
+
+/// 后端状态变化回调
+pub type StatusListener = Arc<dyn Fn(&BackendStatus) + Send + Sync>;
+
+/// 注册 handler 时分配的 ID，用于注销
+pub type HandlerId = u64;
+
+#[derive(Clone)]
+struct RegisteredHandler {
+    id: HandlerId,
+    /// 数值大的先处理，相同优先级按注册顺序
+    priority: i32,
+    /// 只处理该方向的封包，None 表示两个方向都处理
+    direction: Option<Direction>,
+    handler: Arc<dyn PacketHandler>,
+}
+
+/// 看门狗检查间隔
+const WATCHDOG_INTERVAL: Duration = Duration::from_secs(1);
+
+/// 内存中保留的最近封包数量（用于导出 pcapng）
+const HISTORY_SIZE: usize = 10_000;
+
+static NEXT_SESSION: AtomicU64 = AtomicU64::new(1);
 
 pub struct PacketInterceptor {
     pid: u32,
+    spec: BackendSpec,
     running: Arc<AtomicBool>,
-    handlers: Arc<Mutex<Vec<Arc<dyn PacketHandler>>>>,
+    /// 按优先级排好序的 handler；注册、注销时整体替换，处理封包时只克隆 Arc
+    handlers: Mutex<Arc<Vec<RegisteredHandler>>>,
+    next_handler_id: AtomicU64,
+    backend: Mutex<Option<Arc<dyn CaptureBackend>>>,
+    health: Mutex<BackendHealth>,
+    /// 看门狗判定卡死后置位，run 循环据此区分"被停止"和"需要切换后端"
+    stalled: AtomicBool,
+    status: Mutex<BackendStatus>,
+    status_listener: Mutex<Option<StatusListener>>,
+    /// 断线回调，切换后端时转交给新后端
+    disconnect_listener: Arc<Mutex<Option<DisconnectListener>>>,
+    /// 录制中时把每条封包（含时间戳和方向）写入 pcapng
+    recorder: Mutex<Option<PcapngWriter<BufWriter<File>>>>,
+    /// 捕获会话 ID（每个拦截器一个），十六进制查看器据此判断序号是否仍然有效
+    session: u64,
+    /// 最近截获的封包，导出 pcapng 和十六进制查看器使用
+    history: Mutex<PacketHistory>,
+    /// 按命令号和方向的流量统计
+    stats: Mutex<PacketStats>,
+    /// 处理流水线计数
+    pipeline: Arc<PipelineCounters>,
+    /// 声明式规则，在 handler 之前匹配（可热更新）
+    rules: Mutex<Arc<RuleSet>>,
+    /// 旁观模式：只捕获，不发送、不修改、不丢弃（创建后不可更改）
+    read_only: bool,
+}
+
+/// 单个后端的运行结果
+enum BackendOutcome {
+    Stopped,
+    Failed(String),
+}
+
+/// 规则与 handler 处理后对一条封包的最终处理
+#[derive(Debug)]
+struct Verdict {
+    /// 原封包如何发出；None 表示丢弃
+    forward: Option<Forward>,
+    /// 原封包之后按顺序注入的封包
+    inject: Vec<GamePacket>,
+}
+
+#[derive(Debug)]
+enum Forward {
+    Original,
+    Modified(Vec<u8>),
 }
 
 impl PacketInterceptor {
     pub fn new(pid: u32) -> Result<Arc<Self>, WpeError> {
-        info!("[WPE] Creating packet interceptor for PID {}", pid);
+        Self::with_backend(pid, BackendSpec::WinDivert)
+    }
+
+    pub fn with_backend(pid: u32, spec: BackendSpec) -> Result<Arc<Self>, WpeError> {
+        Self::create(pid, spec, false)
+    }
+
+    /// 旁观模式：以 SNIFF 模式的 WinDivert 只读捕获指定进程
+    pub fn spectator(pid: u32) -> Result<Arc<Self>, WpeError> {
+        Self::create(pid, BackendSpec::WinDivert, true)
+    }
+
+    fn create(pid: u32, spec: BackendSpec, read_only: bool) -> Result<Arc<Self>, WpeError> {
+        info!(
+            "[WPE] Creating packet interceptor for PID {} ({:?}, read_only={})",
+            pid, spec, read_only
+        );
 
         let interceptor = Arc::new(Self {
             pid,
+            spec,
             running: Arc::new(AtomicBool::new(true)),
-            handlers: Arc::new(Mutex::new(Vec::new())),
+            handlers: Mutex::new(Arc::new(Vec::new())),
+            next_handler_id: AtomicU64::new(1),
+            backend: Mutex::new(None),
+            health: Mutex::new(BackendHealth::default()),
+            stalled: AtomicBool::new(false),
+            status: Mutex::new(BackendStatus {
+                backend: "none",
+                state: BackendState::Starting,
+                reason: None,
+                can_send: false,
+            }),
+            status_listener: Mutex::new(None),
+            disconnect_listener: Arc::new(Mutex::new(None)),
+            recorder: Mutex::new(None),
+            session: NEXT_SESSION.fetch_add(1, Ordering::Relaxed),
+            history: Mutex::new(PacketHistory::new(HISTORY_SIZE)),
+            stats: Mutex::new(PacketStats::default()),
+            pipeline: Arc::new(PipelineCounters::default()),
+            rules: Mutex::new(Arc::new(RuleSet::default())),
+            read_only,
         });
 
+        // 每个方向一个工作线程，捕获线程结束后随之退出
+        let (pipeline, receivers) = Pipeline::new(QUEUE_CAPACITY, interceptor.pipeline.clone());
+        for (direction, jobs) in receivers {
+            let worker = Arc::downgrade(&interceptor);
+            thread::spawn(move || Self::worker(worker, direction, jobs));
+        }
+
         let interceptor_clone = interceptor.clone();
         thread::spawn(move || {
-            if let Err(e) = interceptor_clone.run() {
+            if let Err(e) = interceptor_clone.run(&pipeline) {
                 error!("[WPE] Interceptor thread error: {}", e);
             }
         });
 
+        let watchdog = Arc::downgrade(&interceptor);
+        thread::spawn(move || Self::watchdog(watchdog));
+
         Ok(interceptor)
     }
 
-    pub fn register_handler(&self, handler: Arc<dyn PacketHandler>) {
-        let mut handlers = self.handlers.lock().expect("handlers lock");
-        handlers.push(handler);
-        info!("[WPE] Registered packet handler");
+    /// 以默认优先级注册处理两个方向的 handler
+    pub fn register_handler(&self, handler: Arc<dyn PacketHandler>) -> HandlerId {
+        self.register_handler_with(handler, 0, None)
+    }
+
+    /// 注册 handler：`priority` 大的先处理，`direction` 为 Some 时只接收该方向的封包
+    pub fn register_handler_with(
+        &self,
+        handler: Arc<dyn PacketHandler>,
+        priority: i32,
+        direction: Option<Direction>,
+    ) -> HandlerId {
+        let id = self.next_handler_id.fetch_add(1, Ordering::Relaxed);
+        let mut guard = self.handlers.lock().expect("handlers lock");
+        let mut handlers = Vec::clone(&guard);
+        let index = handlers.partition_point(|h| h.priority >= priority);
+        handlers.insert(
+            index,
+            RegisteredHandler {
+                id,
+                priority,
+                direction,
+                handler,
+            },
+        );
+        *guard = Arc::new(handlers);
+        drop(guard);
+        info!(
+            "[WPE] Registered packet handler {} (priority {}, {:?})",
+            id, priority, direction
+        );
+        id
+    }
+
+    /// 注销 handler，返回是否找到
+    pub fn unregister_handler(&self, id: HandlerId) -> bool {
+        let mut guard = self.handlers.lock().expect("handlers lock");
+        let mut handlers = Vec::clone(&guard);
+        let before = handlers.len();
+        handlers.retain(|h| h.id != id);
+        let removed = handlers.len() != before;
+        *guard = Arc::new(handlers);
+        drop(guard);
+        if removed {
+            info!("[WPE] Unregistered packet handler {}", id);
+        }
+        removed
+    }
+
+    /// 替换规则集（规则文件热更新时调用）
+    pub fn set_rules(&self, rules: RuleSet) {
+        *self.rules.lock().expect("rules lock") = Arc::new(rules);
+    }
+
+    pub fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+
+    /// 只允许观察封包：旁观模式或纯净模式
+    fn observe_only(&self) -> bool {
+        self.read_only || crate::wpe::clean_mode()
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.running.load(Ordering::Relaxed)
+    }
+
+    /// 订阅后端状态变化；注册时立即回调一次当前状态（打开失败可能发生在注册之前）
+    pub fn set_status_listener(&self, listener: StatusListener) {
+        let current = self.status();
+        *self.status_listener.lock().expect("status listener lock") = Some(listener.clone());
+        listener(&current);
+    }
+
+    /// 设置断线回调（服务器断开游戏连接时调用，可能来自捕获线程或后端的转发线程）
+    pub fn set_disconnect_listener(&self, listener: DisconnectListener) {
+        *self
+            .disconnect_listener
+            .lock()
+            .expect("disconnect listener lock") = Some(listener);
+    }
+
+    pub fn status(&self) -> BackendStatus {
+        let mut status = self.status.lock().expect("status lock").clone();
+        // 纯净模式可以随时切换，查询时重新判断
+        status.can_send = self.check_send().is_ok();
+        status
+    }
+
+    /// 开始录制到指定文件（已在录制时先结束旧文件）
+    pub fn start_recording(&self, path: &Path) -> Result<(), WpeError> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let writer = PcapngWriter::new(BufWriter::new(File::create(path)?))?;
+        let previous = self.recorder.lock().expect("recorder lock").replace(writer);
+        if let Some(previous) = previous {
+            previous.finish()?;
+        }
+        info!("[WPE] Recording packets to {}", path.display());
+        Ok(())
+    }
+
+    /// 结束录制并落盘，返回录制的封包数；未在录制时返回 None
+    pub fn stop_recording(&self) -> Result<Option<usize>, WpeError> {
+        let Some(writer) = self.recorder.lock().expect("recorder lock").take() else {
+            return Ok(None);
+        };
+        let count = writer.packets_written();
+        writer.finish()?;
+        info!("[WPE] Recording stopped: {} packets", count);
+        Ok(Some(count))
+    }
+
+    /// 是否正在录制
+    pub fn is_recording(&self) -> bool {
+        self.recorder.lock().expect("recorder lock").is_some()
+    }
+
+    /// 把内存中最近的封包导出为 pcapng，返回导出的封包数
+    pub fn export_pcap(&self, path: &Path) -> Result<usize, WpeError> {
+        let packets: Vec<CapturedPacket> = self
+            .history
+            .lock()
+            .expect("history lock")
+            .iter()
+            .cloned()
+            .collect();
+        let count = pcapng::export(path, &packets)?;
+        info!("[WPE] Exported {} packets to {}", count, path.display());
+        Ok(count)
+    }
+
+    /// 流量统计快照
+    pub fn stats(&self) -> WpeStats {
+        let now_secs = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        let mut stats = self.stats.lock().expect("stats lock").snapshot(now_secs);
+        stats.pipeline = self.pipeline.snapshot();
+        stats
+    }
+
+    /// 当前会话可读取的封包序号范围
+    pub fn history_range(&self) -> HistoryRange {
+        let (first_index, next_index) = self.history.lock().expect("history lock").range();
+        HistoryRange {
+            session: self.session,
+            first_index,
+            next_index,
+        }
+    }
+
+    /// 按序号分页读取截获封包的字节和标注
+    pub fn packet_bytes(
+        &self,
+        session: u64,
+        index: u64,
+        offset: usize,
+        len: usize,
+    ) -> Result<PacketBytes, WpeError> {
+        if session != self.session {
+            return Err(WpeError::NotRunning);
+        }
+        let packet = self
+            .history
+            .lock()
+            .expect("history lock")
+            .get(index)
+            .cloned()
+            .ok_or_else(|| {
+                WpeError::PacketParse(format!("Packet {index} is no longer in history"))
+            })?;
+        let rules = self.rules.lock().expect("rules lock").clone();
+        let (hex, annotations) = hexview::page(&packet, &rules, offset, len)?;
+        Ok(PacketBytes {
+            session,
+            index,
+            timestamp_us: packet.timestamp_us,
+            direction: packet.direction,
+            total_len: packet.data.len(),
+            offset,
+            hex,
+            annotations,
+        })
+    }
+
+    fn record(&self, packet: &CapturedPacket) {
+        self.stats.lock().expect("stats lock").record(packet);
+        self.history
+            .lock()
+            .expect("history lock")
+            .push(packet.clone());
+
+        let mut recorder = self.recorder.lock().expect("recorder lock");
+        if let Some(writer) = recorder.as_mut() {
+            if let Err(e) = writer.write_packet(packet) {
+                // 磁盘写满等情况下停止录制，避免每条封包都报错
+                warn!("[WPE] Recording failed, stopping: {}", e);
+                *recorder = None;
+            }
+        }
+    }
+
+    /// 当前能否发送封包，不能时返回原因；发送列表、重放在开始前检查，避免启动后逐条失败
+    pub fn check_send(&self) -> Result<(), WpeError> {
+        if self.read_only {
+            return Err(WpeError::ReadOnly("Packet injection"));
+        }
+        if crate::wpe::clean_mode() {
+            return Err(WpeError::Disabled("Packet injection"));
+        }
+        match self.backend.lock().expect("backend lock").as_ref() {
+            Some(backend) if backend.can_send() => Ok(()),
+            Some(backend) => Err(WpeError::Unsupported(format!(
+                "Packet injection on the {} backend",
+                backend.name()
+            ))),
+            None => Err(WpeError::NotRunning),
+        }
+    }
+
+    /// 通过当前后端向服务器发出原始字节
+    pub fn send(&self, data: &[u8]) -> Result<(), WpeError> {
+        self.check_send()?;
+        let backend = self.backend.lock().expect("backend lock").clone();
+        match backend {
+            Some(backend) => backend.send(Direction::Outbound, data),
+            None => Err(WpeError::NotRunning),
+        }
+    }
+
+    /// 按 `mode` 编码后发出：`AsIs` 原样发送，其余先解析成 GamePacket 再重新编码
+    pub fn send_with(&self, data: &[u8], mode: BuildMode) -> Result<(), WpeError> {
+        if mode == BuildMode::AsIs {
+            return self.send(data);
+        }
+        let data = GamePacket::parse(data)?.build_with(mode)?;
+        self.send(&data)
     }
 
     pub fn stop(&self) {
         info!("[WPE] Stopping packet interceptor");
         self.running.store(false, Ordering::Relaxed);
+        if let Err(e) = self.stop_recording() {
+            warn!("[WPE] Failed to finish recording: {}", e);
+        }
+        if let Some(backend) = self.backend.lock().expect("backend lock").take() {
+            backend.close();
+        }
     }
 
-    fn run(&self) -> Result<(), WpeError> {
+    fn set_status(&self, backend: &'static str, state: BackendState, reason: Option<String>) {
+        let status = BackendStatus {
+            backend,
+            state,
+            reason,
+            can_send: self.check_send().is_ok(),
+        };
+        *self.status.lock().expect("status lock") = status.clone();
+        let listener = self
+            .status_listener
+            .lock()
+            .expect("status listener lock")
+            .clone();
+        if let Some(listener) = listener {
+            listener(&status);
+        }
+    }
+
+    /// 看门狗：后端卡死时关闭它，阻塞中的 recv 返回后由 run 切换到备用后端
+    fn watchdog(interceptor: Weak<Self>) {
+        loop {
+            thread::sleep(WATCHDOG_INTERVAL);
+            let Some(interceptor) = interceptor.upgrade() else {
+                return;
+            };
+            if !interceptor.running.load(Ordering::Relaxed) {
+                return;
+            }
+            let stalled = interceptor
+                .health
+                .lock()
+                .expect("health lock")
+                .is_stalled(Instant::now());
+            if stalled && !interceptor.stalled.swap(true, Ordering::Relaxed) {
+                warn!("[WPE] Capture backend stalled, closing it");
+                if let Some(backend) = interceptor.backend.lock().expect("backend lock").take() {
+                    backend.close();
+                }
+            }
+        }
+    }
+
+    fn run(&self, pipeline: &Pipeline) -> Result<(), WpeError> {
         info!("[WPE] Interceptor thread started for PID {}", self.pid);
 
-        let handle = WinDivertHandle::open(self.pid)?;
+        let mut spec = self.spec.clone();
+        loop {
+            let reason = match self.run_backend(&spec, pipeline) {
+                BackendOutcome::Stopped => break,
+                BackendOutcome::Failed(reason) => reason,
+            };
+            if !self.running.load(Ordering::Relaxed) {
+                break;
+            }
+            match spec.fallback() {
+                Some(next) => {
+                    warn!(
+                        "[WPE] Capture backend {:?} failed ({}), failing over to {:?}",
+                        spec, reason, next
+                    );
+                    self.set_status(backend_name(&next), BackendState::FailedOver, Some(reason));
+                    spec = next;
+                }
+                None => {
+                    error!("[WPE] Capture backend {:?} failed: {}", spec, reason);
+                    self.set_status(backend_name(&spec), BackendState::Failed, Some(reason));
+                    return Ok(());
+                }
+            }
+        }
+
+        self.set_status(backend_name(&spec), BackendState::Stopped, None);
+        info!("[WPE] Interceptor thread stopped");
+        Ok(())
+    }
+
+    fn run_backend(&self, spec: &BackendSpec, pipeline: &Pipeline) -> BackendOutcome {
+        self.health.lock().expect("health lock").reset();
+        self.stalled.store(false, Ordering::Relaxed);
+
+        let handle = match spec.open(self.pid, !self.read_only) {
+            Ok(handle) => handle,
+            Err(e) => return BackendOutcome::Failed(format!("open failed: {e}")),
+        };
+        info!("[WPE] Capture backend opened: {}", handle.name());
+        let listener = self.disconnect_listener.clone();
+        handle.set_disconnect_listener(Arc::new(move |reason| {
+            warn!("[WPE] Game connection lost: {}", reason);
+            let listener = listener.lock().expect("disconnect listener lock").clone();
+            if let Some(listener) = listener {
+                listener(reason);
+            }
+        }));
+        *self.backend.lock().expect("backend lock") = Some(handle.clone());
+        if !self.running.load(Ordering::Relaxed) {
+            handle.close();
+            return BackendOutcome::Stopped;
+        }
+        // 切换后端时保留 FailedOver 状态，让前端看到原因
+        if self.status().state != BackendState::FailedOver {
+            self.set_status(handle.name(), BackendState::Active, None);
+        }
 
         while self.running.load(Ordering::Relaxed) {
             match handle.recv() {
-                Ok(data) => {
-                    if let Err(e) = self.process_packet(&data) {
-                        warn!("[WPE] Failed to process packet: {}", e);
-                    }
+                Ok(packet) => {
+                    self.health
+                        .lock()
+                        .expect("health lock")
+                        .record_packet(Instant::now());
+                    self.record(&packet);
+                    pipeline.submit(Job {
+                        packet,
+                        backend: handle.clone(),
+                    });
                 }
                 Err(WpeError::NotRunning) => {
+                    if self.stalled.load(Ordering::Relaxed) {
+                        return BackendOutcome::Failed("no packets received".to_string());
+                    }
                     break;
                 }
                 Err(e) => {
                     warn!("[WPE] Recv error: {}", e);
+                    if self.health.lock().expect("health lock").record_error() {
+                        handle.close();
+                        return BackendOutcome::Failed(format!("repeated recv errors: {e}"));
+                    }
                     thread::sleep(std::time::Duration::from_millis(100));
                 }
             }
         }
+        BackendOutcome::Stopped
+    }
 
-        info!("[WPE] Interceptor thread stopped");
-        Ok(())
+    /// 工作线程：按顺序处理一个方向的封包
+    fn worker(interceptor: Weak<Self>, direction: Direction, jobs: Receiver<Job>) {
+        while let Ok(Job { packet, backend }) = jobs.recv() {
+            let Some(interceptor) = interceptor.upgrade() else {
+                break;
+            };
+            match interceptor.process_packet(&packet) {
+                Ok(verdict) => Self::apply(backend.as_ref(), &packet, verdict),
+                Err(e) => {
+                    warn!("[WPE] Failed to process packet: {}", e);
+                    // 无法解析的封包不归我们处理，原样放行
+                    if !backend.passive() {
+                        if let Err(e) = backend.forward(&packet, &packet.data) {
+                            warn!("[WPE] Failed to forward packet: {}", e);
+                        }
+                    }
+                }
+            }
+            interceptor.pipeline.mark_processed();
+        }
+        info!("[WPE] {:?} packet worker stopped", direction);
     }
 
-    fn process_packet(&self, data: &[u8]) -> Result<(), WpeError> {
-        let packet = GamePacket::parse(data)?;
+    fn process_packet(&self, captured: &CapturedPacket) -> Result<Verdict, WpeError> {
+        let mut packet = GamePacket::parse(&captured.data)?;
+        let mut modified = false;
+        let mut inject = Vec::new();
+
+        // 规则动作都会改动流量，纯净模式和旁观模式下整体跳过
+        let rules = self.rules.lock().expect("rules lock").clone();
+        if !rules.is_empty() && !self.observe_only() {
+            match rules.evaluate(&packet, captured.direction) {
+                Some((rule, PacketAction::Drop)) => {
+                    info!("[WPE] Packet dropped by rule {}", rule);
+                    return Ok(Verdict {
+                        forward: None,
+                        inject,
+                    });
+                }
+                Some((rule, PacketAction::Modified(rewritten))) => {
+                    info!("[WPE] Packet modified by rule {}", rule);
+                    packet = rewritten;
+                    modified = true;
+                }
+                Some((rule, PacketAction::Inject(extra))) => {
+                    info!("[WPE] Packet injection queued by rule {}", rule);
+                    inject.push(extra);
+                }
+                Some((_, PacketAction::Forward)) | None => {}
+            }
+        }
 
-        let handlers = self.handlers.lock().expect("handlers lock");
-        for handler in handlers.iter() {
-            match handler.handle_outbound(&packet) {
+        // handler 依次处理：修改后的封包交给后续 handler，丢弃立即生效，注入排在原封包之后
+        // 只克隆列表的 Arc，不持锁调用：handler 内部可以注册/注销而不会死锁
+        let handlers = self.handlers.lock().expect("handlers lock").clone();
+        for registered in handlers
+            .iter()
+            .filter(|h| h.direction.is_none_or(|d| d == captured.direction))
+        {
+            let handler = &registered.handler;
+            let action = match captured.direction {
+                Direction::Inbound => handler.handle_inbound(&packet),
+                Direction::Outbound => handler.handle_outbound(&packet),
+            };
+            match action {
                 PacketAction::Forward => continue,
-                PacketAction::Modified(modified) => {
+                _ if self.observe_only() => {
+                    warn!("[WPE] Handler action ignored in observe-only mode");
+                    continue;
+                }
+                PacketAction::Modified(rewritten) => {
                     info!("[WPE] Packet modified by handler");
-                    return Ok(());
+                    packet = rewritten;
+                    modified = true;
                 }
                 PacketAction::Drop => {
                     info!("[WPE] Packet dropped by handler");
-                    return Ok(());
+                    return Ok(Verdict {
+                        forward: None,
+                        inject,
+                    });
                 }
-                PacketAction::Inject(inject) => {
-                    info!("[WPE] Handler requested packet injection");
-                    return Ok(());
+                PacketAction::Inject(extra) => {
+                    info!("[WPE] Packet injection queued by handler");
+                    inject.push(extra);
                 }
             }
         }
 
-        Ok(())
+        let forward = if modified {
+            Forward::Modified(packet.build()?)
+        } else {
+            Forward::Original
+        };
+        Ok(Verdict {
+            forward: Some(forward),
+            inject,
+        })
+    }
+
+    /// 按处理结果发出封包：先转发（或改写后的）原封包，再沿同一方向依次注入
+    fn apply(backend: &dyn CaptureBackend, captured: &CapturedPacket, verdict: Verdict) {
+        if let Err(e) = Self::forward(backend, captured, verdict.forward) {
+            warn!("[WPE] Failed to forward packet: {}", e);
+        }
+
+        for packet in verdict.inject {
+            match packet
+                .build()
+                .and_then(|data| backend.send(captured.direction, &data))
+            {
+                Ok(()) => info!("[WPE] Injected queued packet"),
+                Err(e) => warn!("[WPE] Failed to inject packet: {}", e),
+            }
+        }
+    }
+
+    /// 发出原封包或改写后的封包；旁路后端上原封包已经送达，改写和丢弃都无法生效
+    fn forward(
+        backend: &dyn CaptureBackend,
+        captured: &CapturedPacket,
+        forward: Option<Forward>,
+    ) -> Result<(), WpeError> {
+        match forward {
+            Some(Forward::Original) if backend.passive() => Ok(()),
+            Some(Forward::Original) => backend.forward(captured, &captured.data),
+            Some(Forward::Modified(_)) if backend.passive() => {
+                Err(forward_unsupported(backend, "Modifying"))
+            }
+            Some(Forward::Modified(data)) => backend.forward(captured, &data),
+            None if backend.passive() => Err(forward_unsupported(backend, "Dropping")),
+            None => backend.discard(captured),
+        }
+    }
+}
+
+fn forward_unsupported(backend: &dyn CaptureBackend, action: &str) -> WpeError {
+    WpeError::Unsupported(format!(
+        "{action} packets on the passive {} backend",
+        backend.name()
+    ))
+}
+
+fn backend_name(spec: &BackendSpec) -> &'static str {
+    match spec {
+        BackendSpec::WinDivert => "windivert",
+        BackendSpec::Simulation { .. } => "simulation",
+        BackendSpec::Proxy { .. } => "proxy",
+        BackendSpec::Mock => "mock",
     }
 }
 
@@ -100,3 +706,197 @@ impl Drop for PacketInterceptor {
         self.stop();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 把出站封包的地图号改成 9，并在其后注入一条跳图包
+    struct Rewrite;
+
+    impl PacketHandler for Rewrite {
+        fn handle_outbound(&self, _packet: &GamePacket) -> PacketAction {
+            PacketAction::Modified(GamePacket::build_map_jump(10001, 9))
+        }
+
+        fn handle_inbound(&self, _packet: &GamePacket) -> PacketAction {
+            PacketAction::Inject(GamePacket::build_map_jump(10001, 1))
+        }
+    }
+
+    #[test]
+    fn modified_packets_are_rebuilt_and_injections_queued() {
+        let interceptor = PacketInterceptor::with_backend(0, BackendSpec::Mock).unwrap();
+        interceptor.register_handler(Arc::new(Rewrite));
+        let original = GamePacket::build_map_jump(10001, 5).build().unwrap();
+
+        let outbound = CapturedPacket::now(Direction::Outbound, original.clone());
+        let verdict = interceptor.process_packet(&outbound).unwrap();
+        let expected = GamePacket::build_map_jump(10001, 9).build().unwrap();
+        assert!(matches!(verdict.forward, Some(Forward::Modified(data)) if data == expected));
+        assert!(verdict.inject.is_empty());
+
+        // 注入不影响原封包转发
+        let inbound = CapturedPacket::now(Direction::Inbound, original);
+        let verdict = interceptor.process_packet(&inbound).unwrap();
+        assert!(matches!(verdict.forward, Some(Forward::Original)));
+        assert_eq!(verdict.inject.len(), 1);
+
+        interceptor.stop();
+    }
+
+    struct DropAll;
+
+    impl PacketHandler for DropAll {
+        fn handle_outbound(&self, _packet: &GamePacket) -> PacketAction {
+            PacketAction::Drop
+        }
+
+        fn handle_inbound(&self, _packet: &GamePacket) -> PacketAction {
+            PacketAction::Drop
+        }
+    }
+
+    #[test]
+    fn handlers_run_by_priority_and_direction() {
+        let interceptor = PacketInterceptor::with_backend(0, BackendSpec::Mock).unwrap();
+        interceptor.register_handler(Arc::new(Rewrite));
+        let drop_inbound =
+            interceptor.register_handler_with(Arc::new(DropAll), 10, Some(Direction::Inbound));
+        let data = GamePacket::build_map_jump(10001, 5).build().unwrap();
+        let inbound = CapturedPacket::now(Direction::Inbound, data.clone());
+        let outbound = CapturedPacket::now(Direction::Outbound, data);
+
+        // 高优先级的丢弃先生效，Rewrite 不再注入
+        let verdict = interceptor.process_packet(&inbound).unwrap();
+        assert!(verdict.forward.is_none() && verdict.inject.is_empty());
+        // 只注册了入站方向
+        let verdict = interceptor.process_packet(&outbound).unwrap();
+        assert!(matches!(verdict.forward, Some(Forward::Modified(_))));
+
+        assert!(interceptor.unregister_handler(drop_inbound));
+        assert!(!interceptor.unregister_handler(drop_inbound));
+        let verdict = interceptor.process_packet(&inbound).unwrap();
+        assert_eq!(verdict.inject.len(), 1);
+
+        interceptor.stop();
+    }
+
+    /// 记录发出的封包；`passive` 决定是否为旁路后端
+    struct Recording {
+        passive: bool,
+        sent: Mutex<Vec<(Direction, Vec<u8>)>>,
+    }
+
+    impl CaptureBackend for Recording {
+        fn name(&self) -> &'static str {
+            "recording"
+        }
+        fn recv(&self) -> Result<CapturedPacket, WpeError> {
+            Err(WpeError::NotRunning)
+        }
+        fn send(&self, direction: Direction, data: &[u8]) -> Result<(), WpeError> {
+            self.sent.lock().unwrap().push((direction, data.to_vec()));
+            Ok(())
+        }
+        fn passive(&self) -> bool {
+            self.passive
+        }
+        fn close(&self) {}
+    }
+
+    #[test]
+    fn passive_backends_only_inject() {
+        let data = GamePacket::build_map_jump(10001, 5).build().unwrap();
+        let captured = CapturedPacket::now(Direction::Inbound, data.clone());
+        let verdict = |forward| Verdict {
+            forward,
+            inject: vec![GamePacket::build_map_jump(10001, 1)],
+        };
+
+        let passive = Recording {
+            passive: true,
+            sent: Mutex::new(Vec::new()),
+        };
+        for forward in [Some(Forward::Modified(vec![1, 2])), None] {
+            assert!(matches!(
+                PacketInterceptor::forward(&passive, &captured, forward),
+                Err(WpeError::Unsupported(_))
+            ));
+        }
+        // 原封包已经送达，只发出注入的封包，方向与原封包相同
+        PacketInterceptor::apply(&passive, &captured, verdict(Some(Forward::Original)));
+        let sent = passive.sent.lock().unwrap().clone();
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].0, Direction::Inbound);
+
+        let inline = Recording {
+            passive: false,
+            sent: Mutex::new(Vec::new()),
+        };
+        PacketInterceptor::apply(
+            &inline,
+            &captured,
+            verdict(Some(Forward::Modified(vec![7]))),
+        );
+        let sent = inline.sent.lock().unwrap().clone();
+        assert_eq!(sent[0], (Direction::Inbound, vec![7]));
+        assert_eq!(sent.len(), 2);
+    }
+
+    #[test]
+    fn handler_injections_reach_simulation_backend() {
+        let interceptor = PacketInterceptor::with_backend(0, BackendSpec::Mock).unwrap();
+        interceptor.register_handler(Arc::new(Rewrite));
+        let backend = crate::wpe::simulation::SimulationBackend::from_packets(Vec::new(), false);
+        let data = GamePacket::build_map_jump(10001, 5).build().unwrap();
+        let inbound = CapturedPacket::now(Direction::Inbound, data);
+
+        // 仿真后端是旁路后端：原封包不再发出，只有注入的一条计入
+        let verdict = interceptor.process_packet(&inbound).unwrap();
+        PacketInterceptor::apply(&backend, &inbound, verdict);
+        assert_eq!(backend.sent_count(), 1);
+
+        interceptor.stop();
+    }
+
+    #[test]
+    fn read_only_interceptor_never_alters_traffic() {
+        let interceptor = PacketInterceptor::create(0, BackendSpec::Mock, true).unwrap();
+        interceptor.register_handler(Arc::new(Rewrite));
+        interceptor.register_handler_with(Arc::new(DropAll), 10, Some(Direction::Inbound));
+        let data = GamePacket::build_map_jump(10001, 5).build().unwrap();
+
+        for direction in [Direction::Inbound, Direction::Outbound] {
+            let captured = CapturedPacket::now(direction, data.clone());
+            let verdict = interceptor.process_packet(&captured).unwrap();
+            assert!(matches!(verdict.forward, Some(Forward::Original)));
+            assert!(verdict.inject.is_empty());
+        }
+        assert!(matches!(
+            interceptor.send(&data),
+            Err(WpeError::ReadOnly(_))
+        ));
+        assert!(!interceptor.status().can_send);
+
+        interceptor.stop();
+    }
+
+    #[test]
+    fn strict_sends_reject_mismatched_length() {
+        let interceptor = PacketInterceptor::with_backend(0, BackendSpec::Mock).unwrap();
+        let mut packet = GamePacket::build_map_jump(10001, 5);
+        if let GamePacket::Binary { data, .. } = &mut packet {
+            data.push(0xAA);
+        }
+        let data = packet.build().unwrap();
+
+        // 校验在发送之前，不经过后端
+        assert!(matches!(
+            interceptor.send_with(&data, BuildMode::Strict),
+            Err(WpeError::PacketBuild(_))
+        ));
+
+        interceptor.stop();
+    }
+}
@@ -1,11 +1,29 @@
-pub mod injector;
+pub mod backend;
+pub mod commands;
+pub mod decoder;
+pub mod health;
+pub mod hexdump;
+pub mod hexview;
 pub mod interceptor;
 pub mod packet;
+pub mod pcapng;
+pub mod pipeline;
+pub mod proxy;
+pub mod replay;
+pub mod rules;
+pub mod sendlist;
+pub mod simulation;
+pub mod stats;
 pub mod windivert;
 
-pub use injector::PacketInjector;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+pub use backend::{BackendSpec, CaptureBackend, DisconnectListener};
+pub use health::{BackendState, BackendStatus};
 pub use interceptor::PacketInterceptor;
-pub use packet::{GamePacket, PacketAction, PacketHandler};
+pub use packet::{BuildMode, CapturedPacket, Direction, GamePacket, PacketAction, PacketHandler};
+pub use replay::PacketReplayer;
 
 #[derive(Debug, thiserror::Error)]
 pub enum WpeError {
@@ -23,6 +41,49 @@ pub enum WpeError {
 
     #[error("Not running")]
     NotRunning,
+
+    #[error("{0} is disabled in clean mode")]
+    Disabled(&'static str),
+
+    #[error("{0} is unavailable in spectator mode")]
+    ReadOnly(&'static str),
+
+    #[error("{0} is not supported")]
+    Unsupported(String),
+
+    #[error("Incompatible game client: {0}")]
+    IncompatibleClient(String),
+}
+
+// 纯净模式开关由 config 同步过来，WPE 内部只读这个标志，不依赖配置模块
+static CLEAN_MODE: AtomicBool = AtomicBool::new(false);
+
+pub fn set_clean_mode(enabled: bool) {
+    CLEAN_MODE.store(enabled, Ordering::Relaxed);
+}
+
+/// 纯净模式下只允许观察封包，注入、修改、丢弃一律拒绝
+pub fn clean_mode() -> bool {
+    CLEAN_MODE.load(Ordering::Relaxed)
+}
+
+// 客户端版本与命令表不一致时的原因，由 client_version 在启动时同步
+static CLIENT_MISMATCH: Mutex<Option<String>> = Mutex::new(None);
+
+pub fn set_client_mismatch(reason: Option<String>) {
+    *CLIENT_MISMATCH.lock().expect("client mismatch lock") = reason;
+}
+
+/// 按命令表构造、发送封包的功能在执行前调用，客户端版本不一致时返回错误
+pub fn ensure_compatible_client() -> Result<()> {
+    match CLIENT_MISMATCH
+        .lock()
+        .expect("client mismatch lock")
+        .clone()
+    {
+        Some(reason) => Err(WpeError::IncompatibleClient(reason)),
+        None => Ok(()),
+    }
 }
 
 pub type Result<T> = std::result::Result<T, WpeError>;
@@ -1,5 +1,6 @@
 use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
 use std::io::{Cursor, Write};
+use std::time::{SystemTime, UNIX_EPOCH};
 
 #[derive(Debug, Clone)]
 pub enum GamePacket {
@@ -21,6 +22,53 @@ pub enum PacketAction {
     Inject(GamePacket),
 }
 
+/// 二进制封包头里的魔数
+pub const BINARY_MAGIC: u16 = 0x9527;
+
+/// 编码封包时如何处理头部的派生字段（length，以及今后可能加入的校验和）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BuildMode {
+    /// 原样写出各字段
+    #[default]
+    AsIs,
+    /// 按 payload 重新计算 length
+    FixLength,
+    /// 字段与 payload 不一致时拒绝编码
+    Strict,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Direction {
+    /// 服务器 -> 客户端
+    Inbound,
+    /// 客户端 -> 服务器
+    Outbound,
+}
+
+/// 截获的原始封包（带时间戳与方向）
+#[derive(Debug, Clone)]
+pub struct CapturedPacket {
+    /// Unix 时间戳（微秒）
+    pub timestamp_us: u64,
+    pub direction: Direction,
+    pub data: Vec<u8>,
+}
+
+impl CapturedPacket {
+    pub fn now(direction: Direction, data: Vec<u8>) -> Self {
+        Self {
+            timestamp_us: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_micros() as u64)
+                .unwrap_or(0),
+            direction,
+            data,
+        }
+    }
+}
+
 pub trait PacketHandler: Send + Sync {
     fn handle_outbound(&self, packet: &GamePacket) -> PacketAction;
     fn handle_inbound(&self, packet: &GamePacket) -> PacketAction;
@@ -39,7 +87,7 @@ impl GamePacket {
             crate::wpe::WpeError::PacketParse(format!("Failed to read magic: {}", e))
         })?;
 
-        if magic == 0x9527 {
+        if magic == BINARY_MAGIC {
             if data.len() < 16 {
                 return Err(crate::wpe::WpeError::PacketParse(
                     "Binary packet too short".to_string(),
@@ -101,6 +149,59 @@ impl GamePacket {
         }
     }
 
+    /// 按指定方式编码
+    pub fn build_with(&self, mode: BuildMode) -> Result<Vec<u8>, crate::wpe::WpeError> {
+        match mode {
+            BuildMode::AsIs => self.build(),
+            BuildMode::FixLength => self.rebuild_with_length(),
+            BuildMode::Strict => {
+                self.validate()?;
+                self.build()
+            }
+        }
+    }
+
+    /// 重新计算 length 后编码（handler 改动 payload 后使用）
+    pub fn rebuild_with_length(&self) -> Result<Vec<u8>, crate::wpe::WpeError> {
+        let mut packet = self.clone();
+        packet.fix_length();
+        packet.build()
+    }
+
+    /// 把 length 改成 payload 的实际字节数，文本封包不受影响
+    pub fn fix_length(&mut self) {
+        if let GamePacket::Binary { length, data, .. } = self {
+            *length = data.len() as u32;
+        }
+    }
+
+    /// 检查二进制封包的魔数和 length 是否与 payload 一致
+    pub fn validate(&self) -> Result<(), crate::wpe::WpeError> {
+        let GamePacket::Binary {
+            magic,
+            length,
+            data,
+            ..
+        } = self
+        else {
+            return Ok(());
+        };
+        if *magic != BINARY_MAGIC {
+            return Err(crate::wpe::WpeError::PacketBuild(format!(
+                "Unexpected magic: {:#06x}",
+                magic
+            )));
+        }
+        if *length as usize != data.len() {
+            return Err(crate::wpe::WpeError::PacketBuild(format!(
+                "Length field {} does not match payload length {}",
+                length,
+                data.len()
+            )));
+        }
+        Ok(())
+    }
+
     pub fn build_map_jump(qq_num: u64, map_no: u16) -> Self {
         let mut data = Vec::new();
         data.extend_from_slice(&[0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0x00]);
@@ -108,7 +209,7 @@ impl GamePacket {
         data.push(((map_no >> 8) & 0xFF) as u8);
 
         GamePacket::Binary {
-            magic: 0x9527,
+            magic: BINARY_MAGIC,
             length: 0x0B,
             command: 0x0003,
             qq_num,
@@ -122,7 +223,7 @@ impl GamePacket {
         data.push(spirit_pos);
 
         GamePacket::Binary {
-            magic: 0x9527,
+            magic: BINARY_MAGIC,
             length: 0x0B,
             command: 0x0014,
             qq_num,
@@ -140,7 +241,7 @@ impl GamePacket {
         data.push(spirit_pos);
 
         GamePacket::Binary {
-            magic: 0x9527,
+            magic: BINARY_MAGIC,
             length: 0x0B,
             command: 0x0052,
             qq_num,
@@ -148,3 +249,28 @@ impl GamePacket {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn length_is_recomputed_or_rejected() {
+        let mut packet = GamePacket::build_map_jump(10001, 5);
+        if let GamePacket::Binary { data, .. } = &mut packet {
+            data.extend_from_slice(&[0xAA, 0xBB]);
+        }
+        assert!(packet.build_with(BuildMode::Strict).is_err());
+
+        let fixed = GamePacket::parse(&packet.build_with(BuildMode::FixLength).unwrap()).unwrap();
+        assert!(matches!(fixed, GamePacket::Binary { length: 13, .. }));
+        assert!(fixed.build_with(BuildMode::Strict).is_ok());
+
+        // 原样编码保留错误的 length
+        let raw = GamePacket::parse(&packet.build().unwrap()).unwrap();
+        assert!(matches!(raw, GamePacket::Binary { length: 0x0B, .. }));
+
+        let text = GamePacket::build_pet_escape();
+        assert!(text.build_with(BuildMode::Strict).is_ok());
+    }
+}
@@ -0,0 +1,313 @@
+use std::fs::File;
+use std::io::{BufWriter, Read, Write};
+use std::path::Path;
+
+use crate::wpe::{CapturedPacket, Direction, GamePacket, WpeError};
+
+// pcapng 导出与读取（读取用于仿真回放）
+//
+// 封包以 LINKTYPE_USER0 写入（只包含游戏层 payload，不含 IP/TCP 头），
+// 方向写入 epb_flags，命令号写入 opt_comment（不写 QQ 号，注释会原样出现在分享的抓包中），
+// 配合 docs/rocoknight.lua 在 Wireshark 中解析。
+
+const BLOCK_SHB: u32 = 0x0A0D_0D0A;
+const BLOCK_IDB: u32 = 0x0000_0001;
+const BLOCK_EPB: u32 = 0x0000_0006;
+const BYTE_ORDER_MAGIC: u32 = 0x1A2B_3C4D;
+const LINKTYPE_USER0: u16 = 147;
+/// 读取时单个块的长度上限，防止损坏的文件触发超大分配
+const MAX_BLOCK_LEN: usize = 16 * 1024 * 1024;
+
+const OPT_ENDOFOPT: u16 = 0;
+const OPT_COMMENT: u16 = 1;
+const OPT_SHB_USERAPPL: u16 = 4;
+const OPT_IF_NAME: u16 = 2;
+const OPT_EPB_FLAGS: u16 = 2;
+
+pub struct PcapngWriter<W: Write> {
+    out: W,
+    packets_written: usize,
+}
+
+impl<W: Write> PcapngWriter<W> {
+    /// 写入 Section Header 与 Interface Description
+    pub fn new(mut out: W) -> Result<Self, WpeError> {
+        let mut shb = Vec::new();
+        shb.extend_from_slice(&BYTE_ORDER_MAGIC.to_le_bytes());
+        shb.extend_from_slice(&1u16.to_le_bytes());
+        shb.extend_from_slice(&0u16.to_le_bytes());
+        shb.extend_from_slice(&(-1i64).to_le_bytes());
+        push_option(&mut shb, OPT_SHB_USERAPPL, b"RocoKnight");
+        push_end_of_options(&mut shb);
+        write_block(&mut out, BLOCK_SHB, &shb)?;
+
+        let mut idb = Vec::new();
+        idb.extend_from_slice(&LINKTYPE_USER0.to_le_bytes());
+        idb.extend_from_slice(&0u16.to_le_bytes());
+        idb.extend_from_slice(&0u32.to_le_bytes());
+        push_option(&mut idb, OPT_IF_NAME, b"rocoknight-wpe");
+        push_end_of_options(&mut idb);
+        write_block(&mut out, BLOCK_IDB, &idb)?;
+
+        Ok(Self {
+            out,
+            packets_written: 0,
+        })
+    }
+
+    pub fn write_packet(&mut self, packet: &CapturedPacket) -> Result<(), WpeError> {
+        let mut epb = Vec::with_capacity(packet.data.len() + 64);
+        epb.extend_from_slice(&0u32.to_le_bytes());
+        epb.extend_from_slice(&((packet.timestamp_us >> 32) as u32).to_le_bytes());
+        epb.extend_from_slice(&(packet.timestamp_us as u32).to_le_bytes());
+        epb.extend_from_slice(&(packet.data.len() as u32).to_le_bytes());
+        epb.extend_from_slice(&(packet.data.len() as u32).to_le_bytes());
+        epb.extend_from_slice(&packet.data);
+        pad_to_u32(&mut epb);
+
+        let flags: u32 = match packet.direction {
+            Direction::Inbound => 0b01,
+            Direction::Outbound => 0b10,
+        };
+        push_option(&mut epb, OPT_EPB_FLAGS, &flags.to_le_bytes());
+        push_option(&mut epb, OPT_COMMENT, packet_comment(packet).as_bytes());
+        push_end_of_options(&mut epb);
+
+        write_block(&mut self.out, BLOCK_EPB, &epb)?;
+        self.packets_written += 1;
+        Ok(())
+    }
+
+    pub fn packets_written(&self) -> usize {
+        self.packets_written
+    }
+
+    pub fn finish(mut self) -> Result<W, WpeError> {
+        self.out.flush()?;
+        Ok(self.out)
+    }
+}
+
+/// 读取 pcapng 中的全部封包（只识别本模块写出的 EPB 格式，其余块跳过）
+pub fn read_packets<R: Read>(mut input: R) -> Result<Vec<CapturedPacket>, WpeError> {
+    let mut packets = Vec::new();
+    let mut header = [0u8; 8];
+    loop {
+        match input.read_exact(&mut header) {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => break,
+            Err(e) => return Err(e.into()),
+        }
+        let block_type = u32::from_le_bytes([header[0], header[1], header[2], header[3]]);
+        let total_len = u32::from_le_bytes([header[4], header[5], header[6], header[7]]) as usize;
+        if total_len < 12 || total_len % 4 != 0 || total_len > MAX_BLOCK_LEN {
+            return Err(WpeError::PacketParse(format!(
+                "Invalid pcapng block length: {total_len}"
+            )));
+        }
+
+        let mut body = vec![0u8; total_len - 8];
+        input.read_exact(&mut body)?;
+        let body = &body[..body.len() - 4];
+
+        if block_type == BLOCK_SHB {
+            if body.len() < 4 {
+                return Err(WpeError::PacketParse("SHB too short".to_string()));
+            }
+            let magic = u32::from_le_bytes([body[0], body[1], body[2], body[3]]);
+            if magic != BYTE_ORDER_MAGIC {
+                return Err(WpeError::PacketParse(
+                    "Only little-endian pcapng is supported".to_string(),
+                ));
+            }
+        } else if block_type == BLOCK_EPB {
+            packets.push(parse_epb(body)?);
+        }
+    }
+    Ok(packets)
+}
+
+fn parse_epb(body: &[u8]) -> Result<CapturedPacket, WpeError> {
+    if body.len() < 20 {
+        return Err(WpeError::PacketParse("EPB too short".to_string()));
+    }
+    let word = |at: usize| u32::from_le_bytes([body[at], body[at + 1], body[at + 2], body[at + 3]]);
+    let timestamp_us = ((word(4) as u64) << 32) | word(8) as u64;
+    let captured_len = word(12) as usize;
+    let data_end = 20 + captured_len;
+    if data_end > body.len() {
+        return Err(WpeError::PacketParse("EPB data truncated".to_string()));
+    }
+    let data = body[20..data_end].to_vec();
+
+    // 解析选项，只关心 epb_flags 中的方向位
+    let mut direction = Direction::Inbound;
+    let mut offset = data_end.div_ceil(4) * 4;
+    while offset + 4 <= body.len() {
+        let code = u16::from_le_bytes([body[offset], body[offset + 1]]);
+        let len = u16::from_le_bytes([body[offset + 2], body[offset + 3]]) as usize;
+        if code == OPT_ENDOFOPT {
+            break;
+        }
+        let value_start = offset + 4;
+        if code == OPT_EPB_FLAGS
+            && len == 4
+            && value_start + 4 <= body.len()
+            && word(value_start) & 0b11 == 0b10
+        {
+            direction = Direction::Outbound;
+        }
+        offset = value_start + len.div_ceil(4) * 4;
+    }
+
+    Ok(CapturedPacket {
+        timestamp_us,
+        direction,
+        data,
+    })
+}
+
+fn packet_comment(packet: &CapturedPacket) -> String {
+    let dir = match packet.direction {
+        Direction::Inbound => "in",
+        Direction::Outbound => "out",
+    };
+    match GamePacket::parse(&packet.data) {
+        Ok(GamePacket::Binary { command, .. }) => format!("dir={dir} cmd=0x{command:04X}"),
+        _ => format!("dir={dir} text"),
+    }
+}
+
+/// 把一组封包写成完整的 pcapng 文件，返回写入的封包数
+pub fn export<'a>(
+    path: &Path,
+    packets: impl IntoIterator<Item = &'a CapturedPacket>,
+) -> Result<usize, WpeError> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut writer = PcapngWriter::new(BufWriter::new(File::create(path)?))?;
+    for packet in packets {
+        writer.write_packet(packet)?;
+    }
+    let count = writer.packets_written();
+    writer.finish()?;
+    Ok(count)
+}
+
+fn push_option(buf: &mut Vec<u8>, code: u16, value: &[u8]) {
+    buf.extend_from_slice(&code.to_le_bytes());
+    buf.extend_from_slice(&(value.len() as u16).to_le_bytes());
+    buf.extend_from_slice(value);
+    pad_to_u32(buf);
+}
+
+fn push_end_of_options(buf: &mut Vec<u8>) {
+    buf.extend_from_slice(&OPT_ENDOFOPT.to_le_bytes());
+    buf.extend_from_slice(&0u16.to_le_bytes());
+}
+
+fn pad_to_u32(buf: &mut Vec<u8>) {
+    while buf.len() % 4 != 0 {
+        buf.push(0);
+    }
+}
+
+fn write_block<W: Write>(out: &mut W, block_type: u32, body: &[u8]) -> Result<(), WpeError> {
+    let total_len = (body.len() + 12) as u32;
+    out.write_all(&block_type.to_le_bytes())?;
+    out.write_all(&total_len.to_le_bytes())?;
+    out.write_all(body)?;
+    out.write_all(&total_len.to_le_bytes())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read_u32(buf: &[u8], at: usize) -> u32 {
+        u32::from_le_bytes([buf[at], buf[at + 1], buf[at + 2], buf[at + 3]])
+    }
+
+    #[test]
+    fn blocks_are_aligned_and_length_framed() {
+        let mut writer = PcapngWriter::new(Vec::new()).expect("header");
+        writer
+            .write_packet(&CapturedPacket {
+                timestamp_us: 1_700_000_000_000_000,
+                direction: Direction::Outbound,
+                data: b"System_abc".to_vec(),
+            })
+            .expect("packet");
+        let buf = writer.finish().expect("finish");
+
+        assert_eq!(read_u32(&buf, 0), BLOCK_SHB);
+        assert_eq!(read_u32(&buf, 8), BYTE_ORDER_MAGIC);
+
+        let mut offset = 0usize;
+        let mut types = Vec::new();
+        while offset < buf.len() {
+            let block_type = read_u32(&buf, offset);
+            let len = read_u32(&buf, offset + 4) as usize;
+            assert_eq!(len % 4, 0);
+            assert_eq!(read_u32(&buf, offset + len - 4) as usize, len);
+            types.push(block_type);
+            offset += len;
+        }
+        assert_eq!(offset, buf.len());
+        assert_eq!(types, vec![BLOCK_SHB, BLOCK_IDB, BLOCK_EPB]);
+    }
+
+    #[test]
+    fn comment_carries_command_without_qq() {
+        let data = GamePacket::build_map_jump(10001, 5).build().unwrap();
+        let packet = CapturedPacket::now(Direction::Outbound, data);
+        assert_eq!(packet_comment(&packet), "dir=out cmd=0x0003");
+
+        let text = CapturedPacket::now(Direction::Inbound, b"System_abc".to_vec());
+        assert_eq!(packet_comment(&text), "dir=in text");
+    }
+
+    #[test]
+    fn read_back_written_packets() {
+        let packets = vec![
+            CapturedPacket {
+                timestamp_us: 10,
+                direction: Direction::Inbound,
+                data: vec![1, 2, 3],
+            },
+            CapturedPacket {
+                timestamp_us: 20,
+                direction: Direction::Outbound,
+                data: b"System_abcd".to_vec(),
+            },
+        ];
+        let mut writer = PcapngWriter::new(Vec::new()).expect("header");
+        for packet in &packets {
+            writer.write_packet(packet).expect("packet");
+        }
+        let buf = writer.finish().expect("finish");
+
+        let read = read_packets(buf.as_slice()).expect("read");
+        assert_eq!(read.len(), 2);
+        for (a, b) in read.iter().zip(packets.iter()) {
+            assert_eq!(a.timestamp_us, b.timestamp_us);
+            assert_eq!(a.direction, b.direction);
+            assert_eq!(a.data, b.data);
+        }
+    }
+
+    #[test]
+    fn rejects_short_and_oversized_blocks() {
+        // 长度 12 的 SHB 没有字节序魔数
+        let mut short = BLOCK_SHB.to_le_bytes().to_vec();
+        short.extend_from_slice(&12u32.to_le_bytes());
+        short.extend_from_slice(&12u32.to_le_bytes());
+        assert!(read_packets(short.as_slice()).is_err());
+
+        let mut huge = BLOCK_EPB.to_le_bytes().to_vec();
+        huge.extend_from_slice(&0xFFFF_FFF0u32.to_le_bytes());
+        assert!(read_packets(huge.as_slice()).is_err());
+    }
+}
@@ -0,0 +1,174 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, Receiver, SyncSender, TrySendError};
+use std::sync::Arc;
+
+use crate::wpe::{CaptureBackend, CapturedPacket, Direction};
+
+// 封包处理流水线
+//
+// 捕获线程只负责 recv 和记录，规则与 handler 在每个方向各自的工作线程上执行，
+// 二者之间是有界队列：战斗中封包密集时 handler 再慢也不会拖住捕获循环。
+// 同一方向的封包由同一个线程按顺序处理，修改、注入的先后不变。
+//
+// 队列满时：
+// - 旁路后端：原封包已经送达，直接丢弃这条的处理并计数
+// - 拦截型后端：丢弃等于丢包，捕获线程阻塞等待（背压）
+
+/// 每个方向的队列容量
+pub const QUEUE_CAPACITY: usize = 4096;
+
+/// 交给工作线程的一条封包，带上捕获它的后端（转发、注入都通过它）
+pub struct Job {
+    pub packet: CapturedPacket,
+    pub backend: Arc<dyn CaptureBackend>,
+}
+
+/// 流水线计数（`get_wpe_stats` 的 `pipeline`）
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Serialize)]
+pub struct PipelineStats {
+    /// 进入队列的封包数
+    pub queued: u64,
+    /// 已处理完的封包数
+    pub processed: u64,
+    /// 队列满而未处理的封包数
+    pub dropped: u64,
+    /// 当前排队中的封包数
+    pub pending: u64,
+}
+
+#[derive(Debug, Default)]
+pub struct PipelineCounters {
+    queued: AtomicU64,
+    processed: AtomicU64,
+    dropped: AtomicU64,
+}
+
+impl PipelineCounters {
+    pub fn mark_processed(&self) {
+        self.processed.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn snapshot(&self) -> PipelineStats {
+        let queued = self.queued.load(Ordering::Relaxed);
+        let processed = self.processed.load(Ordering::Relaxed);
+        PipelineStats {
+            queued,
+            processed,
+            dropped: self.dropped.load(Ordering::Relaxed),
+            pending: queued.saturating_sub(processed),
+        }
+    }
+}
+
+/// 捕获线程持有的发送端；全部释放后工作线程随之退出
+pub struct Pipeline {
+    inbound: SyncSender<Job>,
+    outbound: SyncSender<Job>,
+    counters: Arc<PipelineCounters>,
+}
+
+impl Pipeline {
+    /// 创建流水线，返回发送端和两个方向的接收端（由调用方交给工作线程）
+    pub fn new(
+        capacity: usize,
+        counters: Arc<PipelineCounters>,
+    ) -> (Self, [(Direction, Receiver<Job>); 2]) {
+        let (inbound, inbound_rx) = mpsc::sync_channel(capacity);
+        let (outbound, outbound_rx) = mpsc::sync_channel(capacity);
+        (
+            Self {
+                inbound,
+                outbound,
+                counters,
+            },
+            [
+                (Direction::Inbound, inbound_rx),
+                (Direction::Outbound, outbound_rx),
+            ],
+        )
+    }
+
+    /// 提交一条封包，返回是否进入了队列（工作线程已退出时也返回 false）
+    pub fn submit(&self, job: Job) -> bool {
+        let sender = match job.packet.direction {
+            Direction::Inbound => &self.inbound,
+            Direction::Outbound => &self.outbound,
+        };
+        let sent = if job.backend.passive() {
+            match sender.try_send(job) {
+                Ok(()) => true,
+                Err(TrySendError::Full(_)) => {
+                    self.counters.dropped.fetch_add(1, Ordering::Relaxed);
+                    return false;
+                }
+                Err(TrySendError::Disconnected(_)) => false,
+            }
+        } else {
+            sender.send(job).is_ok()
+        };
+        if sent {
+            self.counters.queued.fetch_add(1, Ordering::Relaxed);
+        }
+        sent
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wpe::backend::MockBackend;
+    use crate::wpe::WpeError;
+
+    /// 只用来让 passive() 返回 true 的旁路后端
+    struct Passive(MockBackend);
+
+    impl CaptureBackend for Passive {
+        fn name(&self) -> &'static str {
+            "passive"
+        }
+        fn recv(&self) -> Result<CapturedPacket, WpeError> {
+            self.0.recv()
+        }
+        fn send(&self, direction: Direction, data: &[u8]) -> Result<(), WpeError> {
+            self.0.send(direction, data)
+        }
+        fn passive(&self) -> bool {
+            true
+        }
+        fn close(&self) {
+            self.0.close()
+        }
+    }
+
+    #[test]
+    fn full_queue_drops_only_for_passive_backends() {
+        let counters = Arc::new(PipelineCounters::default());
+        let (pipeline, receivers) = Pipeline::new(1, counters.clone());
+        let backend: Arc<dyn CaptureBackend> = Arc::new(Passive(MockBackend::default()));
+        let job = |direction| Job {
+            packet: CapturedPacket::now(direction, vec![1]),
+            backend: backend.clone(),
+        };
+
+        assert!(pipeline.submit(job(Direction::Outbound)));
+        assert!(!pipeline.submit(job(Direction::Outbound)));
+        // 另一个方向有自己的队列
+        assert!(pipeline.submit(job(Direction::Inbound)));
+
+        let [(_, inbound_rx), (_, outbound_rx)] = receivers;
+        assert_eq!(outbound_rx.recv().unwrap().packet.data, vec![1]);
+        counters.mark_processed();
+        assert_eq!(
+            counters.snapshot(),
+            PipelineStats {
+                queued: 2,
+                processed: 1,
+                dropped: 1,
+                pending: 1,
+            }
+        );
+
+        drop(inbound_rx);
+        assert!(!pipeline.submit(job(Direction::Inbound)));
+    }
+}
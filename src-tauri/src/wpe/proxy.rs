@@ -0,0 +1,338 @@
+use std::io::{ErrorKind, Read, Write};
+use std::net::{Shutdown, SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, SyncSender};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tracing::{info, warn};
+
+use crate::wpe::backend::{CaptureBackend, DisconnectListener};
+use crate::wpe::{CapturedPacket, Direction, WpeError};
+
+// 本地 TCP 代理后端（WinDivert 不可用时的备选）
+//
+// 在 127.0.0.1 上监听游戏服务器的端口，把连接转发到真实服务器，转发的同时把数据复制给 handler。
+// 不需要管理员权限和驱动，但游戏必须连到本机：需要在 hosts 中把游戏服务器域名指向 127.0.0.1，
+// 因此 upstream 必须写真实服务器的 IP（写域名会被 hosts 解析回本机）。
+//
+// 与 SNIFF 模式的 WinDivert 一样是旁路后端：数据由转发线程直接送达，处理延迟不影响游戏；
+// 原数据已经送达，修改和丢弃不会生效；send 按方向写入当前连接（Outbound 发往服务器，Inbound 发往游戏），
+// 因此可以注入。
+// 交给 handler 的是一次 read 读到的数据块，不做流重组。
+// 服务器先关闭当前连接时报告断线；游戏先关闭（退出、切服）的不算。
+
+/// 等待封包 / 新连接时检查关闭标志的间隔
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+/// 连接真实服务器的超时
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+/// 等待 handler 处理的数据块上限，超出时只丢弃副本，转发不受影响
+const QUEUE_SIZE: usize = 1024;
+const READ_BUFFER_SIZE: usize = 0xFFFF;
+
+/// 一条连接发往某一端的写入端，转发线程和 send 共用，保证写入不交错
+type Writer = Arc<Mutex<TcpStream>>;
+
+type SharedListener = Arc<Mutex<Option<DisconnectListener>>>;
+
+/// 当前连接的两个写入端
+struct Connection {
+    to_server: Writer,
+    to_client: Writer,
+}
+
+type SharedConnection = Arc<Mutex<Option<Connection>>>;
+
+pub struct ProxyBackend {
+    listen: SocketAddr,
+    upstream: SocketAddr,
+    running: Arc<AtomicBool>,
+    packets: Mutex<Receiver<CapturedPacket>>,
+    /// 最新的连接（注入目标）
+    current: SharedConnection,
+    disconnect: SharedListener,
+}
+
+impl ProxyBackend {
+    /// `listen_port` 为 0 时使用与 upstream 相同的端口
+    pub fn open(upstream: &str, listen_port: u16) -> Result<Self, WpeError> {
+        let upstream: SocketAddr = upstream.parse().map_err(|_| {
+            WpeError::Io(std::io::Error::new(
+                ErrorKind::InvalidInput,
+                format!("Proxy upstream must be an ip:port address: {upstream}"),
+            ))
+        })?;
+        let port = if listen_port == 0 {
+            upstream.port()
+        } else {
+            listen_port
+        };
+        Self::bind(SocketAddr::from(([127, 0, 0, 1], port)), upstream)
+    }
+
+    fn bind(listen: SocketAddr, upstream: SocketAddr) -> Result<Self, WpeError> {
+        let listener = TcpListener::bind(listen)?;
+        listener.set_nonblocking(true)?;
+        let listen = listener.local_addr()?;
+        info!("[WPE] Proxy listening on {} -> {}", listen, upstream);
+
+        let (tx, rx) = mpsc::sync_channel(QUEUE_SIZE);
+        let backend = Self {
+            listen,
+            upstream,
+            running: Arc::new(AtomicBool::new(true)),
+            packets: Mutex::new(rx),
+            current: Arc::new(Mutex::new(None)),
+            disconnect: Arc::new(Mutex::new(None)),
+        };
+        let running = backend.running.clone();
+        let current = backend.current.clone();
+        let disconnect = backend.disconnect.clone();
+        std::thread::spawn(move || {
+            accept_loop(listener, upstream, running, current, disconnect, tx)
+        });
+        Ok(backend)
+    }
+
+    /// 实际监听的地址
+    pub fn local_addr(&self) -> SocketAddr {
+        self.listen
+    }
+}
+
+fn accept_loop(
+    listener: TcpListener,
+    upstream: SocketAddr,
+    running: Arc<AtomicBool>,
+    current: SharedConnection,
+    disconnect: SharedListener,
+    tx: SyncSender<CapturedPacket>,
+) {
+    while running.load(Ordering::Relaxed) {
+        let client = match listener.accept() {
+            Ok((client, peer)) => {
+                info!("[WPE] Proxy accepted connection from {}", peer);
+                client
+            }
+            Err(e) if e.kind() == ErrorKind::WouldBlock => {
+                std::thread::sleep(POLL_INTERVAL);
+                continue;
+            }
+            Err(e) => {
+                warn!("[WPE] Proxy accept failed: {}", e);
+                std::thread::sleep(POLL_INTERVAL);
+                continue;
+            }
+        };
+        if let Err(e) = connect(client, upstream, &running, &current, &disconnect, &tx) {
+            warn!("[WPE] Proxy failed to connect to {}: {}", upstream, e);
+        }
+    }
+    info!("[WPE] Proxy accept loop stopped");
+}
+
+/// 为一个游戏连接建立到服务器的连接，并启动两个方向的转发线程
+fn connect(
+    client: TcpStream,
+    upstream: SocketAddr,
+    running: &Arc<AtomicBool>,
+    current: &SharedConnection,
+    disconnect: &SharedListener,
+    tx: &SyncSender<CapturedPacket>,
+) -> std::io::Result<()> {
+    client.set_nonblocking(false)?;
+    let server = TcpStream::connect_timeout(&upstream, CONNECT_TIMEOUT)?;
+    let _ = server.set_nodelay(true);
+    let _ = client.set_nodelay(true);
+
+    let to_server: Writer = Arc::new(Mutex::new(server.try_clone()?));
+    let to_client: Writer = Arc::new(Mutex::new(client.try_clone()?));
+    // 新连接成为注入目标（游戏重连时旧连接随即失效）
+    *current.lock().expect("proxy current lock") = Some(Connection {
+        to_server: to_server.clone(),
+        to_client: to_client.clone(),
+    });
+
+    // 先结束的一侧决定是谁关闭了连接
+    let closed = Arc::new(AtomicBool::new(false));
+    for (reader, direction, writer) in [
+        (client.try_clone()?, Direction::Outbound, to_server.clone()),
+        (server.try_clone()?, Direction::Inbound, to_client),
+    ] {
+        let (running, tx, closed) = (running.clone(), tx.clone(), closed.clone());
+        let (current, disconnect, connection) =
+            (current.clone(), disconnect.clone(), to_server.clone());
+        let pair = (client.try_clone()?, server.try_clone()?);
+        std::thread::spawn(move || {
+            pump(reader, direction, &writer, &tx, &running);
+            let first = !closed.swap(true, Ordering::Relaxed);
+            let is_current = current
+                .lock()
+                .expect("proxy current lock")
+                .as_ref()
+                .is_some_and(|c| Arc::ptr_eq(&c.to_server, &connection));
+            if first
+                && direction == Direction::Inbound
+                && is_current
+                && running.load(Ordering::Relaxed)
+            {
+                let listener = disconnect.lock().expect("proxy disconnect lock").clone();
+                if let Some(listener) = listener {
+                    listener("upstream closed the connection");
+                }
+            }
+            // 一端关闭后同时关闭另一端，让对向的转发线程退出
+            let _ = pair.0.shutdown(Shutdown::Both);
+            let _ = pair.1.shutdown(Shutdown::Both);
+        });
+    }
+    Ok(())
+}
+
+/// 把 reader 读到的数据交给 handler，并转发给对端
+fn pump(
+    mut reader: TcpStream,
+    direction: Direction,
+    writer: &Mutex<TcpStream>,
+    tx: &SyncSender<CapturedPacket>,
+    running: &AtomicBool,
+) {
+    let mut buf = vec![0u8; READ_BUFFER_SIZE];
+    while running.load(Ordering::Relaxed) {
+        let len = match reader.read(&mut buf) {
+            Ok(0) | Err(_) => break,
+            Ok(len) => len,
+        };
+        // 先入队再转发，handler 看到的顺序与因果顺序一致（请求总在响应之前）；
+        // 队列满时只丢弃副本，不影响转发
+        let _ = tx.try_send(CapturedPacket::now(direction, buf[..len].to_vec()));
+        if let Err(e) = writer
+            .lock()
+            .expect("proxy writer lock")
+            .write_all(&buf[..len])
+        {
+            warn!("[WPE] Proxy forward failed ({:?}): {}", direction, e);
+            break;
+        }
+    }
+    info!("[WPE] Proxy connection closed ({:?})", direction);
+}
+
+impl CaptureBackend for ProxyBackend {
+    fn name(&self) -> &'static str {
+        "proxy"
+    }
+
+    fn recv(&self) -> Result<CapturedPacket, WpeError> {
+        let packets = self.packets.lock().expect("proxy packets lock");
+        loop {
+            if !self.running.load(Ordering::Relaxed) {
+                return Err(WpeError::NotRunning);
+            }
+            match packets.recv_timeout(POLL_INTERVAL) {
+                Ok(packet) => return Ok(packet),
+                Err(RecvTimeoutError::Timeout) => continue,
+                Err(RecvTimeoutError::Disconnected) => return Err(WpeError::NotRunning),
+            }
+        }
+    }
+
+    /// 写入当前连接：Outbound 发往服务器，Inbound 发往游戏
+    fn send(&self, direction: Direction, data: &[u8]) -> Result<(), WpeError> {
+        if !self.running.load(Ordering::Relaxed) {
+            return Err(WpeError::NotRunning);
+        }
+        let writer = self
+            .current
+            .lock()
+            .expect("proxy current lock")
+            .as_ref()
+            .map(|c| match direction {
+                Direction::Outbound => c.to_server.clone(),
+                Direction::Inbound => c.to_client.clone(),
+            });
+        match writer {
+            Some(writer) => Ok(writer.lock().expect("proxy writer lock").write_all(data)?),
+            None => Err(WpeError::NotRunning),
+        }
+    }
+
+    /// 转发线程已经把原数据送达
+    fn passive(&self) -> bool {
+        true
+    }
+
+    fn set_disconnect_listener(&self, listener: DisconnectListener) {
+        *self.disconnect.lock().expect("proxy disconnect lock") = Some(listener);
+    }
+
+    fn close(&self) {
+        if !self.running.swap(false, Ordering::Relaxed) {
+            return;
+        }
+        info!(
+            "[WPE] Closing proxy on {} -> {}",
+            self.listen, self.upstream
+        );
+        if let Some(connection) = self.current.lock().expect("proxy current lock").take() {
+            let _ = connection
+                .to_server
+                .lock()
+                .expect("proxy writer lock")
+                .shutdown(Shutdown::Both);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn forwards_injects_and_reports_upstream_close() {
+        // 回显服务器充当游戏服务器
+        let server = TcpListener::bind("127.0.0.1:0").unwrap();
+        let upstream = server.local_addr().unwrap();
+        let echo = std::thread::spawn(move || {
+            let (mut conn, _) = server.accept().unwrap();
+            let mut buf = [0u8; 64];
+            let mut received = Vec::new();
+            while received.len() < 9 {
+                let len = conn.read(&mut buf).unwrap();
+                conn.write_all(&buf[..len]).unwrap();
+                received.extend_from_slice(&buf[..len]);
+            }
+            received
+        });
+
+        let proxy = ProxyBackend::bind("127.0.0.1:0".parse().unwrap(), upstream).unwrap();
+        let (lost_tx, lost_rx) = mpsc::channel();
+        let lost_tx = Mutex::new(lost_tx);
+        proxy.set_disconnect_listener(Arc::new(move |reason| {
+            let _ = lost_tx.lock().unwrap().send(reason.to_string());
+        }));
+        let mut game = TcpStream::connect(proxy.local_addr()).unwrap();
+        game.write_all(b"hello").unwrap();
+
+        let outbound = proxy.recv().unwrap();
+        assert_eq!(outbound.direction, Direction::Outbound);
+        assert_eq!(outbound.data, b"hello");
+        let inbound = proxy.recv().unwrap();
+        assert_eq!(inbound.direction, Direction::Inbound);
+        assert_eq!(inbound.data, b"hello");
+        let mut reply = [0u8; 5];
+        game.read_exact(&mut reply).unwrap();
+        assert_eq!(&reply, b"hello");
+
+        proxy.send(Direction::Inbound, b"pong").unwrap();
+        game.read_exact(&mut reply[..4]).unwrap();
+        assert_eq!(&reply[..4], b"pong");
+        proxy.send(Direction::Outbound, b"ping").unwrap();
+        assert_eq!(echo.join().unwrap(), b"helloping");
+        // 服务器关闭连接后报告断线
+        let reason = lost_rx.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert_eq!(reason, "upstream closed the connection");
+
+        proxy.close();
+        assert!(matches!(proxy.recv(), Err(WpeError::NotRunning)));
+    }
+}
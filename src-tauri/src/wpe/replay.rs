@@ -0,0 +1,140 @@
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+use crate::wpe::sendlist::wait;
+use crate::wpe::{pcapng, BuildMode, CapturedPacket, Direction, PacketInterceptor, WpeError};
+
+// 录制回放：把 PacketInterceptor 录下的 pcapng 按原始时间间隔重新发出。
+// 只有出站封包（客户端 -> 服务器）可以重放，入站封包只用于对照，跳过。
+
+pub struct PacketReplayer {
+    packets: Vec<CapturedPacket>,
+}
+
+/// 回放结果统计
+#[derive(Debug, Clone, Copy, Default, serde::Serialize)]
+pub struct ReplaySummary {
+    pub sent: usize,
+    pub failed: usize,
+    pub skipped_inbound: usize,
+    /// 是否被提前停止
+    pub stopped: bool,
+}
+
+impl PacketReplayer {
+    pub fn open(path: &Path) -> Result<Self, WpeError> {
+        let file = std::fs::File::open(path)?;
+        let packets = pcapng::read_packets(std::io::BufReader::new(file))?;
+        info!(
+            "[WPE] Loaded {} recorded packets from {}",
+            packets.len(),
+            path.display()
+        );
+        Ok(Self::from_packets(packets))
+    }
+
+    pub fn from_packets(packets: Vec<CapturedPacket>) -> Self {
+        Self { packets }
+    }
+
+    /// 出站封包及其相对第一条出站封包的时间偏移
+    fn schedule(&self) -> Vec<(Duration, &CapturedPacket)> {
+        let mut outbound = self
+            .packets
+            .iter()
+            .filter(|p| p.direction == Direction::Outbound);
+        let Some(first) = outbound.next() else {
+            return Vec::new();
+        };
+        let base_us = first.timestamp_us;
+        std::iter::once(first)
+            .chain(outbound)
+            .map(|p| {
+                (
+                    Duration::from_micros(p.timestamp_us.saturating_sub(base_us)),
+                    p,
+                )
+            })
+            .collect()
+    }
+
+    /// 按原始时间间隔通过运行中的拦截器重放，`mode` 决定发出前是否修正或校验 length，
+    /// stop 置位后尽快返回
+    pub fn replay(
+        &self,
+        interceptor: &PacketInterceptor,
+        mode: BuildMode,
+        stop: &AtomicBool,
+    ) -> ReplaySummary {
+        self.replay_with(stop, |data| {
+            // 配置可能在回放中切换，每条都重新检查
+            crate::wpe::ensure_compatible_client()?;
+            interceptor.send_with(data, mode)
+        })
+    }
+
+    fn replay_with<F>(&self, stop: &AtomicBool, send: F) -> ReplaySummary
+    where
+        F: Fn(&[u8]) -> Result<(), WpeError>,
+    {
+        let schedule = self.schedule();
+        let mut summary = ReplaySummary {
+            skipped_inbound: self.packets.len() - schedule.len(),
+            ..Default::default()
+        };
+        let started = Instant::now();
+        for (offset, packet) in schedule {
+            let remaining = (started + offset).saturating_duration_since(Instant::now());
+            if !wait(remaining, stop) {
+                summary.stopped = true;
+                break;
+            }
+            match send(&packet.data) {
+                Ok(()) => summary.sent += 1,
+                Err(e) => {
+                    warn!("[WPE] Replay send failed: {}", e);
+                    summary.failed += 1;
+                }
+            }
+        }
+        if stop.load(Ordering::Relaxed) {
+            summary.stopped = true;
+        }
+        info!("[WPE] Replay finished: {:?}", summary);
+        summary
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    #[test]
+    fn replays_outbound_only_with_timing() {
+        let packet = |timestamp_us, direction, byte| CapturedPacket {
+            timestamp_us,
+            direction,
+            data: vec![byte],
+        };
+        let replayer = PacketReplayer::from_packets(vec![
+            packet(1_000_000, Direction::Inbound, 1),
+            packet(1_010_000, Direction::Outbound, 2),
+            packet(1_020_000, Direction::Inbound, 3),
+            packet(1_050_000, Direction::Outbound, 4),
+        ]);
+
+        let sent = Mutex::new(Vec::new());
+        let started = Instant::now();
+        let summary = replayer.replay_with(&AtomicBool::new(false), |data| {
+            sent.lock().unwrap().push(data[0]);
+            Ok(())
+        });
+
+        assert_eq!(*sent.lock().unwrap(), vec![2, 4]);
+        assert_eq!((summary.sent, summary.skipped_inbound), (2, 2));
+        assert!(started.elapsed() >= Duration::from_millis(40));
+    }
+}
@@ -0,0 +1,271 @@
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Weak};
+use std::time::{Duration, SystemTime};
+use tracing::{info, warn};
+
+use crate::wpe::{Direction, GamePacket, PacketAction, PacketInterceptor, WpeError};
+
+// 声明式封包规则
+//
+// 规则保存在 JSON 文件中（数组），按顺序匹配，第一条命中的规则生效。
+// 匹配条件全部为可选，未填写的条件视为通配：
+// - direction：inbound / outbound
+// - command、magic：二进制封包头字段
+// - bytes：payload（16 字节头之后；文本封包为整段文本）指定偏移处的字节
+// 字节一律写成十六进制字符串（如 "0B 00"），格式与粘贴导入相同。
+// 修改和丢弃只在拦截型后端上生效；WinDivert（SNIFF 模式）和本地代理都是旁路后端，
+// 原封包已经送达，这两种动作只记录警告，注入在本地代理上可用。
+
+/// 文件轮询间隔
+const WATCH_INTERVAL: Duration = Duration::from_secs(1);
+
+mod hex_bytes {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        let text: Vec<String> = bytes.iter().map(|b| format!("{b:02X}")).collect();
+        serializer.serialize_str(&text.join(" "))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        crate::wpe::hexdump::parse_hex(&text).map_err(serde::de::Error::custom)
+    }
+}
+
+/// payload 指定偏移处的字节
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct BytePattern {
+    pub offset: usize,
+    #[serde(with = "hex_bytes")]
+    pub value: Vec<u8>,
+}
+
+impl BytePattern {
+    fn matches(&self, payload: &[u8]) -> bool {
+        payload
+            .get(self.offset..self.offset + self.value.len())
+            .is_some_and(|slice| slice == self.value.as_slice())
+    }
+
+    /// 覆盖写入；超出 payload 范围时返回 false 且不修改
+    fn apply(&self, payload: &mut [u8]) -> bool {
+        match payload.get_mut(self.offset..self.offset + self.value.len()) {
+            Some(slice) => {
+                slice.copy_from_slice(&self.value);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum RuleAction {
+    /// 丢弃封包
+    Drop,
+    /// 按偏移覆盖 payload 字节
+    Modify { patches: Vec<BytePattern> },
+    /// 原封包照常转发，随后注入一条封包
+    Inject {
+        #[serde(with = "hex_bytes")]
+        packet: Vec<u8>,
+    },
+}
+
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct PacketRule {
+    pub name: String,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    #[serde(default)]
+    pub direction: Option<Direction>,
+    #[serde(default)]
+    pub command: Option<u16>,
+    #[serde(default)]
+    pub magic: Option<u16>,
+    #[serde(default)]
+    pub bytes: Vec<BytePattern>,
+    pub action: RuleAction,
+}
+
+fn payload(packet: &GamePacket) -> &[u8] {
+    match packet {
+        GamePacket::Binary { data, .. } => data,
+        GamePacket::Text(text) => text.as_bytes(),
+    }
+}
+
+impl PacketRule {
+    pub fn matches(&self, packet: &GamePacket, direction: Direction) -> bool {
+        if !self.enabled || self.direction.is_some_and(|d| d != direction) {
+            return false;
+        }
+        if self.command.is_some() || self.magic.is_some() {
+            let GamePacket::Binary { magic, command, .. } = packet else {
+                return false;
+            };
+            if self.command.is_some_and(|c| c != *command)
+                || self.magic.is_some_and(|m| m != *magic)
+            {
+                return false;
+            }
+        }
+        let payload = payload(packet);
+        self.bytes.iter().all(|pattern| pattern.matches(payload))
+    }
+
+    /// 把规则动作转成拦截器动作；动作无法执行（越界、注入内容无法解析）时返回 None
+    fn action_for(&self, packet: &GamePacket) -> Option<PacketAction> {
+        match &self.action {
+            RuleAction::Drop => Some(PacketAction::Drop),
+            RuleAction::Modify { patches } => {
+                let mut modified = packet.clone();
+                let data: &mut [u8] = match &mut modified {
+                    GamePacket::Binary { data, .. } => data,
+                    // 文本封包按字节修改后需要重新校验 UTF-8，简单起见不支持
+                    GamePacket::Text(_) => {
+                        warn!("[WPE] Rule {} cannot modify text packets", self.name);
+                        return None;
+                    }
+                };
+                if !patches.iter().all(|patch| patch.apply(data)) {
+                    warn!("[WPE] Rule {} patch out of range", self.name);
+                    return None;
+                }
+                Some(PacketAction::Modified(modified))
+            }
+            RuleAction::Inject { packet } => match GamePacket::parse(packet) {
+                Ok(inject) => Some(PacketAction::Inject(inject)),
+                Err(e) => {
+                    warn!("[WPE] Rule {} inject packet invalid: {}", self.name, e);
+                    None
+                }
+            },
+        }
+    }
+}
+
+/// 一组按顺序匹配的规则
+#[derive(Debug, Clone, Default)]
+pub struct RuleSet {
+    rules: Vec<PacketRule>,
+}
+
+impl RuleSet {
+    pub fn new(rules: Vec<PacketRule>) -> Self {
+        Self { rules }
+    }
+
+    pub fn from_json(text: &str) -> Result<Self, WpeError> {
+        let rules = serde_json::from_str(text)
+            .map_err(|e| WpeError::PacketParse(format!("Invalid rules: {e}")))?;
+        Ok(Self::new(rules))
+    }
+
+    pub fn rules(&self) -> &[PacketRule] {
+        &self.rules
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// 第一条命中且动作可执行的规则给出的动作，没有时为 None
+    pub fn evaluate(
+        &self,
+        packet: &GamePacket,
+        direction: Direction,
+    ) -> Option<(&str, PacketAction)> {
+        self.rules
+            .iter()
+            .filter(|rule| rule.matches(packet, direction))
+            .find_map(|rule| {
+                rule.action_for(packet)
+                    .map(|action| (rule.name.as_str(), action))
+            })
+    }
+}
+
+fn load(path: &Path) -> Result<RuleSet, WpeError> {
+    RuleSet::from_json(&std::fs::read_to_string(path)?)
+}
+
+/// 监视规则文件，修改后重新加载到拦截器；解析失败时保留上一次的规则
+///
+/// 文件不存在视为没有规则。拦截器释放后线程自动退出。
+pub fn watch_file(interceptor: &Arc<PacketInterceptor>, path: PathBuf) {
+    let interceptor: Weak<PacketInterceptor> = Arc::downgrade(interceptor);
+    std::thread::spawn(move || {
+        let mut loaded: Option<Option<SystemTime>> = None;
+        loop {
+            let Some(target) = interceptor.upgrade() else {
+                return;
+            };
+            if !target.is_running() {
+                return;
+            }
+            let modified = std::fs::metadata(&path).and_then(|m| m.modified()).ok();
+            if loaded != Some(modified) {
+                loaded = Some(modified);
+                match modified.map(|_| load(&path)) {
+                    None => target.set_rules(RuleSet::default()),
+                    Some(Ok(rules)) => {
+                        info!(
+                            "[WPE] Loaded {} packet rules from {}",
+                            rules.rules().len(),
+                            path.display()
+                        );
+                        target.set_rules(rules);
+                    }
+                    Some(Err(e)) => warn!("[WPE] Failed to load packet rules: {}", e),
+                }
+            }
+            drop(target);
+            std::thread::sleep(WATCH_INTERVAL);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn first_matching_rule_wins() {
+        let rules = RuleSet::from_json(
+            r#"[
+                {"name": "off", "enabled": false, "action": {"kind": "drop"}},
+                {"name": "patch", "direction": "outbound", "command": 3,
+                 "bytes": [{"offset": 7, "value": "02"}],
+                 "action": {"kind": "modify", "patches": [{"offset": 9, "value": "09"}]}},
+                {"name": "drop-text", "bytes": [{"offset": 0, "value": "53 79"}],
+                 "action": {"kind": "drop"}}
+            ]"#,
+        )
+        .unwrap();
+
+        let jump = GamePacket::build_map_jump(10001, 5);
+        let (name, action) = rules.evaluate(&jump, Direction::Outbound).unwrap();
+        assert_eq!(name, "patch");
+        match action {
+            PacketAction::Modified(GamePacket::Binary { data, .. }) => assert_eq!(data[9], 0x09),
+            other => panic!("unexpected action {other:?}"),
+        }
+        assert!(rules.evaluate(&jump, Direction::Inbound).is_none());
+
+        let text = GamePacket::Text("System_msg".to_string());
+        let (name, action) = rules.evaluate(&text, Direction::Inbound).unwrap();
+        assert_eq!(name, "drop-text");
+        assert!(matches!(action, PacketAction::Drop));
+
+        // 字节以十六进制字符串往返
+        let json = serde_json::to_string(&rules.rules()[1].bytes).unwrap();
+        assert_eq!(json, r#"[{"offset":7,"value":"02"}]"#);
+    }
+}
@@ -0,0 +1,231 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+use crate::wpe::{GamePacket, WpeError};
+
+/// 发送列表中的一项（保存原始字节，发送时再解析为 GamePacket）
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct SendListEntry {
+    pub name: String,
+    pub data: Vec<u8>,
+}
+
+impl SendListEntry {
+    pub fn packet(&self) -> Result<GamePacket, WpeError> {
+        GamePacket::parse(&self.data)
+    }
+}
+
+/// 发送计划：按顺序发送选中的条目（一轮），再按计划决定是否重复
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SendSchedule {
+    /// 只发送一轮
+    OneShot,
+    /// 每隔 interval_ms 发送一轮，共 times 轮
+    Interval { interval_ms: u64, times: u32 },
+    /// 每隔 interval_ms 发送一轮，直到手动停止
+    Loop { interval_ms: u64 },
+}
+
+impl SendSchedule {
+    fn rounds(&self) -> Option<u32> {
+        match self {
+            SendSchedule::OneShot => Some(1),
+            SendSchedule::Interval { times, .. } => Some(*times),
+            SendSchedule::Loop { .. } => None,
+        }
+    }
+
+    fn interval(&self) -> Duration {
+        match self {
+            SendSchedule::OneShot => Duration::ZERO,
+            SendSchedule::Interval { interval_ms, .. } | SendSchedule::Loop { interval_ms } => {
+                Duration::from_millis(*interval_ms)
+            }
+        }
+    }
+}
+
+/// 两轮之间的最小间隔，避免误配置成 0 时刷屏
+const MIN_INTERVAL: Duration = Duration::from_millis(100);
+
+/// 正在执行的发送任务
+#[derive(Debug)]
+struct ReplayJob {
+    stop: Arc<AtomicBool>,
+    thread: JoinHandle<()>,
+}
+
+/// WPE 风格的发送列表
+#[derive(Debug, Default)]
+pub struct SendList {
+    entries: Vec<SendListEntry>,
+    job: Option<ReplayJob>,
+}
+
+impl SendList {
+    /// 添加或替换同名条目
+    pub fn upsert(&mut self, name: String, data: Vec<u8>) {
+        match self.entries.iter_mut().find(|e| e.name == name) {
+            Some(entry) => entry.data = data,
+            None => self.entries.push(SendListEntry { name, data }),
+        }
+    }
+
+    pub fn remove(&mut self, name: &str) -> bool {
+        let before = self.entries.len();
+        self.entries.retain(|e| e.name != name);
+        self.entries.len() != before
+    }
+
+    pub fn get(&self, name: &str) -> Option<&SendListEntry> {
+        self.entries.iter().find(|e| e.name == name)
+    }
+
+    pub fn entries(&self) -> &[SendListEntry] {
+        &self.entries
+    }
+
+    /// 生成不与现有条目重名的默认名称
+    pub fn next_name(&self, prefix: &str) -> String {
+        (1..)
+            .map(|n| format!("{prefix} {n}"))
+            .find(|name| self.get(name).is_none())
+            .expect("unbounded range")
+    }
+    /// 按计划在后台线程发送选中的条目（names 为空时发送全部）
+    ///
+    /// 已有任务在运行时先停止旧任务。`send` 负责真正发出字节（通常转给拦截器的后端）。
+    pub fn start<F>(
+        &mut self,
+        names: &[String],
+        schedule: SendSchedule,
+        send: F,
+    ) -> Result<(), WpeError>
+    where
+        F: Fn(&[u8]) -> Result<(), WpeError> + Send + 'static,
+    {
+        if crate::wpe::clean_mode() {
+            return Err(WpeError::Disabled("Send list replay"));
+        }
+        crate::wpe::ensure_compatible_client()?;
+        let packets: Vec<SendListEntry> = if names.is_empty() {
+            self.entries.clone()
+        } else {
+            names
+                .iter()
+                .map(|name| {
+                    self.get(name).cloned().ok_or_else(|| {
+                        WpeError::PacketBuild(format!("Send list entry not found: {name}"))
+                    })
+                })
+                .collect::<Result<_, _>>()?
+        };
+        if packets.is_empty() {
+            return Err(WpeError::PacketBuild("Send list is empty".to_string()));
+        }
+
+        self.stop();
+        let stop = Arc::new(AtomicBool::new(false));
+        let stop_flag = stop.clone();
+        let thread = std::thread::spawn(move || run_schedule(&packets, schedule, &stop_flag, send));
+        self.job = Some(ReplayJob { stop, thread });
+        info!("[WPE] Send list started: {:?}", schedule);
+        Ok(())
+    }
+
+    /// 停止正在执行的发送任务，返回是否有任务被停止
+    pub fn stop(&mut self) -> bool {
+        let Some(job) = self.job.take() else {
+            return false;
+        };
+        job.stop.store(true, Ordering::Relaxed);
+        let _ = job.thread.join();
+        info!("[WPE] Send list stopped");
+        true
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.job
+            .as_ref()
+            .is_some_and(|job| !job.thread.is_finished())
+    }
+}
+
+/// 可被 stop 打断的等待，返回 false 表示已被停止
+pub(crate) fn wait(duration: Duration, stop: &AtomicBool) -> bool {
+    let until = Instant::now() + duration;
+    while Instant::now() < until {
+        if stop.load(Ordering::Relaxed) {
+            return false;
+        }
+        let remaining = until.saturating_duration_since(Instant::now());
+        std::thread::sleep(remaining.min(Duration::from_millis(50)));
+    }
+    !stop.load(Ordering::Relaxed)
+}
+
+fn run_schedule<F>(packets: &[SendListEntry], schedule: SendSchedule, stop: &AtomicBool, send: F)
+where
+    F: Fn(&[u8]) -> Result<(), WpeError>,
+{
+    let interval = schedule.interval().max(MIN_INTERVAL);
+    let mut round = 0u32;
+    while schedule.rounds().is_none_or(|rounds| round < rounds) {
+        if round > 0 && !wait(interval, stop) {
+            return;
+        }
+        for entry in packets {
+            if stop.load(Ordering::Relaxed) {
+                return;
+            }
+            if let Err(e) = send(&entry.data) {
+                // 后端不可用时继续重试没有意义，直接结束任务
+                warn!("[WPE] Send list entry {} failed: {}", entry.name, e);
+                return;
+            }
+        }
+        round += 1;
+    }
+    info!("[WPE] Send list finished after {} round(s)", round);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    #[test]
+    fn interval_schedule_sends_each_round_in_order() {
+        let mut list = SendList::default();
+        list.upsert("a".to_string(), vec![1]);
+        list.upsert("b".to_string(), vec![2]);
+
+        let sent = Arc::new(Mutex::new(Vec::new()));
+        let sink = sent.clone();
+        list.start(
+            &[],
+            SendSchedule::Interval {
+                interval_ms: 0,
+                times: 2,
+            },
+            move |data| {
+                sink.lock().unwrap().push(data[0]);
+                Ok(())
+            },
+        )
+        .unwrap();
+
+        while list.is_running() {
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        assert_eq!(*sent.lock().unwrap(), vec![1, 2, 1, 2]);
+        assert!(list
+            .start(&["missing".to_string()], SendSchedule::OneShot, |_| Ok(()))
+            .is_err());
+    }
+}
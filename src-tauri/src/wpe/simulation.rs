@@ -0,0 +1,150 @@
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tracing::info;
+
+use crate::wpe::backend::CaptureBackend;
+use crate::wpe::{pcapng, CapturedPacket, Direction, WpeError};
+
+// 仿真后端：按原始时间间隔回放录制的 pcapng，
+// 让没有游戏或驱动的机器也能开发、调试封包功能。
+// 发送的封包不会真正发出，只记录数量。
+
+pub struct SimulationBackend {
+    packets: Vec<CapturedPacket>,
+    looped: bool,
+    cursor: Mutex<Replay>,
+    running: AtomicBool,
+    sent: AtomicUsize,
+}
+
+struct Replay {
+    index: usize,
+    started: Instant,
+    /// 本轮第一条封包的时间戳（微秒）
+    base_us: u64,
+}
+
+impl SimulationBackend {
+    pub fn open(path: &Path, looped: bool) -> Result<Self, WpeError> {
+        let file = std::fs::File::open(path)?;
+        let packets = pcapng::read_packets(std::io::BufReader::new(file))?;
+        info!(
+            "[WPE] Simulation backend loaded {} packets from {}",
+            packets.len(),
+            path.display()
+        );
+        Ok(Self::from_packets(packets, looped))
+    }
+
+    pub fn from_packets(packets: Vec<CapturedPacket>, looped: bool) -> Self {
+        let base_us = packets.first().map(|p| p.timestamp_us).unwrap_or(0);
+        Self {
+            packets,
+            looped,
+            cursor: Mutex::new(Replay {
+                index: 0,
+                started: Instant::now(),
+                base_us,
+            }),
+            running: AtomicBool::new(true),
+            sent: AtomicUsize::new(0),
+        }
+    }
+
+    /// 通过 send 收到的封包数（不会真正发出）
+    pub fn sent_count(&self) -> usize {
+        self.sent.load(Ordering::Relaxed)
+    }
+}
+
+impl CaptureBackend for SimulationBackend {
+    fn name(&self) -> &'static str {
+        "simulation"
+    }
+
+    fn recv(&self) -> Result<CapturedPacket, WpeError> {
+        let (packet, due) = {
+            let mut replay = self.cursor.lock().expect("simulation cursor lock");
+            if replay.index >= self.packets.len() {
+                if !self.looped || self.packets.is_empty() {
+                    self.running.store(false, Ordering::Relaxed);
+                    return Err(WpeError::NotRunning);
+                }
+                replay.index = 0;
+                replay.started = Instant::now();
+            }
+            let packet = self.packets[replay.index].clone();
+            let offset_us = packet.timestamp_us.saturating_sub(replay.base_us);
+            replay.index += 1;
+            (packet, replay.started + Duration::from_micros(offset_us))
+        };
+
+        // 分段等待，保证 close() 能及时生效
+        while Instant::now() < due {
+            if !self.running.load(Ordering::Relaxed) {
+                return Err(WpeError::NotRunning);
+            }
+            let remaining = due.saturating_duration_since(Instant::now());
+            std::thread::sleep(remaining.min(Duration::from_millis(50)));
+        }
+        if !self.running.load(Ordering::Relaxed) {
+            return Err(WpeError::NotRunning);
+        }
+
+        // 时间戳改为回放时刻，下游看到的就是"实时"流量
+        Ok(CapturedPacket::now(packet.direction, packet.data))
+    }
+
+    fn send(&self, _direction: Direction, data: &[u8]) -> Result<(), WpeError> {
+        if !self.running.load(Ordering::Relaxed) {
+            return Err(WpeError::NotRunning);
+        }
+        self.sent.fetch_add(1, Ordering::Relaxed);
+        info!("[WPE] Simulation send: {} bytes (not sent)", data.len());
+        Ok(())
+    }
+
+    /// 回放的封包来自录制文件，没有需要转发的目标
+    fn passive(&self) -> bool {
+        true
+    }
+
+    fn close(&self) {
+        if self.running.swap(false, Ordering::Relaxed) {
+            info!(
+                "[WPE] Simulation backend closed ({} packets sent)",
+                self.sent_count()
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn replays_in_order_then_stops() {
+        let packets = vec![
+            CapturedPacket {
+                timestamp_us: 1_000,
+                direction: Direction::Inbound,
+                data: vec![1],
+            },
+            CapturedPacket {
+                timestamp_us: 3_000,
+                direction: Direction::Outbound,
+                data: vec![2],
+            },
+        ];
+        let backend = SimulationBackend::from_packets(packets, false);
+        let first = backend.recv().expect("first");
+        let second = backend.recv().expect("second");
+        assert_eq!(first.data, vec![1]);
+        assert_eq!(second.data, vec![2]);
+        assert_eq!(second.direction, Direction::Outbound);
+        assert!(matches!(backend.recv(), Err(WpeError::NotRunning)));
+    }
+}
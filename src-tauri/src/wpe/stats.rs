@@ -0,0 +1,191 @@
+use std::collections::{HashMap, VecDeque};
+
+use crate::wpe::commands;
+use crate::wpe::packet::BINARY_MAGIC;
+use crate::wpe::pipeline::PipelineStats;
+use crate::wpe::{CapturedPacket, Direction};
+
+// 封包统计
+//
+// 按命令号统计出入站的封包数和字节数，并按秒记录最近的流量样本，
+// 用于找出战斗中刷屏的命令、调整过滤规则。时间取封包自带的时间戳，仿真回放同样适用。
+
+/// 保留最近多少秒的流量样本
+const SAMPLE_SECONDS: usize = 60;
+
+/// 计算平均速率使用的窗口（秒，不含当前未结束的一秒）
+const RATE_WINDOW_SECS: u64 = 5;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Serialize)]
+pub struct Traffic {
+    pub packets_in: u64,
+    pub packets_out: u64,
+    pub bytes_in: u64,
+    pub bytes_out: u64,
+}
+
+impl Traffic {
+    fn add(&mut self, direction: Direction, bytes: usize) {
+        match direction {
+            Direction::Inbound => {
+                self.packets_in += 1;
+                self.bytes_in += bytes as u64;
+            }
+            Direction::Outbound => {
+                self.packets_out += 1;
+                self.bytes_out += bytes as u64;
+            }
+        }
+    }
+
+    fn packets(&self) -> u64 {
+        self.packets_in + self.packets_out
+    }
+}
+
+/// 一秒内的流量
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize)]
+pub struct RateSample {
+    /// Unix 时间戳（秒）
+    pub second: u64,
+    #[serde(flatten)]
+    pub traffic: Traffic,
+}
+
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct CommandStats {
+    /// 命令号，文本封包为 None
+    pub command: Option<u16>,
+    pub name: Option<&'static str>,
+    #[serde(flatten)]
+    pub traffic: Traffic,
+}
+
+/// `get_wpe_stats` 的返回值
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct WpeStats {
+    #[serde(flatten)]
+    pub total: Traffic,
+    /// 最近 RATE_WINDOW_SECS 秒的平均封包速率
+    pub packets_per_sec: f64,
+    /// 按封包数从多到少排列
+    pub commands: Vec<CommandStats>,
+    /// 最近的每秒样本（按时间顺序）
+    pub samples: Vec<RateSample>,
+    /// 处理流水线的排队情况（由拦截器填入）
+    pub pipeline: PipelineStats,
+}
+
+#[derive(Debug, Default)]
+pub struct PacketStats {
+    total: Traffic,
+    commands: HashMap<Option<u16>, Traffic>,
+    samples: VecDeque<RateSample>,
+}
+
+/// 只读头部取命令号，避免为统计解析整个封包
+fn command_of(data: &[u8]) -> Option<u16> {
+    if data.len() < 16 || u16::from_le_bytes([data[0], data[1]]) != BINARY_MAGIC {
+        return None;
+    }
+    Some(u16::from_le_bytes([data[6], data[7]]))
+}
+
+impl PacketStats {
+    pub fn record(&mut self, packet: &CapturedPacket) {
+        let bytes = packet.data.len();
+        self.total.add(packet.direction, bytes);
+        self.commands
+            .entry(command_of(&packet.data))
+            .or_default()
+            .add(packet.direction, bytes);
+
+        let second = packet.timestamp_us / 1_000_000;
+        match self.samples.back_mut() {
+            Some(sample) if sample.second == second => sample.traffic.add(packet.direction, bytes),
+            // 时间戳回退（如仿真回放重新开始）时也新开一个样本
+            _ => {
+                let mut traffic = Traffic::default();
+                traffic.add(packet.direction, bytes);
+                self.samples.push_back(RateSample { second, traffic });
+                if self.samples.len() > SAMPLE_SECONDS {
+                    self.samples.pop_front();
+                }
+            }
+        }
+    }
+
+    /// 统计快照；`now_secs` 为当前 Unix 秒，用于计算速率
+    pub fn snapshot(&self, now_secs: u64) -> WpeStats {
+        let window = now_secs.saturating_sub(RATE_WINDOW_SECS)..now_secs;
+        let recent: u64 = self
+            .samples
+            .iter()
+            .filter(|sample| window.contains(&sample.second))
+            .map(|sample| sample.traffic.packets())
+            .sum();
+
+        let mut commands: Vec<CommandStats> = self
+            .commands
+            .iter()
+            .map(|(command, traffic)| CommandStats {
+                command: *command,
+                name: command.and_then(commands::lookup).map(|info| info.name),
+                traffic: *traffic,
+            })
+            .collect();
+        commands.sort_by(|a, b| {
+            b.traffic
+                .packets()
+                .cmp(&a.traffic.packets())
+                .then(a.command.cmp(&b.command))
+        });
+
+        WpeStats {
+            total: self.total,
+            packets_per_sec: recent as f64 / RATE_WINDOW_SECS as f64,
+            commands,
+            samples: self.samples.iter().copied().collect(),
+            pipeline: PipelineStats::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wpe::GamePacket;
+
+    fn packet(second: u64, direction: Direction, data: Vec<u8>) -> CapturedPacket {
+        CapturedPacket {
+            timestamp_us: second * 1_000_000 + 500,
+            direction,
+            data,
+        }
+    }
+
+    #[test]
+    fn counts_commands_and_rates() {
+        let jump = GamePacket::build_map_jump(10001, 5).build().unwrap();
+        let text = b"System_hello".to_vec();
+        let mut stats = PacketStats::default();
+        for second in 100..110 {
+            stats.record(&packet(second, Direction::Outbound, jump.clone()));
+            stats.record(&packet(second, Direction::Outbound, jump.clone()));
+            stats.record(&packet(second, Direction::Inbound, text.clone()));
+        }
+
+        let snapshot = stats.snapshot(110);
+        assert_eq!(snapshot.total.packets_out, 20);
+        assert_eq!(snapshot.total.bytes_in, 10 * text.len() as u64);
+        assert_eq!(snapshot.commands[0].command, Some(0x0003));
+        assert_eq!(snapshot.commands[0].name, Some("map_jump"));
+        assert_eq!(snapshot.commands[0].traffic.packets_out, 20);
+        assert_eq!(snapshot.commands[1].command, None);
+        assert_eq!(snapshot.samples.len(), 10);
+        assert_eq!(snapshot.packets_per_sec, 3.0);
+
+        // 长时间没有流量后速率归零
+        assert_eq!(stats.snapshot(200).packets_per_sec, 0.0);
+    }
+}
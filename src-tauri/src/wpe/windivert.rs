@@ -1,63 +1,340 @@
+use std::collections::{HashSet, VecDeque};
+use std::ops::Range;
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use tracing::{info, warn};
 
-// NOTE: This is a mock implementation of WinDivert.
-// In production, this should be replaced with actual WinDivert integration.
-// WinDivert requires:
-// 1. Administrator privileges (UAC elevation)
-// 2. WinDivert driver installation
-// 3. Proper filter string: "tcp and processId == {pid}"
-// 4. Packet capture and injection logic
+use crate::wpe::backend::{CaptureBackend, DisconnectListener};
+use crate::wpe::{CapturedPacket, Direction, WpeError};
+
+// WinDivert 捕获后端
+//
+// WinDivert.dll / WinDivert64.sys 随程序分发，运行时动态加载（没有驱动时只影响 WPE，不影响启动）。
+// 要求：
+// 1. 管理员权限（加载驱动），未提权时 open 直接返回错误
+// 2. NETWORK 层无法按进程过滤，因此定期通过 GetExtendedTcpTable 取得目标 PID 的本地端口，
+//    在用户态只保留这些端口上的 TCP 报文
+// 3. 默认以 SNIFF 模式打开：只复制不拦截，游戏流量不会因处理延迟受影响，规则和 handler 的修改、丢弃都不会生效
+// 4. 配置 capture.windivert_inline 打开时以拦截模式打开（旁观模式除外）：其他进程的报文立即原样放回，
+//    目标进程带 payload 的报文暂存，拦截器处理完后经 forward 用截获时的地址 WinDivertSend 放行。
+//    只支持等长修改（规则的 modify 按偏移覆盖）：改变长度、丢弃或注入新报文都会让 TCP 序号与对端错位，
+//    连接随之中断，因此这些操作放行原报文并返回 Unsupported
 //
-// For now, this mock logs the intent and allows the feature system to work
-// without actual packet interception.
+// 交给 handler 的是 TCP payload（一个报文段），不做流重组。
+// 服务器发来的 RST/FIN 不含 payload，不交给 handler，只作为断线信号通知拦截器。
+
+/// 端口表刷新间隔
+const PORT_REFRESH_INTERVAL: Duration = Duration::from_secs(2);
+/// 遇到未知端口时的最短刷新间隔
+const PORT_MISS_REFRESH: Duration = Duration::from_millis(250);
+/// 单个 IP 报文最大长度
+const MAX_PACKET_SIZE: usize = 0xFFFF;
+
+const TCP_FIN: u8 = 0x01;
+const TCP_RST: u8 = 0x04;
+
+/// 从 IPv4 报文中取出 TCP 端口、标志位和 payload 的范围
+///
+/// 返回 (源端口, 目的端口, 标志位, payload 范围)，payload 可能为空；非 IPv4/TCP 时为 None。
+fn tcp_segment(frame: &[u8]) -> Option<(u16, u16, u8, Range<usize>)> {
+    if frame.len() < 20 || frame[0] >> 4 != 4 || frame[9] != 6 {
+        return None;
+    }
+    let ip_header = ((frame[0] & 0x0F) as usize) * 4;
+    let total_len = (u16::from_be_bytes([frame[2], frame[3]]) as usize).min(frame.len());
+    if total_len < ip_header + 20 {
+        return None;
+    }
+    let tcp = &frame[ip_header..total_len];
+    let src_port = u16::from_be_bytes([tcp[0], tcp[1]]);
+    let dst_port = u16::from_be_bytes([tcp[2], tcp[3]]);
+    let tcp_header = ((tcp[12] >> 4) as usize) * 4;
+    if tcp_header < 20 || tcp.len() < tcp_header {
+        return None;
+    }
+    Some((
+        src_port,
+        dst_port,
+        tcp[13],
+        ip_header + tcp_header..total_len,
+    ))
+}
+
+/// 用改写后的 payload 覆盖报文中的原 payload；长度不同时不改动报文
+fn overwrite_payload(frame: &mut [u8], payload: Range<usize>, data: &[u8]) -> Result<(), WpeError> {
+    if payload.len() != data.len() {
+        return Err(WpeError::Unsupported(format!(
+            "Changing the payload length ({} -> {} bytes) on the WinDivert backend",
+            payload.len(),
+            data.len()
+        )));
+    }
+    frame[payload].copy_from_slice(data);
+    Ok(())
+}
+
+/// 拦截模式下等待放行的报文
+struct HeldFrame {
+    frame: Vec<u8>,
+    payload: Range<usize>,
+    #[cfg(target_os = "windows")]
+    addr: sys::Address,
+}
+
+fn queue_index(direction: Direction) -> usize {
+    match direction {
+        Direction::Inbound => 0,
+        Direction::Outbound => 1,
+    }
+}
 
 pub struct WinDivertHandle {
     pid: u32,
     running: Arc<AtomicBool>,
+    ports: Mutex<(HashSet<u16>, Option<Instant>)>,
+    disconnect: Mutex<Option<DisconnectListener>>,
+    /// 拦截模式：目标进程的报文由 forward/discard 放行
+    inline: bool,
+    /// 拦截模式下等待放行的报文，每个方向按截获顺序排列
+    held: Mutex<[VecDeque<HeldFrame>; 2]>,
+    #[cfg(target_os = "windows")]
+    raw: sys::RawHandle,
 }
 
 impl WinDivertHandle {
-    pub fn open(pid: u32) -> Result<Self, crate::wpe::WpeError> {
-        info!("[WPE] Opening WinDivert for PID {} (MOCK)", pid);
-
-        // Note: Actual WinDivert implementation would go here
-        // For now, we create a placeholder that logs the intent
-
+    #[cfg(target_os = "windows")]
+    pub fn open(pid: u32, inline: bool) -> Result<Self, WpeError> {
+        info!(
+            "[WPE] Opening WinDivert for PID {} (inline={})",
+            pid, inline
+        );
+        if !sys::is_elevated() {
+            return Err(WpeError::WinDivert(
+                "WinDivert requires administrator privileges".to_string(),
+            ));
+        }
+        let raw = sys::RawHandle::open(
+            "tcp and (tcp.PayloadLength > 0 or tcp.Rst or tcp.Fin)",
+            !inline,
+        )?;
         Ok(Self {
             pid,
             running: Arc::new(AtomicBool::new(true)),
+            ports: Mutex::new((HashSet::new(), None)),
+            disconnect: Mutex::new(None),
+            inline,
+            held: Mutex::new([VecDeque::new(), VecDeque::new()]),
+            raw,
         })
     }
 
-    pub fn recv(&self) -> Result<Vec<u8>, crate::wpe::WpeError> {
-        if !self.running.load(Ordering::Relaxed) {
-            return Err(crate::wpe::WpeError::NotRunning);
+    #[cfg(not(target_os = "windows"))]
+    pub fn open(pid: u32, inline: bool) -> Result<Self, WpeError> {
+        let _ = (pid, inline, &MAX_PACKET_SIZE);
+        Err(WpeError::WinDivert(
+            "WinDivert is only available on Windows".to_string(),
+        ))
+    }
+
+    /// 取出截获 `captured` 时暂存的报文；排在它前面、没有等到处理结果的报文原样放行
+    fn release(&self, captured: &CapturedPacket) -> Result<HeldFrame, WpeError> {
+        let mut held = self.held.lock().expect("held frames lock");
+        let queue = &mut held[queue_index(captured.direction)];
+        let position = queue
+            .iter()
+            .position(|frame| frame.frame[frame.payload.clone()] == captured.data[..])
+            .ok_or_else(|| WpeError::WinDivert("Captured packet is no longer held".to_string()))?;
+        let skipped: Vec<HeldFrame> = queue.drain(..position).collect();
+        let frame = queue.pop_front().expect("held frame at position");
+        drop(held);
+        for mut skipped in skipped {
+            warn!("[WPE] Releasing held packet without a verdict");
+            let _ = self.reinject(&mut skipped, false);
         }
+        Ok(frame)
+    }
 
-        // Placeholder: In real implementation, this would call WinDivert recv
-        // For now, we return an error to indicate no packet available
-        Err(crate::wpe::WpeError::NotRunning)
+    /// 放行暂存的报文；`modified` 时重新计算校验和
+    #[cfg(target_os = "windows")]
+    fn reinject(&self, held: &mut HeldFrame, modified: bool) -> Result<(), WpeError> {
+        self.raw.send(&mut held.frame, &mut held.addr, modified)
     }
 
-    pub fn send(&self, data: &[u8]) -> Result<(), crate::wpe::WpeError> {
-        if !self.running.load(Ordering::Relaxed) {
-            return Err(crate::wpe::WpeError::NotRunning);
+    #[cfg(not(target_os = "windows"))]
+    fn reinject(&self, _held: &mut HeldFrame, _modified: bool) -> Result<(), WpeError> {
+        Err(WpeError::NotRunning)
+    }
+
+    /// 不交给拦截器的报文：拦截模式下立即原样放回
+    #[cfg(target_os = "windows")]
+    fn pass(&self, held: &mut HeldFrame) {
+        if self.inline {
+            if let Err(e) = self.reinject(held, false) {
+                warn!("[WPE] Failed to reinject packet: {}", e);
+            }
+        }
+    }
+
+    /// 报文是否属于目标进程（按本地端口判断，端口表定期刷新）
+    fn owns_port(&self, port: u16) -> bool {
+        let mut guard = self.ports.lock().expect("ports lock");
+        // 新连接的端口不在表中时提前刷新，但限制频率避免其它进程的流量反复触发
+        let known = guard.0.contains(&port);
+        let refresh = guard.1.is_none_or(|at| {
+            let elapsed = at.elapsed();
+            elapsed >= PORT_REFRESH_INTERVAL || (!known && elapsed >= PORT_MISS_REFRESH)
+        });
+        if refresh {
+            guard.0 = local_ports(self.pid);
+            guard.1 = Some(Instant::now());
         }
+        guard.0.contains(&port)
+    }
 
-        info!(
-            "[WPE] Injecting packet: {} bytes (MOCK - not actually sent)",
+    #[cfg(target_os = "windows")]
+    fn notify_disconnect(&self, reason: &str) {
+        let listener = self.disconnect.lock().expect("disconnect lock").clone();
+        if let Some(listener) = listener {
+            listener(reason);
+        }
+    }
+}
+
+impl CaptureBackend for WinDivertHandle {
+    fn name(&self) -> &'static str {
+        "windivert"
+    }
+
+    #[cfg(target_os = "windows")]
+    fn recv(&self) -> Result<CapturedPacket, WpeError> {
+        let mut buf = vec![0u8; MAX_PACKET_SIZE];
+        loop {
+            if !self.running.load(Ordering::Relaxed) {
+                return Err(WpeError::NotRunning);
+            }
+            let (len, addr) = match self.raw.recv(&mut buf) {
+                Ok(result) => result,
+                // close() 会 shutdown 句柄，阻塞中的 recv 随之返回错误
+                Err(_) if !self.running.load(Ordering::Relaxed) => {
+                    return Err(WpeError::NotRunning)
+                }
+                Err(e) => return Err(e),
+            };
+            let outbound = addr.outbound();
+            let mut held = HeldFrame {
+                frame: buf[..len].to_vec(),
+                payload: 0..0,
+                addr,
+            };
+            let Some((src_port, dst_port, flags, payload)) = tcp_segment(&held.frame) else {
+                self.pass(&mut held);
+                continue;
+            };
+            let local_port = if outbound { src_port } else { dst_port };
+            if !self.owns_port(local_port) {
+                self.pass(&mut held);
+                continue;
+            }
+            // 游戏自己关闭连接（退出、切服）时 FIN 是出站的，只有服务器发起的才算断线
+            if !outbound && flags & (TCP_RST | TCP_FIN) != 0 {
+                let reason = if flags & TCP_RST != 0 {
+                    "server reset the connection"
+                } else {
+                    "server closed the connection"
+                };
+                self.notify_disconnect(&format!("{reason} (port {local_port})"));
+            }
+            if payload.is_empty() {
+                self.pass(&mut held);
+                continue;
+            }
+            let direction = if outbound {
+                Direction::Outbound
+            } else {
+                Direction::Inbound
+            };
+            let packet = CapturedPacket::now(direction, held.frame[payload.clone()].to_vec());
+            if self.inline {
+                held.payload = payload;
+                self.held.lock().expect("held frames lock")[queue_index(direction)].push_back(held);
+            }
+            return Ok(packet);
+        }
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    fn recv(&self) -> Result<CapturedPacket, WpeError> {
+        Err(WpeError::NotRunning)
+    }
+
+    fn send(&self, _direction: Direction, data: &[u8]) -> Result<(), WpeError> {
+        if !self.running.load(Ordering::Relaxed) {
+            return Err(WpeError::NotRunning);
+        }
+        // 插入新的报文段需要改写之后整条连接的 TCP 序号，这里不做
+        warn!(
+            "[WPE] Injection not supported by the WinDivert backend ({} bytes)",
             data.len()
         );
+        Err(WpeError::Unsupported(
+            "Packet injection on the WinDivert backend".to_string(),
+        ))
+    }
+
+    /// 用截获时的地址放行报文；改写后长度不同时放行原报文并返回错误
+    fn forward(&self, captured: &CapturedPacket, data: &[u8]) -> Result<(), WpeError> {
+        if !self.inline {
+            return self.send(captured.direction, data);
+        }
+        let mut held = self.release(captured)?;
+        let modified = data != &captured.data[..];
+        let result = if modified {
+            overwrite_payload(&mut held.frame, held.payload.clone(), data)
+        } else {
+            Ok(())
+        };
+        self.reinject(&mut held, modified && result.is_ok())?;
+        result
+    }
+
+    /// 丢弃的报文段会被对端一直重传，连接随之卡住，因此放行原报文
+    fn discard(&self, captured: &CapturedPacket) -> Result<(), WpeError> {
+        if !self.inline {
+            return Ok(());
+        }
+        let mut held = self.release(captured)?;
+        self.reinject(&mut held, false)?;
+        Err(WpeError::Unsupported(
+            "Dropping packets on the WinDivert backend".to_string(),
+        ))
+    }
 
-        // Placeholder: In real implementation, this would call WinDivert send
-        Ok(())
+    /// SNIFF 模式只复制流量，原封包由系统照常发送
+    fn passive(&self) -> bool {
+        !self.inline
     }
 
-    pub fn close(&self) {
-        info!("[WPE] Closing WinDivert for PID {} (MOCK)", self.pid);
-        self.running.store(false, Ordering::Relaxed);
+    fn can_send(&self) -> bool {
+        false
+    }
+
+    fn set_disconnect_listener(&self, listener: DisconnectListener) {
+        *self.disconnect.lock().expect("disconnect lock") = Some(listener);
+    }
+
+    fn close(&self) {
+        if !self.running.swap(false, Ordering::Relaxed) {
+            return;
+        }
+        info!("[WPE] Closing WinDivert for PID {}", self.pid);
+        // 关闭前放行仍在等待处理的报文，否则它们随句柄一起丢失
+        let held = std::mem::take(&mut *self.held.lock().expect("held frames lock"));
+        for mut frame in held.into_iter().flatten() {
+            let _ = self.reinject(&mut frame, false);
+        }
+        #[cfg(target_os = "windows")]
+        self.raw.shutdown();
     }
 }
 
@@ -66,3 +343,334 @@ impl Drop for WinDivertHandle {
         self.close();
     }
 }
+
+/// 当前进程是否以管理员身份运行
+pub fn is_elevated() -> bool {
+    #[cfg(target_os = "windows")]
+    {
+        sys::is_elevated()
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        false
+    }
+}
+
+/// WinDivert.dll 是否可加载
+pub fn driver_available() -> bool {
+    #[cfg(target_os = "windows")]
+    {
+        sys::api().is_ok()
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        false
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn local_ports(pid: u32) -> HashSet<u16> {
+    sys::tcp_local_ports(pid)
+}
+
+#[cfg(not(target_os = "windows"))]
+fn local_ports(_pid: u32) -> HashSet<u16> {
+    HashSet::new()
+}
+
+#[cfg(target_os = "windows")]
+mod sys {
+    use std::collections::HashSet;
+    use std::ffi::c_void;
+    use std::sync::OnceLock;
+
+    use windows::core::{PCSTR, PCWSTR};
+    use windows::Win32::Foundation::{CloseHandle, HANDLE};
+    use windows::Win32::NetworkManagement::IpHelper::{
+        GetExtendedTcpTable, MIB_TCPROW_OWNER_PID, TCP_TABLE_OWNER_PID_ALL,
+    };
+    use windows::Win32::Security::{
+        GetTokenInformation, TokenElevation, TOKEN_ELEVATION, TOKEN_QUERY,
+    };
+    use windows::Win32::System::LibraryLoader::{GetProcAddress, LoadLibraryW};
+    use windows::Win32::System::Threading::{GetCurrentProcess, OpenProcessToken};
+
+    use crate::wpe::WpeError;
+
+    const WINDIVERT_LAYER_NETWORK: i32 = 0;
+    const WINDIVERT_FLAG_SNIFF: u64 = 0x0001;
+    const WINDIVERT_SHUTDOWN_BOTH: i32 = 0x3;
+    const INVALID_HANDLE: isize = -1;
+    const AF_INET: u32 = 2;
+
+    /// WINDIVERT_ADDRESS（80 字节），只读取 Outbound 位；放行报文时原样交回
+    #[repr(C)]
+    pub struct Address {
+        timestamp: i64,
+        flags: u32,
+        reserved2: u32,
+        reserved3: [u8; 64],
+    }
+
+    impl Address {
+        pub fn outbound(&self) -> bool {
+            (self.flags >> 17) & 1 == 1
+        }
+    }
+
+    type OpenFn = unsafe extern "C" fn(*const u8, i32, i16, u64) -> isize;
+    type RecvFn = unsafe extern "C" fn(isize, *mut c_void, u32, *mut u32, *mut Address) -> i32;
+    type SendFn = unsafe extern "C" fn(isize, *const c_void, u32, *mut u32, *const Address) -> i32;
+    type CalcChecksumsFn = unsafe extern "C" fn(*mut c_void, u32, *mut Address, u64) -> i32;
+    type ShutdownFn = unsafe extern "C" fn(isize, i32) -> i32;
+    type CloseFn = unsafe extern "C" fn(isize) -> i32;
+
+    pub struct Api {
+        open: OpenFn,
+        recv: RecvFn,
+        send: SendFn,
+        calc_checksums: CalcChecksumsFn,
+        shutdown: ShutdownFn,
+        close: CloseFn,
+    }
+
+    static API: OnceLock<Result<Api, String>> = OnceLock::new();
+
+    pub fn api() -> Result<&'static Api, WpeError> {
+        API.get_or_init(load)
+            .as_ref()
+            .map_err(|e| WpeError::WinDivert(e.clone()))
+    }
+
+    fn load() -> Result<Api, String> {
+        let name: Vec<u16> = "WinDivert.dll\0".encode_utf16().collect();
+        let module = unsafe { LoadLibraryW(PCWSTR(name.as_ptr())) }
+            .map_err(|e| format!("Failed to load WinDivert.dll: {e}"))?;
+        let symbol = |name: &'static [u8]| {
+            unsafe { GetProcAddress(module, PCSTR(name.as_ptr())) }.ok_or_else(|| {
+                format!(
+                    "WinDivert.dll missing export {}",
+                    String::from_utf8_lossy(&name[..name.len() - 1])
+                )
+            })
+        };
+        unsafe {
+            Ok(Api {
+                open: std::mem::transmute::<_, OpenFn>(symbol(b"WinDivertOpen\0")?),
+                recv: std::mem::transmute::<_, RecvFn>(symbol(b"WinDivertRecv\0")?),
+                send: std::mem::transmute::<_, SendFn>(symbol(b"WinDivertSend\0")?),
+                calc_checksums: std::mem::transmute::<_, CalcChecksumsFn>(symbol(
+                    b"WinDivertHelperCalcChecksums\0",
+                )?),
+                shutdown: std::mem::transmute::<_, ShutdownFn>(symbol(b"WinDivertShutdown\0")?),
+                close: std::mem::transmute::<_, CloseFn>(symbol(b"WinDivertClose\0")?),
+            })
+        }
+    }
+
+    pub struct RawHandle(isize);
+
+    // WinDivert 句柄可跨线程使用（recv 与 shutdown 在不同线程调用是官方支持的用法）
+    unsafe impl Send for RawHandle {}
+    unsafe impl Sync for RawHandle {}
+
+    impl RawHandle {
+        /// `sniff` 为 false 时以拦截模式打开，截获的报文必须用 send 放回
+        pub fn open(filter: &str, sniff: bool) -> Result<Self, WpeError> {
+            let api = api()?;
+            let filter = format!("{filter}\0");
+            let flags = if sniff { WINDIVERT_FLAG_SNIFF } else { 0 };
+            let handle = unsafe { (api.open)(filter.as_ptr(), WINDIVERT_LAYER_NETWORK, 0, flags) };
+            if handle == INVALID_HANDLE {
+                return Err(WpeError::WinDivert(format!(
+                    "WinDivertOpen failed: {}",
+                    std::io::Error::last_os_error()
+                )));
+            }
+            Ok(Self(handle))
+        }
+
+        /// 读取一个报文，返回 (长度, 地址)
+        pub fn recv(&self, buf: &mut [u8]) -> Result<(usize, Address), WpeError> {
+            let api = api()?;
+            let mut len = 0u32;
+            let mut addr = Address {
+                timestamp: 0,
+                flags: 0,
+                reserved2: 0,
+                reserved3: [0; 64],
+            };
+            let ok = unsafe {
+                (api.recv)(
+                    self.0,
+                    buf.as_mut_ptr() as *mut c_void,
+                    buf.len() as u32,
+                    &mut len,
+                    &mut addr,
+                )
+            };
+            if ok == 0 {
+                return Err(WpeError::WinDivert(format!(
+                    "WinDivertRecv failed: {}",
+                    std::io::Error::last_os_error()
+                )));
+            }
+            Ok((len as usize, addr))
+        }
+
+        /// 用截获时的地址发出报文；`recalc` 时先重新计算 IP/TCP 校验和
+        pub fn send(
+            &self,
+            frame: &mut [u8],
+            addr: &mut Address,
+            recalc: bool,
+        ) -> Result<(), WpeError> {
+            let api = api()?;
+            if recalc {
+                unsafe {
+                    (api.calc_checksums)(
+                        frame.as_mut_ptr() as *mut c_void,
+                        frame.len() as u32,
+                        addr,
+                        0,
+                    );
+                }
+            }
+            let mut sent = 0u32;
+            let ok = unsafe {
+                (api.send)(
+                    self.0,
+                    frame.as_ptr() as *const c_void,
+                    frame.len() as u32,
+                    &mut sent,
+                    addr,
+                )
+            };
+            if ok == 0 {
+                return Err(WpeError::WinDivert(format!(
+                    "WinDivertSend failed: {}",
+                    std::io::Error::last_os_error()
+                )));
+            }
+            Ok(())
+        }
+
+        pub fn shutdown(&self) {
+            if let Ok(api) = api() {
+                unsafe {
+                    (api.shutdown)(self.0, WINDIVERT_SHUTDOWN_BOTH);
+                }
+            }
+        }
+    }
+
+    impl Drop for RawHandle {
+        fn drop(&mut self) {
+            if let Ok(api) = api() {
+                unsafe {
+                    (api.close)(self.0);
+                }
+            }
+        }
+    }
+
+    pub fn is_elevated() -> bool {
+        let mut token = HANDLE::default();
+        if unsafe { OpenProcessToken(GetCurrentProcess(), TOKEN_QUERY, &mut token) }.is_err() {
+            return false;
+        }
+        let mut elevation = TOKEN_ELEVATION::default();
+        let mut returned = 0u32;
+        let result = unsafe {
+            GetTokenInformation(
+                token,
+                TokenElevation,
+                Some(&mut elevation as *mut _ as *mut c_void),
+                std::mem::size_of::<TOKEN_ELEVATION>() as u32,
+                &mut returned,
+            )
+        };
+        unsafe {
+            let _ = CloseHandle(token);
+        }
+        result.is_ok() && elevation.TokenIsElevated != 0
+    }
+
+    /// 目标进程当前 IPv4 TCP 连接的本地端口
+    pub fn tcp_local_ports(pid: u32) -> HashSet<u16> {
+        let mut ports = HashSet::new();
+        let mut size = 0u32;
+        unsafe {
+            GetExtendedTcpTable(None, &mut size, false, AF_INET, TCP_TABLE_OWNER_PID_ALL, 0);
+        }
+        if size == 0 {
+            return ports;
+        }
+        // 按 u32 对齐分配
+        let mut buf = vec![0u32; (size as usize).div_ceil(4)];
+        let result = unsafe {
+            GetExtendedTcpTable(
+                Some(buf.as_mut_ptr() as *mut c_void),
+                &mut size,
+                false,
+                AF_INET,
+                TCP_TABLE_OWNER_PID_ALL,
+                0,
+            )
+        };
+        if result != 0 {
+            return ports;
+        }
+        let count = buf[0] as usize;
+        let rows = unsafe {
+            std::slice::from_raw_parts(buf.as_ptr().add(1) as *const MIB_TCPROW_OWNER_PID, count)
+        };
+        for row in rows.iter().filter(|row| row.dwOwningPid == pid) {
+            // dwLocalPort 低 16 位为网络字节序
+            ports.insert(u16::from_be(row.dwLocalPort as u16));
+        }
+        ports
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{overwrite_payload, tcp_segment, TCP_RST};
+
+    #[test]
+    fn extracts_tcp_payload_from_ipv4_frame() {
+        let payload = b"System_abc";
+        let mut frame = vec![0u8; 40];
+        frame[0] = 0x45;
+        let total = (40 + payload.len()) as u16;
+        frame[2..4].copy_from_slice(&total.to_be_bytes());
+        frame[9] = 6;
+        frame[20..22].copy_from_slice(&443u16.to_be_bytes());
+        frame[22..24].copy_from_slice(&50000u16.to_be_bytes());
+        frame[32] = 5 << 4;
+        frame.extend_from_slice(payload);
+
+        let (src, dst, flags, data) = tcp_segment(&frame).expect("tcp payload");
+        assert_eq!((src, dst, flags), (443, 50000, 0));
+        assert_eq!(&frame[data.clone()], payload);
+
+        // 拦截模式只接受等长改写
+        let mut patched = frame.clone();
+        overwrite_payload(&mut patched, data.clone(), b"System_xyz").expect("same length");
+        assert_eq!(&patched[data.clone()], b"System_xyz");
+        assert_eq!(patched[..40], frame[..40]);
+        let mut unchanged = frame.clone();
+        assert!(overwrite_payload(&mut unchanged, data, b"System_longer").is_err());
+        assert_eq!(unchanged, frame);
+
+        // 只有头部的 RST：payload 为空，标志位照常取出
+        let mut reset = frame[..40].to_vec();
+        reset[2..4].copy_from_slice(&40u16.to_be_bytes());
+        reset[33] = TCP_RST;
+        let (_, _, flags, data) = tcp_segment(&reset).expect("tcp header");
+        assert_eq!(flags, TCP_RST);
+        assert!(data.is_empty());
+        assert_eq!(data.start, 40);
+        assert!(tcp_segment(&frame[..30]).is_none());
+    }
+}